@@ -13,6 +13,7 @@ pub enum APIErrorVariant {
     Unauthorized,
     NotFound,
     BadClientData,
+    Conflict,
     UnexpectedStatusCode,
 }
 #[derive(Debug)]
@@ -64,6 +65,7 @@ impl BaseClient {
                 StatusCode::UNAUTHORIZED => APIErrorVariant::Unauthorized,
                 StatusCode::NOT_FOUND => APIErrorVariant::NotFound,
                 StatusCode::UNPROCESSABLE_ENTITY => APIErrorVariant::BadClientData,
+                StatusCode::CONFLICT => APIErrorVariant::Conflict,
                 _ => APIErrorVariant::UnexpectedStatusCode,
             };
             return Err(APIError {
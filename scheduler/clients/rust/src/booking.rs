@@ -0,0 +1,322 @@
+use crate::{APIResponse, BaseClient, ID};
+use nettu_scheduler_api_structs::*;
+use nettu_scheduler_domain::{
+    booking_slots::ServiceChainStep, BookingInvitee, BookingStatus, Metadata,
+};
+use reqwest::StatusCode;
+use serde::Serialize;
+use std::{collections::HashMap, sync::Arc};
+
+#[derive(Clone)]
+pub struct BookingClient {
+    base: Arc<BaseClient>,
+}
+
+pub struct CreateBookingInput {
+    pub service_id: ID,
+    pub host_user_ids: Option<Vec<ID>>,
+    pub start_ts: i64,
+    pub duration: Option<i64>,
+    pub interval: Option<i64>,
+    pub invitee: BookingInvitee,
+    pub metadata: Option<Metadata>,
+}
+
+pub struct CreateBookingChainInput {
+    /// The services to book back to back, in order
+    pub steps: Vec<ServiceChainStep>,
+    /// The start of the first step
+    pub start_ts: i64,
+    pub interval: Option<i64>,
+    pub invitee: BookingInvitee,
+    pub metadata: Option<Metadata>,
+}
+
+pub struct CreateBookingHoldInput {
+    pub service_id: ID,
+    pub host_user_ids: Option<Vec<ID>>,
+    pub start_ts: i64,
+    pub duration: Option<i64>,
+    pub interval: Option<i64>,
+    /// How long the hold lasts in millis
+    pub ttl: Option<i64>,
+    /// Attributes of the invitee matched by the host routing rules of the service
+    pub attributes: HashMap<String, String>,
+    /// External key of a returning invitee, who is assigned the host of
+    /// their previous booking
+    pub customer_key: Option<String>,
+}
+
+pub struct CreateBookingFromHoldInput {
+    pub hold_id: ID,
+    pub invitee: BookingInvitee,
+    pub metadata: Option<Metadata>,
+}
+
+pub struct CancelBookingInput {
+    pub booking_id: ID,
+    /// Overrides the cancellation policy of the service
+    pub ignore_policy: bool,
+}
+
+pub struct RescheduleBookingInput {
+    pub booking_id: ID,
+    pub start_ts: i64,
+    pub duration: Option<i64>,
+    pub interval: Option<i64>,
+    /// Overrides the reschedule policy of the service
+    pub ignore_policy: bool,
+}
+
+pub struct GetServiceBookingsInput {
+    pub service_id: ID,
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub status: Option<BookingStatus>,
+}
+
+pub struct JoinWaitlistInput {
+    pub service_id: ID,
+    pub start_ts: i64,
+    pub duration: Option<i64>,
+    pub invitee: BookingInvitee,
+    pub metadata: Option<Metadata>,
+}
+
+#[derive(Serialize)]
+struct Empty {}
+
+impl BookingClient {
+    pub(crate) fn new(base: Arc<BaseClient>) -> Self {
+        Self { base }
+    }
+
+    pub async fn create(
+        &self,
+        input: CreateBookingInput,
+    ) -> APIResponse<create_booking::APIResponse> {
+        let body = create_booking::RequestBody {
+            host_user_ids: input.host_user_ids,
+            start_ts: input.start_ts,
+            duration: input.duration,
+            interval: input.interval,
+            invitee: input.invitee,
+            metadata: input.metadata,
+        };
+
+        self.base
+            .post(
+                body,
+                format!("service/{}/bookings", input.service_id),
+                StatusCode::CREATED,
+            )
+            .await
+    }
+
+    /// Books every step of a service chain, or none of them if any step is unavailable
+    pub async fn create_chain(
+        &self,
+        input: CreateBookingChainInput,
+    ) -> APIResponse<create_booking_chain::APIResponse> {
+        let body = create_booking_chain::RequestBody {
+            steps: input.steps,
+            start_ts: input.start_ts,
+            interval: input.interval,
+            invitee: input.invitee,
+            metadata: input.metadata,
+        };
+
+        self.base
+            .post(body, "service-chain/bookings".into(), StatusCode::CREATED)
+            .await
+    }
+
+    pub async fn get(&self, booking_id: ID) -> APIResponse<get_booking::APIResponse> {
+        self.base
+            .get(format!("booking/{}", booking_id), StatusCode::OK)
+            .await
+    }
+
+    pub async fn get_by_service(
+        &self,
+        input: GetServiceBookingsInput,
+    ) -> APIResponse<get_service_bookings::APIResponse> {
+        let mut query_string = format!("startTs={}&endTs={}", input.start_ts, input.end_ts);
+        if let Some(status) = input.status {
+            let status = match status {
+                BookingStatus::Pending => "pending",
+                BookingStatus::Confirmed => "confirmed",
+                BookingStatus::Cancelled => "cancelled",
+                BookingStatus::Rescheduled => "rescheduled",
+                BookingStatus::NoShow => "noShow",
+                BookingStatus::Declined => "declined",
+            };
+            query_string = format!("{}&status={}", query_string, status);
+        }
+
+        self.base
+            .get(
+                format!("service/{}/bookings?{}", input.service_id, query_string),
+                StatusCode::OK,
+            )
+            .await
+    }
+
+    pub async fn cancel(
+        &self,
+        input: CancelBookingInput,
+    ) -> APIResponse<cancel_booking::APIResponse> {
+        self.base
+            .post(
+                Empty {},
+                format!(
+                    "booking/{}/cancel?ignorePolicy={}",
+                    input.booking_id, input.ignore_policy
+                ),
+                StatusCode::OK,
+            )
+            .await
+    }
+
+    pub async fn reschedule(
+        &self,
+        input: RescheduleBookingInput,
+    ) -> APIResponse<reschedule_booking::APIResponse> {
+        let body = reschedule_booking::RequestBody {
+            start_ts: input.start_ts,
+            duration: input.duration,
+            interval: input.interval,
+            ignore_policy: input.ignore_policy,
+        };
+
+        self.base
+            .post(
+                body,
+                format!("booking/{}/reschedule", input.booking_id),
+                StatusCode::OK,
+            )
+            .await
+    }
+
+    pub async fn approve(&self, booking_id: ID) -> APIResponse<approve_booking::APIResponse> {
+        self.base
+            .post(
+                Empty {},
+                format!("booking/{}/approve", booking_id),
+                StatusCode::OK,
+            )
+            .await
+    }
+
+    pub async fn decline(&self, booking_id: ID) -> APIResponse<decline_booking::APIResponse> {
+        self.base
+            .post(
+                Empty {},
+                format!("booking/{}/decline", booking_id),
+                StatusCode::OK,
+            )
+            .await
+    }
+
+    pub async fn mark_no_show(
+        &self,
+        booking_id: ID,
+    ) -> APIResponse<mark_booking_no_show::APIResponse> {
+        self.base
+            .post(
+                Empty {},
+                format!("booking/{}/no-show", booking_id),
+                StatusCode::OK,
+            )
+            .await
+    }
+
+    pub async fn hold(
+        &self,
+        input: CreateBookingHoldInput,
+    ) -> APIResponse<create_booking_hold::APIResponse> {
+        let body = create_booking_hold::RequestBody {
+            host_user_ids: input.host_user_ids,
+            start_ts: input.start_ts,
+            duration: input.duration,
+            interval: input.interval,
+            ttl: input.ttl,
+            attributes: input.attributes,
+            customer_key: input.customer_key,
+        };
+
+        self.base
+            .post(
+                body,
+                format!("service/{}/holds", input.service_id),
+                StatusCode::CREATED,
+            )
+            .await
+    }
+
+    pub async fn release_hold(
+        &self,
+        hold_id: ID,
+    ) -> APIResponse<release_booking_hold::APIResponse> {
+        self.base
+            .delete(format!("hold/{}", hold_id), StatusCode::OK)
+            .await
+    }
+
+    pub async fn create_from_hold(
+        &self,
+        input: CreateBookingFromHoldInput,
+    ) -> APIResponse<create_booking_from_hold::APIResponse> {
+        let body = create_booking_from_hold::RequestBody {
+            invitee: input.invitee,
+            metadata: input.metadata,
+        };
+
+        self.base
+            .post(
+                body,
+                format!("hold/{}/booking", input.hold_id),
+                StatusCode::CREATED,
+            )
+            .await
+    }
+
+    pub async fn join_waitlist(
+        &self,
+        input: JoinWaitlistInput,
+    ) -> APIResponse<join_waitlist::APIResponse> {
+        let body = join_waitlist::RequestBody {
+            start_ts: input.start_ts,
+            duration: input.duration,
+            invitee: input.invitee,
+            metadata: input.metadata,
+        };
+
+        self.base
+            .post(
+                body,
+                format!("service/{}/waitlist", input.service_id),
+                StatusCode::CREATED,
+            )
+            .await
+    }
+
+    pub async fn get_waitlist(
+        &self,
+        service_id: ID,
+        start_ts: i64,
+    ) -> APIResponse<get_waitlist::APIResponse> {
+        self.base
+            .get(
+                format!("service/{}/waitlist?startTs={}", service_id, start_ts),
+                StatusCode::OK,
+            )
+            .await
+    }
+
+    pub async fn leave_waitlist(&self, entry_id: ID) -> APIResponse<leave_waitlist::APIResponse> {
+        self.base
+            .delete(format!("waitlist/{}", entry_id), StatusCode::OK)
+            .await
+    }
+}
@@ -1,5 +1,6 @@
 mod account;
 mod base;
+mod booking;
 mod calendar;
 mod event;
 mod schedule;
@@ -11,6 +12,12 @@ mod user;
 use account::AccountClient;
 pub(crate) use base::BaseClient;
 pub use base::{APIError, APIErrorVariant, APIResponse};
+use booking::BookingClient;
+pub use booking::{
+    CancelBookingInput, CreateBookingChainInput, CreateBookingFromHoldInput,
+    CreateBookingHoldInput, CreateBookingInput, GetServiceBookingsInput, JoinWaitlistInput,
+    RescheduleBookingInput,
+};
 use calendar::CalendarClient;
 pub use calendar::{
     CreateCalendarInput, GetCalendarEventsInput, GetGoogleCalendars, GetOutlookCalendars,
@@ -21,17 +28,27 @@ pub use event::{CreateEventInput, GetEventsInstancesInput, UpdateEventInput};
 pub use nettu_scheduler_api_structs::dtos::*;
 pub use nettu_scheduler_api_structs::send_event_reminders::AccountRemindersDTO as AccountReminders;
 pub use nettu_scheduler_domain::{
-    providers::google::*, providers::outlook::*, scheduling::RoundRobinAlgorithm, BusyCalendar,
-    CalendarEventReminder, IntegrationProvider, Metadata, RRuleFrequency, RRuleOptions,
-    ScheduleRule, ServiceMultiPersonOptions, SyncedCalendar, TimePlan, ID,
+    booking_slots::ServiceChainStep,
+    providers::google::*,
+    providers::outlook::*,
+    scheduling::{FairnessBalance, FairnessWindow, HostRoutingRule, RoundRobinAlgorithm},
+    BookingInvitee, BookingReschedule, BookingStatus, BusyCalendar, CalendarEventReminder,
+    CollectiveQuorum, IntakeAnswer, IntakeAnswers, IntakeQuestion, IntakeQuestionKind,
+    IntegrationProvider, Metadata, RRuleFrequency, RRuleOptions, ScheduleRule,
+    ScheduleRuleInterval, ServiceBookingOptions, ServiceMultiPersonOptions, SyncedCalendar,
+    TimePlan, ID,
 };
 use schedule::ScheduleClient;
-pub use schedule::{CreateScheduleInput, UpdateScheduleInput};
+pub use schedule::{
+    CreateScheduleFromVAvailabilityInput, CreateScheduleInput, PreviewScheduleInput,
+    UpdateScheduleFromVAvailabilityInput, UpdateScheduleInput,
+};
 use service::ServiceClient;
 pub use service::{
     AddBusyCalendar, AddServiceUserInput, CreateBookingIntendInput, CreateServiceInput,
-    GetServiceBookingSlotsInput, RemoveBookingIntendInput, RemoveBusyCalendar,
-    RemoveServiceUserInput, UpdateServiceInput, UpdateServiceUserInput,
+    ExplainServiceBookingSlotInput, GetServiceBookingSlotsInput, GetServiceBookingSlotsPageInput,
+    GetServiceChainBookingSlotsInput, GetServiceNextBookingSlotInput, RemoveBookingIntendInput,
+    RemoveBusyCalendar, RemoveServiceUserInput, UpdateServiceInput, UpdateServiceUserInput,
 };
 pub use shared::{KVMetadata, MetadataFindInput};
 use status::StatusClient;
@@ -45,6 +62,8 @@ pub use user::{
 pub use nettu_scheduler_api_structs::dtos::AccountDTO as Account;
 pub use nettu_scheduler_api_structs::dtos::AccountSettingsDTO as AccountSettings;
 pub use nettu_scheduler_api_structs::dtos::AccountWebhookSettingsDTO as AccountWebhookSettings;
+pub use nettu_scheduler_api_structs::dtos::BookingDTO as Booking;
+pub use nettu_scheduler_api_structs::dtos::BookingHoldDTO as BookingHold;
 pub use nettu_scheduler_api_structs::dtos::CalendarDTO as Calendar;
 pub use nettu_scheduler_api_structs::dtos::CalendarEventDTO as CalendarEvent;
 pub use nettu_scheduler_api_structs::dtos::CalendarSettingsDTO as CalendarSettings;
@@ -63,6 +82,7 @@ pub use nettu_scheduler_domain::{Month, Tz, WeekDay, Weekday};
 #[derive(Clone)]
 pub struct NettuSDK {
     pub account: AccountClient,
+    pub booking: BookingClient,
     pub calendar: CalendarClient,
     pub event: CalendarEventClient,
     pub schedule: ScheduleClient,
@@ -77,6 +97,7 @@ impl NettuSDK {
         base.set_api_key(api_key.into());
         let base = Arc::new(base);
         let account = AccountClient::new(base.clone());
+        let booking = BookingClient::new(base.clone());
         let calendar = CalendarClient::new(base.clone());
         let event = CalendarEventClient::new(base.clone());
         let schedule = ScheduleClient::new(base.clone());
@@ -86,6 +107,7 @@ impl NettuSDK {
 
         Self {
             account,
+            booking,
             calendar,
             event,
             schedule,
@@ -24,6 +24,25 @@ pub struct UpdateScheduleInput {
     pub metadata: Option<Metadata>,
 }
 
+pub struct CreateScheduleFromVAvailabilityInput {
+    pub user_id: ID,
+    pub vavailability: String,
+    pub timezone: Option<Tz>,
+    pub metadata: Option<Metadata>,
+}
+
+pub struct UpdateScheduleFromVAvailabilityInput {
+    pub schedule_id: ID,
+    pub vavailability: String,
+}
+
+pub struct PreviewScheduleInput {
+    pub timezone: Tz,
+    pub rules: Vec<ScheduleRule>,
+    pub start_ts: i64,
+    pub end_ts: i64,
+}
+
 impl ScheduleClient {
     pub(crate) fn new(base: Arc<BaseClient>) -> Self {
         Self { base }
@@ -81,4 +100,68 @@ impl ScheduleClient {
             )
             .await
     }
+
+    pub async fn get_vavailability(
+        &self,
+        schedule_id: ID,
+    ) -> APIResponse<get_schedule_vavailability::APIResponse> {
+        self.base
+            .get(
+                format!("user/schedule/{}/vavailability", schedule_id),
+                StatusCode::OK,
+            )
+            .await
+    }
+
+    pub async fn create_from_vavailability(
+        &self,
+        input: CreateScheduleFromVAvailabilityInput,
+    ) -> APIResponse<create_schedule_from_vavailability::APIResponse> {
+        let body = create_schedule_from_vavailability::RequestBody {
+            vavailability: input.vavailability,
+            timezone: input.timezone,
+            metadata: input.metadata,
+        };
+
+        self.base
+            .post(
+                body,
+                format!("user/{}/schedule/vavailability", input.user_id),
+                StatusCode::CREATED,
+            )
+            .await
+    }
+
+    pub async fn update_from_vavailability(
+        &self,
+        input: UpdateScheduleFromVAvailabilityInput,
+    ) -> APIResponse<update_schedule_from_vavailability::APIResponse> {
+        let body = update_schedule_from_vavailability::RequestBody {
+            vavailability: input.vavailability,
+        };
+
+        self.base
+            .put(
+                body,
+                format!("user/schedule/{}/vavailability", input.schedule_id),
+                StatusCode::OK,
+            )
+            .await
+    }
+
+    pub async fn preview(
+        &self,
+        input: PreviewScheduleInput,
+    ) -> APIResponse<preview_schedule::APIResponse> {
+        let body = preview_schedule::RequestBody {
+            timezone: input.timezone,
+            rules: input.rules,
+            start_ts: input.start_ts,
+            end_ts: input.end_ts,
+        };
+
+        self.base
+            .post(body, "user/schedule/preview".into(), StatusCode::OK)
+            .await
+    }
 }
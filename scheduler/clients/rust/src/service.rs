@@ -1,8 +1,12 @@
 use crate::{shared::MetadataFindInput, APIResponse, BaseClient, TimePlan, Tz, ID};
 use nettu_scheduler_api_structs::*;
-use nettu_scheduler_domain::{BusyCalendar, Metadata, ServiceMultiPersonOptions};
+use nettu_scheduler_domain::{
+    booking_slots::ServiceChainStep, BusyCalendar, IntakeAnswers, Metadata, ScheduleRuleInterval,
+    ServiceBookingOptions, ServiceMultiPersonOptions,
+};
 use reqwest::StatusCode;
 use serde::Serialize;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -18,6 +22,13 @@ pub struct AddServiceUserInput {
     pub buffer_before: Option<i64>,
     pub closest_booking_time: Option<i64>,
     pub furthest_booking_time: Option<i64>,
+    pub max_bookings_per_day: Option<i64>,
+    pub max_booked_minutes_per_week: Option<i64>,
+    pub preferred_times: Option<Vec<ScheduleRuleInterval>>,
+    /// Share of the round robin assignments relative to the other hosts
+    pub weight: Option<i64>,
+    /// Hosts with a higher priority are assigned first by round robin
+    pub priority: Option<i64>,
 }
 
 pub struct AddBusyCalendar {
@@ -40,19 +51,32 @@ pub struct UpdateServiceUserInput {
     pub buffer_before: Option<i64>,
     pub closest_booking_time: Option<i64>,
     pub furthest_booking_time: Option<i64>,
+    pub max_bookings_per_day: Option<i64>,
+    pub max_booked_minutes_per_week: Option<i64>,
+    pub preferred_times: Option<Vec<ScheduleRuleInterval>>,
+    /// Share of the round robin assignments relative to the other hosts
+    pub weight: Option<i64>,
+    /// Hosts with a higher priority are assigned first by round robin
+    pub priority: Option<i64>,
 }
 
 pub struct CreateBookingIntendInput {
     pub service_id: ID,
     pub host_user_ids: Option<Vec<ID>>,
     pub timestamp: i64,
-    pub duration: i64,
-    pub interval: i64,
+    pub duration: Option<i64>,
+    pub interval: Option<i64>,
+    /// Attributes of the invitee matched by the host routing rules of the service
+    pub attributes: Option<HashMap<String, String>>,
+    /// Answers of the invitee to the intake questions of the service
+    pub answers: Option<IntakeAnswers>,
 }
 
 pub struct RemoveBookingIntendInput {
     pub service_id: ID,
     pub timestamp: i64,
+    /// Overrides the cancellation policy of the service
+    pub ignore_policy: bool,
 }
 
 pub struct RemoveServiceUserInput {
@@ -64,22 +88,84 @@ pub struct RemoveServiceUserInput {
 pub struct GetServiceBookingSlotsInput {
     pub service_id: ID,
     pub timezone: Option<Tz>,
-    pub duration: i64,
-    pub interval: i64,
+    pub duration: Option<i64>,
+    pub interval: Option<i64>,
     pub start_date: String,
     pub end_date: String,
     pub host_user_ids: Option<Vec<ID>>,
+    pub rank: bool,
+    pub best_per_day: Option<usize>,
+    /// Attributes of the invitee matched by the host routing rules of the service
+    pub attributes: Option<HashMap<String, String>>,
+    /// Query the external busy calendars instead of using their cached busy intervals
+    pub bypass_busy_cache: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct GetServiceBookingSlotsPageInput {
+    pub service_id: ID,
+    pub timezone: Option<Tz>,
+    pub duration: Option<i64>,
+    pub interval: Option<i64>,
+    /// The first date of the first page, ignored when a cursor is given
+    pub start_date: Option<String>,
+    /// The `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    /// Amount of dates of the page
+    pub days: Option<i64>,
+    pub host_user_ids: Option<Vec<ID>>,
+    /// Attributes of the invitee matched by the host routing rules of the service
+    pub attributes: Option<HashMap<String, String>>,
+    pub bypass_busy_cache: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct GetServiceNextBookingSlotInput {
+    pub service_id: ID,
+    /// Timestamp in millis the booking slot should start at or after,
+    /// defaults to now
+    pub after: Option<i64>,
+    pub duration: Option<i64>,
+    pub interval: Option<i64>,
+    pub host_user_ids: Option<Vec<ID>>,
+    /// Attributes of the invitee matched by the host routing rules of the service
+    pub attributes: Option<HashMap<String, String>>,
+    pub bypass_busy_cache: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExplainServiceBookingSlotInput {
+    pub service_id: ID,
+    pub user_id: ID,
+    /// Timestamp in millis of the start of the booking slot
+    pub timestamp: i64,
+    pub duration: Option<i64>,
+    pub bypass_busy_cache: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct GetServiceChainBookingSlotsInput {
+    /// The services to book back to back, in order
+    pub steps: Vec<ServiceChainStep>,
+    pub timezone: Option<Tz>,
+    pub interval: Option<i64>,
+    pub start_date: String,
+    pub end_date: String,
+    /// Attributes of the invitee matched by the host routing rules of the services
+    pub attributes: Option<HashMap<String, String>>,
 }
 
 pub struct UpdateServiceInput {
     pub service_id: ID,
     pub metadata: Option<Metadata>,
     pub multi_person: Option<ServiceMultiPersonOptions>,
+    pub booking_options: Option<ServiceBookingOptions>,
 }
 
 pub struct CreateServiceInput {
     pub metadata: Option<Metadata>,
     pub multi_person: Option<ServiceMultiPersonOptions>,
+    pub booking_options: Option<ServiceBookingOptions>,
 }
 
 #[derive(Serialize)]
@@ -96,14 +182,31 @@ impl ServiceClient {
             .await
     }
 
+    /// The bookings of every host within the fairness window of the `Service`
+    pub async fn distribution(
+        &self,
+        service_id: ID,
+    ) -> APIResponse<get_service_distribution::APIResponse> {
+        self.base
+            .get(
+                format!("service/{}/distribution", service_id),
+                StatusCode::OK,
+            )
+            .await
+    }
+
     pub async fn bookingslots(
         &self,
         input: GetServiceBookingSlotsInput,
     ) -> APIResponse<get_service_bookingslots::APIResponse> {
-        let mut query_string = format!(
-            "duration={}&interval={}&startDate={}&endDate={}",
-            input.duration, input.interval, input.start_date, input.end_date
-        );
+        let mut query_string = format!("startDate={}&endDate={}", input.start_date, input.end_date);
+
+        if let Some(duration) = input.duration {
+            query_string = format!("{}&duration={}", query_string, duration);
+        }
+        if let Some(interval) = input.interval {
+            query_string = format!("{}&interval={}", query_string, interval);
+        }
 
         if let Some(timezone) = input.timezone {
             query_string = format!("{}&ianaTz={}", query_string, timezone);
@@ -119,6 +222,26 @@ impl ServiceClient {
                     .join(",")
             );
         }
+        if input.rank {
+            query_string = format!("{}&rank=true", query_string);
+        }
+        if let Some(best_per_day) = input.best_per_day {
+            query_string = format!("{}&bestPerDay={}", query_string, best_per_day);
+        }
+        if let Some(attributes) = input.attributes {
+            query_string = format!(
+                "{}&attributes={}",
+                query_string,
+                attributes
+                    .into_iter()
+                    .map(|(key, value)| format!("{}:{}", key, value))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            );
+        }
+        if input.bypass_busy_cache {
+            query_string = format!("{}&bypassBusyCache=true", query_string);
+        }
 
         self.base
             .get(
@@ -128,6 +251,161 @@ impl ServiceClient {
             .await
     }
 
+    /// One page of booking slots together with the cursor of the following page
+    pub async fn bookingslots_page(
+        &self,
+        input: GetServiceBookingSlotsPageInput,
+    ) -> APIResponse<get_service_bookingslots_page::APIResponse> {
+        let mut query_params = Vec::new();
+        if let Some(start_date) = input.start_date {
+            query_params.push(format!("startDate={}", start_date));
+        }
+        if let Some(cursor) = input.cursor {
+            query_params.push(format!("cursor={}", cursor));
+        }
+        if let Some(days) = input.days {
+            query_params.push(format!("days={}", days));
+        }
+        if let Some(duration) = input.duration {
+            query_params.push(format!("duration={}", duration));
+        }
+        if let Some(interval) = input.interval {
+            query_params.push(format!("interval={}", interval));
+        }
+        if let Some(timezone) = input.timezone {
+            query_params.push(format!("timezone={}", timezone));
+        }
+        if let Some(host_user_ids) = input.host_user_ids {
+            query_params.push(format!(
+                "hostUserIds={}",
+                host_user_ids
+                    .into_iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ));
+        }
+        if let Some(attributes) = input.attributes {
+            query_params.push(format!(
+                "attributes={}",
+                attributes
+                    .into_iter()
+                    .map(|(key, value)| format!("{}:{}", key, value))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ));
+        }
+        if input.bypass_busy_cache {
+            query_params.push("bypassBusyCache=true".into());
+        }
+
+        self.base
+            .get(
+                format!(
+                    "service/{}/booking/page?{}",
+                    input.service_id,
+                    query_params.join("&")
+                ),
+                StatusCode::OK,
+            )
+            .await
+    }
+
+    /// The earliest bookable slot of the service
+    pub async fn next_bookingslot(
+        &self,
+        input: GetServiceNextBookingSlotInput,
+    ) -> APIResponse<get_service_next_bookingslot::APIResponse> {
+        let mut query_params = Vec::new();
+        if let Some(after) = input.after {
+            query_params.push(format!("after={}", after));
+        }
+        if let Some(duration) = input.duration {
+            query_params.push(format!("duration={}", duration));
+        }
+        if let Some(interval) = input.interval {
+            query_params.push(format!("interval={}", interval));
+        }
+        if let Some(host_user_ids) = input.host_user_ids {
+            query_params.push(format!(
+                "hostUserIds={}",
+                host_user_ids
+                    .into_iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ));
+        }
+        if let Some(attributes) = input.attributes {
+            query_params.push(format!(
+                "attributes={}",
+                attributes
+                    .into_iter()
+                    .map(|(key, value)| format!("{}:{}", key, value))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ));
+        }
+        if input.bypass_busy_cache {
+            query_params.push("bypassBusyCache=true".into());
+        }
+
+        self.base
+            .get(
+                format!(
+                    "service/{}/booking/next?{}",
+                    input.service_id,
+                    query_params.join("&")
+                ),
+                StatusCode::OK,
+            )
+            .await
+    }
+
+    /// Every reason the host can not be booked at the booking slot
+    pub async fn explain_bookingslot(
+        &self,
+        input: ExplainServiceBookingSlotInput,
+    ) -> APIResponse<explain_service_bookingslot::APIResponse> {
+        let mut query_params = vec![format!("timestamp={}", input.timestamp)];
+        if let Some(duration) = input.duration {
+            query_params.push(format!("duration={}", duration));
+        }
+        if input.bypass_busy_cache {
+            query_params.push("bypassBusyCache=true".into());
+        }
+
+        self.base
+            .get(
+                format!(
+                    "service/{}/users/{}/explain?{}",
+                    input.service_id,
+                    input.user_id,
+                    query_params.join("&")
+                ),
+                StatusCode::OK,
+            )
+            .await
+    }
+
+    /// Start times where every step of the service chain can be booked back to back
+    pub async fn chain_bookingslots(
+        &self,
+        input: GetServiceChainBookingSlotsInput,
+    ) -> APIResponse<get_service_chain_bookingslots::APIResponse> {
+        let body = get_service_chain_bookingslots::RequestBody {
+            steps: input.steps,
+            timezone: input.timezone,
+            start_date: input.start_date,
+            end_date: input.end_date,
+            interval: input.interval,
+            attributes: input.attributes.unwrap_or_default(),
+        };
+        self.base
+            .post(body, "service-chain/booking".into(), StatusCode::OK)
+            .await
+    }
+
     pub async fn create_booking_intend(
         &self,
         input: CreateBookingIntendInput,
@@ -137,6 +415,8 @@ impl ServiceClient {
             host_user_ids: input.host_user_ids,
             interval: input.interval,
             timestamp: input.timestamp,
+            attributes: input.attributes.unwrap_or_default(),
+            answers: input.answers.unwrap_or_default(),
         };
         self.base
             .post(
@@ -154,8 +434,8 @@ impl ServiceClient {
         self.base
             .delete(
                 format!(
-                    "service/{}/booking-intend?timestamp={}",
-                    input.service_id, input.timestamp
+                    "service/{}/booking-intend?timestamp={}&ignorePolicy={}",
+                    input.service_id, input.timestamp, input.ignore_policy
                 ),
                 StatusCode::OK,
             )
@@ -175,6 +455,7 @@ impl ServiceClient {
         let body = create_service::RequestBody {
             metadata: input.metadata,
             multi_person: input.multi_person,
+            booking_options: input.booking_options,
         };
         self.base
             .post(body, "service".into(), StatusCode::CREATED)
@@ -188,6 +469,7 @@ impl ServiceClient {
         let body = update_service::RequestBody {
             metadata: input.metadata,
             multi_person: input.multi_person,
+            booking_options: input.booking_options,
         };
         self.base
             .put(
@@ -234,6 +516,11 @@ impl ServiceClient {
             buffer_before: input.buffer_before,
             closest_booking_time: input.closest_booking_time,
             furthest_booking_time: input.furthest_booking_time,
+            max_bookings_per_day: input.max_bookings_per_day,
+            max_booked_minutes_per_week: input.max_booked_minutes_per_week,
+            preferred_times: input.preferred_times,
+            weight: input.weight,
+            priority: input.priority,
         };
 
         self.base
@@ -257,6 +544,11 @@ impl ServiceClient {
             buffer_before: input.buffer_before,
             closest_booking_time: input.closest_booking_time,
             furthest_booking_time: input.furthest_booking_time,
+            max_bookings_per_day: input.max_bookings_per_day,
+            max_booked_minutes_per_week: input.max_booked_minutes_per_week,
+            preferred_times: input.preferred_times,
+            weight: input.weight,
+            priority: input.priority,
         };
 
         self.base
@@ -0,0 +1,117 @@
+use crate::error::NettuError;
+use crate::event::update_event::UpdateEventUseCase;
+use crate::shared::{
+    auth::protect_account_route,
+    usecase::{execute, UseCase},
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use nettu_scheduler_api_structs::approve_booking::*;
+use nettu_scheduler_domain::{Account, Booking, ID};
+use nettu_scheduler_infra::NettuContext;
+
+pub async fn approve_booking_controller(
+    http_req: HttpRequest,
+    path_params: web::Path<PathParams>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let account = protect_account_route(&http_req, &ctx).await?;
+
+    let usecase = ApproveBookingUseCase {
+        account,
+        booking_id: path_params.booking_id.clone(),
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|booking| HttpResponse::Ok().json(APIResponse::new(booking)))
+        .map_err(NettuError::from)
+}
+
+#[derive(Debug)]
+struct ApproveBookingUseCase {
+    account: Account,
+    booking_id: ID,
+}
+
+#[derive(Debug)]
+enum UseCaseError {
+    NotFound(ID),
+    NotPending,
+    DeadlinePassed,
+    StorageError,
+}
+
+impl From<UseCaseError> for NettuError {
+    fn from(e: UseCaseError) -> Self {
+        match e {
+            UseCaseError::NotFound(id) => {
+                Self::NotFound(format!("The booking with id: {} was not found.", id))
+            }
+            UseCaseError::NotPending => {
+                Self::Conflict("The booking is not waiting for approval".into())
+            }
+            UseCaseError::DeadlinePassed => {
+                Self::Conflict("The approval deadline of the booking has passed".into())
+            }
+            UseCaseError::StorageError => Self::InternalError,
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for ApproveBookingUseCase {
+    type Response = Booking;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "ApproveBooking";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        let mut booking = match ctx.repos.bookings.find(&self.booking_id).await {
+            Some(booking) if booking.account_id == self.account.id => booking,
+            _ => return Err(UseCaseError::NotFound(self.booking_id.clone())),
+        };
+
+        let now = ctx.sys.get_timestamp_millis();
+        if matches!(booking.approval_deadline, Some(deadline) if deadline <= now) {
+            return Err(UseCaseError::DeadlinePassed);
+        }
+        if !booking.approve(now) {
+            return Err(UseCaseError::NotPending);
+        }
+
+        let events = ctx
+            .repos
+            .events
+            .find_many(&booking.event_ids)
+            .await
+            .map_err(|_| UseCaseError::StorageError)?
+            .into_iter()
+            .filter(|e| e.tentative)
+            .map(|mut event| {
+                event.tentative = false;
+                event.updated = now;
+                event
+            })
+            .collect::<Vec<_>>();
+
+        let approved = ctx
+            .repos
+            .bookings
+            .approve(&booking, &events)
+            .await
+            .map_err(|_| UseCaseError::StorageError)?;
+        if !approved {
+            return Err(UseCaseError::NotPending);
+        }
+
+        // Lets the synced calendars know that the events are confirmed
+        for event in &events {
+            for subscriber in UpdateEventUseCase::subscribers() {
+                subscriber.notify(event, ctx).await;
+            }
+        }
+
+        Ok(booking)
+    }
+}
@@ -0,0 +1,167 @@
+use super::offer_waitlist_seat::OfferWaitlistSeatUseCase;
+use crate::error::NettuError;
+use crate::event::delete_event::{DeleteEventUseCase, UseCaseError as DeleteEventError};
+use crate::shared::{
+    auth::protect_account_route,
+    usecase::{execute, UseCase},
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use nettu_scheduler_api_structs::cancel_booking::*;
+use nettu_scheduler_domain::{
+    Account, Booking, BookingPolicyViolation, BookingStatus, CalendarEvent, Service,
+    ServiceMultiPersonOptions, ID,
+};
+use nettu_scheduler_infra::NettuContext;
+
+pub async fn cancel_booking_controller(
+    http_req: HttpRequest,
+    query_params: web::Query<QueryParams>,
+    path_params: web::Path<PathParams>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let account = protect_account_route(&http_req, &ctx).await?;
+
+    let usecase = CancelBookingUseCase {
+        account,
+        booking_id: path_params.booking_id.clone(),
+        ignore_policy: query_params.ignore_policy,
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|booking| HttpResponse::Ok().json(APIResponse::new(booking)))
+        .map_err(NettuError::from)
+}
+
+#[derive(Debug)]
+struct CancelBookingUseCase {
+    account: Account,
+    booking_id: ID,
+    /// Whether the cancellation policy of the `Service` is overridden
+    ignore_policy: bool,
+}
+
+#[derive(Debug)]
+enum UseCaseError {
+    NotFound(ID),
+    NotActive,
+    PolicyViolation(BookingPolicyViolation),
+    StorageError,
+}
+
+impl From<UseCaseError> for NettuError {
+    fn from(e: UseCaseError) -> Self {
+        match e {
+            UseCaseError::NotFound(id) => {
+                Self::NotFound(format!("The booking with id: {} was not found.", id))
+            }
+            UseCaseError::NotActive => {
+                Self::Conflict("The booking has already been cancelled or completed".into())
+            }
+            UseCaseError::PolicyViolation(e) => e.into(),
+            UseCaseError::StorageError => Self::InternalError,
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for CancelBookingUseCase {
+    type Response = Booking;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "CancelBooking";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        let mut booking = match ctx.repos.bookings.find(&self.booking_id).await {
+            Some(booking) if booking.account_id == self.account.id => booking,
+            _ => return Err(UseCaseError::NotFound(self.booking_id.clone())),
+        };
+        let service = match ctx.repos.services.find(&booking.service_id).await {
+            Some(service) => service,
+            None => return Err(UseCaseError::NotFound(self.booking_id.clone())),
+        };
+
+        let now = ctx.sys.get_timestamp_millis();
+        if !self.ignore_policy {
+            service
+                .booking_options
+                .check_cancellation(booking.start_ts, now)
+                .map_err(UseCaseError::PolicyViolation)?;
+        }
+        if !booking.cancel(now) {
+            return Err(UseCaseError::NotActive);
+        }
+
+        let released = release_booking_slot(&booking, &BookingStatus::ACTIVE, &service, ctx)
+            .await
+            .map_err(|_| UseCaseError::StorageError)?;
+        if !released {
+            return Err(UseCaseError::NotActive);
+        }
+
+        Ok(booking)
+    }
+}
+
+/// Stores the cancelled or declined `Booking` and releases the time it took
+/// on its `Service`, by giving back the seat of a group `Service` or deleting
+/// the `CalendarEvent`s of its hosts. Returns false without releasing anything
+/// when the `Booking` is no longer in one of the `from` statuses, because it
+/// was changed concurrently. A seat given back is offered to the waitlist of
+/// the booking slot.
+pub(super) async fn release_booking_slot(
+    booking: &Booking,
+    from: &[BookingStatus],
+    service: &Service,
+    ctx: &NettuContext,
+) -> anyhow::Result<bool> {
+    let seats = match service.multi_person {
+        ServiceMultiPersonOptions::Group(max_count) => Some(max_count),
+        _ => None,
+    };
+    let event_ids = match ctx.repos.bookings.release(booking, from, seats).await? {
+        Some(event_ids) => event_ids,
+        None => return Ok(false),
+    };
+    let events = ctx.repos.events.find_many(&event_ids).await?;
+    delete_booking_events(events, ctx).await?;
+
+    if seats.is_some() {
+        let offer = OfferWaitlistSeatUseCase {
+            service_id: service.id.clone(),
+            start_ts: booking.start_ts,
+        };
+        execute(offer, ctx)
+            .await
+            .map_err(|e| anyhow::Error::msg(format!("{:?}", e)))?;
+    }
+
+    Ok(true)
+}
+
+pub(super) async fn delete_booking_events(
+    events: Vec<CalendarEvent>,
+    ctx: &NettuContext,
+) -> anyhow::Result<()> {
+    let user_ids = events.iter().map(|e| e.user_id.clone()).collect::<Vec<_>>();
+    let users = ctx.repos.users.find_many(&user_ids).await;
+
+    for event in events {
+        let user = match users.iter().find(|user| user.id == event.user_id) {
+            Some(user) => user.clone(),
+            None => continue,
+        };
+        let delete_event = DeleteEventUseCase {
+            user,
+            event_id: event.id,
+        };
+        match execute(delete_event, ctx).await {
+            // The event was already deleted by its host
+            Ok(_) | Err(DeleteEventError::NotFound(_)) => (),
+            Err(e) => return Err(anyhow::Error::msg(format!("{:?}", e))),
+        }
+    }
+
+    Ok(())
+}
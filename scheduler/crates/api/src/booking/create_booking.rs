@@ -0,0 +1,327 @@
+use crate::error::NettuError;
+use crate::event::create_event::CreateEventUseCase;
+use crate::service::create_service_event_intend::{self, CreateServiceEventIntendUseCase};
+use crate::shared::{
+    auth::protect_account_route,
+    usecase::{execute, UseCase},
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use awc::Client;
+use nettu_scheduler_api_structs::{create_booking::*, BookingApprovalRequestDTO};
+use nettu_scheduler_domain::{
+    validate_intake_answers, Account, Booking, BookingInvitee, BookingStatus, BusyCalendar,
+    CalendarEvent, IntakeAnswerError, Metadata, ServiceMultiPersonOptions, ServiceWithUsers,
+    TimePlan, User, ID,
+};
+use nettu_scheduler_infra::{BookingCommit, BookingCommitError, NettuContext};
+use tracing::error;
+
+pub async fn create_booking_controller(
+    http_req: HttpRequest,
+    body: web::Json<RequestBody>,
+    mut path: web::Path<PathParams>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let account = protect_account_route(&http_req, &ctx).await?;
+
+    let body = body.0;
+    let usecase = CreateBookingUseCase {
+        account,
+        service_id: std::mem::take(&mut path.service_id),
+        host_user_ids: body.host_user_ids,
+        start_ts: body.start_ts,
+        duration: body.duration,
+        interval: body.interval,
+        invitee: body.invitee,
+        metadata: body.metadata.unwrap_or_default(),
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|booking| HttpResponse::Created().json(APIResponse::new(booking)))
+        .map_err(NettuError::from)
+}
+
+#[derive(Debug)]
+struct CreateBookingUseCase {
+    pub account: Account,
+    pub service_id: ID,
+    pub host_user_ids: Option<Vec<ID>>,
+    pub start_ts: i64,
+    pub duration: Option<i64>,
+    pub interval: Option<i64>,
+    pub invitee: BookingInvitee,
+    pub metadata: Metadata,
+}
+
+#[derive(Debug)]
+enum UseCaseError {
+    ServiceNotFound,
+    InvalidDuration,
+    InvalidIntakeAnswers(IntakeAnswerError),
+    BookingIntend(create_service_event_intend::UseCaseError),
+    Conflict,
+    StorageError,
+}
+
+impl From<UseCaseError> for NettuError {
+    fn from(e: UseCaseError) -> Self {
+        match e {
+            UseCaseError::ServiceNotFound => {
+                Self::NotFound("The requested service was not found".into())
+            }
+            UseCaseError::InvalidDuration => Self::BadClientData(
+                "Invalid duration specified. It should be one of the durations allowed by the service.".into(),
+            ),
+            UseCaseError::InvalidIntakeAnswers(e) => Self::BadClientData(e.to_string()),
+            UseCaseError::BookingIntend(e) => e.into(),
+            UseCaseError::Conflict => Self::Conflict(
+                "The selected time was booked by someone else. Please choose another time.".into(),
+            ),
+            UseCaseError::StorageError => Self::InternalError,
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for CreateBookingUseCase {
+    type Response = Booking;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "CreateBooking";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        let service = match ctx.repos.services.find_with_users(&self.service_id).await {
+            Some(service) if service.account_id == self.account.id => service,
+            _ => return Err(UseCaseError::ServiceNotFound),
+        };
+        let duration = service
+            .booking_options
+            .duration(self.duration)
+            .ok_or(UseCaseError::InvalidDuration)?;
+        validate_intake_answers(
+            &service.booking_options.intake_questions,
+            &self.invitee.answers,
+        )
+        .map_err(UseCaseError::InvalidIntakeAnswers)?;
+
+        let intend = CreateServiceEventIntendUseCase {
+            account: self.account.clone(),
+            service_id: service.id.clone(),
+            host_user_ids: self.host_user_ids.clone(),
+            timestamp: self.start_ts,
+            duration: Some(duration),
+            interval: self.interval,
+            reserve_seat: false,
+            ignored_events: Vec::new(),
+            customer_key: self.invitee.customer_key.clone(),
+            attributes: self.invitee.attributes.clone(),
+            intake_answers: None,
+            slot_offset: 0,
+            resource_id: None,
+        };
+        let intend = execute(intend, ctx)
+            .await
+            .map_err(UseCaseError::BookingIntend)?;
+
+        let now = ctx.sys.get_timestamp_millis();
+        let approval_deadline = service.booking_options.approval_deadline(now);
+        let seats = match service.multi_person {
+            ServiceMultiPersonOptions::Group(max_count) => Some(max_count),
+            _ => None,
+        };
+        // The events are always prepared, but for a group `Service` they are
+        // only created if the `Booking` turns out to take the last seat
+        let events = if intend.create_event_for_hosts || seats.is_some() {
+            prepare_host_events(
+                &service,
+                &intend.booked_users(),
+                self.start_ts,
+                duration,
+                now,
+                approval_deadline.is_some(),
+                ctx,
+            )
+            .await
+        } else {
+            Vec::new()
+        };
+
+        let booking = Booking {
+            id: Default::default(),
+            account_id: self.account.id.clone(),
+            service_id: service.id.clone(),
+            host_user_ids: intend
+                .selected_hosts
+                .iter()
+                .map(|host| host.id.clone())
+                .collect(),
+            resource_id: intend.selected_resource.map(|resource| resource.id),
+            event_ids: Vec::new(),
+            invitee: self.invitee.clone(),
+            start_ts: self.start_ts,
+            duration,
+            status: booking_status(approval_deadline),
+            approval_deadline,
+            reschedules: Vec::new(),
+            created: now,
+            updated: now,
+            metadata: self.metadata.clone(),
+        };
+        let commit = BookingCommit {
+            booking,
+            events,
+            seats,
+            hold_id: None,
+        };
+        commit_booking(commit, ctx).await.map_err(|e| match e {
+            BookingCommitError::Conflict => UseCaseError::Conflict,
+            BookingCommitError::StorageError => UseCaseError::StorageError,
+        })
+    }
+}
+
+/// The initial status of a `Booking`, which is pending if it has to be
+/// approved by a host
+pub(super) fn booking_status(approval_deadline: Option<i64>) -> BookingStatus {
+    match approval_deadline {
+        Some(_) => BookingStatus::Pending,
+        None => BookingStatus::Confirmed,
+    }
+}
+
+/// Prepares the busy `CalendarEvent`s of the hosts and the resource of a `Booking`
+pub(super) async fn prepare_host_events(
+    service: &ServiceWithUsers,
+    hosts: &[User],
+    start_ts: i64,
+    duration: i64,
+    timestamp: i64,
+    tentative: bool,
+    ctx: &NettuContext,
+) -> Vec<CalendarEvent> {
+    let mut events = Vec::with_capacity(hosts.len());
+    for host in hosts {
+        let calendar_id = match find_booking_calendar(service, &host.id, ctx).await {
+            Some(calendar_id) => calendar_id,
+            None => continue,
+        };
+        events.push(CalendarEvent {
+            id: Default::default(),
+            busy: true,
+            tentative,
+            start_ts,
+            duration,
+            end_ts: start_ts + duration,
+            created: timestamp,
+            updated: timestamp,
+            recurrence: None,
+            exdates: Vec::new(),
+            calendar_id,
+            user_id: host.id.clone(),
+            account_id: host.account_id.clone(),
+            reminders: Vec::new(),
+            service_id: Some(service.id.clone()),
+            metadata: Default::default(),
+        });
+    }
+    events
+}
+
+/// Stores the `Booking` together with its `CalendarEvent`s and reservation
+pub(super) async fn commit_booking(
+    commit: BookingCommit,
+    ctx: &NettuContext,
+) -> Result<Booking, BookingCommitError> {
+    let account_id = commit.booking.account_id.clone();
+    let (booking, events) = ctx.repos.bookings.commit(commit).await?;
+    notify_booking_committed(&account_id, &booking, &events, ctx).await;
+
+    Ok(booking)
+}
+
+/// Triggers the side effects of a stored `Booking` and its `CalendarEvent`s
+pub(super) async fn notify_booking_committed(
+    account_id: &ID,
+    booking: &Booking,
+    events: &[CalendarEvent],
+    ctx: &NettuContext,
+) {
+    // The events were stored as part of the booking, so the side effects
+    // of creating them are triggered afterwards
+    for event in events {
+        for subscriber in CreateEventUseCase::subscribers() {
+            subscriber.notify(event, ctx).await;
+        }
+    }
+
+    if booking.status == BookingStatus::Pending {
+        if let Some(account) = ctx.repos.accounts.find(account_id).await {
+            request_booking_approval(&account, booking).await;
+        }
+    }
+}
+
+/// Lets the `Account` know through its webhook that a `Booking` is waiting
+/// for a host to approve it
+async fn request_booking_approval(account: &Account, booking: &Booking) {
+    let webhook = match &account.settings.webhook {
+        Some(webhook) => webhook,
+        None => return,
+    };
+    if let Err(e) = Client::new()
+        .post(&webhook.url)
+        .insert_header(("nettu-scheduler-webhook-key", webhook.key.clone()))
+        .send_json(&BookingApprovalRequestDTO::new(booking.clone()))
+        .await
+    {
+        error!(
+            "Error informing client of booking approval request: {:?}",
+            e
+        );
+    }
+}
+
+/// Finds the `Calendar` of a host or a resource where the `CalendarEvent` for
+/// a `Booking` should be created. This is the first busy `Calendar` of the host
+/// on the `Service`, otherwise the `Calendar` used as availability and otherwise
+/// any `Calendar` of the host.
+async fn find_booking_calendar(
+    service: &ServiceWithUsers,
+    host_user_id: &ID,
+    ctx: &NettuContext,
+) -> Option<ID> {
+    let busy_calendar = ctx
+        .repos
+        .service_user_busy_calendars
+        .find(&service.id, host_user_id)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .find_map(|calendar| match calendar {
+            BusyCalendar::Nettu(id) => Some(id),
+            _ => None,
+        });
+    if busy_calendar.is_some() {
+        return busy_calendar;
+    }
+
+    let availability = service
+        .users
+        .iter()
+        .chain(&service.resources)
+        .find(|resource| resource.user_id == *host_user_id)
+        .map(|resource| &resource.availability);
+    if let Some(TimePlan::Calendar(calendar_id)) = availability {
+        return Some(calendar_id.clone());
+    }
+
+    ctx.repos
+        .calendars
+        .find_by_user(host_user_id)
+        .await
+        .into_iter()
+        .next()
+        .map(|calendar| calendar.id)
+}
@@ -0,0 +1,197 @@
+use super::create_booking::{booking_status, notify_booking_committed, prepare_host_events};
+use crate::error::NettuError;
+use crate::service::create_service_event_intend::{self, CreateServiceEventIntendUseCase};
+use crate::service::get_service_chain_bookingslots::{self, ServiceChain};
+use crate::shared::{
+    auth::protect_account_route,
+    usecase::{execute, UseCase},
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use nettu_scheduler_api_structs::create_booking_chain::*;
+use nettu_scheduler_domain::{
+    booking_slots::ServiceChainStep, validate_intake_answers, Account, Booking, BookingInvitee,
+    IntakeAnswerError, Metadata, ServiceMultiPersonOptions,
+};
+use nettu_scheduler_infra::{BookingCommit, BookingCommitError, NettuContext};
+
+pub async fn create_booking_chain_controller(
+    http_req: HttpRequest,
+    body: web::Json<RequestBody>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let account = protect_account_route(&http_req, &ctx).await?;
+
+    let body = body.0;
+    let usecase = CreateBookingChainUseCase {
+        account,
+        steps: body.steps,
+        start_ts: body.start_ts,
+        interval: body.interval,
+        invitee: body.invitee,
+        metadata: body.metadata.unwrap_or_default(),
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|bookings| HttpResponse::Created().json(APIResponse::new(bookings)))
+        .map_err(NettuError::from)
+}
+
+#[derive(Debug)]
+struct CreateBookingChainUseCase {
+    pub account: Account,
+    pub steps: Vec<ServiceChainStep>,
+    pub start_ts: i64,
+    pub interval: Option<i64>,
+    pub invitee: BookingInvitee,
+    pub metadata: Metadata,
+}
+
+#[derive(Debug)]
+enum UseCaseError {
+    Chain(get_service_chain_bookingslots::UseCaseError),
+    InvalidIntakeAnswers(IntakeAnswerError),
+    BookingIntend(create_service_event_intend::UseCaseError),
+    Conflict,
+    StorageError,
+}
+
+impl From<UseCaseError> for NettuError {
+    fn from(e: UseCaseError) -> Self {
+        match e {
+            UseCaseError::Chain(e) => e.into(),
+            UseCaseError::InvalidIntakeAnswers(e) => Self::BadClientData(e.to_string()),
+            UseCaseError::BookingIntend(e) => e.into(),
+            UseCaseError::Conflict => Self::Conflict(
+                "The selected time was booked by someone else. Please choose another time.".into(),
+            ),
+            UseCaseError::StorageError => Self::InternalError,
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for CreateBookingChainUseCase {
+    /// The `Booking` of every step, in order
+    type Response = Vec<Booking>;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "CreateBookingChain";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        let chain = ServiceChain::resolve(&self.steps, self.interval, ctx)
+            .await
+            .map_err(UseCaseError::Chain)?;
+        if let Some(service) = chain
+            .services
+            .iter()
+            .find(|service| service.account_id != self.account.id)
+        {
+            return Err(UseCaseError::Chain(
+                get_service_chain_bookingslots::UseCaseError::ServiceNotFound(service.id.clone()),
+            ));
+        }
+        // The invitee answers the intake questions of every step at once
+        let intake_questions = chain
+            .services
+            .iter()
+            .flat_map(|service| service.booking_options.intake_questions.clone())
+            .collect::<Vec<_>>();
+        validate_intake_answers(&intake_questions, &self.invitee.answers)
+            .map_err(UseCaseError::InvalidIntakeAnswers)?;
+
+        let now = ctx.sys.get_timestamp_millis();
+        let mut commits = Vec::with_capacity(self.steps.len());
+        for (i, step) in self.steps.iter().enumerate() {
+            let service = &chain.services[i];
+            let duration = chain.durations[i];
+            let start_ts = self.start_ts + chain.offsets[i];
+
+            let intend = CreateServiceEventIntendUseCase {
+                account: self.account.clone(),
+                service_id: service.id.clone(),
+                host_user_ids: step.host_user_ids.clone(),
+                timestamp: start_ts,
+                duration: Some(duration),
+                interval: Some(chain.interval),
+                reserve_seat: false,
+                ignored_events: Vec::new(),
+                customer_key: self.invitee.customer_key.clone(),
+                attributes: self.invitee.attributes.clone(),
+                intake_answers: None,
+                slot_offset: chain.offsets[i],
+                resource_id: None,
+            };
+            let intend = execute(intend, ctx)
+                .await
+                .map_err(UseCaseError::BookingIntend)?;
+
+            let approval_deadline = service.booking_options.approval_deadline(now);
+            let seats = match service.multi_person {
+                ServiceMultiPersonOptions::Group(max_count) => Some(max_count),
+                _ => None,
+            };
+            let events = if intend.create_event_for_hosts || seats.is_some() {
+                prepare_host_events(
+                    service,
+                    &intend.booked_users(),
+                    start_ts,
+                    duration,
+                    now,
+                    approval_deadline.is_some(),
+                    ctx,
+                )
+                .await
+            } else {
+                Vec::new()
+            };
+
+            let booking = Booking {
+                id: Default::default(),
+                account_id: self.account.id.clone(),
+                service_id: service.id.clone(),
+                host_user_ids: intend
+                    .selected_hosts
+                    .iter()
+                    .map(|host| host.id.clone())
+                    .collect(),
+                resource_id: intend.selected_resource.map(|resource| resource.id),
+                event_ids: Vec::new(),
+                invitee: self.invitee.clone(),
+                start_ts,
+                duration,
+                status: booking_status(approval_deadline),
+                approval_deadline,
+                reschedules: Vec::new(),
+                created: now,
+                updated: now,
+                metadata: self.metadata.clone(),
+            };
+            commits.push(BookingCommit {
+                booking,
+                events,
+                seats,
+                hold_id: None,
+            });
+        }
+
+        // Either every step of the chain is booked or none of them
+        let committed = ctx
+            .repos
+            .bookings
+            .commit_chain(commits)
+            .await
+            .map_err(|e| match e {
+                BookingCommitError::Conflict => UseCaseError::Conflict,
+                BookingCommitError::StorageError => UseCaseError::StorageError,
+            })?;
+
+        let mut bookings = Vec::with_capacity(committed.len());
+        for (booking, events) in committed {
+            notify_booking_committed(&self.account.id, &booking, &events, ctx).await;
+            bookings.push(booking);
+        }
+        Ok(bookings)
+    }
+}
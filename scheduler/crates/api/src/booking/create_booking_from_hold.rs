@@ -0,0 +1,158 @@
+use super::create_booking::{booking_status, commit_booking, prepare_host_events};
+use crate::error::NettuError;
+use crate::shared::{
+    auth::protect_account_route,
+    usecase::{execute, UseCase},
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use nettu_scheduler_api_structs::create_booking_from_hold::*;
+use nettu_scheduler_domain::{
+    validate_intake_answers, Account, Booking, BookingInvitee, IntakeAnswerError, Metadata,
+    ServiceMultiPersonOptions, ID,
+};
+use nettu_scheduler_infra::{BookingCommit, BookingCommitError, NettuContext};
+
+pub async fn create_booking_from_hold_controller(
+    http_req: HttpRequest,
+    body: web::Json<RequestBody>,
+    path: web::Path<PathParams>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let account = protect_account_route(&http_req, &ctx).await?;
+
+    let body = body.0;
+    let usecase = CreateBookingFromHoldUseCase {
+        account,
+        hold_id: path.hold_id.clone(),
+        invitee: body.invitee,
+        metadata: body.metadata.unwrap_or_default(),
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|booking| HttpResponse::Created().json(APIResponse::new(booking)))
+        .map_err(NettuError::from)
+}
+
+#[derive(Debug)]
+struct CreateBookingFromHoldUseCase {
+    pub account: Account,
+    pub hold_id: ID,
+    pub invitee: BookingInvitee,
+    pub metadata: Metadata,
+}
+
+#[derive(Debug)]
+enum UseCaseError {
+    NotFound(ID),
+    HoldExpired,
+    InvalidIntakeAnswers(IntakeAnswerError),
+    StorageError,
+}
+
+impl From<UseCaseError> for NettuError {
+    fn from(e: UseCaseError) -> Self {
+        match e {
+            UseCaseError::NotFound(id) => {
+                Self::NotFound(format!("The booking hold with id: {} was not found.", id))
+            }
+            UseCaseError::HoldExpired => {
+                Self::Conflict("The booking hold has expired. Please choose another time.".into())
+            }
+            UseCaseError::InvalidIntakeAnswers(e) => Self::BadClientData(e.to_string()),
+            UseCaseError::StorageError => Self::InternalError,
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for CreateBookingFromHoldUseCase {
+    type Response = Booking;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "CreateBookingFromHold";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        let hold = match ctx.repos.booking_holds.find(&self.hold_id).await {
+            Some(hold) if hold.account_id == self.account.id => hold,
+            _ => return Err(UseCaseError::NotFound(self.hold_id.clone())),
+        };
+        let now = ctx.sys.get_timestamp_millis();
+        if hold.is_expired(now) {
+            return Err(UseCaseError::HoldExpired);
+        }
+        let service = match ctx.repos.services.find_with_users(&hold.service_id).await {
+            Some(service) => service,
+            None => return Err(UseCaseError::NotFound(self.hold_id.clone())),
+        };
+        validate_intake_answers(
+            &service.booking_options.intake_questions,
+            &self.invitee.answers,
+        )
+        .map_err(UseCaseError::InvalidIntakeAnswers)?;
+
+        let seats = match service.multi_person {
+            ServiceMultiPersonOptions::Group(max_count) => Some(max_count),
+            _ => None,
+        };
+        let approval_deadline = service.booking_options.approval_deadline(now);
+        let booked_user_ids = hold
+            .host_user_ids
+            .iter()
+            .chain(&hold.resource_id)
+            .cloned()
+            .collect::<Vec<_>>();
+        let hosts = ctx.repos.users.find_many(&booked_user_ids).await;
+        let events = prepare_host_events(
+            &service,
+            &hosts,
+            hold.start_ts,
+            hold.duration,
+            now,
+            approval_deadline.is_some(),
+            ctx,
+        )
+        .await;
+
+        let booking = Booking {
+            id: Default::default(),
+            account_id: self.account.id.clone(),
+            service_id: service.id.clone(),
+            host_user_ids: hold.host_user_ids.clone(),
+            resource_id: hold.resource_id.clone(),
+            event_ids: Vec::new(),
+            invitee: self.invitee.clone(),
+            start_ts: hold.start_ts,
+            duration: hold.duration,
+            status: booking_status(approval_deadline),
+            approval_deadline,
+            reschedules: Vec::new(),
+            created: now,
+            updated: now,
+            metadata: self.metadata.clone(),
+        };
+        let commit = BookingCommit {
+            booking,
+            events,
+            seats,
+            hold_id: Some(hold.id.clone()),
+        };
+        let booking = commit_booking(commit, ctx).await.map_err(|e| match e {
+            // The hold is what guarantees the slot, so it must have expired
+            BookingCommitError::Conflict => UseCaseError::HoldExpired,
+            BookingCommitError::StorageError => UseCaseError::StorageError,
+        })?;
+
+        // The seat offered from the waitlist has been booked
+        if let Some(entry) = ctx.repos.waitlist.find_by_hold(&hold.id).await {
+            ctx.repos
+                .waitlist
+                .delete(&entry.id)
+                .await
+                .map_err(|_| UseCaseError::StorageError)?;
+        }
+
+        Ok(booking)
+    }
+}
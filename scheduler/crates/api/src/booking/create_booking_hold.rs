@@ -0,0 +1,159 @@
+use crate::error::NettuError;
+use crate::service::create_service_event_intend::{self, CreateServiceEventIntendUseCase};
+use crate::shared::{
+    auth::protect_account_route,
+    usecase::{execute, UseCase},
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use nettu_scheduler_api_structs::create_booking_hold::*;
+use nettu_scheduler_domain::{Account, BookingHold, ServiceMultiPersonOptions, ID};
+use nettu_scheduler_infra::{BookingCommitError, NettuContext};
+use std::collections::HashMap;
+
+pub async fn create_booking_hold_controller(
+    http_req: HttpRequest,
+    body: web::Json<RequestBody>,
+    mut path: web::Path<PathParams>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let account = protect_account_route(&http_req, &ctx).await?;
+
+    let body = body.0;
+    let usecase = CreateBookingHoldUseCase {
+        account,
+        service_id: std::mem::take(&mut path.service_id),
+        host_user_ids: body.host_user_ids,
+        start_ts: body.start_ts,
+        duration: body.duration,
+        interval: body.interval,
+        ttl: body.ttl.unwrap_or(BookingHold::DEFAULT_TTL),
+        attributes: body.attributes,
+        customer_key: body.customer_key,
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|hold| HttpResponse::Created().json(APIResponse::new(hold)))
+        .map_err(NettuError::from)
+}
+
+#[derive(Debug)]
+struct CreateBookingHoldUseCase {
+    pub account: Account,
+    pub service_id: ID,
+    pub host_user_ids: Option<Vec<ID>>,
+    pub start_ts: i64,
+    pub duration: Option<i64>,
+    pub interval: Option<i64>,
+    pub ttl: i64,
+    /// Attributes of the invitee matched by the host routing rules of the `Service`
+    pub attributes: HashMap<String, String>,
+    /// External key of a returning invitee
+    pub customer_key: Option<String>,
+}
+
+#[derive(Debug)]
+enum UseCaseError {
+    ServiceNotFound,
+    InvalidDuration,
+    InvalidTTL,
+    BookingIntend(create_service_event_intend::UseCaseError),
+    Conflict,
+    StorageError,
+}
+
+impl From<UseCaseError> for NettuError {
+    fn from(e: UseCaseError) -> Self {
+        match e {
+            UseCaseError::ServiceNotFound => {
+                Self::NotFound("The requested service was not found".into())
+            }
+            UseCaseError::InvalidDuration => Self::BadClientData(
+                "Invalid duration specified. It should be one of the durations allowed by the service.".into(),
+            ),
+            UseCaseError::InvalidTTL => Self::BadClientData(format!(
+                "Invalid ttl specified. It should be a positive number of millis up to {}.",
+                BookingHold::MAX_TTL
+            )),
+            UseCaseError::BookingIntend(e) => e.into(),
+            UseCaseError::Conflict => Self::Conflict(
+                "The selected time was booked by someone else. Please choose another time.".into(),
+            ),
+            UseCaseError::StorageError => Self::InternalError,
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for CreateBookingHoldUseCase {
+    type Response = BookingHold;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "CreateBookingHold";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        let service = match ctx.repos.services.find(&self.service_id).await {
+            Some(service) if service.account_id == self.account.id => service,
+            _ => return Err(UseCaseError::ServiceNotFound),
+        };
+        let duration = service
+            .booking_options
+            .duration(self.duration)
+            .ok_or(UseCaseError::InvalidDuration)?;
+        if !BookingHold::is_valid_ttl(self.ttl) {
+            return Err(UseCaseError::InvalidTTL);
+        }
+
+        let intend = CreateServiceEventIntendUseCase {
+            account: self.account.clone(),
+            service_id: service.id.clone(),
+            host_user_ids: self.host_user_ids.clone(),
+            timestamp: self.start_ts,
+            duration: Some(duration),
+            interval: self.interval,
+            reserve_seat: false,
+            ignored_events: Vec::new(),
+            customer_key: self.customer_key.clone(),
+            attributes: self.attributes.clone(),
+            intake_answers: None,
+            slot_offset: 0,
+            resource_id: None,
+        };
+        let intend = execute(intend, ctx)
+            .await
+            .map_err(UseCaseError::BookingIntend)?;
+
+        let seats = match service.multi_person {
+            ServiceMultiPersonOptions::Group(max_count) => Some(max_count),
+            _ => None,
+        };
+        let now = ctx.sys.get_timestamp_millis();
+        let hold = BookingHold {
+            id: Default::default(),
+            account_id: self.account.id.clone(),
+            service_id: service.id.clone(),
+            host_user_ids: intend
+                .selected_hosts
+                .iter()
+                .map(|host| host.id.clone())
+                .collect(),
+            resource_id: intend.selected_resource.map(|resource| resource.id),
+            start_ts: self.start_ts,
+            duration,
+            seat: seats.is_some(),
+            created: now,
+            expires_at: now + self.ttl,
+        };
+        ctx.repos
+            .booking_holds
+            .commit(&hold, seats)
+            .await
+            .map_err(|e| match e {
+                BookingCommitError::Conflict => UseCaseError::Conflict,
+                BookingCommitError::StorageError => UseCaseError::StorageError,
+            })?;
+
+        Ok(hold)
+    }
+}
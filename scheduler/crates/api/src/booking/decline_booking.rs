@@ -0,0 +1,91 @@
+use super::cancel_booking::release_booking_slot;
+use crate::error::NettuError;
+use crate::shared::{
+    auth::protect_account_route,
+    usecase::{execute, UseCase},
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use nettu_scheduler_api_structs::decline_booking::*;
+use nettu_scheduler_domain::{Booking, BookingStatus, ID};
+use nettu_scheduler_infra::NettuContext;
+
+pub async fn decline_booking_controller(
+    http_req: HttpRequest,
+    path_params: web::Path<PathParams>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let account = protect_account_route(&http_req, &ctx).await?;
+
+    let usecase = DeclineBookingUseCase {
+        account_id: account.id,
+        booking_id: path_params.booking_id.clone(),
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|booking| HttpResponse::Ok().json(APIResponse::new(booking)))
+        .map_err(NettuError::from)
+}
+
+/// Declines a pending `Booking` and releases its slot. Used both by a host
+/// and for `Booking`s which were not approved before their deadline. Only
+/// one of a concurrent approve and decline of the same `Booking` succeeds.
+#[derive(Debug)]
+pub struct DeclineBookingUseCase {
+    pub account_id: ID,
+    pub booking_id: ID,
+}
+
+#[derive(Debug)]
+pub enum UseCaseError {
+    NotFound(ID),
+    NotPending,
+    StorageError,
+}
+
+impl From<UseCaseError> for NettuError {
+    fn from(e: UseCaseError) -> Self {
+        match e {
+            UseCaseError::NotFound(id) => {
+                Self::NotFound(format!("The booking with id: {} was not found.", id))
+            }
+            UseCaseError::NotPending => {
+                Self::Conflict("The booking is not waiting for approval".into())
+            }
+            UseCaseError::StorageError => Self::InternalError,
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for DeclineBookingUseCase {
+    type Response = Booking;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "DeclineBooking";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        let mut booking = match ctx.repos.bookings.find(&self.booking_id).await {
+            Some(booking) if booking.account_id == self.account_id => booking,
+            _ => return Err(UseCaseError::NotFound(self.booking_id.clone())),
+        };
+        let service = match ctx.repos.services.find(&booking.service_id).await {
+            Some(service) => service,
+            None => return Err(UseCaseError::NotFound(self.booking_id.clone())),
+        };
+
+        if !booking.decline(ctx.sys.get_timestamp_millis()) {
+            return Err(UseCaseError::NotPending);
+        }
+
+        let released = release_booking_slot(&booking, &[BookingStatus::Pending], &service, ctx)
+            .await
+            .map_err(|_| UseCaseError::StorageError)?;
+        if !released {
+            return Err(UseCaseError::NotPending);
+        }
+
+        Ok(booking)
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{
+    error::NettuError,
+    shared::{
+        auth::protect_account_route,
+        usecase::{execute, UseCase},
+    },
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use nettu_scheduler_api_structs::get_booking::*;
+use nettu_scheduler_domain::{Account, Booking, ID};
+use nettu_scheduler_infra::NettuContext;
+
+pub async fn get_booking_controller(
+    http_req: HttpRequest,
+    path_params: web::Path<PathParams>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let account = protect_account_route(&http_req, &ctx).await?;
+
+    let usecase = GetBookingUseCase {
+        account,
+        booking_id: path_params.booking_id.clone(),
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|booking| HttpResponse::Ok().json(APIResponse::new(booking)))
+        .map_err(NettuError::from)
+}
+
+#[derive(Debug)]
+struct GetBookingUseCase {
+    account: Account,
+    booking_id: ID,
+}
+
+#[derive(Debug)]
+enum UseCaseError {
+    NotFound(ID),
+}
+
+impl From<UseCaseError> for NettuError {
+    fn from(e: UseCaseError) -> Self {
+        match e {
+            UseCaseError::NotFound(id) => {
+                Self::NotFound(format!("The booking with id: {} was not found.", id))
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for GetBookingUseCase {
+    type Response = Booking;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "GetBooking";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        match ctx.repos.bookings.find(&self.booking_id).await {
+            Some(booking) if booking.account_id == self.account.id => Ok(booking),
+            _ => Err(UseCaseError::NotFound(self.booking_id.clone())),
+        }
+    }
+}
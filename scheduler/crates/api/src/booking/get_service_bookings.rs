@@ -0,0 +1,101 @@
+use crate::{
+    error::NettuError,
+    shared::{
+        auth::protect_account_route,
+        usecase::{execute, UseCase},
+    },
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use nettu_scheduler_api_structs::get_service_bookings::*;
+use nettu_scheduler_domain::{Account, Booking, BookingStatus, TimeSpan, ID};
+use nettu_scheduler_infra::NettuContext;
+
+pub async fn get_service_bookings_controller(
+    http_req: HttpRequest,
+    query_params: web::Query<QueryParams>,
+    path_params: web::Path<PathParams>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let account = protect_account_route(&http_req, &ctx).await?;
+
+    let usecase = GetServiceBookingsUseCase {
+        account,
+        service_id: path_params.service_id.clone(),
+        start_ts: query_params.start_ts,
+        end_ts: query_params.end_ts,
+        status: query_params.status,
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|bookings| HttpResponse::Ok().json(APIResponse::new(bookings)))
+        .map_err(NettuError::from)
+}
+
+#[derive(Debug)]
+struct GetServiceBookingsUseCase {
+    account: Account,
+    service_id: ID,
+    start_ts: i64,
+    end_ts: i64,
+    status: Option<BookingStatus>,
+}
+
+#[derive(Debug)]
+enum UseCaseError {
+    NotFound(ID),
+    InvalidTimespan,
+    StorageError,
+}
+
+impl From<UseCaseError> for NettuError {
+    fn from(e: UseCaseError) -> Self {
+        match e {
+            UseCaseError::NotFound(id) => {
+                Self::NotFound(format!("The service with id: {} was not found.", id))
+            }
+            UseCaseError::InvalidTimespan => {
+                Self::BadClientData("The provided start_ts and end_ts is invalid".into())
+            }
+            UseCaseError::StorageError => Self::InternalError,
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for GetServiceBookingsUseCase {
+    type Response = Vec<Booking>;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "GetServiceBookings";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        match ctx.repos.services.find(&self.service_id).await {
+            Some(service) if service.account_id == self.account.id => (),
+            _ => return Err(UseCaseError::NotFound(self.service_id.clone())),
+        };
+
+        let timespan = TimeSpan::new(self.start_ts, self.end_ts);
+        if self.start_ts >= self.end_ts
+            || timespan.greater_than(ctx.config.booking_slots_query_duration_limit)
+        {
+            return Err(UseCaseError::InvalidTimespan);
+        }
+
+        let bookings = ctx
+            .repos
+            .bookings
+            .find_by_service(&self.service_id, self.start_ts, self.end_ts)
+            .await
+            .map_err(|_| UseCaseError::StorageError)?;
+
+        Ok(match self.status {
+            Some(status) => bookings
+                .into_iter()
+                .filter(|booking| booking.status == status)
+                .collect(),
+            None => bookings,
+        })
+    }
+}
@@ -0,0 +1,73 @@
+use crate::error::NettuError;
+use crate::shared::{
+    auth::protect_account_route,
+    usecase::{execute, UseCase},
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use nettu_scheduler_api_structs::get_waitlist::*;
+use nettu_scheduler_domain::{Account, WaitlistEntry, ID};
+use nettu_scheduler_infra::NettuContext;
+
+pub async fn get_waitlist_controller(
+    http_req: HttpRequest,
+    query_params: web::Query<QueryParams>,
+    mut path: web::Path<PathParams>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let account = protect_account_route(&http_req, &ctx).await?;
+
+    let usecase = GetWaitlistUseCase {
+        account,
+        service_id: std::mem::take(&mut path.service_id),
+        start_ts: query_params.start_ts,
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|entries| HttpResponse::Ok().json(APIResponse::new(entries)))
+        .map_err(NettuError::from)
+}
+
+#[derive(Debug)]
+struct GetWaitlistUseCase {
+    pub account: Account,
+    pub service_id: ID,
+    pub start_ts: i64,
+}
+
+#[derive(Debug)]
+enum UseCaseError {
+    ServiceNotFound,
+}
+
+impl From<UseCaseError> for NettuError {
+    fn from(e: UseCaseError) -> Self {
+        match e {
+            UseCaseError::ServiceNotFound => {
+                Self::NotFound("The requested service was not found".into())
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for GetWaitlistUseCase {
+    type Response = Vec<WaitlistEntry>;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "GetWaitlist";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        match ctx.repos.services.find(&self.service_id).await {
+            Some(service) if service.account_id == self.account.id => (),
+            _ => return Err(UseCaseError::ServiceNotFound),
+        };
+
+        Ok(ctx
+            .repos
+            .waitlist
+            .find_by_slot(&self.service_id, self.start_ts)
+            .await)
+    }
+}
@@ -0,0 +1,145 @@
+use crate::error::NettuError;
+use crate::shared::{
+    auth::protect_account_route,
+    usecase::{execute, UseCase},
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use nettu_scheduler_api_structs::join_waitlist::*;
+use nettu_scheduler_domain::{
+    Account, BookingInvitee, Metadata, ServiceMultiPersonOptions, WaitlistEntry, ID,
+};
+use nettu_scheduler_infra::NettuContext;
+
+pub async fn join_waitlist_controller(
+    http_req: HttpRequest,
+    body: web::Json<RequestBody>,
+    mut path: web::Path<PathParams>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let account = protect_account_route(&http_req, &ctx).await?;
+
+    let body = body.0;
+    let usecase = JoinWaitlistUseCase {
+        account,
+        service_id: std::mem::take(&mut path.service_id),
+        start_ts: body.start_ts,
+        duration: body.duration,
+        invitee: body.invitee,
+        metadata: body.metadata.unwrap_or_default(),
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|entry| HttpResponse::Created().json(APIResponse::new(entry)))
+        .map_err(NettuError::from)
+}
+
+#[derive(Debug)]
+struct JoinWaitlistUseCase {
+    pub account: Account,
+    pub service_id: ID,
+    pub start_ts: i64,
+    pub duration: Option<i64>,
+    pub invitee: BookingInvitee,
+    pub metadata: Metadata,
+}
+
+#[derive(Debug)]
+enum UseCaseError {
+    ServiceNotFound,
+    NotGroupService,
+    InvalidDuration,
+    InvalidTimestamp,
+    SlotNotFull,
+    StorageError,
+}
+
+impl From<UseCaseError> for NettuError {
+    fn from(e: UseCaseError) -> Self {
+        match e {
+            UseCaseError::ServiceNotFound => {
+                Self::NotFound("The requested service was not found".into())
+            }
+            UseCaseError::NotGroupService => Self::BadClientData(
+                "Only services with group bookings have a waitlist.".into(),
+            ),
+            UseCaseError::InvalidDuration => Self::BadClientData(
+                "Invalid duration specified. It should be one of the durations allowed by the service.".into(),
+            ),
+            UseCaseError::InvalidTimestamp => {
+                Self::BadClientData("The booking slot has already started.".into())
+            }
+            UseCaseError::SlotNotFull => Self::Conflict(
+                "The booking slot still has free seats. Please book it instead.".into(),
+            ),
+            UseCaseError::StorageError => Self::InternalError,
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for JoinWaitlistUseCase {
+    type Response = WaitlistEntry;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "JoinWaitlist";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        let service = match ctx.repos.services.find(&self.service_id).await {
+            Some(service) if service.account_id == self.account.id => service,
+            _ => return Err(UseCaseError::ServiceNotFound),
+        };
+        let max_count = match service.multi_person {
+            ServiceMultiPersonOptions::Group(max_count) => max_count,
+            _ => return Err(UseCaseError::NotGroupService),
+        };
+        let duration = service
+            .booking_options
+            .duration(self.duration)
+            .ok_or(UseCaseError::InvalidDuration)?;
+        let now = ctx.sys.get_timestamp_millis();
+        if self.start_ts <= now {
+            return Err(UseCaseError::InvalidTimestamp);
+        }
+
+        let reservations = ctx
+            .repos
+            .reservations
+            .count(&service.id, self.start_ts)
+            .await
+            .map_err(|_| UseCaseError::StorageError)?;
+        let held_seats: usize = ctx
+            .repos
+            .booking_holds
+            .find_seats_by_timespan(&service.id, self.start_ts, self.start_ts + 1, now)
+            .await
+            .map_err(|_| UseCaseError::StorageError)?
+            .iter()
+            .map(|seats| seats.count)
+            .sum();
+        if reservations + held_seats < max_count {
+            return Err(UseCaseError::SlotNotFull);
+        }
+
+        let entry = WaitlistEntry {
+            id: Default::default(),
+            account_id: self.account.id.clone(),
+            service_id: service.id.clone(),
+            start_ts: self.start_ts,
+            duration,
+            invitee: self.invitee.clone(),
+            created: now,
+            hold_id: None,
+            offer_expires_at: None,
+            metadata: self.metadata.clone(),
+        };
+        ctx.repos
+            .waitlist
+            .insert(&entry)
+            .await
+            .map_err(|_| UseCaseError::StorageError)?;
+
+        Ok(entry)
+    }
+}
@@ -0,0 +1,91 @@
+use super::offer_waitlist_seat::OfferWaitlistSeatUseCase;
+use crate::error::NettuError;
+use crate::shared::{
+    auth::protect_account_route,
+    usecase::{execute, UseCase},
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use nettu_scheduler_api_structs::leave_waitlist::*;
+use nettu_scheduler_domain::{Account, WaitlistEntry, ID};
+use nettu_scheduler_infra::NettuContext;
+
+pub async fn leave_waitlist_controller(
+    http_req: HttpRequest,
+    path_params: web::Path<PathParams>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let account = protect_account_route(&http_req, &ctx).await?;
+
+    let usecase = LeaveWaitlistUseCase {
+        account,
+        entry_id: path_params.entry_id.clone(),
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|entry| HttpResponse::Ok().json(APIResponse::new(entry)))
+        .map_err(NettuError::from)
+}
+
+#[derive(Debug)]
+struct LeaveWaitlistUseCase {
+    account: Account,
+    entry_id: ID,
+}
+
+#[derive(Debug)]
+enum UseCaseError {
+    NotFound(ID),
+    StorageError,
+}
+
+impl From<UseCaseError> for NettuError {
+    fn from(e: UseCaseError) -> Self {
+        match e {
+            UseCaseError::NotFound(id) => {
+                Self::NotFound(format!("The waitlist entry with id: {} was not found.", id))
+            }
+            UseCaseError::StorageError => Self::InternalError,
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for LeaveWaitlistUseCase {
+    type Response = WaitlistEntry;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "LeaveWaitlist";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        let entry = match ctx.repos.waitlist.find(&self.entry_id).await {
+            Some(entry) if entry.account_id == self.account.id => entry,
+            _ => return Err(UseCaseError::NotFound(self.entry_id.clone())),
+        };
+
+        ctx.repos
+            .waitlist
+            .delete(&entry.id)
+            .await
+            .map_err(|_| UseCaseError::StorageError)?;
+
+        // The seat offered to the invitee goes to the next one in line
+        if let Some(hold_id) = &entry.hold_id {
+            ctx.repos
+                .booking_holds
+                .delete(hold_id)
+                .await
+                .map_err(|_| UseCaseError::StorageError)?;
+            let offer = OfferWaitlistSeatUseCase {
+                service_id: entry.service_id.clone(),
+                start_ts: entry.start_ts,
+            };
+            execute(offer, ctx)
+                .await
+                .map_err(|_| UseCaseError::StorageError)?;
+        }
+
+        Ok(entry)
+    }
+}
@@ -0,0 +1,89 @@
+use crate::error::NettuError;
+use crate::shared::{
+    auth::protect_account_route,
+    usecase::{execute, UseCase},
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use nettu_scheduler_api_structs::mark_booking_no_show::*;
+use nettu_scheduler_domain::{Account, Booking, BookingStatus, ID};
+use nettu_scheduler_infra::NettuContext;
+
+pub async fn mark_booking_no_show_controller(
+    http_req: HttpRequest,
+    path_params: web::Path<PathParams>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let account = protect_account_route(&http_req, &ctx).await?;
+
+    let usecase = MarkBookingNoShowUseCase {
+        account,
+        booking_id: path_params.booking_id.clone(),
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|booking| HttpResponse::Ok().json(APIResponse::new(booking)))
+        .map_err(NettuError::from)
+}
+
+/// Records that the invitee did not show up to a confirmed `Booking` which
+/// has ended. The `CalendarEvent`s of the hosts are kept, as the time has passed.
+#[derive(Debug)]
+struct MarkBookingNoShowUseCase {
+    account: Account,
+    booking_id: ID,
+}
+
+#[derive(Debug)]
+enum UseCaseError {
+    NotFound(ID),
+    NotEnded,
+    StorageError,
+}
+
+impl From<UseCaseError> for NettuError {
+    fn from(e: UseCaseError) -> Self {
+        match e {
+            UseCaseError::NotFound(id) => {
+                Self::NotFound(format!("The booking with id: {} was not found.", id))
+            }
+            UseCaseError::NotEnded => Self::Conflict(
+                "Only confirmed bookings which have ended can be marked as no-show".into(),
+            ),
+            UseCaseError::StorageError => Self::InternalError,
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for MarkBookingNoShowUseCase {
+    type Response = Booking;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "MarkBookingNoShow";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        let mut booking = match ctx.repos.bookings.find(&self.booking_id).await {
+            Some(booking) if booking.account_id == self.account.id => booking,
+            _ => return Err(UseCaseError::NotFound(self.booking_id.clone())),
+        };
+        let from = booking.status;
+        if !booking.mark_no_show(ctx.sys.get_timestamp_millis()) {
+            return Err(UseCaseError::NotEnded);
+        }
+
+        let marked = ctx
+            .repos
+            .bookings
+            .set_status(&booking, &[from])
+            .await
+            .map_err(|_| UseCaseError::StorageError)?;
+        if !marked {
+            // The booking was cancelled or marked by someone else
+            return Err(UseCaseError::NotEnded);
+        }
+
+        Ok(booking)
+    }
+}
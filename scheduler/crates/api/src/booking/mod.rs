@@ -0,0 +1,96 @@
+mod approve_booking;
+mod cancel_booking;
+mod create_booking;
+mod create_booking_chain;
+mod create_booking_from_hold;
+mod create_booking_hold;
+pub(crate) mod decline_booking;
+mod get_booking;
+mod get_service_bookings;
+mod get_waitlist;
+mod join_waitlist;
+mod leave_waitlist;
+mod mark_booking_no_show;
+pub(crate) mod offer_waitlist_seat;
+mod release_booking_hold;
+mod reschedule_booking;
+
+use actix_web::web;
+use approve_booking::approve_booking_controller;
+use cancel_booking::cancel_booking_controller;
+use create_booking::create_booking_controller;
+use create_booking_chain::create_booking_chain_controller;
+use create_booking_from_hold::create_booking_from_hold_controller;
+use create_booking_hold::create_booking_hold_controller;
+use decline_booking::decline_booking_controller;
+use get_booking::get_booking_controller;
+use get_service_bookings::get_service_bookings_controller;
+use get_waitlist::get_waitlist_controller;
+use join_waitlist::join_waitlist_controller;
+use leave_waitlist::leave_waitlist_controller;
+use mark_booking_no_show::mark_booking_no_show_controller;
+use release_booking_hold::release_booking_hold_controller;
+use reschedule_booking::reschedule_booking_controller;
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route(
+        "/service/{service_id}/bookings",
+        web::post().to(create_booking_controller),
+    );
+    cfg.route(
+        "/service/{service_id}/bookings",
+        web::get().to(get_service_bookings_controller),
+    );
+    cfg.route(
+        "/service-chain/bookings",
+        web::post().to(create_booking_chain_controller),
+    );
+    cfg.route(
+        "/booking/{booking_id}",
+        web::get().to(get_booking_controller),
+    );
+    cfg.route(
+        "/booking/{booking_id}/cancel",
+        web::post().to(cancel_booking_controller),
+    );
+    cfg.route(
+        "/booking/{booking_id}/reschedule",
+        web::post().to(reschedule_booking_controller),
+    );
+    cfg.route(
+        "/booking/{booking_id}/approve",
+        web::post().to(approve_booking_controller),
+    );
+    cfg.route(
+        "/booking/{booking_id}/decline",
+        web::post().to(decline_booking_controller),
+    );
+    cfg.route(
+        "/booking/{booking_id}/no-show",
+        web::post().to(mark_booking_no_show_controller),
+    );
+    cfg.route(
+        "/service/{service_id}/holds",
+        web::post().to(create_booking_hold_controller),
+    );
+    cfg.route(
+        "/hold/{hold_id}",
+        web::delete().to(release_booking_hold_controller),
+    );
+    cfg.route(
+        "/hold/{hold_id}/booking",
+        web::post().to(create_booking_from_hold_controller),
+    );
+    cfg.route(
+        "/service/{service_id}/waitlist",
+        web::post().to(join_waitlist_controller),
+    );
+    cfg.route(
+        "/service/{service_id}/waitlist",
+        web::get().to(get_waitlist_controller),
+    );
+    cfg.route(
+        "/waitlist/{entry_id}",
+        web::delete().to(leave_waitlist_controller),
+    );
+}
@@ -0,0 +1,94 @@
+use crate::shared::usecase::UseCase;
+use awc::Client;
+use nettu_scheduler_api_structs::WaitlistOfferDTO;
+use nettu_scheduler_domain::{BookingHold, ServiceMultiPersonOptions, WaitlistEntry, ID};
+use nettu_scheduler_infra::{BookingCommitError, NettuContext};
+use tracing::error;
+
+/// Offers a free seat of a group `Service` to the first invitee on the
+/// waitlist of the booking slot, by holding the seat for the invitee and
+/// letting the `Account` know through its webhook. Used whenever a seat of
+/// the booking slot is released.
+#[derive(Debug)]
+pub struct OfferWaitlistSeatUseCase {
+    pub service_id: ID,
+    pub start_ts: i64,
+}
+
+#[derive(Debug)]
+pub enum UseCaseError {
+    StorageError,
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for OfferWaitlistSeatUseCase {
+    /// The entry that was offered the seat, if any
+    type Response = Option<WaitlistEntry>;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "OfferWaitlistSeat";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        let now = ctx.sys.get_timestamp_millis();
+        if self.start_ts <= now {
+            return Ok(None);
+        }
+        let service = match ctx.repos.services.find_with_users(&self.service_id).await {
+            Some(service) => service,
+            None => return Ok(None),
+        };
+        let max_count = match service.multi_person {
+            ServiceMultiPersonOptions::Group(max_count) => max_count,
+            _ => return Ok(None),
+        };
+        let host_user_ids = service
+            .users
+            .iter()
+            .map(|resource| resource.user_id.clone())
+            .collect();
+        let (entry, hold) = match ctx
+            .repos
+            .waitlist
+            .offer_next(
+                &self.service_id,
+                self.start_ts,
+                host_user_ids,
+                max_count,
+                now,
+            )
+            .await
+        {
+            Ok(Some(offer)) => offer,
+            Ok(None) => return Ok(None),
+            // The seat was taken by someone else before it could be offered
+            Err(BookingCommitError::Conflict) => return Ok(None),
+            Err(BookingCommitError::StorageError) => return Err(UseCaseError::StorageError),
+        };
+
+        notify_waitlist_offer(&entry, &hold, ctx).await;
+
+        Ok(Some(entry))
+    }
+}
+
+/// Lets the `Account` know through its webhook that a seat is held for an
+/// invitee on the waitlist
+async fn notify_waitlist_offer(entry: &WaitlistEntry, hold: &BookingHold, ctx: &NettuContext) {
+    let account = match ctx.repos.accounts.find(&entry.account_id).await {
+        Some(account) => account,
+        None => return,
+    };
+    let webhook = match &account.settings.webhook {
+        Some(webhook) => webhook,
+        None => return,
+    };
+    if let Err(e) = Client::new()
+        .post(&webhook.url)
+        .insert_header(("nettu-scheduler-webhook-key", webhook.key.clone()))
+        .send_json(&WaitlistOfferDTO::new(entry.clone(), hold.clone()))
+        .await
+    {
+        error!("Error informing client of waitlist offer: {:?}", e);
+    }
+}
@@ -0,0 +1,93 @@
+use super::offer_waitlist_seat::OfferWaitlistSeatUseCase;
+use crate::error::NettuError;
+use crate::shared::{
+    auth::protect_account_route,
+    usecase::{execute, UseCase},
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use nettu_scheduler_api_structs::release_booking_hold::*;
+use nettu_scheduler_domain::{Account, BookingHold, ID};
+use nettu_scheduler_infra::NettuContext;
+
+pub async fn release_booking_hold_controller(
+    http_req: HttpRequest,
+    path_params: web::Path<PathParams>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let account = protect_account_route(&http_req, &ctx).await?;
+
+    let usecase = ReleaseBookingHoldUseCase {
+        account,
+        hold_id: path_params.hold_id.clone(),
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|hold| HttpResponse::Ok().json(APIResponse::new(hold)))
+        .map_err(NettuError::from)
+}
+
+#[derive(Debug)]
+struct ReleaseBookingHoldUseCase {
+    account: Account,
+    hold_id: ID,
+}
+
+#[derive(Debug)]
+enum UseCaseError {
+    NotFound(ID),
+    StorageError,
+}
+
+impl From<UseCaseError> for NettuError {
+    fn from(e: UseCaseError) -> Self {
+        match e {
+            UseCaseError::NotFound(id) => {
+                Self::NotFound(format!("The booking hold with id: {} was not found.", id))
+            }
+            UseCaseError::StorageError => Self::InternalError,
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for ReleaseBookingHoldUseCase {
+    type Response = BookingHold;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "ReleaseBookingHold";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        let hold = match ctx.repos.booking_holds.find(&self.hold_id).await {
+            Some(hold) if hold.account_id == self.account.id => hold,
+            _ => return Err(UseCaseError::NotFound(self.hold_id.clone())),
+        };
+
+        ctx.repos
+            .booking_holds
+            .delete(&hold.id)
+            .await
+            .map_err(|_| UseCaseError::StorageError)?;
+
+        if hold.seat {
+            // A seat offered from the waitlist goes to the next one in line
+            if let Some(entry) = ctx.repos.waitlist.find_by_hold(&hold.id).await {
+                ctx.repos
+                    .waitlist
+                    .delete(&entry.id)
+                    .await
+                    .map_err(|_| UseCaseError::StorageError)?;
+            }
+            let offer = OfferWaitlistSeatUseCase {
+                service_id: hold.service_id.clone(),
+                start_ts: hold.start_ts,
+            };
+            execute(offer, ctx)
+                .await
+                .map_err(|_| UseCaseError::StorageError)?;
+        }
+
+        Ok(hold)
+    }
+}
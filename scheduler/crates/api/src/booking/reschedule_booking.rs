@@ -0,0 +1,264 @@
+use super::cancel_booking::delete_booking_events;
+use super::create_booking::prepare_host_events;
+use crate::error::NettuError;
+use crate::event::{create_event::CreateEventUseCase, update_event::UpdateEventUseCase};
+use crate::service::create_service_event_intend::{self, CreateServiceEventIntendUseCase};
+use crate::shared::{
+    auth::protect_account_route,
+    usecase::{execute, UseCase},
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use nettu_scheduler_api_structs::reschedule_booking::*;
+use nettu_scheduler_domain::{
+    Account, Booking, BookingPolicyViolation, BookingStatus, ServiceMultiPersonOptions, ID,
+};
+use nettu_scheduler_infra::{BookingCommitError, BookingRescheduleCommit, NettuContext};
+
+pub async fn reschedule_booking_controller(
+    http_req: HttpRequest,
+    body: web::Json<RequestBody>,
+    path_params: web::Path<PathParams>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let account = protect_account_route(&http_req, &ctx).await?;
+
+    let body = body.0;
+    let usecase = RescheduleBookingUseCase {
+        account,
+        booking_id: path_params.booking_id.clone(),
+        start_ts: body.start_ts,
+        duration: body.duration,
+        interval: body.interval,
+        ignore_policy: body.ignore_policy,
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|booking| HttpResponse::Ok().json(APIResponse::new(booking)))
+        .map_err(NettuError::from)
+}
+
+/// Moves a `Booking` to another time while keeping its identity. The
+/// `CalendarEvent`s of the hosts are moved along with it, so that reminders
+/// and synced events stay attached to them.
+#[derive(Debug)]
+struct RescheduleBookingUseCase {
+    account: Account,
+    booking_id: ID,
+    start_ts: i64,
+    duration: Option<i64>,
+    interval: Option<i64>,
+    /// Whether the reschedule policy of the `Service` is overridden
+    ignore_policy: bool,
+}
+
+#[derive(Debug)]
+enum UseCaseError {
+    NotFound(ID),
+    NotActive,
+    GroupService,
+    PolicyViolation(BookingPolicyViolation),
+    InvalidDuration,
+    BookingIntend(create_service_event_intend::UseCaseError),
+    Conflict,
+    StorageError,
+}
+
+impl From<UseCaseError> for NettuError {
+    fn from(e: UseCaseError) -> Self {
+        match e {
+            UseCaseError::NotFound(id) => {
+                Self::NotFound(format!("The booking with id: {} was not found.", id))
+            }
+            UseCaseError::NotActive => {
+                Self::Conflict("The booking has already been cancelled or completed".into())
+            }
+            UseCaseError::GroupService => Self::BadClientData(
+                "Bookings of group services can not be rescheduled. Cancel the booking and book another seat instead.".into(),
+            ),
+            UseCaseError::PolicyViolation(e) => e.into(),
+            UseCaseError::InvalidDuration => Self::BadClientData(
+                "Invalid duration specified. It should be one of the durations allowed by the service.".into(),
+            ),
+            UseCaseError::BookingIntend(e) => e.into(),
+            UseCaseError::Conflict => Self::Conflict(
+                "The selected time was booked by someone else. Please choose another time.".into(),
+            ),
+            UseCaseError::StorageError => Self::InternalError,
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for RescheduleBookingUseCase {
+    type Response = Booking;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "RescheduleBooking";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        let mut booking = match ctx.repos.bookings.find(&self.booking_id).await {
+            Some(booking) if booking.account_id == self.account.id => booking,
+            _ => return Err(UseCaseError::NotFound(self.booking_id.clone())),
+        };
+        if !booking.status.is_active() {
+            return Err(UseCaseError::NotActive);
+        }
+        let service = match ctx
+            .repos
+            .services
+            .find_with_users(&booking.service_id)
+            .await
+        {
+            Some(service) => service,
+            None => return Err(UseCaseError::NotFound(self.booking_id.clone())),
+        };
+        if let ServiceMultiPersonOptions::Group(_) = service.multi_person {
+            return Err(UseCaseError::GroupService);
+        }
+        if !self.ignore_policy {
+            service
+                .booking_options
+                .check_reschedule(&booking, ctx.sys.get_timestamp_millis())
+                .map_err(UseCaseError::PolicyViolation)?;
+        }
+        let duration = service
+            .booking_options
+            .duration(Some(self.duration.unwrap_or(booking.duration)))
+            .ok_or(UseCaseError::InvalidDuration)?;
+
+        let events = ctx
+            .repos
+            .events
+            .find_many(&booking.event_ids)
+            .await
+            .map_err(|_| UseCaseError::StorageError)?;
+
+        // The current hosts keep the booking if they are available at the new time
+        let intend = CreateServiceEventIntendUseCase {
+            account: self.account.clone(),
+            service_id: service.id.clone(),
+            host_user_ids: Some(booking.host_user_ids.clone()),
+            timestamp: self.start_ts,
+            duration: Some(duration),
+            interval: self.interval,
+            reserve_seat: false,
+            ignored_events: events.clone(),
+            customer_key: None,
+            attributes: booking.invitee.attributes.clone(),
+            intake_answers: None,
+            slot_offset: 0,
+            resource_id: booking.resource_id.clone(),
+        };
+        let intend = match execute(intend, ctx).await {
+            Ok(intend) => intend,
+            Err(create_service_event_intend::UseCaseError::UserNotAvailable)
+                if matches!(
+                    service.multi_person,
+                    ServiceMultiPersonOptions::RoundRobinAlgorithm(_)
+                        | ServiceMultiPersonOptions::CollectiveQuorum(_)
+                ) =>
+            {
+                // Otherwise the booking is assigned to other hosts
+                let intend = CreateServiceEventIntendUseCase {
+                    account: self.account.clone(),
+                    service_id: service.id.clone(),
+                    host_user_ids: None,
+                    timestamp: self.start_ts,
+                    duration: Some(duration),
+                    interval: self.interval,
+                    reserve_seat: false,
+                    ignored_events: events.clone(),
+                    customer_key: None,
+                    attributes: booking.invitee.attributes.clone(),
+                    intake_answers: None,
+                    slot_offset: 0,
+                    resource_id: booking.resource_id.clone(),
+                };
+                execute(intend, ctx)
+                    .await
+                    .map_err(UseCaseError::BookingIntend)?
+            }
+            Err(e) => return Err(UseCaseError::BookingIntend(e)),
+        };
+
+        let now = ctx.sys.get_timestamp_millis();
+        let host_user_ids = intend
+            .selected_hosts
+            .iter()
+            .map(|host| host.id.clone())
+            .collect::<Vec<_>>();
+        let resource_id = intend
+            .selected_resource
+            .as_ref()
+            .map(|resource| resource.id.clone());
+        let (mut moved_events, removed_events): (Vec<_>, Vec<_>) =
+            events.into_iter().partition(|e| {
+                host_user_ids.contains(&e.user_id) || resource_id.as_ref() == Some(&e.user_id)
+            });
+        for event in moved_events.iter_mut() {
+            event.start_ts = self.start_ts;
+            event.duration = duration;
+            event.end_ts = self.start_ts + duration;
+            event.updated = now;
+        }
+        let new_hosts = intend
+            .booked_users()
+            .into_iter()
+            .filter(|user| {
+                !booking.host_user_ids.contains(&user.id)
+                    && booking.resource_id.as_ref() != Some(&user.id)
+            })
+            .collect::<Vec<_>>();
+        let new_events = prepare_host_events(
+            &service,
+            &new_hosts,
+            self.start_ts,
+            duration,
+            now,
+            booking.status == BookingStatus::Pending,
+            ctx,
+        )
+        .await;
+
+        let updated = booking.updated;
+        if !booking.reschedule(self.start_ts, duration, host_user_ids, now) {
+            return Err(UseCaseError::NotActive);
+        }
+        booking.resource_id = resource_id;
+        let reschedule = BookingRescheduleCommit {
+            booking,
+            updated,
+            moved_events: moved_events.clone(),
+            new_events: new_events.clone(),
+        };
+        let booking = ctx
+            .repos
+            .bookings
+            .reschedule(reschedule)
+            .await
+            .map_err(|e| match e {
+                BookingCommitError::Conflict => UseCaseError::Conflict,
+                BookingCommitError::StorageError => UseCaseError::StorageError,
+            })?;
+
+        // The events were stored as part of the booking, so the side effects
+        // of changing them are triggered afterwards
+        for event in &moved_events {
+            for subscriber in UpdateEventUseCase::subscribers() {
+                subscriber.notify(event, ctx).await;
+            }
+        }
+        for event in &new_events {
+            for subscriber in CreateEventUseCase::subscribers() {
+                subscriber.notify(event, ctx).await;
+            }
+        }
+        delete_booking_events(removed_events, ctx)
+            .await
+            .map_err(|_| UseCaseError::StorageError)?;
+
+        Ok(booking)
+    }
+}
@@ -2,6 +2,7 @@ use actix_web::{
     http::{header, StatusCode},
     HttpResponse,
 };
+use nettu_scheduler_domain::BookingPolicyViolation;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -40,3 +41,20 @@ impl actix_web::error::ResponseError for NettuError {
             .body(self.to_string())
     }
 }
+
+impl From<BookingPolicyViolation> for NettuError {
+    fn from(e: BookingPolicyViolation) -> Self {
+        let msg = match e {
+            BookingPolicyViolation::CancellationCutoff => {
+                "The booking starts too soon to be cancelled"
+            }
+            BookingPolicyViolation::RescheduleCutoff => {
+                "The booking starts too soon to be rescheduled"
+            }
+            BookingPolicyViolation::MaxReschedules => {
+                "The booking has been rescheduled the maximum number of times"
+            }
+        };
+        Self::Conflict(msg.into())
+    }
+}
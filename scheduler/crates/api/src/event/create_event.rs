@@ -128,6 +128,7 @@ impl UseCase for CreateEventUseCase {
         let mut e = CalendarEvent {
             id: Default::default(),
             busy: self.busy,
+            tentative: false,
             start_ts: self.start_ts,
             duration: self.duration,
             created: ctx.sys.get_timestamp_millis(),
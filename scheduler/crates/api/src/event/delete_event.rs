@@ -160,6 +160,11 @@ impl DeleteEventUseCase {
                 {
                     error!("Unable to delete external outlook calendar event");
                 };
+                let _ = ctx
+                    .repos
+                    .external_busy_cache
+                    .invalidate(&user.id, &cal.provider, &cal.ext_calendar_id)
+                    .await;
             }
         }
 
@@ -179,6 +184,11 @@ impl DeleteEventUseCase {
                 {
                     error!("Unable to delete google external calendar event");
                 };
+                let _ = ctx
+                    .repos
+                    .external_busy_cache
+                    .invalidate(&user.id, &cal.provider, &cal.ext_calendar_id)
+                    .await;
             }
         }
     }
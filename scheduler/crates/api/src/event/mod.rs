@@ -1,12 +1,12 @@
-mod create_event;
-mod delete_event;
+pub(crate) mod create_event;
+pub(crate) mod delete_event;
 mod get_event;
 mod get_event_instances;
 mod get_events_by_meta;
 pub mod get_upcoming_reminders;
 mod subscribers;
 pub mod sync_event_reminders;
-mod update_event;
+pub(crate) mod update_event;
 
 use actix_web::web;
 use create_event::{create_event_admin_controller, create_event_controller};
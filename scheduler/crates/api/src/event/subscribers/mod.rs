@@ -97,6 +97,15 @@ impl Subscriber<CreateEventUseCase> for CreateSyncedEventsOnEventCreated {
                         continue;
                     }
                 };
+                let _ = ctx
+                    .repos
+                    .external_busy_cache
+                    .invalidate(
+                        &user.id,
+                        &synced_o_cal.provider,
+                        &synced_o_cal.ext_calendar_id,
+                    )
+                    .await;
 
                 let synced_event = SyncedCalendarEvent {
                     calendar_id: e.calendar_id.clone(),
@@ -132,6 +141,15 @@ impl Subscriber<CreateEventUseCase> for CreateSyncedEventsOnEventCreated {
                         continue;
                     }
                 };
+                let _ = ctx
+                    .repos
+                    .external_busy_cache
+                    .invalidate(
+                        &user.id,
+                        &synced_g_cal.provider,
+                        &synced_g_cal.ext_calendar_id,
+                    )
+                    .await;
 
                 println!("Going to insert google synced events");
 
@@ -207,6 +225,15 @@ impl Subscriber<UpdateEventUseCase> for UpdateSyncedEventsOnEventUpdated {
                 {
                     error!("Unable to update external outlook calendar event");
                 };
+                let _ = ctx
+                    .repos
+                    .external_busy_cache
+                    .invalidate(
+                        &user.id,
+                        &synced_o_event.provider,
+                        &synced_o_event.ext_calendar_id,
+                    )
+                    .await;
             }
         }
 
@@ -230,6 +257,15 @@ impl Subscriber<UpdateEventUseCase> for UpdateSyncedEventsOnEventUpdated {
                 {
                     error!("Unable to update google external calendar event");
                 };
+                let _ = ctx
+                    .repos
+                    .external_busy_cache
+                    .invalidate(
+                        &user.id,
+                        &synced_g_event.provider,
+                        &synced_g_event.ext_calendar_id,
+                    )
+                    .await;
             }
         }
     }
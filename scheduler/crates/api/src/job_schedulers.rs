@@ -1,4 +1,7 @@
 use crate::{
+    booking::{
+        decline_booking::DeclineBookingUseCase, offer_waitlist_seat::OfferWaitlistSeatUseCase,
+    },
     event::{
         get_upcoming_reminders::GetUpcomingRemindersUseCase,
         sync_event_reminders::{SyncEventRemindersTrigger, SyncEventRemindersUseCase},
@@ -35,6 +38,64 @@ pub fn start_reminder_generation_job_scheduler(ctx: NettuContext) {
     });
 }
 
+/// Expired booking holds are already ignored everywhere, this only removes them
+pub fn start_expired_booking_holds_cleanup_job(ctx: NettuContext) {
+    actix_web::rt::spawn(async move {
+        let mut interval = interval(Duration::from_secs(10 * 60));
+        loop {
+            interval.tick().await;
+
+            let now = ctx.sys.get_timestamp_millis();
+            let _ = ctx.repos.booking_holds.delete_expired(now).await;
+        }
+    });
+}
+
+/// Declines the pending bookings which were not approved in time
+pub fn start_booking_approval_deadline_job(ctx: NettuContext) {
+    actix_web::rt::spawn(async move {
+        let mut interval = interval(Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+
+            let now = ctx.sys.get_timestamp_millis();
+            for booking in ctx.repos.bookings.find_pending_past_deadline(now).await {
+                let usecase = DeclineBookingUseCase {
+                    account_id: booking.account_id,
+                    booking_id: booking.id,
+                };
+                let _ = execute(usecase, &ctx).await;
+            }
+        }
+    });
+}
+
+/// Moves the seats offered to invitees on a waitlist who did not book them in
+/// time on to the next ones in line
+pub fn start_waitlist_offer_expiry_job(ctx: NettuContext) {
+    actix_web::rt::spawn(async move {
+        let mut interval = interval(Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+
+            let now = ctx.sys.get_timestamp_millis();
+            for entry in ctx.repos.waitlist.find_expired_offers(now).await {
+                if let Some(hold_id) = &entry.hold_id {
+                    let _ = ctx.repos.booking_holds.delete(hold_id).await;
+                }
+                if ctx.repos.waitlist.delete(&entry.id).await.is_err() {
+                    continue;
+                }
+                let usecase = OfferWaitlistSeatUseCase {
+                    service_id: entry.service_id,
+                    start_ts: entry.start_ts,
+                };
+                let _ = execute(usecase, &ctx).await;
+            }
+        }
+    });
+}
+
 pub fn start_send_reminders_job(ctx: NettuContext) {
     actix_web::rt::spawn(async move {
         let now = ctx.sys.get_timestamp_millis();
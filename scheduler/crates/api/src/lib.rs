@@ -1,4 +1,5 @@
 mod account;
+mod booking;
 mod calendar;
 mod error;
 mod event;
@@ -12,7 +13,11 @@ mod user;
 use actix_cors::Cors;
 use actix_web::web::Data;
 use actix_web::{dev::Server, middleware, web, App, HttpServer};
-use job_schedulers::{start_reminder_generation_job_scheduler, start_send_reminders_job};
+use job_schedulers::{
+    start_booking_approval_deadline_job, start_expired_booking_holds_cleanup_job,
+    start_reminder_generation_job_scheduler, start_send_reminders_job,
+    start_waitlist_offer_expiry_job,
+};
 use nettu_scheduler_domain::{
     Account, AccountIntegration, AccountWebhookSettings, IntegrationProvider, PEMKey, ID,
 };
@@ -23,6 +28,7 @@ use tracing_actix_web::TracingLogger;
 
 pub fn configure_server_api(cfg: &mut web::ServiceConfig) {
     account::configure_routes(cfg);
+    booking::configure_routes(cfg);
     calendar::configure_routes(cfg);
     event::configure_routes(cfg);
     schedule::configure_routes(cfg);
@@ -55,7 +61,10 @@ impl Application {
 
     fn start_job_schedulers(context: NettuContext) {
         start_send_reminders_job(context.clone());
-        start_reminder_generation_job_scheduler(context);
+        start_reminder_generation_job_scheduler(context.clone());
+        start_expired_booking_holds_cleanup_job(context.clone());
+        start_booking_approval_deadline_job(context.clone());
+        start_waitlist_offer_expiry_job(context);
     }
 
     async fn configure_server(context: NettuContext) -> Result<(Server, u16), std::io::Error> {
@@ -1,15 +1,31 @@
 mod create_schedule;
+mod create_schedule_from_vavailability;
 mod delete_schedule;
 mod get_schedule;
+mod get_schedule_vavailability;
 mod get_schedules_by_meta;
+mod preview_schedule;
 mod update_schedule;
+mod update_schedule_from_vavailability;
 
 use actix_web::web;
 use create_schedule::{create_schedule_admin_controller, create_schedule_controller};
+use create_schedule_from_vavailability::{
+    create_schedule_from_vavailability_admin_controller,
+    create_schedule_from_vavailability_controller,
+};
 use delete_schedule::{delete_schedule_admin_controller, delete_schedule_controller};
 use get_schedule::{get_schedule_admin_controller, get_schedule_controller};
+use get_schedule_vavailability::{
+    get_schedule_vavailability_admin_controller, get_schedule_vavailability_controller,
+};
 use get_schedules_by_meta::get_schedules_by_meta_controller;
+use preview_schedule::{preview_schedule_admin_controller, preview_schedule_controller};
 use update_schedule::{update_schedule_admin_controller, update_schedule_controller};
+use update_schedule_from_vavailability::{
+    update_schedule_from_vavailability_admin_controller,
+    update_schedule_from_vavailability_controller,
+};
 
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
     cfg.route("/schedule", web::post().to(create_schedule_controller));
@@ -23,6 +39,15 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
         web::get().to(get_schedules_by_meta_controller),
     );
 
+    cfg.route(
+        "/schedule/preview",
+        web::post().to(preview_schedule_controller),
+    );
+    cfg.route(
+        "/user/schedule/preview",
+        web::post().to(preview_schedule_admin_controller),
+    );
+
     cfg.route(
         "/schedule/{schedule_id}",
         web::get().to(get_schedule_controller),
@@ -49,4 +74,31 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
         "/user/schedule/{schedule_id}",
         web::put().to(update_schedule_admin_controller),
     );
+
+    cfg.route(
+        "/schedule/vavailability",
+        web::post().to(create_schedule_from_vavailability_controller),
+    );
+    cfg.route(
+        "/user/{user_id}/schedule/vavailability",
+        web::post().to(create_schedule_from_vavailability_admin_controller),
+    );
+
+    cfg.route(
+        "/schedule/{schedule_id}/vavailability",
+        web::get().to(get_schedule_vavailability_controller),
+    );
+    cfg.route(
+        "/user/schedule/{schedule_id}/vavailability",
+        web::get().to(get_schedule_vavailability_admin_controller),
+    );
+
+    cfg.route(
+        "/schedule/{schedule_id}/vavailability",
+        web::put().to(update_schedule_from_vavailability_controller),
+    );
+    cfg.route(
+        "/user/schedule/{schedule_id}/vavailability",
+        web::put().to(update_schedule_from_vavailability_admin_controller),
+    );
 }
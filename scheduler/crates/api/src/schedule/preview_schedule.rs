@@ -0,0 +1,108 @@
+use crate::error::NettuError;
+use crate::shared::{
+    auth::{protect_account_route, protect_route},
+    usecase::{execute, UseCase},
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono_tz::Tz;
+use nettu_scheduler_api_structs::preview_schedule::*;
+use nettu_scheduler_domain::{EventInstance, Schedule, ScheduleRule, TimeSpan, ID};
+use nettu_scheduler_infra::NettuContext;
+
+pub async fn preview_schedule_admin_controller(
+    http_req: HttpRequest,
+    body: web::Json<RequestBody>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let account = protect_account_route(&http_req, &ctx).await?;
+
+    let body = body.0;
+    let usecase = PreviewScheduleUseCase {
+        user_id: Default::default(),
+        account_id: account.id,
+        timezone: body.timezone,
+        rules: body.rules,
+        start_ts: body.start_ts,
+        end_ts: body.end_ts,
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|instances| HttpResponse::Ok().json(APIResponse::new(instances)))
+        .map_err(NettuError::from)
+}
+
+pub async fn preview_schedule_controller(
+    http_req: HttpRequest,
+    body: web::Json<RequestBody>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let (user, _policy) = protect_route(&http_req, &ctx).await?;
+
+    let body = body.0;
+    let usecase = PreviewScheduleUseCase {
+        user_id: user.id,
+        account_id: user.account_id,
+        timezone: body.timezone,
+        rules: body.rules,
+        start_ts: body.start_ts,
+        end_ts: body.end_ts,
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|instances| HttpResponse::Ok().json(APIResponse::new(instances)))
+        .map_err(NettuError::from)
+}
+
+/// Computes the free instances of a `Schedule` built from the given rules
+/// without persisting it
+#[derive(Debug)]
+struct PreviewScheduleUseCase {
+    pub user_id: ID,
+    pub account_id: ID,
+    pub timezone: Tz,
+    pub rules: Vec<ScheduleRule>,
+    pub start_ts: i64,
+    pub end_ts: i64,
+}
+
+#[derive(Debug)]
+enum UseCaseError {
+    InvalidTimespan,
+}
+
+impl From<UseCaseError> for NettuError {
+    fn from(e: UseCaseError) -> Self {
+        match e {
+            UseCaseError::InvalidTimespan => {
+                Self::BadClientData("The provided start_ts and end_ts is invalid".into())
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for PreviewScheduleUseCase {
+    type Response = Vec<EventInstance>;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "PreviewSchedule";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        let timespan = TimeSpan::new(self.start_ts, self.end_ts);
+        if timespan.greater_than(ctx.config.event_instances_query_duration_limit) {
+            return Err(UseCaseError::InvalidTimespan);
+        }
+
+        let mut schedule = Schedule::new(
+            self.user_id.clone(),
+            self.account_id.clone(),
+            &self.timezone,
+        );
+        schedule.set_rules(&self.rules);
+
+        Ok(schedule.freebusy(&timespan).inner().into())
+    }
+}
@@ -0,0 +1,125 @@
+use crate::shared::{
+    auth::{account_can_modify_schedule, protect_account_route},
+    usecase::{execute, UseCase},
+};
+use crate::{
+    error::NettuError,
+    shared::{
+        auth::{protect_route, Permission},
+        usecase::{execute_with_policy, PermissionBoundary},
+    },
+};
+use actix_web::{web, HttpResponse};
+use nettu_scheduler_api_structs::update_schedule_from_vavailability::*;
+use nettu_scheduler_domain::{Schedule, VAvailability, VAvailabilityError, ID};
+use nettu_scheduler_infra::NettuContext;
+
+pub async fn update_schedule_from_vavailability_admin_controller(
+    http_req: web::HttpRequest,
+    path: web::Path<PathParams>,
+    body: web::Json<RequestBody>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let account = protect_account_route(&http_req, &ctx).await?;
+    let schedule = account_can_modify_schedule(&account, &path.schedule_id, &ctx).await?;
+
+    let usecase = UpdateScheduleFromVAvailabilityUseCase {
+        user_id: schedule.user_id,
+        schedule_id: schedule.id,
+        vavailability: body.0.vavailability,
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|res| HttpResponse::Ok().json(APIResponse::new(res.schedule)))
+        .map_err(NettuError::from)
+}
+
+pub async fn update_schedule_from_vavailability_controller(
+    http_req: web::HttpRequest,
+    ctx: web::Data<NettuContext>,
+    mut path: web::Path<PathParams>,
+    body: web::Json<RequestBody>,
+) -> Result<HttpResponse, NettuError> {
+    let (user, policy) = protect_route(&http_req, &ctx).await?;
+
+    let usecase = UpdateScheduleFromVAvailabilityUseCase {
+        user_id: user.id,
+        schedule_id: std::mem::take(&mut path.schedule_id),
+        vavailability: body.0.vavailability,
+    };
+
+    execute_with_policy(usecase, &policy, &ctx)
+        .await
+        .map(|res| HttpResponse::Ok().json(APIResponse::new(res.schedule)))
+        .map_err(NettuError::from)
+}
+
+#[derive(Debug)]
+struct UpdateScheduleFromVAvailabilityUseCase {
+    pub user_id: ID,
+    pub schedule_id: ID,
+    pub vavailability: String,
+}
+
+#[derive(Debug)]
+enum UseCaseError {
+    ScheduleNotFound(ID),
+    InvalidVAvailability(VAvailabilityError),
+    StorageError,
+}
+
+impl From<UseCaseError> for NettuError {
+    fn from(e: UseCaseError) -> Self {
+        match e {
+            UseCaseError::ScheduleNotFound(schedule_id) => Self::NotFound(format!(
+                "The schedule with id: {}, was not found.",
+                schedule_id
+            )),
+            UseCaseError::InvalidVAvailability(e) => {
+                Self::BadClientData(format!("Invalid VAVAILABILITY: {}", e))
+            }
+            UseCaseError::StorageError => Self::InternalError,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct UseCaseRes {
+    pub schedule: Schedule,
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for UpdateScheduleFromVAvailabilityUseCase {
+    type Response = UseCaseRes;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "UpdateScheduleFromVAvailability";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        let mut schedule = match ctx.repos.schedules.find(&self.schedule_id).await {
+            Some(schedule) if schedule.user_id == self.user_id => schedule,
+            _ => return Err(UseCaseError::ScheduleNotFound(self.schedule_id.clone())),
+        };
+
+        let vavailability = VAvailability::parse(&self.vavailability)
+            .map_err(UseCaseError::InvalidVAvailability)?;
+        if let Some(tz) = vavailability.timezone {
+            schedule.timezone = tz;
+        }
+        schedule.set_rules(&vavailability.rules);
+
+        let repo_res = ctx.repos.schedules.save(&schedule).await;
+        match repo_res {
+            Ok(_) => Ok(UseCaseRes { schedule }),
+            Err(_) => Err(UseCaseError::StorageError),
+        }
+    }
+}
+
+impl PermissionBoundary for UpdateScheduleFromVAvailabilityUseCase {
+    fn permissions(&self) -> Vec<Permission> {
+        vec![Permission::UpdateSchedule]
+    }
+}
@@ -5,7 +5,7 @@ use crate::shared::{
 };
 use actix_web::{web, HttpRequest, HttpResponse};
 use nettu_scheduler_api_structs::add_user_to_service::*;
-use nettu_scheduler_domain::{Account, ServiceResource, TimePlan, ID};
+use nettu_scheduler_domain::{Account, ScheduleRuleInterval, ServiceResource, TimePlan, ID};
 use nettu_scheduler_infra::NettuContext;
 
 pub async fn add_user_to_service_controller(
@@ -25,6 +25,11 @@ pub async fn add_user_to_service_controller(
         buffer_after: body.buffer_after,
         closest_booking_time: body.closest_booking_time,
         furthest_booking_time: body.furthest_booking_time,
+        max_bookings_per_day: body.max_bookings_per_day,
+        max_booked_minutes_per_week: body.max_booked_minutes_per_week,
+        preferred_times: std::mem::take(&mut body.preferred_times),
+        weight: body.weight,
+        priority: body.priority,
     };
 
     execute(usecase, &ctx)
@@ -43,6 +48,11 @@ struct AddUserToServiceUseCase {
     pub buffer_after: Option<i64>,
     pub closest_booking_time: Option<i64>,
     pub furthest_booking_time: Option<i64>,
+    pub max_bookings_per_day: Option<i64>,
+    pub max_booked_minutes_per_week: Option<i64>,
+    pub preferred_times: Option<Vec<ScheduleRuleInterval>>,
+    pub weight: Option<i64>,
+    pub priority: Option<i64>,
 }
 
 #[derive(Debug)]
@@ -104,6 +114,11 @@ impl UseCase for AddUserToServiceUseCase {
                 buffer_before: self.buffer_before,
                 closest_booking_time: self.closest_booking_time,
                 furthest_booking_time: self.furthest_booking_time,
+                max_bookings_per_day: self.max_bookings_per_day,
+                max_booked_minutes_per_week: self.max_booked_minutes_per_week,
+                preferred_times: self.preferred_times.clone(),
+                weight: self.weight,
+                priority: self.priority,
             },
             ctx,
         )
@@ -128,6 +143,11 @@ pub struct ServiceResourceUpdate {
     pub buffer_before: Option<i64>,
     pub closest_booking_time: Option<i64>,
     pub furthest_booking_time: Option<i64>,
+    pub max_bookings_per_day: Option<i64>,
+    pub max_booked_minutes_per_week: Option<i64>,
+    pub preferred_times: Option<Vec<ScheduleRuleInterval>>,
+    pub weight: Option<i64>,
+    pub priority: Option<i64>,
 }
 
 #[derive(Debug)]
@@ -136,6 +156,9 @@ pub enum UpdateServiceResourceError {
     CalendarNotOwnedByUser(String),
     ScheduleNotOwnedByUser(String),
     InvalidBookingTimespan(String),
+    InvalidBookingCap(String),
+    InvalidPreferredTimes,
+    InvalidWeight,
 }
 
 impl UpdateServiceResourceError {
@@ -154,6 +177,15 @@ impl UpdateServiceResourceError {
             Self::InvalidBookingTimespan(e) => {
                 NettuError::BadClientData(e.to_string())
             }
+            Self::InvalidBookingCap(e) => {
+                NettuError::BadClientData(e.to_string())
+            }
+            Self::InvalidPreferredTimes => {
+                NettuError::BadClientData("The provided preferred times were invalid, there can be at most 10 intervals and every interval should start before it ends.".into())
+            }
+            Self::InvalidWeight => {
+                NettuError::BadClientData(format!("The provided weight was invalid, it should be between 1 and {}.", ServiceResource::MAX_WEIGHT))
+            }
         }
     }
 }
@@ -217,5 +249,39 @@ pub async fn update_resource_values(
     }
     user_resource.furthest_booking_time = update.furthest_booking_time;
 
+    if let Some(max_bookings_per_day) = &update.max_bookings_per_day {
+        if *max_bookings_per_day < 0 {
+            return Err(UpdateServiceResourceError::InvalidBookingCap(
+                "Max bookings per day cannot be negative.".into(),
+            ));
+        }
+    }
+    user_resource.max_bookings_per_day = update.max_bookings_per_day;
+
+    if let Some(max_booked_minutes_per_week) = &update.max_booked_minutes_per_week {
+        if *max_booked_minutes_per_week < 0 {
+            return Err(UpdateServiceResourceError::InvalidBookingCap(
+                "Max booked minutes per week cannot be negative.".into(),
+            ));
+        }
+    }
+    user_resource.max_booked_minutes_per_week = update.max_booked_minutes_per_week;
+
+    if let Some(preferred_times) = &update.preferred_times {
+        if preferred_times.len() > 10 || preferred_times.iter().any(|i| !i.is_valid()) {
+            return Err(UpdateServiceResourceError::InvalidPreferredTimes);
+        }
+        user_resource.preferred_times = preferred_times.clone();
+    }
+
+    if let Some(weight) = update.weight {
+        if !user_resource.set_weight(weight) {
+            return Err(UpdateServiceResourceError::InvalidWeight);
+        }
+    }
+    if let Some(priority) = update.priority {
+        user_resource.priority = priority;
+    }
+
     Ok(())
 }
@@ -2,7 +2,9 @@ use crate::shared::usecase::{execute, UseCase};
 use crate::{error::NettuError, shared::auth::protect_account_route};
 use actix_web::{web, HttpRequest, HttpResponse};
 use nettu_scheduler_api_structs::create_service::*;
-use nettu_scheduler_domain::{Account, Metadata, Service, ServiceMultiPersonOptions};
+use nettu_scheduler_domain::{
+    Account, Metadata, Service, ServiceBookingOptions, ServiceMultiPersonOptions,
+};
 use nettu_scheduler_infra::NettuContext;
 
 pub async fn create_service_controller(
@@ -17,6 +19,7 @@ pub async fn create_service_controller(
         account,
         metadata: body.metadata.unwrap_or_default(),
         multi_person: body.multi_person.unwrap_or_default(),
+        booking_options: body.booking_options.unwrap_or_default(),
     };
 
     execute(usecase, &ctx)
@@ -29,6 +32,7 @@ pub async fn create_service_controller(
 struct CreateServiceUseCase {
     account: Account,
     multi_person: ServiceMultiPersonOptions,
+    booking_options: ServiceBookingOptions,
     metadata: Metadata,
 }
 #[derive(Debug)]
@@ -38,12 +42,16 @@ struct UseCaseRes {
 
 #[derive(Debug)]
 enum UseCaseError {
+    InvalidBookingOptions,
     StorageError,
 }
 
 impl From<UseCaseError> for NettuError {
     fn from(e: UseCaseError) -> Self {
         match e {
+            UseCaseError::InvalidBookingOptions => {
+                Self::BadClientData("The provided booking options are invalid".into())
+            }
             UseCaseError::StorageError => Self::InternalError,
         }
     }
@@ -58,9 +66,14 @@ impl UseCase for CreateServiceUseCase {
     const NAME: &'static str = "CreateService";
 
     async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        if !self.booking_options.is_valid() {
+            return Err(UseCaseError::InvalidBookingOptions);
+        }
+
         let mut service = Service::new(self.account.id.clone());
         service.metadata = self.metadata.clone();
         service.multi_person = self.multi_person.clone();
+        service.booking_options = self.booking_options.clone();
 
         ctx.repos
             .services
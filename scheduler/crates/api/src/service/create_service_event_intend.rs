@@ -5,7 +5,7 @@ use crate::shared::{
     usecase::{execute, UseCase},
 };
 use actix_web::{web, HttpRequest, HttpResponse};
-use chrono::{Duration, TimeZone, Utc};
+use chrono::{Duration, TimeZone};
 use chrono_tz::UTC;
 use get_service_bookingslots::GetServiceBookingSlotsUseCase;
 use nettu_scheduler_api_structs::create_service_event_intend::*;
@@ -13,12 +13,14 @@ use nettu_scheduler_domain::{
     format_date,
     scheduling::{
         RoundRobinAlgorithm, RoundRobinAvailabilityAssignment,
-        RoundRobinEqualDistributionAssignment,
+        RoundRobinEqualDistributionAssignment, RoundRobinHost,
     },
-    ServiceMultiPersonOptions, User,
+    validate_intake_answers, CalendarEvent, IntakeAnswerError, IntakeAnswers,
+    ServiceMultiPersonOptions, ServiceWithUsers, TimeSpan, User,
 };
 use nettu_scheduler_domain::{Account, ID};
-use nettu_scheduler_infra::NettuContext;
+use nettu_scheduler_infra::{BookingCommitError, NettuContext};
+use std::collections::HashMap;
 
 pub async fn create_service_event_intend_controller(
     http_req: HttpRequest,
@@ -36,6 +38,13 @@ pub async fn create_service_event_intend_controller(
         duration: body.duration,
         timestamp: body.timestamp,
         interval: body.interval,
+        reserve_seat: true,
+        ignored_events: Vec::new(),
+        customer_key: None,
+        attributes: body.attributes,
+        intake_answers: Some(body.answers),
+        slot_offset: 0,
+        resource_id: None,
     };
 
     execute(usecase, &ctx)
@@ -43,6 +52,7 @@ pub async fn create_service_event_intend_controller(
         .map(|res| {
             HttpResponse::Ok().json(APIResponse::new(
                 res.selected_hosts,
+                res.selected_resource,
                 res.create_event_for_hosts,
             ))
         })
@@ -50,24 +60,60 @@ pub async fn create_service_event_intend_controller(
 }
 
 #[derive(Debug)]
-struct CreateServiceEventIntendUseCase {
+pub(crate) struct CreateServiceEventIntendUseCase {
     pub account: Account,
     pub service_id: ID,
     pub host_user_ids: Option<Vec<ID>>,
     pub timestamp: i64,
-    pub duration: i64,
-    pub interval: i64,
+    pub duration: Option<i64>,
+    pub interval: Option<i64>,
+    /// Whether a seat of a group `Service` is reserved right away. A `Booking`
+    /// reserves the seat itself when it is committed.
+    pub reserve_seat: bool,
+    /// The `CalendarEvent`s of a `Booking` that is being rescheduled, whose
+    /// time is considered free
+    pub ignored_events: Vec<CalendarEvent>,
+    /// Identifies a returning invitee, who is assigned the host of their
+    /// previous `Booking` if the `Service` has sticky hosts
+    pub customer_key: Option<String>,
+    /// Attributes of the invitee matched by the host routing rules of the `Service`
+    pub attributes: HashMap<String, String>,
+    /// Answers of the invitee which are validated against the intake questions
+    /// of the `Service`. `Booking`s validate the answers of their invitee
+    /// themselves and leave this empty.
+    pub intake_answers: Option<IntakeAnswers>,
+    /// Time in *milliseconds* the booking slots are shifted from the start of
+    /// the day, used for the later steps of a service chain
+    pub slot_offset: i64,
+    /// The resource that is kept if it is free, e.g. the resource of a
+    /// `Booking` that is being rescheduled
+    pub resource_id: Option<ID>,
 }
 
 #[derive(Debug)]
-struct UseCaseRes {
+pub(crate) struct UseCaseRes {
     pub selected_hosts: Vec<User>,
+    /// The resource from the resource pool of the `Service`, if it has one
+    pub selected_resource: Option<User>,
     pub create_event_for_hosts: bool,
 }
 
+impl UseCaseRes {
+    /// The selected hosts together with the selected resource, which all
+    /// get a `CalendarEvent` for the `Booking`
+    pub fn booked_users(&self) -> Vec<User> {
+        self.selected_hosts
+            .iter()
+            .chain(&self.selected_resource)
+            .cloned()
+            .collect()
+    }
+}
+
 #[derive(Debug)]
-enum UseCaseError {
+pub(crate) enum UseCaseError {
     UserNotAvailable,
+    InvalidIntakeAnswers(IntakeAnswerError),
     StorageError,
     BookingSlotsQuery(get_service_bookingslots::UseCaseError),
 }
@@ -78,12 +124,68 @@ impl From<UseCaseError> for NettuError {
             UseCaseError::UserNotAvailable => {
                 Self::BadClientData("The user is not available at the given time".into())
             }
+            UseCaseError::InvalidIntakeAnswers(e) => Self::BadClientData(e.to_string()),
             UseCaseError::StorageError => Self::InternalError,
             UseCaseError::BookingSlotsQuery(e) => e.into(),
         }
     }
 }
 
+/// Finds the assignments of the given hosts within the `FairnessWindow`
+/// of the `Service`
+pub(crate) async fn equal_distribution_assignment(
+    ctx: &NettuContext,
+    service: &ServiceWithUsers,
+    hosts: Vec<RoundRobinHost>,
+) -> RoundRobinEqualDistributionAssignment {
+    let fairness = &service.booking_options.fairness;
+    let window = fairness.timespan(ctx.sys.get_timestamp_millis());
+    let user_ids = hosts
+        .iter()
+        .map(|host| host.user_id.clone())
+        .collect::<Vec<_>>();
+
+    let events = ctx
+        .repos
+        .events
+        .find_by_service(&service.id, &user_ids, window.start(), window.end())
+        .await;
+    let bookings = ctx
+        .repos
+        .bookings
+        .find_active_by_hosts(&service.id, &user_ids, window.start(), window.end())
+        .await;
+
+    RoundRobinEqualDistributionAssignment {
+        events,
+        bookings,
+        hosts,
+        balance: fairness.balance,
+    }
+}
+
+/// Finds the host of the previous `Booking` of a returning invitee, if the
+/// `Service` has sticky hosts and that host is available at the slot
+async fn find_returning_host(
+    ctx: &NettuContext,
+    service: &ServiceWithUsers,
+    customer_key: Option<&str>,
+    user_ids_at_slot: &[ID],
+) -> Option<ID> {
+    if !service.booking_options.sticky_hosts {
+        return None;
+    }
+    let previous_booking = ctx
+        .repos
+        .bookings
+        .find_last_by_customer(&service.id, customer_key?)
+        .await?;
+    previous_booking
+        .host_user_ids
+        .into_iter()
+        .find(|user_id| user_ids_at_slot.contains(user_id))
+}
+
 #[async_trait::async_trait(?Send)]
 impl UseCase for CreateServiceEventIntendUseCase {
     type Response = UseCaseRes;
@@ -93,7 +195,7 @@ impl UseCase for CreateServiceEventIntendUseCase {
     const NAME: &'static str = "CreateServiceEventIntend";
 
     async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
-        let start = UTC.timestamp_millis(self.timestamp);
+        let start = UTC.timestamp_millis(self.timestamp - self.slot_offset);
         let start_date = format_date(&start);
         let day_after = start + Duration::days(1);
         let end_date = format_date(&day_after);
@@ -106,12 +208,29 @@ impl UseCase for CreateServiceEventIntendUseCase {
             timezone: Some(chrono_tz::UTC),
             interval: self.interval,
             host_user_ids: self.host_user_ids.clone(),
+            rank: false,
+            best_per_day: None,
+            ignored_events: self.ignored_events.clone(),
+            attributes: self.attributes.clone(),
+            slot_offset: self.slot_offset,
+            // The booked time is checked against the current external busy calendars
+            bypass_busy_cache: true,
         };
         let res = execute(get_bookingslots_usecase, ctx)
             .await
             .map_err(UseCaseError::BookingSlotsQuery)?;
         let service = res.service;
+        if let Some(answers) = &self.intake_answers {
+            validate_intake_answers(&service.booking_options.intake_questions, answers)
+                .map_err(UseCaseError::InvalidIntakeAnswers)?;
+        }
         let booking_slots_dates = res.booking_slots.dates;
+        let resource_ids_at_slot = booking_slots_dates
+            .iter()
+            .flat_map(|date| &date.slots)
+            .find(|slot| slot.start == self.timestamp)
+            .and_then(|slot| slot.resource_ids.clone())
+            .unwrap_or_default();
 
         let mut create_event_for_hosts = true;
         let selected_host_user_ids = if let Some(host_user_ids) = &self.host_user_ids {
@@ -169,8 +288,26 @@ impl UseCase for CreateServiceEventIntendUseCase {
                     .iter()
                     .map(|h| h.user_id.clone())
                     .collect::<Vec<_>>();
+                let returning_host = match &service.multi_person {
+                    ServiceMultiPersonOptions::RoundRobinAlgorithm(_) => {
+                        find_returning_host(
+                            ctx,
+                            &service,
+                            self.customer_key.as_deref(),
+                            &user_ids_at_slot,
+                        )
+                        .await
+                    }
+                    _ => None,
+                };
                 // Do round robin to get host
                 match &service.multi_person {
+                    // Returning invitees keep the host of their previous booking
+                    ServiceMultiPersonOptions::RoundRobinAlgorithm(_)
+                        if returning_host.is_some() =>
+                    {
+                        returning_host.into_iter().collect()
+                    }
                     ServiceMultiPersonOptions::RoundRobinAlgorithm(round_robin) => {
                         match round_robin {
                             RoundRobinAlgorithm::Availability => {
@@ -185,12 +322,30 @@ impl UseCase for CreateServiceEventIntendUseCase {
                                             &user_ids_at_slot,
                                         )
                                         .await;
+                                    let bookings = ctx
+                                        .repos
+                                        .bookings
+                                        .find_most_recently_created(&service.id, &user_ids_at_slot)
+                                        .await;
 
                                     let query = RoundRobinAvailabilityAssignment {
                                         members: events
                                             .into_iter()
-                                            .map(|e| (e.user_id, e.created))
+                                            .filter_map(|e| {
+                                                let host = hosts_at_slot
+                                                    .iter()
+                                                    .find(|h| h.user_id == e.user_id)?;
+                                                let booked = bookings
+                                                    .iter()
+                                                    .find(|b| b.user_id == e.user_id)
+                                                    .and_then(|b| b.created);
+                                                Some((
+                                                    RoundRobinHost::from(*host),
+                                                    e.created.max(booked),
+                                                ))
+                                            })
                                             .collect(),
+                                        timestamp: ctx.sys.get_timestamp_millis(),
                                     };
                                     let selected_user_id = query.assign().expect("At least one host can be picked when there are at least one host available");
                                     vec![selected_user_id]
@@ -200,24 +355,15 @@ impl UseCase for CreateServiceEventIntendUseCase {
                                 if hosts_at_slot.len() == 1 {
                                     vec![hosts_at_slot[0].user_id.clone()]
                                 } else {
-                                    let now = Utc::now().timestamp_millis();
-                                    let timestamp_in_two_months = now + 1000 * 60 * 60 * 24 * 61;
-
-                                    let service_events = ctx
-                                        .repos
-                                        .events
-                                        .find_by_service(
-                                            &service.id,
-                                            &user_ids_at_slot,
-                                            now,
-                                            timestamp_in_two_months,
-                                        )
-                                        .await;
-
-                                    let query = RoundRobinEqualDistributionAssignment {
-                                        events: service_events,
-                                        user_ids: user_ids_at_slot,
-                                    };
+                                    let query = equal_distribution_assignment(
+                                        ctx,
+                                        &service,
+                                        hosts_at_slot
+                                            .iter()
+                                            .map(|h| RoundRobinHost::from(*h))
+                                            .collect(),
+                                    )
+                                    .await;
                                     let selected_user_id = query.assign().expect("At least one host can be picked when there are at least one host available");
                                     vec![selected_user_id]
                                 }
@@ -238,6 +384,34 @@ impl UseCase for CreateServiceEventIntendUseCase {
 
                         all_hosts_user_ids
                     }
+                    ServiceMultiPersonOptions::CollectiveQuorum(quorum) => {
+                        let optional_user_ids = quorum
+                            .available_optional(&user_ids_at_slot)
+                            .ok_or(UseCaseError::UserNotAvailable)?;
+
+                        // The optional hosts are picked so that they get an equal
+                        // share of the bookings
+                        let mut query = equal_distribution_assignment(
+                            ctx,
+                            &service,
+                            hosts_at_slot
+                                .iter()
+                                .filter(|h| optional_user_ids.contains(&h.user_id))
+                                .map(|h| RoundRobinHost::from(*h))
+                                .collect(),
+                        )
+                        .await;
+
+                        let mut selected_user_ids = quorum.required_user_ids.clone();
+                        for _ in 0..quorum.min_optional {
+                            let selected_user_id = query.clone().assign().expect(
+                                "The quorum guarantees that enough optional hosts are available",
+                            );
+                            query.hosts.retain(|host| host.user_id != selected_user_id);
+                            selected_user_ids.push(selected_user_id);
+                        }
+                        selected_user_ids
+                    }
                     ServiceMultiPersonOptions::Group(max_count) => {
                         let all_hosts_user_ids: Vec<_> = service
                             .users
@@ -250,23 +424,47 @@ impl UseCase for CreateServiceEventIntendUseCase {
                             return Err(UseCaseError::UserNotAvailable);
                         }
 
-                        let reservations = ctx
-                            .repos
-                            .reservations
-                            .count(&service.id, self.timestamp)
-                            .await
-                            .map_err(|_| UseCaseError::StorageError)?;
+                        let reservations = if self.reserve_seat {
+                            let duration = service
+                                .booking_options
+                                .duration(self.duration)
+                                .ok_or(UseCaseError::UserNotAvailable)?;
+                            let timespan = TimeSpan::new(self.timestamp, self.timestamp + duration);
+                            // The seat is reserved under the same lock as `Booking`s and
+                            // `BookingHold`s, so the slot can not be overbooked
+                            ctx.repos
+                                .reservations
+                                .reserve_seat(
+                                    &service.id,
+                                    &all_hosts_user_ids,
+                                    &timespan,
+                                    *max_count,
+                                    ctx.sys.get_timestamp_millis(),
+                                )
+                                .await
+                                .map_err(|e| match e {
+                                    BookingCommitError::Conflict => UseCaseError::UserNotAvailable,
+                                    BookingCommitError::StorageError => UseCaseError::StorageError,
+                                })?
+                        } else {
+                            let reservations = ctx
+                                .repos
+                                .reservations
+                                .count(&service.id, self.timestamp)
+                                .await
+                                .map_err(|_| UseCaseError::StorageError)?;
+                            // The booking slots query hides full slots, but the seats
+                            // could have been taken since then
+                            if reservations >= *max_count {
+                                return Err(UseCaseError::UserNotAvailable);
+                            }
+                            reservations
+                        };
                         if reservations + 1 < *max_count {
                             // Client do not need to create service event yet
                             create_event_for_hosts = false;
                         }
 
-                        ctx.repos
-                            .reservations
-                            .increment(&service.id, self.timestamp)
-                            .await
-                            .map_err(|_| UseCaseError::StorageError)?;
-
                         all_hosts_user_ids
                     }
                 }
@@ -274,9 +472,23 @@ impl UseCase for CreateServiceEventIntendUseCase {
         };
 
         let selected_hosts = ctx.repos.users.find_many(&selected_host_user_ids).await;
+        let selected_resource = if service.resources.is_empty() {
+            None
+        } else {
+            // The requested or previous resource is kept when it is free at the
+            // slot, otherwise the first free resource of the pool is picked
+            let resource_id = self
+                .resource_id
+                .as_ref()
+                .filter(|resource_id| resource_ids_at_slot.contains(resource_id))
+                .or_else(|| resource_ids_at_slot.first())
+                .ok_or(UseCaseError::UserNotAvailable)?;
+            ctx.repos.users.find(resource_id).await
+        };
 
         Ok(UseCaseRes {
             selected_hosts,
+            selected_resource,
             create_event_for_hosts,
         })
     }
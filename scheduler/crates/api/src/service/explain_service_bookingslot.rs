@@ -0,0 +1,128 @@
+use super::get_service_bookingslots::{self, GetServiceBookingSlotsUseCase};
+use crate::{
+    error::NettuError,
+    shared::{
+        auth::protect_account_route,
+        usecase::{execute, UseCase},
+    },
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::TimeZone;
+use nettu_scheduler_api_structs::explain_service_bookingslot::*;
+use nettu_scheduler_domain::{
+    booking_slots::UnavailabilityReason, format_date, Account, TimeSpan, ID,
+};
+use nettu_scheduler_infra::NettuContext;
+
+pub async fn explain_service_bookingslot_controller(
+    http_req: HttpRequest,
+    query_params: web::Query<QueryParams>,
+    path_params: web::Path<PathParams>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let account = protect_account_route(&http_req, &ctx).await?;
+
+    let path_params = path_params.into_inner();
+    let usecase = ExplainServiceBookingSlotUseCase {
+        account,
+        service_id: path_params.service_id,
+        user_id: path_params.user_id,
+        timestamp: query_params.timestamp,
+        duration: query_params.duration,
+        bypass_busy_cache: query_params.bypass_busy_cache,
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|reasons| HttpResponse::Ok().json(APIResponse::new(reasons)))
+        .map_err(NettuError::from)
+}
+
+#[derive(Debug)]
+struct ExplainServiceBookingSlotUseCase {
+    pub account: Account,
+    pub service_id: ID,
+    pub user_id: ID,
+    pub timestamp: i64,
+    pub duration: Option<i64>,
+    pub bypass_busy_cache: bool,
+}
+
+#[derive(Debug)]
+enum UseCaseError {
+    ServiceNotFound,
+    UserNotFound,
+    InvalidDuration,
+    InvalidTimestamp,
+    BookingSlotsQuery(get_service_bookingslots::UseCaseError),
+}
+
+impl From<UseCaseError> for NettuError {
+    fn from(e: UseCaseError) -> Self {
+        match e {
+            UseCaseError::ServiceNotFound => Self::NotFound("Service was not found".into()),
+            UseCaseError::UserNotFound => {
+                Self::NotFound("The user is not registered on the service".into())
+            }
+            UseCaseError::InvalidDuration => Self::BadClientData(
+                "Invalid duration specified. It should be one of the durations allowed by the service.".into(),
+            ),
+            UseCaseError::InvalidTimestamp => {
+                Self::BadClientData("The provided timestamp is invalid".into())
+            }
+            UseCaseError::BookingSlotsQuery(e) => e.into(),
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for ExplainServiceBookingSlotUseCase {
+    /// Every reason the `User` can not be booked at the booking slot
+    type Response = Vec<UnavailabilityReason>;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "ExplainServiceBookingSlot";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        let service = match ctx.repos.services.find_with_users(&self.service_id).await {
+            Some(service) if service.account_id == self.account.id => service,
+            _ => return Err(UseCaseError::ServiceNotFound),
+        };
+        let service_resource = service
+            .users
+            .iter()
+            .chain(service.resources.iter())
+            .find(|resource| resource.user_id == self.user_id)
+            .ok_or(UseCaseError::UserNotFound)?;
+        let duration = service
+            .booking_options
+            .duration(self.duration)
+            .ok_or(UseCaseError::InvalidDuration)?;
+        let date = match chrono_tz::UTC.timestamp_millis_opt(self.timestamp) {
+            chrono::LocalResult::Single(date) => format_date(&date),
+            _ => return Err(UseCaseError::InvalidTimestamp),
+        };
+
+        let usecase = GetServiceBookingSlotsUseCase {
+            service_id: service.id.clone(),
+            start_date: date.clone(),
+            end_date: date,
+            timezone: Some(chrono_tz::UTC),
+            duration: Some(duration),
+            interval: None,
+            host_user_ids: Some(vec![self.user_id.clone()]),
+            rank: false,
+            best_per_day: None,
+            ignored_events: Vec::new(),
+            attributes: Default::default(),
+            slot_offset: 0,
+            bypass_busy_cache: self.bypass_busy_cache,
+        };
+        let slot = TimeSpan::new(self.timestamp, self.timestamp + duration);
+        usecase
+            .explain_unavailability(&service, service_resource, &slot, ctx)
+            .await
+            .map_err(UseCaseError::BookingSlotsQuery)
+    }
+}
@@ -0,0 +1,222 @@
+use futures::future::join_all;
+use nettu_scheduler_domain::{
+    BusyCacheFreshness, EventInstance, ExternalBusyCacheEntry, IntegrationProvider, TimeSpan, User,
+};
+use nettu_scheduler_infra::{
+    google_calendar::GoogleCalendarProvider, outlook_calendar::OutlookCalendarProvider,
+    FreeBusyProviderQuery, NettuContext,
+};
+use tracing::error;
+
+/// The timespan whose busy intervals are fetched and cached for a query. It is
+/// widened to whole days, as the timespans of booking slot queries are clamped
+/// to the current time and would otherwise never be covered by a cache entry.
+fn cached_timespan(timespan: &TimeSpan) -> TimeSpan {
+    let day = 1000 * 60 * 60 * 24;
+    TimeSpan::new(
+        timespan.start().div_euclid(day) * day,
+        ((timespan.end() - 1).div_euclid(day) + 1) * day,
+    )
+}
+
+/// Finds the busy intervals of the external calendars of the `User` at the
+/// given provider. The intervals are served from the cache of external busy
+/// intervals while they are fresh, and only the calendars without a usable
+/// cache entry are queried at the provider. Stale entries are served while
+/// they are refetched in the background, by only one request at a time.
+pub async fn get_external_busy(
+    user: &User,
+    provider: IntegrationProvider,
+    ext_calendar_ids: Vec<String>,
+    timespan: &TimeSpan,
+    bypass_cache: bool,
+    ctx: &NettuContext,
+) -> Vec<EventInstance> {
+    let cached_timespan = cached_timespan(timespan);
+    let busy_in_timespan = |busy: Vec<EventInstance>| {
+        busy.into_iter()
+            .filter(|instance| {
+                instance.start_ts < timespan.end() && instance.end_ts > timespan.start()
+            })
+            .collect::<Vec<_>>()
+    };
+    if bypass_cache {
+        let busy =
+            fetch_external_busy(user, &provider, ext_calendar_ids, &cached_timespan, ctx).await;
+        return busy_in_timespan(busy);
+    }
+
+    let now = ctx.sys.get_timestamp_millis();
+    let mut busy = Vec::new();
+    let mut expired_calendar_ids = Vec::new();
+    for ext_calendar_id in ext_calendar_ids {
+        let entry = match ctx
+            .repos
+            .external_busy_cache
+            .find(&user.id, &provider, &ext_calendar_id, &cached_timespan)
+            .await
+        {
+            Some(entry) => entry,
+            None => {
+                expired_calendar_ids.push(ext_calendar_id);
+                continue;
+            }
+        };
+        match entry.freshness(
+            now,
+            ctx.config.external_busy_cache_ttl,
+            ctx.config.external_busy_cache_stale_ttl,
+        ) {
+            BusyCacheFreshness::Fresh => busy.append(&mut entry.busy_in(timespan)),
+            BusyCacheFreshness::Stale => {
+                busy.append(&mut entry.busy_in(timespan));
+                refresh_external_busy(user, entry, now, ctx).await;
+            }
+            BusyCacheFreshness::Expired => expired_calendar_ids.push(ext_calendar_id),
+        }
+    }
+
+    if !expired_calendar_ids.is_empty() {
+        let expired_busy =
+            fetch_external_busy(user, &provider, expired_calendar_ids, &cached_timespan, ctx).await;
+        busy.append(&mut busy_in_timespan(expired_busy));
+    }
+
+    busy
+}
+
+/// Refetches the whole timespan of a stale entry in the background, unless
+/// another request is refetching it already
+async fn refresh_external_busy(
+    user: &User,
+    entry: ExternalBusyCacheEntry,
+    now: i64,
+    ctx: &NettuContext,
+) {
+    match ctx
+        .repos
+        .external_busy_cache
+        .start_refresh(&entry, now)
+        .await
+    {
+        Ok(true) => (),
+        _ => return,
+    }
+    let user = user.clone();
+    let ctx = ctx.clone();
+    actix_web::rt::spawn(async move {
+        let timespan = TimeSpan::new(entry.start_ts, entry.end_ts);
+        fetch_external_busy(
+            &user,
+            &entry.provider,
+            vec![entry.ext_calendar_id],
+            &timespan,
+            &ctx,
+        )
+        .await;
+    });
+}
+
+/// Queries the busy intervals of the external calendars at the provider and
+/// caches them for every calendar they could be fetched for
+async fn fetch_external_busy(
+    user: &User,
+    provider: &IntegrationProvider,
+    ext_calendar_ids: Vec<String>,
+    timespan: &TimeSpan,
+    ctx: &NettuContext,
+) -> Vec<EventInstance> {
+    let query = |ext_calendar_id: &String| FreeBusyProviderQuery {
+        calendar_ids: vec![ext_calendar_id.clone()],
+        start: timespan.start(),
+        end: timespan.end(),
+    };
+    let calendars_busy = match provider {
+        IntegrationProvider::Google => match GoogleCalendarProvider::new(user, ctx).await {
+            Ok(google_calendar_provider) => {
+                join_all(
+                    ext_calendar_ids
+                        .iter()
+                        .map(|id| google_calendar_provider.try_freebusy(query(id))),
+                )
+                .await
+            }
+            Err(_) => {
+                error!(
+                    "Unable to initialize google calendar provider for user : {:?}",
+                    user
+                );
+                return Vec::new();
+            }
+        },
+        IntegrationProvider::Outlook => match OutlookCalendarProvider::new(user, ctx).await {
+            Ok(outlook_calendar_provider) => {
+                join_all(
+                    ext_calendar_ids
+                        .iter()
+                        .map(|id| outlook_calendar_provider.try_freebusy(query(id))),
+                )
+                .await
+            }
+            Err(_) => {
+                error!(
+                    "Unable to initialize outlook calendar provider for user : {:?}",
+                    user
+                );
+                return Vec::new();
+            }
+        },
+    };
+
+    let fetched_at = ctx.sys.get_timestamp_millis();
+    // Entries which can no longer be served are pruned from the cache
+    let expired_before =
+        fetched_at - ctx.config.external_busy_cache_ttl - ctx.config.external_busy_cache_stale_ttl;
+    let mut busy = Vec::new();
+    for (ext_calendar_id, calendar_busy) in ext_calendar_ids.into_iter().zip(calendars_busy) {
+        let calendar_busy = match calendar_busy {
+            Ok(calendar_busy) => calendar_busy.inner().into(),
+            // Failures are not cached, so the calendar is queried again next time
+            Err(_) => continue,
+        };
+        let entry = ExternalBusyCacheEntry {
+            user_id: user.id.clone(),
+            provider: provider.clone(),
+            ext_calendar_id,
+            start_ts: timespan.start(),
+            end_ts: timespan.end(),
+            busy: calendar_busy,
+            fetched_at,
+        };
+        // Sideeffect, the busy intervals are refetched next time if this fails
+        let _ = ctx
+            .repos
+            .external_busy_cache
+            .save(&entry, expired_before)
+            .await;
+        busy.extend(entry.busy);
+    }
+
+    busy
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_caches_whole_days_of_moving_timespans() {
+        let day = 1000 * 60 * 60 * 24;
+        let cached = cached_timespan(&TimeSpan::new(day * 2 + 1234, day * 9 + 5678));
+        assert_eq!(cached.start(), day * 2);
+        assert_eq!(cached.end(), day * 10);
+        // The timespan of a later query with the same dates is covered
+        let later = cached_timespan(&TimeSpan::new(day * 2 + 4321, day * 9 + 8765));
+        assert_eq!(later.start(), cached.start());
+        assert_eq!(later.end(), cached.end());
+
+        let whole_days = cached_timespan(&TimeSpan::new(day, day * 3));
+        assert_eq!(whole_days.start(), day);
+        assert_eq!(whole_days.end(), day * 3);
+    }
+}
@@ -1,3 +1,4 @@
+use super::external_busy::get_external_busy;
 use crate::shared::usecase::{execute, UseCase};
 use crate::{error::NettuError, user::parse_vec_query_value};
 use actix_web::{web, HttpRequest, HttpResponse};
@@ -5,17 +6,18 @@ use futures::future::join_all;
 use nettu_scheduler_api_structs::get_service_bookingslots::*;
 use nettu_scheduler_domain::{
     booking_slots::{
+        apply_collective_quorum, apply_group_capacity, apply_resource_pool,
         get_service_bookingslots, validate_bookingslots_query, validate_slots_interval,
         BookingQueryError, BookingSlotsOptions, BookingSlotsQuery, ServiceBookingSlots,
-        UserFreeEvents,
+        UnavailabilityReason, UserFreeEvents,
     },
-    get_free_busy, BusyCalendar, Calendar, CompatibleInstances, EventInstance,
+    get_free_busy,
+    scheduling::{matches_host_routing, score_slots, SlotScoreAggregation},
+    BusyCalendar, Calendar, CalendarEvent, CompatibleInstances, EventInstance, IntegrationProvider,
     ServiceMultiPersonOptions, ServiceResource, ServiceWithUsers, TimePlan, TimeSpan, Tz, ID,
 };
-use nettu_scheduler_infra::{
-    google_calendar::GoogleCalendarProvider, outlook_calendar::OutlookCalendarProvider,
-    FreeBusyProviderQuery, NettuContext,
-};
+use nettu_scheduler_infra::NettuContext;
+use std::collections::HashMap;
 use tracing::error;
 
 pub async fn get_service_bookingslots_controller(
@@ -36,6 +38,12 @@ pub async fn get_service_bookingslots_controller(
         duration: query_params.duration,
         interval: query_params.interval,
         host_user_ids,
+        rank: query_params.rank,
+        best_per_day: query_params.best_per_day,
+        ignored_events: Vec::new(),
+        attributes: parse_attributes_query_value(&query_params.attributes),
+        slot_offset: 0,
+        bypass_busy_cache: query_params.bypass_busy_cache,
     };
 
     execute(usecase, &ctx)
@@ -44,15 +52,41 @@ pub async fn get_service_bookingslots_controller(
         .map_err(NettuError::from)
 }
 
+/// Parses invitee attributes given as comma separated `key:value` pairs
+pub(super) fn parse_attributes_query_value(val: &Option<String>) -> HashMap<String, String> {
+    val.as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|attribute| attribute.split_once(':'))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
 #[derive(Debug)]
 pub(crate) struct GetServiceBookingSlotsUseCase {
     pub service_id: ID,
     pub start_date: String,
     pub end_date: String,
     pub timezone: Option<Tz>,
-    pub duration: i64,
-    pub interval: i64,
+    pub duration: Option<i64>,
+    pub interval: Option<i64>,
     pub host_user_ids: Option<Vec<ID>>,
+    /// Score every booking slot by how well it fits the hosts
+    pub rank: bool,
+    /// Only keep the highest scoring booking slots of every date
+    pub best_per_day: Option<usize>,
+    /// The `CalendarEvent`s of a `Booking` that is being rescheduled. The
+    /// time they occupy is considered free.
+    pub ignored_events: Vec<CalendarEvent>,
+    /// Attributes of the invitee matched by the host routing rules of the `Service`
+    pub attributes: HashMap<String, String>,
+    /// Time in *milliseconds* the booking slots are shifted from the start of
+    /// the dates, so that the later steps of a service chain line up with the
+    /// booking slots of the first step
+    pub slot_offset: i64,
+    /// Query the external Google and Outlook busy calendars at the provider
+    /// instead of using the cached busy intervals
+    pub bypass_busy_cache: bool,
 }
 
 impl From<UseCaseError> for NettuError {
@@ -69,10 +103,16 @@ impl From<UseCaseError> for NettuError {
                     "Invalid interval specified. It should be between 10 - 60 minutes inclusively and be specified as milliseconds.".into()
                 )
             }
+            UseCaseError::InvalidDuration => {
+                Self::BadClientData(
+                    "Invalid duration specified. It should be one of the durations allowed by the service.".into()
+                )
+            }
             UseCaseError::InvalidTimespan => {
                 Self::BadClientData("The provided start and end is invalid".into())
             }
-            UseCaseError::ServiceNotFound => Self::NotFound("Service was not found".into())
+            UseCaseError::ServiceNotFound => Self::NotFound("Service was not found".into()),
+            UseCaseError::StorageError => Self::InternalError,
         }
     }
 }
@@ -83,12 +123,44 @@ pub(crate) struct UseCaseRes {
     pub service: ServiceWithUsers,
 }
 
+/// The busy times of a `ServiceResource` by what makes them busy
+#[derive(Debug, Default)]
+struct UserBusyTimes {
+    /// Busy `CalendarEvent`s of the nettu busy calendars and the events of
+    /// the other `Service`s of the `User`
+    events: Vec<EventInstance>,
+    /// The buffers around the `Service` events in the nettu busy calendars
+    buffers: Vec<EventInstance>,
+    /// Times held by `BookingHold`s
+    holds: Vec<EventInstance>,
+    google: Vec<EventInstance>,
+    outlook: Vec<EventInstance>,
+}
+
+impl UserBusyTimes {
+    fn into_instances(self) -> CompatibleInstances {
+        // This should be optimized later
+        CompatibleInstances::new(
+            [
+                self.events,
+                self.buffers,
+                self.holds,
+                self.google,
+                self.outlook,
+            ]
+            .concat(),
+        )
+    }
+}
+
 #[derive(Debug)]
 pub(crate) enum UseCaseError {
     ServiceNotFound,
+    InvalidDuration,
     InvalidInterval,
     InvalidTimespan,
     InvalidDate(String),
+    StorageError,
 }
 
 impl From<BookingQueryError> for UseCaseError {
@@ -110,23 +182,30 @@ impl UseCase for GetServiceBookingSlotsUseCase {
     const NAME: &'static str = "GetServiceBookingSlots";
 
     async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
-        if !validate_slots_interval(self.interval) {
-            return Err(UseCaseError::InvalidInterval);
-        }
+        let service = match ctx.repos.services.find_with_users(&self.service_id).await {
+            Some(s) => s,
+            None => return Err(UseCaseError::ServiceNotFound),
+        };
+
+        let duration = service
+            .booking_options
+            .duration(self.duration)
+            .ok_or(UseCaseError::InvalidDuration)?;
+        let interval = match service.booking_options.interval(self.interval) {
+            Some(interval) if validate_slots_interval(interval) => interval,
+            _ => return Err(UseCaseError::InvalidInterval),
+        };
 
         let query = BookingSlotsQuery {
             start_date: self.start_date.clone(),
             end_date: self.end_date.clone(),
             timezone: self.timezone,
-            interval: self.interval,
-            duration: self.duration,
-        };
-        let booking_timespan = validate_bookingslots_query(&query)?;
-
-        let service = match ctx.repos.services.find_with_users(&self.service_id).await {
-            Some(s) => s,
-            None => return Err(UseCaseError::ServiceNotFound),
+            interval,
+            duration,
         };
+        let mut booking_timespan = validate_bookingslots_query(&query)?;
+        booking_timespan.start_ts += self.slot_offset;
+        booking_timespan.end_ts += self.slot_offset;
 
         if ServiceMultiPersonOptions::Group(0) == service.multi_person {
             return Ok(UseCaseRes {
@@ -142,35 +221,35 @@ impl UseCase for GetServiceBookingSlotsUseCase {
             return Err(UseCaseError::InvalidTimespan);
         }
 
+        let hosts = self.route_hosts(&service, ctx).await;
         match &self.host_user_ids {
             Some(host_user_ids) => {
                 for user_id in host_user_ids {
-                    let user = service.users.iter().find(|u| u.user_id == *user_id);
+                    let user = hosts.iter().find(|u| u.user_id == *user_id);
                     if let Some(user) = user {
                         let timespan = timespan.clone();
-                        usecase_futures.push(self.get_bookable_times(user, timespan, ctx));
+                        usecase_futures
+                            .push(self.get_bookable_times(user, timespan, duration, ctx));
                     }
                 }
             }
             None => {
-                for user in &service.users {
+                for user in &hosts {
                     let timespan = timespan.clone();
-                    usecase_futures.push(self.get_bookable_times(user, timespan, ctx));
+                    usecase_futures.push(self.get_bookable_times(user, timespan, duration, ctx));
                 }
             }
         }
 
         let users_free_events = join_all(usecase_futures).await;
 
-        let mut booking_slots = get_service_bookingslots(
-            users_free_events,
-            &BookingSlotsOptions {
-                interval: self.interval,
-                duration: self.duration,
-                end_ts: booking_timespan.end_ts,
-                start_ts: booking_timespan.start_ts,
-            },
-        );
+        let options = BookingSlotsOptions {
+            interval,
+            duration,
+            end_ts: booking_timespan.end_ts,
+            start_ts: booking_timespan.start_ts,
+        };
+        let mut booking_slots = get_service_bookingslots(&users_free_events, &options);
 
         booking_slots = match service.multi_person {
             ServiceMultiPersonOptions::Collective | ServiceMultiPersonOptions::Group(_) => {
@@ -179,25 +258,129 @@ impl UseCase for GetServiceBookingSlotsUseCase {
                     .filter(|slot| slot.user_ids.len() == service.users.len())
                     .collect()
             }
+            ServiceMultiPersonOptions::CollectiveQuorum(ref quorum) => {
+                apply_collective_quorum(booking_slots, quorum)
+            }
             _ => booking_slots,
         };
 
+        if !service.resources.is_empty() {
+            let resources_free_events = join_all(service.resources.iter().map(|resource| {
+                self.get_bookable_times(resource, timespan.clone(), duration, ctx)
+            }))
+            .await;
+            let resource_slots = get_service_bookingslots(&resources_free_events, &options);
+            booking_slots = apply_resource_pool(booking_slots, resource_slots);
+        }
+
+        if let ServiceMultiPersonOptions::Group(max_count) = service.multi_person {
+            let mut reservations = ctx
+                .repos
+                .reservations
+                .find_by_timespan(
+                    &service.id,
+                    booking_timespan.start_ts,
+                    booking_timespan.end_ts,
+                )
+                .await
+                .map_err(|_| UseCaseError::StorageError)?;
+            let mut held_seats = ctx
+                .repos
+                .booking_holds
+                .find_seats_by_timespan(
+                    &service.id,
+                    booking_timespan.start_ts,
+                    booking_timespan.end_ts,
+                    ctx.sys.get_timestamp_millis(),
+                )
+                .await
+                .map_err(|_| UseCaseError::StorageError)?;
+            reservations.append(&mut held_seats);
+            booking_slots = apply_group_capacity(booking_slots, max_count, &reservations);
+        }
+
+        if self.rank || self.best_per_day.is_some() {
+            let aggregation = match service.multi_person {
+                ServiceMultiPersonOptions::RoundRobinAlgorithm(_) => SlotScoreAggregation::Best,
+                _ => SlotScoreAggregation::Average,
+            };
+            score_slots(&mut booking_slots, &users_free_events, aggregation);
+        }
+
+        let mut booking_slots = ServiceBookingSlots::new(booking_slots, chrono_tz::UTC);
+        if let Some(best_per_day) = self.best_per_day {
+            booking_slots.retain_best_per_day(best_per_day);
+        }
+
         Ok(UseCaseRes {
-            booking_slots: ServiceBookingSlots::new(booking_slots, chrono_tz::UTC),
+            booking_slots,
             service,
         })
     }
 }
 
 impl GetServiceBookingSlotsUseCase {
+    /// The hosts of the `Service` that can be booked by the invitee. For round
+    /// robin `Service`s these are the hosts whose metadata satisfies the host
+    /// routing rules.
+    async fn route_hosts<'a>(
+        &self,
+        service: &'a ServiceWithUsers,
+        ctx: &NettuContext,
+    ) -> Vec<&'a ServiceResource> {
+        let rules = &service.booking_options.host_routing;
+        if rules.is_empty()
+            || !matches!(
+                service.multi_person,
+                ServiceMultiPersonOptions::RoundRobinAlgorithm(_)
+            )
+        {
+            return service.users.iter().collect();
+        }
+
+        let user_ids = service
+            .users
+            .iter()
+            .map(|resource| resource.user_id.clone())
+            .collect::<Vec<_>>();
+        let routed_user_ids = ctx
+            .repos
+            .users
+            .find_many(&user_ids)
+            .await
+            .into_iter()
+            .filter(|user| matches_host_routing(rules, &user.metadata, &self.attributes))
+            .map(|user| user.id)
+            .collect::<Vec<_>>();
+        service
+            .users
+            .iter()
+            .filter(|resource| routed_user_ids.contains(&resource.user_id))
+            .collect()
+    }
+
+    fn is_ignored(&self, event: &CalendarEvent) -> bool {
+        self.ignored_events.iter().any(|e| e.id == event.id)
+    }
+
+    /// Busy times from external calendars only tell the time, so the synced
+    /// copies of the ignored `CalendarEvent`s are recognized by their timespan
+    fn is_ignored_instance(&self, user_id: &ID, instance: &EventInstance) -> bool {
+        self.ignored_events.iter().any(|e| {
+            e.user_id == *user_id && e.start_ts == instance.start_ts && e.end_ts == instance.end_ts
+        })
+    }
+
+    /// Finds the available times for a `User` together with the timezone of
+    /// the time plan describing the availability
     async fn get_user_availability(
         &self,
         user: &ServiceResource,
         user_calendars: &[Calendar],
         timespan: &TimeSpan,
         ctx: &NettuContext,
-    ) -> CompatibleInstances {
-        let empty = CompatibleInstances::new(Vec::new());
+    ) -> (CompatibleInstances, Tz) {
+        let empty = (CompatibleInstances::new(Vec::new()), chrono_tz::UTC);
         match &user.availability {
             TimePlan::Calendar(id) => {
                 let calendar = match user_calendars.iter().find(|cal| cal.id == *id) {
@@ -219,10 +402,15 @@ impl GetServiceBookingSlotsUseCase {
                     .flatten()
                     .collect::<Vec<_>>();
 
-                get_free_busy(all_event_instances).free
+                (
+                    get_free_busy(all_event_instances).free,
+                    calendar.settings.timezone,
+                )
             }
             TimePlan::Schedule(id) => match ctx.repos.schedules.find(id).await {
-                Some(schedule) if schedule.user_id == user.user_id => schedule.freebusy(timespan),
+                Some(schedule) if schedule.user_id == user.user_id => {
+                    (schedule.freebusy(timespan), schedule.timezone)
+                }
                 _ => empty,
             },
             TimePlan::Empty => empty,
@@ -235,7 +423,7 @@ impl GetServiceBookingSlotsUseCase {
         user_nettu_calendars: &[Calendar],
         timespan: &TimeSpan,
         ctx: &NettuContext,
-    ) -> CompatibleInstances {
+    ) -> UserBusyTimes {
         let busy_calendars = match ctx
             .repos
             .service_user_busy_calendars
@@ -243,7 +431,7 @@ impl GetServiceBookingSlotsUseCase {
             .await
         {
             Ok(val) => val,
-            Err(_) => return CompatibleInstances::new(Vec::new()),
+            Err(_) => return Default::default(),
         };
 
         let nettu_busy_calendar_ids = busy_calendars
@@ -272,11 +460,15 @@ impl GetServiceBookingSlotsUseCase {
             })
             .collect::<Vec<_>>();
 
-        let mut busy_events: Vec<EventInstance> = Vec::new();
+        let mut busy = UserBusyTimes::default();
 
-        let all_service_resources = ctx.repos.service_users.find_by_user(&user.user_id).await;
+        let service_buffers = if nettu_busy_calendars.is_empty() {
+            Vec::new()
+        } else {
+            Self::get_service_buffers(&user.user_id, ctx).await
+        };
 
-        let mut busy_service_events = ctx
+        busy.events = ctx
             .repos
             .events
             .find_user_service_events(&user.user_id, false, timespan.start(), timespan.end())
@@ -287,13 +479,31 @@ impl GetServiceBookingSlotsUseCase {
                 Some(service_id) => service_id != &self.service_id,
                 _ => unreachable!("Queried only for events with a service id"),
             })
+            .filter(|e| !self.is_ignored(e))
             .map(|e| EventInstance {
                 busy: true,
                 start_ts: e.start_ts,
                 end_ts: e.end_ts,
             })
             .collect::<Vec<_>>();
-        busy_events.append(&mut busy_service_events);
+
+        busy.holds = ctx
+            .repos
+            .booking_holds
+            .find_active_by_host(
+                &user.user_id,
+                timespan.start(),
+                timespan.end(),
+                ctx.sys.get_timestamp_millis(),
+            )
+            .await
+            .into_iter()
+            .map(|hold| EventInstance {
+                busy: true,
+                start_ts: hold.start_ts,
+                end_ts: hold.end_ts(),
+            })
+            .collect::<Vec<_>>();
 
         for cal in nettu_busy_calendars {
             match ctx
@@ -303,35 +513,41 @@ impl GetServiceBookingSlotsUseCase {
                 .await
             {
                 Ok(calendar_events) => {
-                    let mut calendar_busy_events = calendar_events
+                    for e in calendar_events
                         .into_iter()
-                        .filter(|e| e.busy)
-                        .map(|e| {
-                            let mut instances = e.expand(Some(timespan), &cal.settings);
-
-                            // Add buffer to instances if event is a service event
-                            if let Some(service_id) = e.service_id {
-                                if let Some(service_resource) = all_service_resources
-                                    .iter()
-                                    .find(|s| s.service_id == service_id)
-                                {
-                                    let buffer_after_in_millis =
-                                        service_resource.buffer_after * 60 * 1000;
-                                    let buffer_before_in_millis =
-                                        service_resource.buffer_before * 60 * 1000;
-                                    for instance in instances.iter_mut() {
-                                        instance.end_ts += buffer_after_in_millis;
-                                        instance.start_ts -= buffer_before_in_millis;
-                                    }
-                                }
+                        .filter(|e| e.busy && !self.is_ignored(e))
+                    {
+                        let instances = e.expand(Some(timespan), &cal.settings);
+
+                        // Add buffer to instances if event is a service event
+                        let buffers = e.service_id.and_then(|service_id| {
+                            service_buffers.iter().find(|(id, _, _)| *id == service_id)
+                        });
+                        if let Some((_, buffer_before, buffer_after)) = buffers {
+                            let buffer_after_in_millis = buffer_after * 60 * 1000;
+                            let buffer_before_in_millis = buffer_before * 60 * 1000;
+                            for instance in &instances {
+                                let before = EventInstance {
+                                    busy: true,
+                                    start_ts: instance.start_ts - buffer_before_in_millis,
+                                    end_ts: instance.start_ts,
+                                };
+                                let after = EventInstance {
+                                    busy: true,
+                                    start_ts: instance.end_ts,
+                                    end_ts: instance.end_ts + buffer_after_in_millis,
+                                };
+                                busy.buffers.extend(
+                                    [before, after]
+                                        .iter()
+                                        .filter(|buffer| buffer.end_ts > buffer.start_ts)
+                                        .cloned(),
+                                );
                             }
+                        }
 
-                            instances
-                        })
-                        .flatten()
-                        .collect::<Vec<_>>();
-
-                    busy_events.append(&mut calendar_busy_events);
+                        busy.events.extend(instances);
+                    }
                 }
                 Err(e) => {
                     error!("Unable to fetch user calendars: {}", e);
@@ -339,7 +555,14 @@ impl GetServiceBookingSlotsUseCase {
             }
         }
 
-        if !google_busy_calendar_ids.is_empty() {
+        let external_busy_calendar_ids = [
+            (IntegrationProvider::Google, google_busy_calendar_ids),
+            (IntegrationProvider::Outlook, outlook_busy_calendar_ids),
+        ];
+        if external_busy_calendar_ids
+            .iter()
+            .any(|(_, ext_calendar_ids)| !ext_calendar_ids.is_empty())
+        {
             // TODO: no unwrap
             let user = ctx
                 .repos
@@ -347,50 +570,62 @@ impl GetServiceBookingSlotsUseCase {
                 .find(&user.user_id)
                 .await
                 .expect("User to be found");
-            match GoogleCalendarProvider::new(&user, ctx).await {
-                Ok(google_calendar_provider) => {
-                    let query = FreeBusyProviderQuery {
-                        calendar_ids: google_busy_calendar_ids,
-                        end: timespan.end(),
-                        start: timespan.start(),
-                    };
-                    let google_busy = google_calendar_provider.freebusy(query).await;
-                    for google_busy_event in google_busy.inner() {
-                        busy_events.push(google_busy_event);
-                    }
-                }
-                Err(_) => {
-                    error!(
-                        "Unable to initialize google calendar provider for user : {:?}",
-                        user
-                    );
+            for (provider, ext_calendar_ids) in external_busy_calendar_ids {
+                if ext_calendar_ids.is_empty() {
+                    continue;
                 }
-            }
-        }
-
-        if !outlook_busy_calendar_ids.is_empty() {
-            // TODO: no unwrap
-            let user = ctx
-                .repos
-                .users
-                .find(&user.user_id)
+                let external_busy = get_external_busy(
+                    &user,
+                    provider.clone(),
+                    ext_calendar_ids,
+                    timespan,
+                    self.bypass_busy_cache,
+                    ctx,
+                )
                 .await
-                .expect("User to be found");
-            if let Ok(provider) = OutlookCalendarProvider::new(&user, ctx).await {
-                let query = FreeBusyProviderQuery {
-                    calendar_ids: outlook_busy_calendar_ids,
-                    end: timespan.end(),
-                    start: timespan.start(),
-                };
-                let outlook_busy = provider.freebusy(query).await;
-                for outlook_busy_event in outlook_busy.inner() {
-                    busy_events.push(outlook_busy_event);
+                .into_iter()
+                .filter(|instance| !self.is_ignored_instance(&user.id, instance));
+                match provider {
+                    IntegrationProvider::Google => busy.google.extend(external_busy),
+                    IntegrationProvider::Outlook => busy.outlook.extend(external_busy),
                 }
             }
         }
 
-        // This should be optimized later
-        CompatibleInstances::new(busy_events)
+        busy
+    }
+
+    /// Finds the buffers in minutes for every `Service` the `User` is registered on.
+    /// The booking options of a `Service` take precedence over the buffers of
+    /// the `ServiceResource`.
+    async fn get_service_buffers(user_id: &ID, ctx: &NettuContext) -> Vec<(ID, i64, i64)> {
+        let service_resources = ctx.repos.service_users.find_by_user(user_id).await;
+        let service_ids = service_resources
+            .iter()
+            .map(|resource| resource.service_id.clone())
+            .collect::<Vec<_>>();
+        let services = ctx.repos.services.find_many(&service_ids).await;
+
+        service_resources
+            .into_iter()
+            .map(|resource| {
+                match services
+                    .iter()
+                    .find(|service| service.id == resource.service_id)
+                {
+                    Some(service) => (
+                        resource.service_id.clone(),
+                        service.booking_options.buffer_before(&resource),
+                        service.booking_options.buffer_after(&resource),
+                    ),
+                    None => (
+                        resource.service_id.clone(),
+                        resource.buffer_before,
+                        resource.buffer_after,
+                    ),
+                }
+            })
+            .collect()
     }
 
     /// Ensure that calendar timespan fits within user settings for when
@@ -422,16 +657,166 @@ impl GetServiceBookingSlotsUseCase {
         }
     }
 
+    /// Finds the days and weeks where the `ServiceResource` has reached its
+    /// booking caps for this `Service`. The caps are not locked when a
+    /// `Booking` is committed, so they are only enforced on a best-effort basis.
+    async fn get_booking_caps_reached(
+        &self,
+        service_resource: &ServiceResource,
+        timespan: &TimeSpan,
+        tz: &Tz,
+        duration: i64,
+        ctx: &NettuContext,
+    ) -> CompatibleInstances {
+        let caps_timespan = match service_resource.booking_caps_timespan(timespan, tz) {
+            Some(caps_timespan) => caps_timespan,
+            None => return CompatibleInstances::new(Vec::new()),
+        };
+
+        let bookings = ctx
+            .repos
+            .events
+            .find_by_service(
+                &self.service_id,
+                std::slice::from_ref(&service_resource.user_id),
+                caps_timespan.start(),
+                caps_timespan.end(),
+            )
+            .await
+            .into_iter()
+            .filter(|e| !self.is_ignored(e))
+            .map(|e| EventInstance {
+                busy: true,
+                start_ts: e.start_ts,
+                end_ts: e.end_ts,
+            })
+            .collect::<Vec<_>>();
+
+        CompatibleInstances::new(
+            service_resource.booking_caps_reached(&bookings, timespan, tz, duration),
+        )
+    }
+
+    /// Finds every reason the `ServiceResource` can not be booked during the
+    /// given timespan. Empty if it can be booked.
+    pub(super) async fn explain_unavailability(
+        &self,
+        service: &ServiceWithUsers,
+        service_resource: &ServiceResource,
+        slot: &TimeSpan,
+        ctx: &NettuContext,
+    ) -> Result<Vec<UnavailabilityReason>, UseCaseError> {
+        let overlaps = |instances: &[EventInstance]| {
+            instances
+                .iter()
+                .any(|instance| instance.start_ts < slot.end() && instance.end_ts > slot.start())
+        };
+        let now = ctx.sys.get_timestamp_millis();
+        let mut reasons = Vec::new();
+
+        if slot.start() < now + service_resource.closest_booking_time * 60 * 1000 {
+            reasons.push(UnavailabilityReason::ClosestBookingTime);
+        }
+        if let Some(furthest_booking_time) = service_resource.furthest_booking_time {
+            if now + furthest_booking_time * 60 * 1000 < slot.end() {
+                reasons.push(UnavailabilityReason::FurthestBookingTime);
+            }
+        }
+
+        let user_calendars = ctx
+            .repos
+            .calendars
+            .find_by_user(&service_resource.user_id)
+            .await;
+        let (free_events, tz) = self
+            .get_user_availability(service_resource, &user_calendars, slot, ctx)
+            .await;
+        if !free_events
+            .as_ref()
+            .iter()
+            .any(|free| free.start_ts <= slot.start() && free.end_ts >= slot.end())
+        {
+            reasons.push(UnavailabilityReason::OutsideSchedule);
+        }
+
+        // Events ending before or starting after the slot can still block it
+        // with their buffers, so the busy times around the slot are loaded as well
+        let service_buffers = Self::get_service_buffers(&service_resource.user_id, ctx).await;
+        let max_buffer_before = service_buffers
+            .iter()
+            .map(|(_, buffer_before, _)| *buffer_before)
+            .max()
+            .unwrap_or(0);
+        let max_buffer_after = service_buffers
+            .iter()
+            .map(|(_, _, buffer_after)| *buffer_after)
+            .max()
+            .unwrap_or(0);
+        let busy_timespan = TimeSpan::new(
+            slot.start() - max_buffer_after * 60 * 1000,
+            slot.end() + max_buffer_before * 60 * 1000,
+        );
+        let busy = self
+            .get_user_busy(service_resource, &user_calendars, &busy_timespan, ctx)
+            .await;
+        for (busy, reason) in [
+            (&busy.events, UnavailabilityReason::BusyEvent),
+            (&busy.google, UnavailabilityReason::BusyGoogleCalendar),
+            (&busy.outlook, UnavailabilityReason::BusyOutlookCalendar),
+            (&busy.holds, UnavailabilityReason::Held),
+            (&busy.buffers, UnavailabilityReason::Buffer),
+        ] {
+            if overlaps(busy) {
+                reasons.push(reason);
+            }
+        }
+
+        let booking_caps_reached = self
+            .get_booking_caps_reached(service_resource, slot, &tz, slot.duration(), ctx)
+            .await;
+        if overlaps(&Vec::from(booking_caps_reached.inner())) {
+            reasons.push(UnavailabilityReason::BookingCapReached);
+        }
+
+        if let ServiceMultiPersonOptions::Group(max_count) = service.multi_person {
+            let mut reservations = ctx
+                .repos
+                .reservations
+                .find_by_timespan(&service.id, slot.start(), slot.end())
+                .await
+                .map_err(|_| UseCaseError::StorageError)?;
+            let mut held_seats = ctx
+                .repos
+                .booking_holds
+                .find_seats_by_timespan(&service.id, slot.start(), slot.end(), now)
+                .await
+                .map_err(|_| UseCaseError::StorageError)?;
+            reservations.append(&mut held_seats);
+            let seats_taken: usize = reservations
+                .iter()
+                .filter(|reservation| reservation.timestamp == slot.start())
+                .map(|reservation| reservation.count)
+                .sum();
+            if seats_taken >= max_count {
+                reasons.push(UnavailabilityReason::GroupCapacity);
+            }
+        }
+
+        Ok(reasons)
+    }
+
     /// Finds the bookable times for a `User`.
     async fn get_bookable_times(
         &self,
         service_resource: &ServiceResource,
         mut timespan: TimeSpan,
+        duration: i64,
         ctx: &NettuContext,
     ) -> UserFreeEvents {
         let empty = UserFreeEvents {
             free_events: CompatibleInstances::new(Vec::new()),
             user_id: service_resource.user_id.clone(),
+            preferred_times: Vec::new(),
         };
 
         match Self::parse_calendar_timespan(service_resource, timespan, ctx) {
@@ -458,19 +843,26 @@ impl GetServiceBookingSlotsUseCase {
         //     })
         //     .collect::<Vec<_>>();
 
-        let mut free_events = self
+        let (mut free_events, tz) = self
             .get_user_availability(service_resource, &user_calendars, &timespan, ctx)
             .await;
 
         let busy_events = self
             .get_user_busy(service_resource, &user_calendars, &timespan, ctx)
-            .await;
+            .await
+            .into_instances();
 
         free_events.remove_instances(&busy_events, 0);
 
+        let booking_caps_reached = self
+            .get_booking_caps_reached(service_resource, &timespan, &tz, duration, ctx)
+            .await;
+        free_events.remove_instances(&booking_caps_reached, 0);
+
         UserFreeEvents {
             free_events,
             user_id: service_resource.user_id.clone(),
+            preferred_times: service_resource.preferred_times_instances(&timespan, &tz),
         }
     }
 }
@@ -530,6 +922,11 @@ mod test {
             availability: TimePlan::Empty,
             closest_booking_time: 0,
             furthest_booking_time: None,
+            max_bookings_per_day: None,
+            max_booked_minutes_per_week: None,
+            preferred_times: Vec::new(),
+            weight: ServiceResource::DEFAULT_WEIGHT,
+            priority: 0,
         };
         let mut resource2 = ServiceResource {
             user_id: user2.id.clone(),
@@ -539,6 +936,11 @@ mod test {
             availability: TimePlan::Empty,
             closest_booking_time: 0,
             furthest_booking_time: None,
+            max_bookings_per_day: None,
+            max_booked_minutes_per_week: None,
+            preferred_times: Vec::new(),
+            weight: ServiceResource::DEFAULT_WEIGHT,
+            priority: 0,
         };
 
         let calendar_user_1 = Calendar::new(&resource1.user_id, &account_id);
@@ -596,11 +998,17 @@ mod test {
         let mut usecase = GetServiceBookingSlotsUseCase {
             start_date: "2010-1-1".into(),
             end_date: "2010-1-1".into(),
-            duration: 1000 * 60 * 60,
+            duration: Some(1000 * 60 * 60),
             timezone: Some(chrono_tz::UTC),
-            interval: 1000 * 60 * 15,
+            interval: Some(1000 * 60 * 15),
             service_id: service.id,
             host_user_ids: None,
+            rank: false,
+            best_per_day: None,
+            ignored_events: Vec::new(),
+            attributes: HashMap::new(),
+            slot_offset: 0,
+            bypass_busy_cache: false,
         };
 
         let res = usecase.execute(&ctx).await;
@@ -621,11 +1029,17 @@ mod test {
         let mut usecase = GetServiceBookingSlotsUseCase {
             start_date: "2010-1-1".into(),
             end_date: "2010-1-1".into(),
-            duration: 1000 * 60 * 60,
+            duration: Some(1000 * 60 * 60),
             timezone: Some(chrono_tz::UTC),
-            interval: 1000 * 60 * 15,
+            interval: Some(1000 * 60 * 15),
             service_id: service.id.clone(),
             host_user_ids: None,
+            rank: false,
+            best_per_day: None,
+            ignored_events: Vec::new(),
+            attributes: HashMap::new(),
+            slot_offset: 0,
+            bypass_busy_cache: false,
         };
 
         let res = usecase.execute(&ctx).await;
@@ -636,7 +1050,7 @@ mod test {
 
         assert_eq!(booking_slots.len(), 4);
         for i in 0..4 {
-            assert_eq!(booking_slots[i].duration, usecase.duration);
+            assert_eq!(booking_slots[i].duration, 1000 * 60 * 60);
             assert_eq!(booking_slots[i].user_ids.len(), 1);
             assert_eq!(
                 booking_slots[i].start,
@@ -649,11 +1063,17 @@ mod test {
         let mut usecase = GetServiceBookingSlotsUseCase {
             start_date: "1970-1-1".into(),
             end_date: "1970-1-1".into(),
-            duration: 1000 * 60 * 60,
+            duration: Some(1000 * 60 * 60),
             timezone: Some(chrono_tz::UTC),
-            interval: 1000 * 60 * 15,
+            interval: Some(1000 * 60 * 15),
             service_id: service.id,
             host_user_ids: None,
+            rank: false,
+            best_per_day: None,
+            ignored_events: Vec::new(),
+            attributes: HashMap::new(),
+            slot_offset: 0,
+            bypass_busy_cache: false,
         };
 
         let res = usecase.execute(&ctx).await;
@@ -665,7 +1085,7 @@ mod test {
         assert_eq!(booking_slots.len(), 5);
         assert_eq!(booking_slots[0].user_ids.len(), 2);
         for i in 0..5 {
-            assert_eq!(booking_slots[i].duration, usecase.duration);
+            assert_eq!(booking_slots[i].duration, 1000 * 60 * 60);
             if i > 0 {
                 assert_eq!(booking_slots[i].user_ids.len(), 1);
                 assert_eq!(
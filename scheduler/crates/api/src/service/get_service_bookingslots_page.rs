@@ -0,0 +1,141 @@
+use super::get_service_bookingslots::{
+    self, parse_attributes_query_value, GetServiceBookingSlotsUseCase,
+};
+use crate::shared::usecase::{execute, UseCase};
+use crate::{error::NettuError, user::parse_vec_query_value};
+use actix_web::{web, HttpResponse};
+use nettu_scheduler_api_structs::get_service_bookingslots_page::*;
+use nettu_scheduler_domain::{
+    booking_slots::{BookingSlotsPage, ServiceBookingSlots},
+    Tz, ID,
+};
+use nettu_scheduler_infra::NettuContext;
+use std::collections::HashMap;
+
+pub async fn get_service_bookingslots_page_controller(
+    query_params: web::Query<QueryParams>,
+    mut path_params: web::Path<PathParams>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let query_params = query_params.0;
+
+    let usecase = GetServiceBookingSlotsPageUseCase {
+        service_id: std::mem::take(&mut path_params.service_id),
+        timezone: query_params.timezone,
+        // The cursor is the first date of the following page
+        start_date: query_params.cursor.or(query_params.start_date),
+        days: query_params.days.unwrap_or(DEFAULT_PAGE_DAYS),
+        duration: query_params.duration,
+        interval: query_params.interval,
+        host_user_ids: parse_vec_query_value(&query_params.host_user_ids),
+        attributes: parse_attributes_query_value(&query_params.attributes),
+        bypass_busy_cache: query_params.bypass_busy_cache,
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|res| HttpResponse::Ok().json(APIResponse::new(res.booking_slots, res.next_cursor)))
+        .map_err(NettuError::from)
+}
+
+const DEFAULT_PAGE_DAYS: i64 = 7;
+
+#[derive(Debug)]
+struct GetServiceBookingSlotsPageUseCase {
+    pub service_id: ID,
+    pub start_date: Option<String>,
+    pub days: i64,
+    pub timezone: Option<Tz>,
+    pub duration: Option<i64>,
+    pub interval: Option<i64>,
+    pub host_user_ids: Option<Vec<ID>>,
+    pub attributes: HashMap<String, String>,
+    pub bypass_busy_cache: bool,
+}
+
+#[derive(Debug)]
+struct UseCaseRes {
+    pub booking_slots: ServiceBookingSlots,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug)]
+enum UseCaseError {
+    MissingStartDate,
+    InvalidPageSize,
+    InvalidDate(String),
+    BookingSlotsQuery(get_service_bookingslots::UseCaseError),
+}
+
+impl From<UseCaseError> for NettuError {
+    fn from(e: UseCaseError) -> Self {
+        match e {
+            UseCaseError::MissingStartDate => {
+                Self::BadClientData("Either a start date or a cursor has to be provided".into())
+            }
+            UseCaseError::InvalidPageSize => Self::BadClientData(format!(
+                "Invalid amount of days specified. It should be between 1 and {}.",
+                BookingSlotsPage::MAX_DAYS
+            )),
+            UseCaseError::InvalidDate(date) => Self::BadClientData(format!(
+                "Invalid start date or cursor: {}. The date should be YYYY-MM-DD, e.g. January 1. 2020 => 2020-1-1",
+                date
+            )),
+            UseCaseError::BookingSlotsQuery(e) => e.into(),
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for GetServiceBookingSlotsPageUseCase {
+    type Response = UseCaseRes;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "GetServiceBookingSlotsPage";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        if !(1..=BookingSlotsPage::MAX_DAYS).contains(&self.days) {
+            return Err(UseCaseError::InvalidPageSize);
+        }
+        let start_date = self
+            .start_date
+            .as_ref()
+            .ok_or(UseCaseError::MissingStartDate)?;
+        let page = BookingSlotsPage::new(start_date, self.days)
+            .map_err(|_| UseCaseError::InvalidDate(start_date.clone()))?;
+
+        let usecase = GetServiceBookingSlotsUseCase {
+            service_id: self.service_id.clone(),
+            start_date: page.start_date.clone(),
+            end_date: page.end_date.clone(),
+            timezone: self.timezone,
+            duration: self.duration,
+            interval: self.interval,
+            host_user_ids: self.host_user_ids.clone(),
+            rank: false,
+            best_per_day: None,
+            ignored_events: Vec::new(),
+            attributes: self.attributes.clone(),
+            slot_offset: 0,
+            bypass_busy_cache: self.bypass_busy_cache,
+        };
+        let res = execute(usecase, ctx)
+            .await
+            .map_err(UseCaseError::BookingSlotsQuery)?;
+
+        let last_bookable = res.service.last_bookable_ts(
+            self.host_user_ids.as_deref(),
+            ctx.sys.get_timestamp_millis(),
+        );
+        let next_cursor = match last_bookable {
+            Some(last_bookable) if page.next_start_ts(self.timezone) >= last_bookable => None,
+            _ => Some(page.next_start_date),
+        };
+
+        Ok(UseCaseRes {
+            booking_slots: res.booking_slots,
+            next_cursor,
+        })
+    }
+}
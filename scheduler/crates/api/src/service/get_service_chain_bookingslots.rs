@@ -0,0 +1,194 @@
+use super::get_service_bookingslots::{self, GetServiceBookingSlotsUseCase};
+use crate::error::NettuError;
+use crate::shared::usecase::{execute, UseCase};
+use actix_web::{web, HttpResponse};
+use nettu_scheduler_api_structs::get_service_chain_bookingslots::*;
+use nettu_scheduler_domain::{
+    booking_slots::{
+        get_service_chain_bookingslots, validate_slots_interval, ServiceChainBookingSlot,
+        ServiceChainStep,
+    },
+    ServiceWithUsers, Tz, ID,
+};
+use nettu_scheduler_infra::NettuContext;
+use std::collections::HashMap;
+
+pub async fn get_service_chain_bookingslots_controller(
+    body: web::Json<RequestBody>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let body = body.0;
+    let usecase = GetServiceChainBookingSlotsUseCase {
+        steps: body.steps,
+        start_date: body.start_date,
+        end_date: body.end_date,
+        timezone: body.timezone,
+        interval: body.interval,
+        attributes: body.attributes,
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|res| HttpResponse::Ok().json(APIResponse::new(res.slots, &res.steps)))
+        .map_err(NettuError::from)
+}
+
+#[derive(Debug)]
+struct GetServiceChainBookingSlotsUseCase {
+    pub steps: Vec<ServiceChainStep>,
+    pub start_date: String,
+    pub end_date: String,
+    pub timezone: Option<Tz>,
+    pub interval: Option<i64>,
+    pub attributes: HashMap<String, String>,
+}
+
+#[derive(Debug)]
+struct UseCaseRes {
+    pub slots: Vec<ServiceChainBookingSlot>,
+    pub steps: Vec<ServiceChainStep>,
+}
+
+#[derive(Debug)]
+pub(crate) enum UseCaseError {
+    InvalidChain,
+    ServiceNotFound(ID),
+    InvalidDuration(ID),
+    InvalidInterval,
+    IntervalNotAllowed(ID),
+    BookingSlotsQuery(get_service_bookingslots::UseCaseError),
+}
+
+impl From<UseCaseError> for NettuError {
+    fn from(e: UseCaseError) -> Self {
+        match e {
+            UseCaseError::InvalidChain => Self::BadClientData(format!(
+                "A service chain should have between 1 and {} steps and the gaps between them can not be negative.",
+                ServiceChainStep::MAX_STEPS
+            )),
+            UseCaseError::ServiceNotFound(id) => {
+                Self::NotFound(format!("The service with id: {} was not found.", id))
+            }
+            UseCaseError::InvalidDuration(id) => Self::BadClientData(format!(
+                "Invalid duration specified for the service with id: {}. It should be one of the durations allowed by the service.",
+                id
+            )),
+            UseCaseError::InvalidInterval => Self::BadClientData(
+                "Invalid interval specified. It should be between 10 - 60 minutes inclusively and be specified as milliseconds.".into(),
+            ),
+            UseCaseError::IntervalNotAllowed(id) => Self::BadClientData(format!(
+                "The interval of the chain is not allowed by the service with id: {}. It should be a multiple of the default interval of every service in the chain.",
+                id
+            )),
+            UseCaseError::BookingSlotsQuery(e) => e.into(),
+        }
+    }
+}
+
+/// The `Service`s of a chain together with the resolved duration of every
+/// step and its offset from the start of the chain
+#[derive(Debug)]
+pub(crate) struct ServiceChain {
+    pub services: Vec<ServiceWithUsers>,
+    pub durations: Vec<i64>,
+    pub offsets: Vec<i64>,
+    /// Interval between the start times of the chain
+    pub interval: i64,
+}
+
+impl ServiceChain {
+    pub async fn resolve(
+        steps: &[ServiceChainStep],
+        interval: Option<i64>,
+        ctx: &NettuContext,
+    ) -> Result<Self, UseCaseError> {
+        if !ServiceChainStep::is_valid_chain(steps) {
+            return Err(UseCaseError::InvalidChain);
+        }
+
+        let mut services = Vec::with_capacity(steps.len());
+        let mut durations = Vec::with_capacity(steps.len());
+        for step in steps {
+            let service = ctx
+                .repos
+                .services
+                .find_with_users(&step.service_id)
+                .await
+                .ok_or_else(|| UseCaseError::ServiceNotFound(step.service_id.clone()))?;
+            let duration = service
+                .booking_options
+                .duration(step.duration)
+                .ok_or_else(|| UseCaseError::InvalidDuration(step.service_id.clone()))?;
+            services.push(service);
+            durations.push(duration);
+        }
+        let interval = match services[0].booking_options.interval(interval) {
+            Some(interval) if validate_slots_interval(interval) => interval,
+            _ => return Err(UseCaseError::InvalidInterval),
+        };
+        // Every step is queried with the interval of the chain, so all the
+        // services have to allow it
+        if let Some(service) = services
+            .iter()
+            .find(|service| service.booking_options.interval(Some(interval)).is_none())
+        {
+            return Err(UseCaseError::IntervalNotAllowed(service.id.clone()));
+        }
+
+        Ok(Self {
+            offsets: ServiceChainStep::offsets(steps, &durations),
+            services,
+            durations,
+            interval,
+        })
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for GetServiceChainBookingSlotsUseCase {
+    type Response = UseCaseRes;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "GetServiceChainBookingSlots";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        let chain = ServiceChain::resolve(&self.steps, self.interval, ctx).await?;
+
+        let mut steps_slots = Vec::with_capacity(self.steps.len());
+        for (i, step) in self.steps.iter().enumerate() {
+            // Every step uses the interval of the chain, shifted by its offset,
+            // so that its booking slots line up with the ones of the first step
+            let usecase = GetServiceBookingSlotsUseCase {
+                service_id: step.service_id.clone(),
+                start_date: self.start_date.clone(),
+                end_date: self.end_date.clone(),
+                timezone: self.timezone,
+                duration: Some(chain.durations[i]),
+                interval: Some(chain.interval),
+                host_user_ids: step.host_user_ids.clone(),
+                rank: false,
+                best_per_day: None,
+                ignored_events: Vec::new(),
+                attributes: self.attributes.clone(),
+                slot_offset: chain.offsets[i],
+                bypass_busy_cache: false,
+            };
+            let res = execute(usecase, ctx)
+                .await
+                .map_err(UseCaseError::BookingSlotsQuery)?;
+            steps_slots.push(
+                res.booking_slots
+                    .dates
+                    .into_iter()
+                    .flat_map(|date| date.slots)
+                    .collect(),
+            );
+        }
+
+        Ok(UseCaseRes {
+            slots: get_service_chain_bookingslots(steps_slots, &chain.offsets),
+            steps: std::mem::take(&mut self.steps),
+        })
+    }
+}
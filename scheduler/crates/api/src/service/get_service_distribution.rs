@@ -0,0 +1,79 @@
+use super::create_service_event_intend::equal_distribution_assignment;
+use crate::{
+    error::NettuError,
+    shared::{
+        auth::protect_account_route,
+        usecase::{execute, UseCase},
+    },
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use nettu_scheduler_api_structs::get_service_distribution::*;
+use nettu_scheduler_domain::{scheduling::RoundRobinHost, Account, ID};
+use nettu_scheduler_infra::NettuContext;
+
+pub async fn get_service_distribution_controller(
+    http_req: HttpRequest,
+    path_params: web::Path<PathParams>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let account = protect_account_route(&http_req, &ctx).await?;
+
+    let usecase = GetServiceDistributionUseCase {
+        account,
+        service_id: path_params.service_id.clone(),
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|res| HttpResponse::Ok().json(res))
+        .map_err(NettuError::from)
+}
+
+#[derive(Debug)]
+struct GetServiceDistributionUseCase {
+    account: Account,
+    service_id: ID,
+}
+
+#[derive(Debug)]
+enum UseCaseError {
+    NotFound(ID),
+}
+
+impl From<UseCaseError> for NettuError {
+    fn from(e: UseCaseError) -> Self {
+        match e {
+            UseCaseError::NotFound(id) => {
+                Self::NotFound(format!("The service with id: {} was not found.", id))
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for GetServiceDistributionUseCase {
+    type Response = APIResponse;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "GetServiceDistribution";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        let service = match ctx.repos.services.find_with_users(&self.service_id).await {
+            Some(service) if service.account_id == self.account.id => service,
+            _ => return Err(UseCaseError::NotFound(self.service_id.clone())),
+        };
+
+        let fairness = &service.booking_options.fairness;
+        let window = fairness.timespan(ctx.sys.get_timestamp_millis());
+        let hosts = service.users.iter().map(RoundRobinHost::from).collect();
+        let query = equal_distribution_assignment(ctx, &service, hosts).await;
+
+        Ok(APIResponse::new(
+            window.start(),
+            window.end(),
+            fairness.balance,
+            query.distribution(),
+        ))
+    }
+}
@@ -0,0 +1,140 @@
+use super::get_service_bookingslots::{
+    self, parse_attributes_query_value, GetServiceBookingSlotsUseCase,
+};
+use crate::shared::usecase::{execute, UseCase};
+use crate::{error::NettuError, user::parse_vec_query_value};
+use actix_web::{web, HttpResponse};
+use chrono::TimeZone;
+use nettu_scheduler_api_structs::get_service_next_bookingslot::*;
+use nettu_scheduler_domain::{
+    booking_slots::{BookingSlotsPage, ServiceBookingSlot},
+    format_date, ID,
+};
+use nettu_scheduler_infra::NettuContext;
+use std::collections::HashMap;
+
+pub async fn get_service_next_bookingslot_controller(
+    query_params: web::Query<QueryParams>,
+    mut path_params: web::Path<PathParams>,
+    ctx: web::Data<NettuContext>,
+) -> Result<HttpResponse, NettuError> {
+    let query_params = query_params.0;
+
+    let usecase = GetServiceNextBookingSlotUseCase {
+        service_id: std::mem::take(&mut path_params.service_id),
+        after: query_params.after,
+        duration: query_params.duration,
+        interval: query_params.interval,
+        host_user_ids: parse_vec_query_value(&query_params.host_user_ids),
+        attributes: parse_attributes_query_value(&query_params.attributes),
+        bypass_busy_cache: query_params.bypass_busy_cache,
+    };
+
+    execute(usecase, &ctx)
+        .await
+        .map(|slot| HttpResponse::Ok().json(APIResponse::new(slot)))
+        .map_err(NettuError::from)
+}
+
+#[derive(Debug)]
+struct GetServiceNextBookingSlotUseCase {
+    pub service_id: ID,
+    pub after: Option<i64>,
+    pub duration: Option<i64>,
+    pub interval: Option<i64>,
+    pub host_user_ids: Option<Vec<ID>>,
+    pub attributes: HashMap<String, String>,
+    pub bypass_busy_cache: bool,
+}
+
+#[derive(Debug)]
+enum UseCaseError {
+    InvalidTimestamp,
+    BookingSlotsQuery(get_service_bookingslots::UseCaseError),
+}
+
+impl From<UseCaseError> for NettuError {
+    fn from(e: UseCaseError) -> Self {
+        match e {
+            UseCaseError::InvalidTimestamp => {
+                Self::BadClientData("The provided start timestamp is invalid".into())
+            }
+            UseCaseError::BookingSlotsQuery(e) => e.into(),
+        }
+    }
+}
+
+impl GetServiceNextBookingSlotUseCase {
+    /// Amount of dates searched at first. Every following search window is
+    /// twice as long, up to `BookingSlotsPage::MAX_DAYS`.
+    const INITIAL_SEARCH_DAYS: i64 = 7;
+    /// How far ahead to search when the hosts have no furthest booking time, 1 year
+    const MAX_SEARCH_DURATION: i64 = 1000 * 60 * 60 * 24 * 365;
+}
+
+#[async_trait::async_trait(?Send)]
+impl UseCase for GetServiceNextBookingSlotUseCase {
+    /// The earliest bookable slot
+    type Response = Option<ServiceBookingSlot>;
+
+    type Error = UseCaseError;
+
+    const NAME: &'static str = "GetServiceNextBookingSlot";
+
+    async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        let now = ctx.sys.get_timestamp_millis();
+        // Slots in the past are never bookable, so the search starts now at the earliest
+        let after = self.after.map_or(now, |after| after.max(now));
+        let search_end = after
+            .checked_add(Self::MAX_SEARCH_DURATION)
+            .ok_or(UseCaseError::InvalidTimestamp)?;
+
+        let mut start_date = match chrono_tz::UTC.timestamp_millis_opt(after) {
+            chrono::LocalResult::Single(date) => format_date(&date),
+            _ => return Err(UseCaseError::InvalidTimestamp),
+        };
+        let mut days = Self::INITIAL_SEARCH_DAYS;
+        loop {
+            let page = BookingSlotsPage::new(&start_date, days)
+                .map_err(|_| UseCaseError::InvalidTimestamp)?;
+            let usecase = GetServiceBookingSlotsUseCase {
+                service_id: self.service_id.clone(),
+                start_date: page.start_date.clone(),
+                end_date: page.end_date.clone(),
+                timezone: Some(chrono_tz::UTC),
+                duration: self.duration,
+                interval: self.interval,
+                host_user_ids: self.host_user_ids.clone(),
+                rank: false,
+                best_per_day: None,
+                ignored_events: Vec::new(),
+                attributes: self.attributes.clone(),
+                slot_offset: 0,
+                bypass_busy_cache: self.bypass_busy_cache,
+            };
+            let res = execute(usecase, ctx)
+                .await
+                .map_err(UseCaseError::BookingSlotsQuery)?;
+
+            let slot = res
+                .booking_slots
+                .dates
+                .into_iter()
+                .flat_map(|date| date.slots)
+                .find(|slot| slot.start >= after);
+            if slot.is_some() {
+                return Ok(slot);
+            }
+
+            let last_bookable = res
+                .service
+                .last_bookable_ts(self.host_user_ids.as_deref(), now)
+                .map_or(search_end, |last_bookable| last_bookable.min(search_end));
+            if page.next_start_ts(None) >= last_bookable {
+                return Ok(None);
+            }
+            start_date = page.next_start_date;
+            days = (days * 2).min(BookingSlotsPage::MAX_DAYS);
+        }
+    }
+}
@@ -1,10 +1,16 @@
 mod add_busy_calendar;
 mod add_user_to_service;
 mod create_service;
-mod create_service_event_intend;
+pub(crate) mod create_service_event_intend;
 mod delete_service;
+mod explain_service_bookingslot;
+mod external_busy;
 mod get_service;
 mod get_service_bookingslots;
+mod get_service_bookingslots_page;
+pub(crate) mod get_service_chain_bookingslots;
+mod get_service_distribution;
+mod get_service_next_bookingslot;
 mod get_services_by_meta;
 mod remove_busy_calendar;
 mod remove_service_event_intend;
@@ -18,8 +24,13 @@ use add_user_to_service::add_user_to_service_controller;
 use create_service::create_service_controller;
 use create_service_event_intend::create_service_event_intend_controller;
 use delete_service::delete_service_controller;
+use explain_service_bookingslot::explain_service_bookingslot_controller;
 use get_service::get_service_controller;
 use get_service_bookingslots::get_service_bookingslots_controller;
+use get_service_bookingslots_page::get_service_bookingslots_page_controller;
+use get_service_chain_bookingslots::get_service_chain_bookingslots_controller;
+use get_service_distribution::get_service_distribution_controller;
+use get_service_next_bookingslot::get_service_next_bookingslot_controller;
 use get_services_by_meta::get_services_by_meta_controller;
 use remove_busy_calendar::remove_busy_calendar_controller;
 use remove_service_event_intend::remove_service_event_intend_controller;
@@ -65,10 +76,30 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
         "/service/{service_id}/users/{user_id}/busy",
         web::delete().to(remove_busy_calendar_controller),
     );
+    cfg.route(
+        "/service/{service_id}/users/{user_id}/explain",
+        web::get().to(explain_service_bookingslot_controller),
+    );
     cfg.route(
         "/service/{service_id}/booking",
         web::get().to(get_service_bookingslots_controller),
     );
+    cfg.route(
+        "/service/{service_id}/booking/page",
+        web::get().to(get_service_bookingslots_page_controller),
+    );
+    cfg.route(
+        "/service/{service_id}/booking/next",
+        web::get().to(get_service_next_bookingslot_controller),
+    );
+    cfg.route(
+        "/service-chain/booking",
+        web::post().to(get_service_chain_bookingslots_controller),
+    );
+    cfg.route(
+        "/service/{service_id}/distribution",
+        web::get().to(get_service_distribution_controller),
+    );
     cfg.route(
         "/service/{service_id}/booking-intend",
         web::post().to(create_service_event_intend_controller),
@@ -1,3 +1,4 @@
+use crate::booking::offer_waitlist_seat::OfferWaitlistSeatUseCase;
 use crate::error::NettuError;
 use crate::shared::{
     auth::protect_account_route,
@@ -5,7 +6,7 @@ use crate::shared::{
 };
 use actix_web::{web, HttpRequest, HttpResponse};
 use nettu_scheduler_api_structs::remove_service_event_intend::*;
-use nettu_scheduler_domain::{Account, ID};
+use nettu_scheduler_domain::{Account, BookingPolicyViolation, ID};
 use nettu_scheduler_infra::NettuContext;
 
 pub async fn remove_service_event_intend_controller(
@@ -21,6 +22,7 @@ pub async fn remove_service_event_intend_controller(
         account,
         service_id: std::mem::take(&mut path_params.service_id),
         timestamp: query.timestamp,
+        ignore_policy: query.ignore_policy,
     };
 
     execute(usecase, &ctx)
@@ -34,6 +36,8 @@ struct RemoveServiceEventIntendUseCase {
     pub account: Account,
     pub service_id: ID,
     pub timestamp: i64,
+    /// Whether the cancellation policy of the `Service` is overridden
+    pub ignore_policy: bool,
 }
 
 #[derive(Debug)]
@@ -42,6 +46,7 @@ struct UseCaseRes {}
 #[derive(Debug)]
 enum UseCaseError {
     ServiceNotFound,
+    PolicyViolation(BookingPolicyViolation),
     StorageError,
 }
 
@@ -51,6 +56,7 @@ impl From<UseCaseError> for NettuError {
             UseCaseError::ServiceNotFound => {
                 Self::NotFound("The requested service was not found".into())
             }
+            UseCaseError::PolicyViolation(e) => e.into(),
             UseCaseError::StorageError => Self::InternalError,
         }
     }
@@ -65,13 +71,33 @@ impl UseCase for RemoveServiceEventIntendUseCase {
     const NAME: &'static str = "RemoveServiceEventIntend";
 
     async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
-        match ctx.repos.services.find(&self.service_id).await {
-            Some(s) if s.account_id == self.account.id => (),
+        let service = match ctx.repos.services.find(&self.service_id).await {
+            Some(s) if s.account_id == self.account.id => s,
             _ => return Err(UseCaseError::ServiceNotFound),
         };
-        ctx.repos
+        if !self.ignore_policy {
+            service
+                .booking_options
+                .check_cancellation(self.timestamp, ctx.sys.get_timestamp_millis())
+                .map_err(UseCaseError::PolicyViolation)?;
+        }
+        let released = ctx
+            .repos
             .reservations
             .decrement(&self.service_id, self.timestamp)
+            .await
+            .map_err(|_| UseCaseError::StorageError)?;
+        if !released {
+            // There was no seat reserved at the booking slot
+            return Ok(UseCaseRes {});
+        }
+
+        // The released seat is offered to the waitlist of the booking slot
+        let offer = OfferWaitlistSeatUseCase {
+            service_id: self.service_id.clone(),
+            start_ts: self.timestamp,
+        };
+        execute(offer, ctx)
             .await
             .map(|_| UseCaseRes {})
             .map_err(|_| UseCaseError::StorageError)
@@ -2,7 +2,9 @@ use crate::shared::usecase::{execute, UseCase};
 use crate::{error::NettuError, shared::auth::protect_account_route};
 use actix_web::{web, HttpRequest, HttpResponse};
 use nettu_scheduler_api_structs::update_service::*;
-use nettu_scheduler_domain::{Metadata, Service, ServiceMultiPersonOptions, ID};
+use nettu_scheduler_domain::{
+    Metadata, Service, ServiceBookingOptions, ServiceMultiPersonOptions, ID,
+};
 use nettu_scheduler_infra::NettuContext;
 
 pub async fn update_service_controller(
@@ -19,6 +21,7 @@ pub async fn update_service_controller(
         service_id: std::mem::take(&mut path.service_id),
         metadata: body.metadata,
         multi_person: body.multi_person,
+        booking_options: body.booking_options,
     };
 
     execute(usecase, &ctx)
@@ -33,6 +36,7 @@ struct UpdateServiceUseCase {
     service_id: ID,
     metadata: Option<Metadata>,
     multi_person: Option<ServiceMultiPersonOptions>,
+    booking_options: Option<ServiceBookingOptions>,
 }
 #[derive(Debug)]
 struct UseCaseRes {
@@ -43,6 +47,7 @@ struct UseCaseRes {
 enum UseCaseError {
     StorageError,
     ServiceNotFound(ID),
+    InvalidBookingOptions,
 }
 
 impl From<UseCaseError> for NettuError {
@@ -52,6 +57,9 @@ impl From<UseCaseError> for NettuError {
                 Self::NotFound(format!("Service with id: {} was not found.", id))
             }
             UseCaseError::StorageError => Self::InternalError,
+            UseCaseError::InvalidBookingOptions => {
+                Self::BadClientData("The provided booking options are invalid".into())
+            }
         }
     }
 }
@@ -65,6 +73,12 @@ impl UseCase for UpdateServiceUseCase {
     const NAME: &'static str = "UpdateService";
 
     async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        if let Some(booking_options) = &self.booking_options {
+            if !booking_options.is_valid() {
+                return Err(UseCaseError::InvalidBookingOptions);
+            }
+        }
+
         let mut service = match ctx.repos.services.find(&self.service_id).await {
             Some(service) if service.account_id == self.account_id => service,
             _ => return Err(UseCaseError::ServiceNotFound(self.service_id.clone())),
@@ -89,6 +103,9 @@ impl UseCase for UpdateServiceUseCase {
             }
             service.multi_person = opts.clone();
         }
+        if let Some(booking_options) = &self.booking_options {
+            service.booking_options = booking_options.clone();
+        }
 
         ctx.repos
             .services
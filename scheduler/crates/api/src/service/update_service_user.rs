@@ -10,7 +10,7 @@ use crate::{
 };
 use actix_web::{web, HttpRequest, HttpResponse};
 use nettu_scheduler_api_structs::update_service_user::*;
-use nettu_scheduler_domain::{Account, ServiceResource, TimePlan, ID};
+use nettu_scheduler_domain::{Account, ScheduleRuleInterval, ServiceResource, TimePlan, ID};
 use nettu_scheduler_infra::NettuContext;
 
 pub async fn update_service_user_controller(
@@ -30,6 +30,11 @@ pub async fn update_service_user_controller(
         buffer_before: body.buffer_before,
         closest_booking_time: body.closest_booking_time,
         furthest_booking_time: body.furthest_booking_time,
+        max_bookings_per_day: body.max_bookings_per_day,
+        max_booked_minutes_per_week: body.max_booked_minutes_per_week,
+        preferred_times: std::mem::take(&mut body.preferred_times),
+        weight: body.weight,
+        priority: body.priority,
     };
 
     execute(usecase, &ctx)
@@ -48,6 +53,11 @@ struct UpdateServiceUserUseCase {
     pub buffer_before: Option<i64>,
     pub closest_booking_time: Option<i64>,
     pub furthest_booking_time: Option<i64>,
+    pub max_bookings_per_day: Option<i64>,
+    pub max_booked_minutes_per_week: Option<i64>,
+    pub preferred_times: Option<Vec<ScheduleRuleInterval>>,
+    pub weight: Option<i64>,
+    pub priority: Option<i64>,
 }
 
 #[derive(Debug)]
@@ -108,6 +118,11 @@ impl UseCase for UpdateServiceUserUseCase {
                 buffer_before: self.buffer_before,
                 closest_booking_time: self.closest_booking_time,
                 furthest_booking_time: self.furthest_booking_time,
+                max_bookings_per_day: self.max_bookings_per_day,
+                max_booked_minutes_per_week: self.max_booked_minutes_per_week,
+                preferred_times: self.preferred_times.clone(),
+                weight: self.weight,
+                priority: self.priority,
             },
             ctx,
         )
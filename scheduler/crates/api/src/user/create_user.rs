@@ -12,9 +12,11 @@ pub async fn create_user_controller(
 ) -> Result<HttpResponse, NettuError> {
     let account = protect_account_route(&http_req, &ctx).await?;
 
+    let body = body.0;
     let usecase = CreateUserUseCase {
         account_id: account.id,
-        metadata: body.0.metadata.unwrap_or_default(),
+        metadata: body.metadata.unwrap_or_default(),
+        resource_kind: body.resource_kind,
     };
 
     execute(usecase, &ctx)
@@ -27,6 +29,7 @@ pub async fn create_user_controller(
 pub struct CreateUserUseCase {
     pub account_id: ID,
     pub metadata: Metadata,
+    pub resource_kind: Option<String>,
 }
 
 #[derive(Debug)]
@@ -38,6 +41,7 @@ pub struct UseCaseRes {
 pub enum UseCaseError {
     StorageError,
     UserAlreadyExists,
+    InvalidResourceKind,
 }
 
 impl From<UseCaseError> for NettuError {
@@ -47,6 +51,9 @@ impl From<UseCaseError> for NettuError {
             UseCaseError::UserAlreadyExists => Self::Conflict(
                 "A user with that userId already exist. UserIds need to be unique.".into(),
             ),
+            UseCaseError::InvalidResourceKind => {
+                Self::BadClientData("The resource kind can not be empty.".into())
+            }
         }
     }
 }
@@ -58,8 +65,12 @@ impl UseCase for CreateUserUseCase {
     const NAME: &'static str = "CreateUser";
 
     async fn execute(&mut self, ctx: &NettuContext) -> Result<Self::Response, Self::Error> {
+        if matches!(&self.resource_kind, Some(kind) if kind.trim().is_empty()) {
+            return Err(UseCaseError::InvalidResourceKind);
+        }
         let mut user = User::new(self.account_id.clone());
         user.metadata = self.metadata.clone();
+        user.resource_kind = self.resource_kind.clone();
 
         if let Some(_existing_user) = ctx.repos.users.find(&user.id).await {
             return Err(UseCaseError::UserAlreadyExists);
@@ -0,0 +1,395 @@
+use crate::dtos::{BookingDTO, BookingHoldDTO, WaitlistEntryDTO};
+use nettu_scheduler_domain::{Booking, BookingHold, WaitlistEntry, ID};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookingResponse {
+    pub booking: BookingDTO,
+}
+
+impl BookingResponse {
+    pub fn new(booking: Booking) -> Self {
+        Self {
+            booking: BookingDTO::new(booking),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookingHoldResponse {
+    pub hold: BookingHoldDTO,
+}
+
+impl BookingHoldResponse {
+    pub fn new(hold: BookingHold) -> Self {
+        Self {
+            hold: BookingHoldDTO::new(hold),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaitlistEntryResponse {
+    pub entry: WaitlistEntryDTO,
+}
+
+impl WaitlistEntryResponse {
+    pub fn new(entry: WaitlistEntry) -> Self {
+        Self {
+            entry: WaitlistEntryDTO::new(entry),
+        }
+    }
+}
+
+/// Sent to the webhook of the `Account` when a new `Booking` is waiting
+/// for a host to approve it
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookingApprovalRequestDTO {
+    pub booking: BookingDTO,
+}
+
+impl BookingApprovalRequestDTO {
+    pub fn new(booking: Booking) -> Self {
+        Self {
+            booking: BookingDTO::new(booking),
+        }
+    }
+}
+
+/// Sent to the webhook of the `Account` when a released seat is offered to an
+/// invitee on the waitlist. The seat is held for the invitee until the hold
+/// expires, and is booked by creating a booking from the hold.
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaitlistOfferDTO {
+    pub entry: WaitlistEntryDTO,
+    pub hold: BookingHoldDTO,
+}
+
+impl WaitlistOfferDTO {
+    pub fn new(entry: WaitlistEntry, hold: BookingHold) -> Self {
+        Self {
+            entry: WaitlistEntryDTO::new(entry),
+            hold: BookingHoldDTO::new(hold),
+        }
+    }
+}
+
+pub mod create_booking {
+    use super::*;
+    use nettu_scheduler_domain::{BookingInvitee, Metadata};
+
+    #[derive(Deserialize)]
+    pub struct PathParams {
+        pub service_id: ID,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct RequestBody {
+        #[serde(default)]
+        pub host_user_ids: Option<Vec<ID>>,
+        pub start_ts: i64,
+        #[serde(default)]
+        pub duration: Option<i64>,
+        #[serde(default)]
+        pub interval: Option<i64>,
+        #[serde(default)]
+        pub invitee: BookingInvitee,
+        #[serde(default)]
+        pub metadata: Option<Metadata>,
+    }
+
+    pub type APIResponse = BookingResponse;
+}
+
+pub mod create_booking_chain {
+    use super::*;
+    use crate::dtos::BookingDTO;
+    use nettu_scheduler_domain::{booking_slots::ServiceChainStep, BookingInvitee, Metadata};
+
+    #[derive(Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct RequestBody {
+        /// The `Service`s to book back to back, in order
+        pub steps: Vec<ServiceChainStep>,
+        /// The start of the first step
+        pub start_ts: i64,
+        #[serde(default)]
+        pub interval: Option<i64>,
+        #[serde(default)]
+        pub invitee: BookingInvitee,
+        #[serde(default)]
+        pub metadata: Option<Metadata>,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct APIResponse {
+        /// The `Booking` of every step of the chain, in order
+        pub bookings: Vec<BookingDTO>,
+    }
+
+    impl APIResponse {
+        pub fn new(bookings: Vec<Booking>) -> Self {
+            Self {
+                bookings: bookings.into_iter().map(BookingDTO::new).collect(),
+            }
+        }
+    }
+}
+
+pub mod get_booking {
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub struct PathParams {
+        pub booking_id: ID,
+    }
+
+    pub type APIResponse = BookingResponse;
+}
+
+pub mod get_service_bookings {
+    use super::*;
+    use nettu_scheduler_domain::BookingStatus;
+
+    #[derive(Deserialize)]
+    pub struct PathParams {
+        pub service_id: ID,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct QueryParams {
+        pub start_ts: i64,
+        pub end_ts: i64,
+        #[serde(default)]
+        pub status: Option<BookingStatus>,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct APIResponse {
+        pub bookings: Vec<BookingDTO>,
+    }
+
+    impl APIResponse {
+        pub fn new(bookings: Vec<Booking>) -> Self {
+            Self {
+                bookings: bookings.into_iter().map(BookingDTO::new).collect(),
+            }
+        }
+    }
+}
+
+pub mod cancel_booking {
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub struct PathParams {
+        pub booking_id: ID,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct QueryParams {
+        /// Cancels the booking even if the policy of the service does not allow it
+        #[serde(default)]
+        pub ignore_policy: bool,
+    }
+
+    pub type APIResponse = BookingResponse;
+}
+
+pub mod create_booking_hold {
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub struct PathParams {
+        pub service_id: ID,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct RequestBody {
+        #[serde(default)]
+        pub host_user_ids: Option<Vec<ID>>,
+        pub start_ts: i64,
+        #[serde(default)]
+        pub duration: Option<i64>,
+        #[serde(default)]
+        pub interval: Option<i64>,
+        /// How long the hold lasts in millis
+        #[serde(default)]
+        pub ttl: Option<i64>,
+        /// Attributes of the invitee matched by the host routing rules of the `Service`
+        #[serde(default)]
+        pub attributes: HashMap<String, String>,
+        /// External key of a returning invitee, who is assigned the host of
+        /// their previous booking
+        #[serde(default)]
+        pub customer_key: Option<String>,
+    }
+
+    pub type APIResponse = BookingHoldResponse;
+}
+
+pub mod release_booking_hold {
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub struct PathParams {
+        pub hold_id: ID,
+    }
+
+    pub type APIResponse = BookingHoldResponse;
+}
+
+pub mod create_booking_from_hold {
+    use super::*;
+    use nettu_scheduler_domain::{BookingInvitee, Metadata};
+
+    #[derive(Deserialize)]
+    pub struct PathParams {
+        pub hold_id: ID,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct RequestBody {
+        #[serde(default)]
+        pub invitee: BookingInvitee,
+        #[serde(default)]
+        pub metadata: Option<Metadata>,
+    }
+
+    pub type APIResponse = BookingResponse;
+}
+
+pub mod approve_booking {
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub struct PathParams {
+        pub booking_id: ID,
+    }
+
+    pub type APIResponse = BookingResponse;
+}
+
+pub mod decline_booking {
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub struct PathParams {
+        pub booking_id: ID,
+    }
+
+    pub type APIResponse = BookingResponse;
+}
+
+pub mod mark_booking_no_show {
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub struct PathParams {
+        pub booking_id: ID,
+    }
+
+    pub type APIResponse = BookingResponse;
+}
+
+pub mod reschedule_booking {
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub struct PathParams {
+        pub booking_id: ID,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct RequestBody {
+        pub start_ts: i64,
+        /// Defaults to the current duration of the booking
+        #[serde(default)]
+        pub duration: Option<i64>,
+        #[serde(default)]
+        pub interval: Option<i64>,
+        /// Reschedules the booking even if the policy of the service does not allow it
+        #[serde(default)]
+        pub ignore_policy: bool,
+    }
+
+    pub type APIResponse = BookingResponse;
+}
+
+pub mod join_waitlist {
+    use super::*;
+    use nettu_scheduler_domain::{BookingInvitee, Metadata};
+
+    #[derive(Deserialize)]
+    pub struct PathParams {
+        pub service_id: ID,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct RequestBody {
+        pub start_ts: i64,
+        #[serde(default)]
+        pub duration: Option<i64>,
+        #[serde(default)]
+        pub invitee: BookingInvitee,
+        #[serde(default)]
+        pub metadata: Option<Metadata>,
+    }
+
+    pub type APIResponse = WaitlistEntryResponse;
+}
+
+pub mod get_waitlist {
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub struct PathParams {
+        pub service_id: ID,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct QueryParams {
+        pub start_ts: i64,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct APIResponse {
+        pub entries: Vec<WaitlistEntryDTO>,
+    }
+
+    impl APIResponse {
+        pub fn new(entries: Vec<WaitlistEntry>) -> Self {
+            Self {
+                entries: entries.into_iter().map(WaitlistEntryDTO::new).collect(),
+            }
+        }
+    }
+}
+
+pub mod leave_waitlist {
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub struct PathParams {
+        pub entry_id: ID,
+    }
+
+    pub type APIResponse = WaitlistEntryResponse;
+}
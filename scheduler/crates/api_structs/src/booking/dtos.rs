@@ -0,0 +1,103 @@
+use nettu_scheduler_domain::{
+    Booking, BookingHold, BookingInvitee, BookingReschedule, BookingStatus, Metadata,
+    WaitlistEntry, ID,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BookingDTO {
+    pub id: ID,
+    pub service_id: ID,
+    pub host_user_ids: Vec<ID>,
+    pub resource_id: Option<ID>,
+    pub event_ids: Vec<ID>,
+    pub invitee: BookingInvitee,
+    pub start_ts: i64,
+    pub duration: i64,
+    pub status: BookingStatus,
+    pub approval_deadline: Option<i64>,
+    pub reschedules: Vec<BookingReschedule>,
+    pub created: i64,
+    pub updated: i64,
+    pub metadata: Metadata,
+}
+
+impl BookingDTO {
+    pub fn new(booking: Booking) -> Self {
+        Self {
+            id: booking.id,
+            service_id: booking.service_id,
+            host_user_ids: booking.host_user_ids,
+            resource_id: booking.resource_id,
+            event_ids: booking.event_ids,
+            invitee: booking.invitee,
+            start_ts: booking.start_ts,
+            duration: booking.duration,
+            status: booking.status,
+            approval_deadline: booking.approval_deadline,
+            reschedules: booking.reschedules,
+            created: booking.created,
+            updated: booking.updated,
+            metadata: booking.metadata,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BookingHoldDTO {
+    pub id: ID,
+    pub service_id: ID,
+    pub host_user_ids: Vec<ID>,
+    pub resource_id: Option<ID>,
+    pub start_ts: i64,
+    pub duration: i64,
+    pub created: i64,
+    pub expires_at: i64,
+}
+
+impl BookingHoldDTO {
+    pub fn new(hold: BookingHold) -> Self {
+        Self {
+            id: hold.id,
+            service_id: hold.service_id,
+            host_user_ids: hold.host_user_ids,
+            resource_id: hold.resource_id,
+            start_ts: hold.start_ts,
+            duration: hold.duration,
+            created: hold.created,
+            expires_at: hold.expires_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WaitlistEntryDTO {
+    pub id: ID,
+    pub service_id: ID,
+    pub start_ts: i64,
+    pub duration: i64,
+    pub invitee: BookingInvitee,
+    pub created: i64,
+    pub hold_id: Option<ID>,
+    pub offer_expires_at: Option<i64>,
+    pub metadata: Metadata,
+}
+
+impl WaitlistEntryDTO {
+    pub fn new(entry: WaitlistEntry) -> Self {
+        Self {
+            id: entry.id,
+            service_id: entry.service_id,
+            start_ts: entry.start_ts,
+            duration: entry.duration,
+            invitee: entry.invitee,
+            created: entry.created,
+            hold_id: entry.hold_id,
+            offer_expires_at: entry.offer_expires_at,
+            metadata: entry.metadata,
+        }
+    }
+}
@@ -10,6 +10,7 @@ pub struct CalendarEventDTO {
     pub start_ts: i64,
     pub duration: i64,
     pub busy: bool,
+    pub tentative: bool,
     pub updated: i64,
     pub created: i64,
     pub recurrence: Option<RRuleOptions>,
@@ -27,6 +28,7 @@ impl CalendarEventDTO {
             start_ts: event.start_ts,
             duration: event.duration,
             busy: event.busy,
+            tentative: event.tentative,
             updated: event.updated,
             created: event.created,
             recurrence: event.recurrence,
@@ -1,4 +1,5 @@
 mod account;
+mod booking;
 mod calendar;
 mod event;
 mod schedule;
@@ -7,6 +8,7 @@ mod status;
 mod user;
 pub mod dtos {
     pub use crate::account::dtos::*;
+    pub use crate::booking::dtos::*;
     pub use crate::calendar::dtos::*;
     pub use crate::event::dtos::*;
     pub use crate::schedule::dtos::*;
@@ -14,6 +16,7 @@ pub mod dtos {
     pub use crate::user::dtos::*;
 }
 pub use crate::account::api::*;
+pub use crate::booking::api::*;
 pub use crate::calendar::api::*;
 pub use crate::event::api::*;
 pub use crate::schedule::api::*;
@@ -106,3 +106,89 @@ pub mod get_schedules_by_meta {
         }
     }
 }
+
+pub mod get_schedule_vavailability {
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub struct PathParams {
+        pub schedule_id: ID,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct APIResponse {
+        /// iCalendar object containing a VAVAILABILITY component (RFC 7953)
+        pub vavailability: String,
+    }
+
+    impl APIResponse {
+        pub fn new(vavailability: String) -> Self {
+            Self { vavailability }
+        }
+    }
+}
+
+pub mod create_schedule_from_vavailability {
+    use super::*;
+    use nettu_scheduler_domain::Metadata;
+
+    #[derive(Deserialize)]
+    pub struct PathParams {
+        pub user_id: ID,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct RequestBody {
+        pub vavailability: String,
+        /// Used when the VAVAILABILITY component only contains floating times
+        pub timezone: Option<Tz>,
+        pub metadata: Option<Metadata>,
+    }
+
+    pub type APIResponse = ScheduleResponse;
+}
+
+pub mod update_schedule_from_vavailability {
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub struct PathParams {
+        pub schedule_id: ID,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct RequestBody {
+        pub vavailability: String,
+    }
+
+    pub type APIResponse = ScheduleResponse;
+}
+
+pub mod preview_schedule {
+    use super::*;
+    use nettu_scheduler_domain::{EventInstance, ScheduleRule};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct RequestBody {
+        pub timezone: Tz,
+        pub rules: Vec<ScheduleRule>,
+        pub start_ts: i64,
+        pub end_ts: i64,
+    }
+
+    #[derive(Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct APIResponse {
+        pub instances: Vec<EventInstance>,
+    }
+
+    impl APIResponse {
+        pub fn new(instances: Vec<EventInstance>) -> Self {
+            Self { instances }
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::dtos::{ServiceDTO, ServiceResourceDTO, ServiceWithUsersDTO};
 use nettu_scheduler_domain::{
-    BusyCalendar, Service, ServiceResource, ServiceWithUsers, TimePlan, Tz, ID,
+    BusyCalendar, ScheduleRuleInterval, Service, ServiceResource, ServiceWithUsers, TimePlan, Tz,
+    ID,
 };
 use serde::{Deserialize, Serialize};
 
@@ -65,6 +66,14 @@ pub mod add_user_to_service {
         pub buffer_before: Option<i64>,
         pub closest_booking_time: Option<i64>,
         pub furthest_booking_time: Option<i64>,
+        pub max_bookings_per_day: Option<i64>,
+        pub max_booked_minutes_per_week: Option<i64>,
+        #[serde(default)]
+        pub preferred_times: Option<Vec<ScheduleRuleInterval>>,
+        #[serde(default)]
+        pub weight: Option<i64>,
+        #[serde(default)]
+        pub priority: Option<i64>,
     }
 
     pub type APIResponse = ServiceResourceDTO;
@@ -118,6 +127,10 @@ pub mod remove_service_event_intend {
     #[serde(rename_all = "camelCase")]
     pub struct QueryParams {
         pub timestamp: i64,
+        /// Removes the intend even if the cancellation policy of the service
+        /// does not allow it
+        #[serde(default)]
+        pub ignore_policy: bool,
     }
 
     #[derive(Deserialize, Serialize, Debug)]
@@ -138,7 +151,8 @@ pub mod remove_service_event_intend {
 pub mod create_service_event_intend {
     use super::*;
     use crate::dtos::UserDTO;
-    use nettu_scheduler_domain::User;
+    use nettu_scheduler_domain::{IntakeAnswers, User};
+    use std::collections::HashMap;
 
     #[derive(Deserialize)]
     pub struct PathParams {
@@ -151,21 +165,38 @@ pub mod create_service_event_intend {
         #[serde(default)]
         pub host_user_ids: Option<Vec<ID>>,
         pub timestamp: i64,
-        pub duration: i64,
-        pub interval: i64,
+        #[serde(default)]
+        pub duration: Option<i64>,
+        #[serde(default)]
+        pub interval: Option<i64>,
+        /// Attributes of the invitee matched by the host routing rules of the `Service`
+        #[serde(default)]
+        pub attributes: HashMap<String, String>,
+        /// Answers of the invitee to the intake questions of the `Service`
+        #[serde(default)]
+        pub answers: IntakeAnswers,
     }
 
     #[derive(Deserialize, Serialize, Debug)]
     #[serde(rename_all = "camelCase")]
     pub struct APIResponse {
         pub selected_hosts: Vec<UserDTO>,
+        /// The resource from the resource pool of the `Service`, which also
+        /// needs a `CalendarEvent` like the hosts
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub selected_resource: Option<UserDTO>,
         pub create_event_for_hosts: bool,
     }
 
     impl APIResponse {
-        pub fn new(selected_hosts: Vec<User>, create_event_for_hosts: bool) -> Self {
+        pub fn new(
+            selected_hosts: Vec<User>,
+            selected_resource: Option<User>,
+            create_event_for_hosts: bool,
+        ) -> Self {
             Self {
                 selected_hosts: selected_hosts.into_iter().map(UserDTO::new).collect(),
+                selected_resource: selected_resource.map(UserDTO::new),
                 create_event_for_hosts,
             }
         }
@@ -173,7 +204,7 @@ pub mod create_service_event_intend {
 }
 
 pub mod create_service {
-    use nettu_scheduler_domain::{Metadata, ServiceMultiPersonOptions};
+    use nettu_scheduler_domain::{Metadata, ServiceBookingOptions, ServiceMultiPersonOptions};
 
     use super::*;
 
@@ -184,13 +215,15 @@ pub mod create_service {
         pub metadata: Option<Metadata>,
         #[serde(default)]
         pub multi_person: Option<ServiceMultiPersonOptions>,
+        #[serde(default)]
+        pub booking_options: Option<ServiceBookingOptions>,
     }
 
     pub type APIResponse = ServiceResponse;
 }
 
 pub mod update_service {
-    use nettu_scheduler_domain::{Metadata, ServiceMultiPersonOptions};
+    use nettu_scheduler_domain::{Metadata, ServiceBookingOptions, ServiceMultiPersonOptions};
 
     use super::*;
 
@@ -201,6 +234,8 @@ pub mod update_service {
         pub metadata: Option<Metadata>,
         #[serde(default)]
         pub multi_person: Option<ServiceMultiPersonOptions>,
+        #[serde(default)]
+        pub booking_options: Option<ServiceBookingOptions>,
     }
 
     #[derive(Debug, Deserialize)]
@@ -226,12 +261,28 @@ pub mod get_service_bookingslots {
     #[serde(rename_all = "camelCase")]
     pub struct QueryParams {
         pub timezone: Option<Tz>,
-        pub duration: i64,
-        pub interval: i64,
+        #[serde(default)]
+        pub duration: Option<i64>,
+        #[serde(default)]
+        pub interval: Option<i64>,
         pub start_date: String,
         pub end_date: String,
         #[serde(default)]
         pub host_user_ids: Option<String>,
+        /// Scores the slots by how little they fragment the calendars of the hosts
+        #[serde(default)]
+        pub rank: bool,
+        /// Only returns the best ranked slots for every date
+        #[serde(default)]
+        pub best_per_day: Option<usize>,
+        /// Attributes of the invitee as comma separated `key:value` pairs,
+        /// which are matched by the host routing rules of the `Service`
+        #[serde(default)]
+        pub attributes: Option<String>,
+        /// Queries the external Google and Outlook busy calendars of the hosts
+        /// instead of using their cached busy intervals
+        #[serde(default)]
+        pub bypass_busy_cache: bool,
     }
 
     #[derive(Deserialize, Serialize, Debug)]
@@ -240,6 +291,16 @@ pub mod get_service_bookingslots {
         pub start: i64,
         pub duration: i64,
         pub user_ids: Vec<ID>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub score: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub seats_taken: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub seats_remaining: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub optional_user_ids: Option<Vec<ID>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub resource_ids: Option<Vec<ID>>,
     }
 
     impl ServiceBookingSlotDTO {
@@ -248,6 +309,11 @@ pub mod get_service_bookingslots {
                 duration: slot.duration,
                 start: slot.start,
                 user_ids: slot.user_ids,
+                score: slot.score,
+                seats_taken: slot.seats_taken,
+                seats_remaining: slot.seats_remaining,
+                optional_user_ids: slot.optional_user_ids,
+                resource_ids: slot.resource_ids,
             }
         }
     }
@@ -291,6 +357,227 @@ pub mod get_service_bookingslots {
     }
 }
 
+pub mod get_service_bookingslots_page {
+    use super::*;
+    use get_service_bookingslots::ServiceBookingSlotsDateDTO;
+    use nettu_scheduler_domain::booking_slots::ServiceBookingSlots;
+
+    #[derive(Debug, Deserialize)]
+    pub struct PathParams {
+        pub service_id: ID,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct QueryParams {
+        pub timezone: Option<Tz>,
+        #[serde(default)]
+        pub duration: Option<i64>,
+        #[serde(default)]
+        pub interval: Option<i64>,
+        /// The first date of the first page. Ignored when a `cursor` is given.
+        #[serde(default)]
+        pub start_date: Option<String>,
+        /// The `next_cursor` of the previous page
+        #[serde(default)]
+        pub cursor: Option<String>,
+        /// Amount of dates of the page. Defaults to 7.
+        #[serde(default)]
+        pub days: Option<i64>,
+        #[serde(default)]
+        pub host_user_ids: Option<String>,
+        /// Attributes of the invitee as comma separated `key:value` pairs,
+        /// which are matched by the host routing rules of the `Service`
+        #[serde(default)]
+        pub attributes: Option<String>,
+        #[serde(default)]
+        pub bypass_busy_cache: bool,
+    }
+
+    #[derive(Deserialize, Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct APIResponse {
+        pub dates: Vec<ServiceBookingSlotsDateDTO>,
+        /// Continuation token for the following page. Missing when the
+        /// following page is after the furthest booking time of every host.
+        pub next_cursor: Option<String>,
+    }
+
+    impl APIResponse {
+        pub fn new(booking_slots: ServiceBookingSlots, next_cursor: Option<String>) -> Self {
+            Self {
+                dates: booking_slots
+                    .dates
+                    .into_iter()
+                    .map(ServiceBookingSlotsDateDTO::new)
+                    .collect(),
+                next_cursor,
+            }
+        }
+    }
+}
+
+pub mod get_service_next_bookingslot {
+    use super::*;
+    use get_service_bookingslots::ServiceBookingSlotDTO;
+    use nettu_scheduler_domain::booking_slots::ServiceBookingSlot;
+
+    #[derive(Debug, Deserialize)]
+    pub struct PathParams {
+        pub service_id: ID,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct QueryParams {
+        /// Timestamp in millis the booking slot should start at or after.
+        /// Defaults to now.
+        #[serde(default)]
+        pub after: Option<i64>,
+        #[serde(default)]
+        pub duration: Option<i64>,
+        #[serde(default)]
+        pub interval: Option<i64>,
+        #[serde(default)]
+        pub host_user_ids: Option<String>,
+        /// Attributes of the invitee as comma separated `key:value` pairs,
+        /// which are matched by the host routing rules of the `Service`
+        #[serde(default)]
+        pub attributes: Option<String>,
+        #[serde(default)]
+        pub bypass_busy_cache: bool,
+    }
+
+    #[derive(Deserialize, Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct APIResponse {
+        /// The earliest bookable slot, if any before the furthest booking
+        /// time of the hosts
+        pub slot: Option<ServiceBookingSlotDTO>,
+    }
+
+    impl APIResponse {
+        pub fn new(slot: Option<ServiceBookingSlot>) -> Self {
+            Self {
+                slot: slot.map(ServiceBookingSlotDTO::new),
+            }
+        }
+    }
+}
+
+pub mod explain_service_bookingslot {
+    use super::*;
+    use nettu_scheduler_domain::booking_slots::UnavailabilityReason;
+
+    #[derive(Debug, Deserialize)]
+    pub struct PathParams {
+        pub service_id: ID,
+        pub user_id: ID,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct QueryParams {
+        /// The start of the booking slot
+        pub timestamp: i64,
+        #[serde(default)]
+        pub duration: Option<i64>,
+        #[serde(default)]
+        pub bypass_busy_cache: bool,
+    }
+
+    #[derive(Deserialize, Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct APIResponse {
+        pub available: bool,
+        /// Every constraint excluding the booking slot
+        pub reasons: Vec<UnavailabilityReason>,
+    }
+
+    impl APIResponse {
+        pub fn new(reasons: Vec<UnavailabilityReason>) -> Self {
+            Self {
+                available: reasons.is_empty(),
+                reasons,
+            }
+        }
+    }
+}
+
+pub mod get_service_chain_bookingslots {
+    use super::*;
+    use get_service_bookingslots::ServiceBookingSlotDTO;
+    use nettu_scheduler_domain::booking_slots::{ServiceChainBookingSlot, ServiceChainStep};
+    use std::collections::HashMap;
+
+    #[derive(Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct RequestBody {
+        /// The `Service`s to book back to back, in order
+        pub steps: Vec<ServiceChainStep>,
+        #[serde(default)]
+        pub timezone: Option<Tz>,
+        pub start_date: String,
+        pub end_date: String,
+        /// Interval between the start times of the chain. Defaults to the
+        /// default interval of the first `Service`.
+        #[serde(default)]
+        pub interval: Option<i64>,
+        /// Attributes of the invitee matched by the host routing rules of the `Service`s
+        #[serde(default)]
+        pub attributes: HashMap<String, String>,
+    }
+
+    #[derive(Deserialize, Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ServiceChainStepSlotDTO {
+        pub service_id: ID,
+        #[serde(flatten)]
+        pub slot: ServiceBookingSlotDTO,
+    }
+
+    #[derive(Deserialize, Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ServiceChainBookingSlotDTO {
+        pub start: i64,
+        pub steps: Vec<ServiceChainStepSlotDTO>,
+    }
+
+    impl ServiceChainBookingSlotDTO {
+        pub fn new(chain_slot: ServiceChainBookingSlot, steps: &[ServiceChainStep]) -> Self {
+            Self {
+                start: chain_slot.start,
+                steps: chain_slot
+                    .steps
+                    .into_iter()
+                    .zip(steps)
+                    .map(|(slot, step)| ServiceChainStepSlotDTO {
+                        service_id: step.service_id.clone(),
+                        slot: ServiceBookingSlotDTO::new(slot),
+                    })
+                    .collect(),
+            }
+        }
+    }
+
+    #[derive(Deserialize, Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct APIResponse {
+        pub slots: Vec<ServiceChainBookingSlotDTO>,
+    }
+
+    impl APIResponse {
+        pub fn new(slots: Vec<ServiceChainBookingSlot>, steps: &[ServiceChainStep]) -> Self {
+            Self {
+                slots: slots
+                    .into_iter()
+                    .map(|slot| ServiceChainBookingSlotDTO::new(slot, steps))
+                    .collect(),
+            }
+        }
+    }
+}
+
 pub mod get_service {
     use super::*;
 
@@ -302,6 +589,67 @@ pub mod get_service {
     pub type APIResponse = ServiceWithUsersDTO;
 }
 
+pub mod get_service_distribution {
+    use super::*;
+    use nettu_scheduler_domain::scheduling::{FairnessBalance, HostDistribution};
+
+    #[derive(Deserialize)]
+    pub struct PathParams {
+        pub service_id: ID,
+    }
+
+    #[derive(Deserialize, Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct HostDistributionDTO {
+        pub user_id: ID,
+        pub weight: i64,
+        pub priority: i64,
+        pub assignments: i64,
+        pub booked_minutes: i64,
+        /// Share of the balanced load of the `Service` assigned to this host
+        pub share: f64,
+        /// Share of the balanced load this host should be assigned by its weight
+        pub target_share: f64,
+    }
+
+    #[derive(Deserialize, Serialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub struct APIResponse {
+        pub start_ts: i64,
+        pub end_ts: i64,
+        pub balance: FairnessBalance,
+        pub hosts: Vec<HostDistributionDTO>,
+    }
+
+    impl APIResponse {
+        pub fn new(
+            start_ts: i64,
+            end_ts: i64,
+            balance: FairnessBalance,
+            distribution: Vec<HostDistribution>,
+        ) -> Self {
+            let hosts = distribution
+                .iter()
+                .map(|host| HostDistributionDTO {
+                    user_id: host.host.user_id.clone(),
+                    weight: host.host.weight,
+                    priority: host.host.priority,
+                    assignments: host.assignments,
+                    booked_minutes: host.booked_duration / (1000 * 60),
+                    share: host.share(&distribution, balance),
+                    target_share: host.target_share(&distribution),
+                })
+                .collect();
+            Self {
+                start_ts,
+                end_ts,
+                balance,
+                hosts,
+            }
+        }
+    }
+}
+
 pub mod get_services_by_meta {
     use crate::dtos::ServiceDTO;
 
@@ -372,6 +720,14 @@ pub mod update_service_user {
         pub buffer_before: Option<i64>,
         pub closest_booking_time: Option<i64>,
         pub furthest_booking_time: Option<i64>,
+        pub max_bookings_per_day: Option<i64>,
+        pub max_booked_minutes_per_week: Option<i64>,
+        #[serde(default)]
+        pub preferred_times: Option<Vec<ScheduleRuleInterval>>,
+        #[serde(default)]
+        pub weight: Option<i64>,
+        #[serde(default)]
+        pub priority: Option<i64>,
     }
 
     pub type APIResponse = ServiceResourceDTO;
@@ -1,4 +1,7 @@
-use nettu_scheduler_domain::{Metadata, Service, ServiceResource, ServiceWithUsers, TimePlan, ID};
+use nettu_scheduler_domain::{
+    Metadata, ScheduleRuleInterval, Service, ServiceBookingOptions, ServiceResource,
+    ServiceWithUsers, TimePlan, ID,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -11,6 +14,11 @@ pub struct ServiceResourceDTO {
     pub buffer_before: i64,
     pub closest_booking_time: i64,
     pub furthest_booking_time: Option<i64>,
+    pub max_bookings_per_day: Option<i64>,
+    pub max_booked_minutes_per_week: Option<i64>,
+    pub preferred_times: Vec<ScheduleRuleInterval>,
+    pub weight: i64,
+    pub priority: i64,
 }
 
 impl ServiceResourceDTO {
@@ -23,6 +31,11 @@ impl ServiceResourceDTO {
             buffer_before: resource.buffer_before,
             closest_booking_time: resource.closest_booking_time,
             furthest_booking_time: resource.furthest_booking_time,
+            max_bookings_per_day: resource.max_bookings_per_day,
+            max_booked_minutes_per_week: resource.max_booked_minutes_per_week,
+            preferred_times: resource.preferred_times,
+            weight: resource.weight,
+            priority: resource.priority,
         }
     }
 }
@@ -31,6 +44,7 @@ impl ServiceResourceDTO {
 #[serde(rename_all = "camelCase")]
 pub struct ServiceDTO {
     pub id: ID,
+    pub booking_options: ServiceBookingOptions,
     pub metadata: Metadata,
 }
 
@@ -38,6 +52,7 @@ impl ServiceDTO {
     pub fn new(service: Service) -> Self {
         Self {
             id: service.id,
+            booking_options: service.booking_options,
             metadata: service.metadata,
         }
     }
@@ -48,6 +63,9 @@ impl ServiceDTO {
 pub struct ServiceWithUsersDTO {
     pub id: ID,
     pub users: Vec<ServiceResourceDTO>,
+    /// The resource pool of the `Service`
+    pub resources: Vec<ServiceResourceDTO>,
+    pub booking_options: ServiceBookingOptions,
     pub metadata: Metadata,
 }
 
@@ -60,6 +78,12 @@ impl ServiceWithUsersDTO {
                 .into_iter()
                 .map(ServiceResourceDTO::new)
                 .collect(),
+            resources: service
+                .resources
+                .into_iter()
+                .map(ServiceResourceDTO::new)
+                .collect(),
+            booking_options: service.booking_options,
             metadata: service.metadata,
         }
     }
@@ -32,6 +32,10 @@ pub mod create_user {
     pub struct RequestBody {
         #[serde(default)]
         pub metadata: Option<Metadata>,
+        /// Creates a non-human bookable resource of this kind, e.g. "room",
+        /// which can be added to the resource pool of a `Service`
+        #[serde(default)]
+        pub resource_kind: Option<String>,
     }
 
     pub type APIResponse = UserResponse;
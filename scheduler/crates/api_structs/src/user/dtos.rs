@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 pub struct UserDTO {
     pub id: ID,
     pub metadata: Metadata,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_kind: Option<String>,
 }
 
 impl UserDTO {
@@ -13,6 +15,7 @@ impl UserDTO {
         Self {
             id: user.id,
             metadata: user.metadata,
+            resource_kind: user.resource_kind,
         }
     }
 }
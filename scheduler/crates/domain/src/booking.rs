@@ -0,0 +1,416 @@
+use crate::{
+    shared::entity::{Entity, ID},
+    IntakeAnswers, Meta, Metadata,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The lifecycle state of a `Booking`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BookingStatus {
+    /// The `Booking` is waiting to be confirmed
+    Pending,
+    Confirmed,
+    Cancelled,
+    /// The `Booking` has been moved to another time
+    Rescheduled,
+    /// The invitee did not show up to the `Booking`
+    NoShow,
+    /// A host declined the pending `Booking`, or it was not approved in time
+    Declined,
+}
+
+impl BookingStatus {
+    /// The statuses of a `Booking` that still occupies the time of its hosts
+    pub const ACTIVE: [Self; 3] = [Self::Pending, Self::Confirmed, Self::Rescheduled];
+
+    /// Whether the `Booking` still occupies the time of its hosts
+    pub fn is_active(&self) -> bool {
+        Self::ACTIVE.contains(self)
+    }
+}
+
+impl From<BookingStatus> for String {
+    fn from(e: BookingStatus) -> Self {
+        match e {
+            BookingStatus::Pending => "pending".into(),
+            BookingStatus::Confirmed => "confirmed".into(),
+            BookingStatus::Cancelled => "cancelled".into(),
+            BookingStatus::Rescheduled => "rescheduled".into(),
+            BookingStatus::NoShow => "no_show".into(),
+            BookingStatus::Declined => "declined".into(),
+        }
+    }
+}
+
+impl From<String> for BookingStatus {
+    fn from(e: String) -> Self {
+        match &e[..] {
+            "pending" => BookingStatus::Pending,
+            "confirmed" => BookingStatus::Confirmed,
+            "cancelled" => BookingStatus::Cancelled,
+            "rescheduled" => BookingStatus::Rescheduled,
+            "no_show" => BookingStatus::NoShow,
+            "declined" => BookingStatus::Declined,
+            _ => unreachable!("Invalid booking status"),
+        }
+    }
+}
+
+/// The person who made a `Booking`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookingInvitee {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    /// External key that identifies a returning invitee across `Booking`s
+    pub customer_key: Option<String>,
+    /// Attributes of the invitee which are matched by the `HostRoutingRule`s
+    /// of the `Service`
+    #[serde(default)]
+    pub attributes: HashMap<String, String>,
+    /// Answers to the intake questions of the `Service`
+    #[serde(default)]
+    pub answers: IntakeAnswers,
+}
+
+/// A previous time of a rescheduled `Booking`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookingReschedule {
+    pub start_ts: i64,
+    pub duration: i64,
+    pub host_user_ids: Vec<ID>,
+    /// When the `Booking` was moved away from this time
+    pub rescheduled_at: i64,
+}
+
+/// A `Booking` is a reserved time of a `Service` made by an invitee
+/// with one or more of the `User`s registered on that `Service`
+#[derive(Debug, Clone)]
+pub struct Booking {
+    pub id: ID,
+    pub account_id: ID,
+    pub service_id: ID,
+    /// The `User`s that were assigned this `Booking`
+    pub host_user_ids: Vec<ID>,
+    /// The resource, e.g. a room, assigned this `Booking` from the resource
+    /// pool of the `Service`
+    pub resource_id: Option<ID>,
+    /// The `CalendarEvent`s created for the hosts of this `Booking`.
+    /// For `Service`s with `ServiceMultiPersonOptions::Group` these are only
+    /// created by the `Booking` that takes the last seat.
+    pub event_ids: Vec<ID>,
+    pub invitee: BookingInvitee,
+    pub start_ts: i64,
+    pub duration: i64,
+    pub status: BookingStatus,
+    /// When a pending `Booking` is declined unless a host approves it
+    pub approval_deadline: Option<i64>,
+    /// The previous times of the `Booking`, oldest first
+    pub reschedules: Vec<BookingReschedule>,
+    pub created: i64,
+    pub updated: i64,
+    pub metadata: Metadata,
+}
+
+impl Booking {
+    pub fn end_ts(&self) -> i64 {
+        self.start_ts + self.duration
+    }
+
+    /// Cancels the `Booking`. Returns false if it was not active.
+    pub fn cancel(&mut self, timestamp: i64) -> bool {
+        if !self.status.is_active() {
+            return false;
+        }
+        self.status = BookingStatus::Cancelled;
+        self.updated = timestamp;
+        true
+    }
+
+    /// Moves the `Booking` to another time and records the previous one.
+    /// Returns false if it was not active.
+    pub fn reschedule(
+        &mut self,
+        start_ts: i64,
+        duration: i64,
+        host_user_ids: Vec<ID>,
+        timestamp: i64,
+    ) -> bool {
+        if !self.status.is_active() {
+            return false;
+        }
+        self.reschedules.push(BookingReschedule {
+            start_ts: self.start_ts,
+            duration: self.duration,
+            host_user_ids: std::mem::replace(&mut self.host_user_ids, host_user_ids),
+            rescheduled_at: timestamp,
+        });
+        self.start_ts = start_ts;
+        self.duration = duration;
+        // A pending `Booking` still has to be approved
+        if self.status == BookingStatus::Confirmed {
+            self.status = BookingStatus::Rescheduled;
+        }
+        self.updated = timestamp;
+        true
+    }
+
+    /// Confirms a pending `Booking`. Returns false if it was not pending.
+    pub fn approve(&mut self, timestamp: i64) -> bool {
+        if self.status != BookingStatus::Pending {
+            return false;
+        }
+        self.status = BookingStatus::Confirmed;
+        self.approval_deadline = None;
+        self.updated = timestamp;
+        true
+    }
+
+    /// Marks a confirmed `Booking` that has ended as not attended by the
+    /// invitee. Returns false if it was not confirmed or has not ended yet.
+    pub fn mark_no_show(&mut self, timestamp: i64) -> bool {
+        if !matches!(
+            self.status,
+            BookingStatus::Confirmed | BookingStatus::Rescheduled
+        ) || self.end_ts() > timestamp
+        {
+            return false;
+        }
+        self.status = BookingStatus::NoShow;
+        self.updated = timestamp;
+        true
+    }
+
+    /// Declines a pending `Booking`. Returns false if it was not pending.
+    pub fn decline(&mut self, timestamp: i64) -> bool {
+        if self.status != BookingStatus::Pending {
+            return false;
+        }
+        self.status = BookingStatus::Declined;
+        self.approval_deadline = None;
+        self.updated = timestamp;
+        true
+    }
+}
+
+impl Entity<ID> for Booking {
+    fn id(&self) -> ID {
+        self.id.clone()
+    }
+}
+
+impl Meta<ID> for Booking {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+    fn account_id(&self) -> &ID {
+        &self.account_id
+    }
+}
+
+/// A `BookingHold` temporarily keeps a booking slot of a `Service` for an
+/// invitee, e.g. while a checkout form is filled out. It expires on its own
+/// unless it is converted into a `Booking` before that.
+#[derive(Debug, Clone)]
+pub struct BookingHold {
+    pub id: ID,
+    pub account_id: ID,
+    pub service_id: ID,
+    /// The `User`s that will be assigned the `Booking`
+    pub host_user_ids: Vec<ID>,
+    /// The resource that will be assigned the `Booking`
+    pub resource_id: Option<ID>,
+    pub start_ts: i64,
+    pub duration: i64,
+    /// Whether the `BookingHold` takes a seat of a `Service` with
+    /// `ServiceMultiPersonOptions::Group` instead of the time of its hosts
+    pub seat: bool,
+    pub created: i64,
+    pub expires_at: i64,
+}
+
+impl BookingHold {
+    /// The time to live of a `BookingHold` when none is given, 10 minutes
+    pub const DEFAULT_TTL: i64 = 1000 * 60 * 10;
+    /// The longest time to live of a `BookingHold`, 1 hour
+    pub const MAX_TTL: i64 = 1000 * 60 * 60;
+
+    pub fn end_ts(&self) -> i64 {
+        self.start_ts + self.duration
+    }
+
+    pub fn is_valid_ttl(ttl: i64) -> bool {
+        ttl > 0 && ttl <= Self::MAX_TTL
+    }
+
+    pub fn is_expired(&self, timestamp: i64) -> bool {
+        self.expires_at <= timestamp
+    }
+}
+
+impl Entity<ID> for BookingHold {
+    fn id(&self) -> ID {
+        self.id.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_cancels_active_bookings_only() {
+        let mut booking = Booking {
+            id: Default::default(),
+            account_id: Default::default(),
+            service_id: Default::default(),
+            host_user_ids: Vec::new(),
+            resource_id: None,
+            event_ids: Vec::new(),
+            invitee: Default::default(),
+            start_ts: 0,
+            duration: 10,
+            status: BookingStatus::Confirmed,
+            approval_deadline: None,
+            reschedules: Vec::new(),
+            created: 0,
+            updated: 0,
+            metadata: Default::default(),
+        };
+        assert!(booking.cancel(5));
+        assert_eq!(booking.status, BookingStatus::Cancelled);
+        assert_eq!(booking.updated, 5);
+        assert!(!booking.cancel(6));
+        assert_eq!(booking.updated, 5);
+
+        booking.status = BookingStatus::NoShow;
+        assert!(!booking.cancel(7));
+    }
+
+    #[test]
+    fn it_reschedules_and_records_previous_time() {
+        let host_1 = ID::default();
+        let host_2 = ID::default();
+        let mut booking = Booking {
+            id: Default::default(),
+            account_id: Default::default(),
+            service_id: Default::default(),
+            host_user_ids: vec![host_1.clone()],
+            resource_id: None,
+            event_ids: Vec::new(),
+            invitee: Default::default(),
+            start_ts: 0,
+            duration: 10,
+            status: BookingStatus::Confirmed,
+            approval_deadline: None,
+            reschedules: Vec::new(),
+            created: 0,
+            updated: 0,
+            metadata: Default::default(),
+        };
+        assert!(booking.reschedule(100, 20, vec![host_2.clone()], 5));
+        assert_eq!(booking.status, BookingStatus::Rescheduled);
+        assert_eq!(booking.start_ts, 100);
+        assert_eq!(booking.end_ts(), 120);
+        assert_eq!(booking.host_user_ids, vec![host_2.clone()]);
+        assert_eq!(
+            booking.reschedules,
+            vec![BookingReschedule {
+                start_ts: 0,
+                duration: 10,
+                host_user_ids: vec![host_1],
+                rescheduled_at: 5,
+            }]
+        );
+
+        assert!(booking.reschedule(200, 20, vec![host_2], 6));
+        assert_eq!(booking.reschedules.len(), 2);
+        assert_eq!(booking.reschedules[1].start_ts, 100);
+
+        booking.cancel(7);
+        assert!(!booking.reschedule(300, 20, Vec::new(), 8));
+        assert_eq!(booking.start_ts, 200);
+    }
+
+    #[test]
+    fn it_approves_and_declines_pending_bookings_only() {
+        let mut booking = Booking {
+            id: Default::default(),
+            account_id: Default::default(),
+            service_id: Default::default(),
+            host_user_ids: Vec::new(),
+            resource_id: None,
+            event_ids: Vec::new(),
+            invitee: Default::default(),
+            start_ts: 0,
+            duration: 10,
+            status: BookingStatus::Pending,
+            approval_deadline: Some(100),
+            reschedules: Vec::new(),
+            created: 0,
+            updated: 0,
+            metadata: Default::default(),
+        };
+        assert!(booking.approve(5));
+        assert_eq!(booking.status, BookingStatus::Confirmed);
+        assert_eq!(booking.approval_deadline, None);
+        assert!(!booking.approve(6));
+        assert!(!booking.decline(6));
+
+        booking.status = BookingStatus::Pending;
+        assert!(booking.decline(7));
+        assert_eq!(booking.status, BookingStatus::Declined);
+        assert!(!booking.status.is_active());
+        assert_eq!(booking.updated, 7);
+    }
+
+    #[test]
+    fn it_marks_ended_confirmed_bookings_as_no_show() {
+        let mut booking = Booking {
+            id: Default::default(),
+            account_id: Default::default(),
+            service_id: Default::default(),
+            host_user_ids: Vec::new(),
+            resource_id: None,
+            event_ids: Vec::new(),
+            invitee: Default::default(),
+            start_ts: 0,
+            duration: 10,
+            status: BookingStatus::Pending,
+            approval_deadline: Some(100),
+            reschedules: Vec::new(),
+            created: 0,
+            updated: 0,
+            metadata: Default::default(),
+        };
+        assert!(!booking.mark_no_show(20));
+
+        booking.status = BookingStatus::Rescheduled;
+        // The booking has not ended yet
+        assert!(!booking.mark_no_show(9));
+        assert!(booking.mark_no_show(10));
+        assert_eq!(booking.status, BookingStatus::NoShow);
+        assert_eq!(booking.updated, 10);
+        assert!(!booking.status.is_active());
+        assert!(!booking.mark_no_show(20));
+        assert!(!booking.cancel(20));
+    }
+
+    #[test]
+    fn it_converts_status_to_and_from_string() {
+        for status in [
+            BookingStatus::Pending,
+            BookingStatus::Confirmed,
+            BookingStatus::Cancelled,
+            BookingStatus::Rescheduled,
+            BookingStatus::NoShow,
+            BookingStatus::Declined,
+        ] {
+            let status_str: String = status.into();
+            assert_eq!(BookingStatus::from(status_str), status);
+        }
+    }
+}
@@ -1,8 +1,10 @@
-use crate::{date, event_instance::EventInstance, CompatibleInstances, ID};
+use crate::{
+    date, event_instance::EventInstance, CollectiveQuorum, CompatibleInstances, Reservation, ID,
+};
 use chrono::prelude::*;
 use chrono_tz::Tz;
 use date::format_date;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
 
 #[derive(Serialize, PartialEq, Debug)]
@@ -37,6 +39,8 @@ pub struct BookingSlotsOptions {
 pub struct UserFreeEvents {
     pub free_events: CompatibleInstances,
     pub user_id: ID,
+    /// Times the user prefers to be booked at, only used for ranking slots
+    pub preferred_times: Vec<EventInstance>,
 }
 
 #[derive(PartialEq, Debug)]
@@ -44,6 +48,18 @@ pub struct ServiceBookingSlot {
     pub start: i64,
     pub duration: i64,
     pub user_ids: Vec<ID>,
+    /// Score between 0 and 1 set when the slots are ranked, higher is better
+    pub score: Option<f64>,
+    /// Seats already taken, only set for `Service`s with `ServiceMultiPersonOptions::Group`
+    pub seats_taken: Option<usize>,
+    /// Seats left, only set for `Service`s with `ServiceMultiPersonOptions::Group`
+    pub seats_remaining: Option<usize>,
+    /// The available hosts that are not required to attend, only set for
+    /// `Service`s with `ServiceMultiPersonOptions::CollectiveQuorum`
+    pub optional_user_ids: Option<Vec<ID>>,
+    /// The free resources of the resource pool, only set for `Service`s
+    /// that require a resource
+    pub resource_ids: Option<Vec<ID>>,
 }
 
 #[derive(Debug)]
@@ -62,6 +78,23 @@ impl ServiceBookingSlots {
 
         Self { dates }
     }
+
+    /// Only keeps the `n` slots with the highest score for every date.
+    /// Earlier slots are preferred when the scores are equal.
+    pub fn retain_best_per_day(&mut self, n: usize) {
+        for date in &mut self.dates {
+            date.slots.sort_by(|s1, s2| {
+                s2.score
+                    .unwrap_or_default()
+                    .partial_cmp(&s1.score.unwrap_or_default())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then(s1.start.cmp(&s2.start))
+            });
+            date.slots.truncate(n);
+            date.slots.sort_by_key(|s| s.start);
+        }
+        self.dates.retain(|date| !date.slots.is_empty());
+    }
 }
 
 #[derive(Debug)]
@@ -94,14 +127,78 @@ impl ServiceBookingSlotsDate {
     }
 }
 
+/// Sets the taken and remaining seats of the slots of a `Service` with
+/// `ServiceMultiPersonOptions::Group` and removes the slots that are fully booked.
+/// `Reservation`s with the same timestamp are added together.
+pub fn apply_group_capacity(
+    slots: Vec<ServiceBookingSlot>,
+    max_count: usize,
+    reservations: &[Reservation],
+) -> Vec<ServiceBookingSlot> {
+    let mut seats_taken = HashMap::new();
+    for reservation in reservations {
+        *seats_taken.entry(reservation.timestamp).or_insert(0) += reservation.count;
+    }
+
+    slots
+        .into_iter()
+        .filter_map(|mut slot| {
+            let taken = seats_taken.get(&slot.start).copied().unwrap_or(0);
+            if taken >= max_count {
+                return None;
+            }
+            slot.seats_taken = Some(taken);
+            slot.seats_remaining = Some(max_count - taken);
+            Some(slot)
+        })
+        .collect()
+}
+
+/// Sets the available optional hosts of the booking slots of a `Service` with
+/// `ServiceMultiPersonOptions::CollectiveQuorum` and removes the slots where
+/// the quorum of hosts is not available.
+pub fn apply_collective_quorum(
+    slots: Vec<ServiceBookingSlot>,
+    quorum: &CollectiveQuorum,
+) -> Vec<ServiceBookingSlot> {
+    slots
+        .into_iter()
+        .filter_map(|mut slot| {
+            slot.optional_user_ids = Some(quorum.available_optional(&slot.user_ids)?);
+            Some(slot)
+        })
+        .collect()
+}
+
+/// Sets the free resources of the booking slots of a `Service` that requires
+/// a resource and removes the slots where no resource of the pool is free.
+/// The `resource_slots` are the booking slots of the resource pool.
+pub fn apply_resource_pool(
+    slots: Vec<ServiceBookingSlot>,
+    resource_slots: Vec<ServiceBookingSlot>,
+) -> Vec<ServiceBookingSlot> {
+    let mut free_resources = resource_slots
+        .into_iter()
+        .map(|slot| (slot.start, slot.user_ids))
+        .collect::<HashMap<_, _>>();
+
+    slots
+        .into_iter()
+        .filter_map(|mut slot| {
+            slot.resource_ids = Some(free_resources.remove(&slot.start)?);
+            Some(slot)
+        })
+        .collect()
+}
+
 pub fn get_service_bookingslots(
-    users_free: Vec<UserFreeEvents>,
+    users_free: &[UserFreeEvents],
     options: &BookingSlotsOptions,
 ) -> Vec<ServiceBookingSlot> {
     // Maybe use HashMap::with_capacity
     let mut slots_lookup: HashMap<i64, ServiceBookingSlot> = HashMap::new();
 
-    for user in &users_free {
+    for user in users_free {
         let slots = get_booking_slots(&user.free_events, options);
         for slot in slots {
             if let Some(val) = slots_lookup.get(&slot.start) {
@@ -113,6 +210,11 @@ pub fn get_service_bookingslots(
                         duration: slot.duration,
                         start: slot.start,
                         user_ids,
+                        score: None,
+                        seats_taken: None,
+                        seats_remaining: None,
+                        optional_user_ids: None,
+                        resource_ids: None,
                     },
                 );
             } else {
@@ -122,6 +224,11 @@ pub fn get_service_bookingslots(
                         duration: slot.duration,
                         start: slot.start,
                         user_ids: vec![user.user_id.clone()],
+                        score: None,
+                        seats_taken: None,
+                        seats_remaining: None,
+                        optional_user_ids: None,
+                        resource_ids: None,
                     },
                 );
             }
@@ -134,6 +241,100 @@ pub fn get_service_bookingslots(
     slots
 }
 
+/// A step of a chain of `Service`s that are booked back to back
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceChainStep {
+    pub service_id: ID,
+    /// The duration of the step. Defaults to the first duration of the `Service`.
+    #[serde(default)]
+    pub duration: Option<i64>,
+    /// Time in *milliseconds* between the end of the previous step and the
+    /// start of this step. Ignored for the first step.
+    #[serde(default)]
+    pub gap: i64,
+    /// Only these hosts are considered for the step
+    #[serde(default)]
+    pub host_user_ids: Option<Vec<ID>>,
+}
+
+impl ServiceChainStep {
+    pub const MAX_STEPS: usize = 10;
+
+    pub fn is_valid_chain(steps: &[Self]) -> bool {
+        !steps.is_empty() && steps.len() <= Self::MAX_STEPS && steps.iter().all(|s| s.gap >= 0)
+    }
+
+    /// The offsets in *milliseconds* of the steps from the start of the chain,
+    /// given the resolved duration of every step
+    pub fn offsets(steps: &[Self], durations: &[i64]) -> Vec<i64> {
+        let mut offset = 0;
+        steps
+            .iter()
+            .zip(durations)
+            .enumerate()
+            .map(|(i, (step, duration))| {
+                if i > 0 {
+                    offset += step.gap;
+                }
+                let step_offset = offset;
+                offset += duration;
+                step_offset
+            })
+            .collect()
+    }
+}
+
+/// A time where every step of a chain of `Service`s can be booked
+#[derive(PartialEq, Debug)]
+pub struct ServiceChainBookingSlot {
+    pub start: i64,
+    /// The booking slot of every step of the chain
+    pub steps: Vec<ServiceBookingSlot>,
+}
+
+/// Finds the booking slots of the first step of a chain where every other
+/// step has a booking slot starting at its offset from the first step
+pub fn get_service_chain_bookingslots(
+    steps_slots: Vec<Vec<ServiceBookingSlot>>,
+    offsets: &[i64],
+) -> Vec<ServiceChainBookingSlot> {
+    let mut steps_slots = steps_slots
+        .into_iter()
+        .map(|slots| {
+            slots
+                .into_iter()
+                .map(|slot| (slot.start, slot))
+                .collect::<HashMap<_, _>>()
+        })
+        .collect::<Vec<_>>();
+    let mut starts = match steps_slots.first() {
+        Some(first_step) => first_step.keys().copied().collect::<Vec<_>>(),
+        None => return Vec::new(),
+    };
+    starts.sort_unstable();
+
+    let mut chain_slots = Vec::new();
+    for start in starts {
+        let is_available = steps_slots
+            .iter()
+            .zip(offsets)
+            .all(|(slots, offset)| slots.contains_key(&(start + offset)));
+        if !is_available {
+            continue;
+        }
+        chain_slots.push(ServiceChainBookingSlot {
+            start,
+            steps: steps_slots
+                .iter_mut()
+                .zip(offsets)
+                .filter_map(|(slots, offset)| slots.remove(&(start + offset)))
+                .collect(),
+        });
+    }
+    chain_slots
+}
+
 pub fn get_booking_slots(
     free_events: &CompatibleInstances,
     options: &BookingSlotsOptions,
@@ -167,6 +368,32 @@ pub fn get_booking_slots(
     booking_slots
 }
 
+/// Why a host of a `Service` can not be booked at a booking slot
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UnavailabilityReason {
+    /// The slot is not within the availability of the host
+    OutsideSchedule,
+    /// A busy `CalendarEvent` in a nettu busy calendar or an event of
+    /// another `Service` overlaps the slot
+    BusyEvent,
+    BusyGoogleCalendar,
+    BusyOutlookCalendar,
+    /// The slot is held by a `BookingHold`
+    Held,
+    /// The slot overlaps the buffer around a `Service` event
+    Buffer,
+    /// The slot starts before the `closest_booking_time` of the host
+    ClosestBookingTime,
+    /// The slot ends after the `furthest_booking_time` of the host
+    FurthestBookingTime,
+    /// The host has reached its maximum bookings per day or booked minutes
+    /// per week
+    BookingCapReached,
+    /// Every seat of the slot is taken
+    GroupCapacity,
+}
+
 pub fn validate_slots_interval(interval: i64) -> bool {
     let min_interval = 1000 * 60 * 5;
     let max_interval = 1000 * 60 * 60 * 2;
@@ -181,6 +408,7 @@ pub struct BookingSlotsQuery {
     pub interval: i64,
 }
 
+#[derive(Debug)]
 pub enum BookingQueryError {
     InvalidInterval,
     InvalidDate(String),
@@ -222,6 +450,48 @@ pub fn validate_bookingslots_query(
     Ok(BookingTimespan { start_ts, end_ts })
 }
 
+/// The dates covered by one page of booking slots. Clients scroll through the
+/// booking slots of a `Service` page by page, so that only a few weeks of
+/// booking slots are computed at once.
+#[derive(Debug, PartialEq)]
+pub struct BookingSlotsPage {
+    pub start_date: String,
+    /// The last date of the page, inclusive
+    pub end_date: String,
+    /// The first date of the following page
+    pub next_start_date: String,
+}
+
+impl BookingSlotsPage {
+    /// Maximum amount of dates of a page
+    pub const MAX_DAYS: i64 = 31;
+
+    pub fn new(start_date: &str, days: i64) -> Result<Self, BookingQueryError> {
+        if !(1..=Self::MAX_DAYS).contains(&days) {
+            return Err(BookingQueryError::InvalidTimespan);
+        }
+        let (year, month, day) = date::is_valid_date(start_date)
+            .map_err(|_| BookingQueryError::InvalidDate(start_date.to_string()))?;
+        let start = chrono_tz::UTC.ymd(year, month, day).and_hms(0, 0, 0);
+        let end = start + chrono::Duration::days(days - 1);
+        let next_start = start + chrono::Duration::days(days);
+
+        Ok(Self {
+            start_date: format_date(&start),
+            end_date: format_date(&end),
+            next_start_date: format_date(&next_start),
+        })
+    }
+
+    /// The start of the following page in the given timezone
+    pub fn next_start_ts(&self, timezone: Option<Tz>) -> i64 {
+        let tz = timezone.unwrap_or(chrono_tz::UTC);
+        // The date was formatted by `BookingSlotsPage::new`
+        let (year, month, day) = date::is_valid_date(&self.next_start_date).unwrap();
+        tz.ymd(year, month, day).and_hms(0, 0, 0).timestamp_millis()
+    }
+}
+
 #[cfg(test)]
 mod test {
 
@@ -572,10 +842,11 @@ mod test {
         users_free.push(UserFreeEvents {
             free_events: CompatibleInstances::new(vec![e1]),
             user_id: user_id.clone(),
+            preferred_times: Vec::new(),
         });
 
         let slots = get_service_bookingslots(
-            users_free,
+            &users_free,
             &BookingSlotsOptions {
                 start_ts: 10,
                 end_ts: 100,
@@ -590,7 +861,12 @@ mod test {
             ServiceBookingSlot {
                 duration: 10,
                 start: 10,
-                user_ids: vec![user_id.clone()]
+                user_ids: vec![user_id.clone()],
+                score: None,
+                seats_taken: None,
+                seats_remaining: None,
+                optional_user_ids: None,
+                resource_ids: None,
             }
         );
         assert_eq!(
@@ -598,7 +874,12 @@ mod test {
             ServiceBookingSlot {
                 duration: 10,
                 start: 20,
-                user_ids: vec![user_id.clone()]
+                user_ids: vec![user_id.clone()],
+                score: None,
+                seats_taken: None,
+                seats_remaining: None,
+                optional_user_ids: None,
+                resource_ids: None,
             }
         );
     }
@@ -623,14 +904,16 @@ mod test {
         users_free.push(UserFreeEvents {
             free_events: CompatibleInstances::new(vec![e1.clone()]),
             user_id: user_id_1.clone(),
+            preferred_times: Vec::new(),
         });
         users_free.push(UserFreeEvents {
             free_events: CompatibleInstances::new(vec![e1, e2]),
             user_id: user_id_2.clone(),
+            preferred_times: Vec::new(),
         });
 
         let slots = get_service_bookingslots(
-            users_free,
+            &users_free,
             &BookingSlotsOptions {
                 start_ts: 10,
                 end_ts: 100,
@@ -644,7 +927,12 @@ mod test {
             ServiceBookingSlot {
                 duration: 10,
                 start: 10,
-                user_ids: vec![user_id_1.clone(), user_id_2.clone()]
+                user_ids: vec![user_id_1.clone(), user_id_2.clone()],
+                score: None,
+                seats_taken: None,
+                seats_remaining: None,
+                optional_user_ids: None,
+                resource_ids: None,
             }
         );
         assert_eq!(
@@ -652,7 +940,12 @@ mod test {
             ServiceBookingSlot {
                 duration: 10,
                 start: 20,
-                user_ids: vec![user_id_1.clone(), user_id_2.clone()]
+                user_ids: vec![user_id_1.clone(), user_id_2.clone()],
+                score: None,
+                seats_taken: None,
+                seats_remaining: None,
+                optional_user_ids: None,
+                resource_ids: None,
             }
         );
         assert_eq!(
@@ -660,7 +953,12 @@ mod test {
             ServiceBookingSlot {
                 duration: 10,
                 start: 40,
-                user_ids: vec![user_id_2.clone()]
+                user_ids: vec![user_id_2.clone()],
+                score: None,
+                seats_taken: None,
+                seats_remaining: None,
+                optional_user_ids: None,
+                resource_ids: None,
             }
         );
     }
@@ -680,6 +978,11 @@ mod test {
             duration: 1000 * 60 * 15,
             start: 0,
             user_ids: vec![user_id],
+            score: None,
+            seats_taken: None,
+            seats_remaining: None,
+            optional_user_ids: None,
+            resource_ids: None,
         });
 
         let grouped_slots = ServiceBookingSlots::new(slots, chrono_tz::UTC);
@@ -700,6 +1003,11 @@ mod test {
                     duration: 1000 * 60 * interval,
                     start: i * 1000 * 60 * interval,
                     user_ids: vec![user_id.clone()],
+                    score: None,
+                    seats_taken: None,
+                    seats_remaining: None,
+                    optional_user_ids: None,
+                    resource_ids: None,
                 });
             }
 
@@ -710,4 +1018,219 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn retains_best_bookingslots_per_date() {
+        let user_id = ID::default();
+        let day = 1000 * 60 * 60 * 24;
+        let scores = [0.2, 0.9, 0.5, 0.9];
+        let mut slots = Vec::new();
+        for date in 0..2 {
+            for (i, score) in scores.iter().enumerate() {
+                slots.push(ServiceBookingSlot {
+                    duration: 1000 * 60 * 15,
+                    start: date * day + i as i64 * 1000 * 60 * 15,
+                    user_ids: vec![user_id.clone()],
+                    score: Some(*score),
+                    seats_taken: None,
+                    seats_remaining: None,
+                    optional_user_ids: None,
+                    resource_ids: None,
+                });
+            }
+        }
+
+        let mut grouped_slots = ServiceBookingSlots::new(slots, chrono_tz::UTC);
+        grouped_slots.retain_best_per_day(3);
+        assert_eq!(grouped_slots.dates.len(), 2);
+        for date in &grouped_slots.dates {
+            let slot_scores = date
+                .slots
+                .iter()
+                .map(|s| s.score.unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(slot_scores, vec![0.9, 0.5, 0.9]);
+        }
+
+        grouped_slots.retain_best_per_day(1);
+        for date in &grouped_slots.dates {
+            assert_eq!(date.slots.len(), 1);
+            assert_eq!(date.slots[0].start % day, 1000 * 60 * 15);
+        }
+
+        grouped_slots.retain_best_per_day(0);
+        assert!(grouped_slots.dates.is_empty());
+    }
+
+    #[test]
+    fn applies_group_capacity_to_bookingslots() {
+        let service_id = ID::default();
+        let slots = (0..3)
+            .map(|i| ServiceBookingSlot {
+                duration: 10,
+                start: i * 10,
+                user_ids: vec![ID::default()],
+                score: None,
+                seats_taken: None,
+                seats_remaining: None,
+                optional_user_ids: None,
+                resource_ids: None,
+            })
+            .collect::<Vec<_>>();
+        let reservations = vec![
+            Reservation {
+                service_id: service_id.clone(),
+                timestamp: 10,
+                count: 1,
+            },
+            Reservation {
+                service_id: service_id.clone(),
+                timestamp: 20,
+                count: 2,
+            },
+            // E.g. seats held for invitees
+            Reservation {
+                service_id,
+                timestamp: 20,
+                count: 1,
+            },
+        ];
+
+        let slots = apply_group_capacity(slots, 3, &reservations);
+        assert_eq!(slots.len(), 2);
+        assert_eq!(slots[0].start, 0);
+        assert_eq!(slots[0].seats_taken, Some(0));
+        assert_eq!(slots[0].seats_remaining, Some(3));
+        assert_eq!(slots[1].start, 10);
+        assert_eq!(slots[1].seats_taken, Some(1));
+        assert_eq!(slots[1].seats_remaining, Some(2));
+    }
+
+    fn slot(start: i64, duration: i64) -> ServiceBookingSlot {
+        ServiceBookingSlot {
+            duration,
+            start,
+            user_ids: vec![ID::default()],
+            score: None,
+            seats_taken: None,
+            seats_remaining: None,
+            optional_user_ids: None,
+            resource_ids: None,
+        }
+    }
+
+    #[test]
+    fn get_service_bookingslots_with_resource_pool() {
+        let host = ID::default();
+        let room_1 = ID::default();
+        let room_2 = ID::default();
+        let options = BookingSlotsOptions {
+            start_ts: 0,
+            end_ts: 40,
+            duration: 10,
+            interval: 10,
+        };
+        let free = |user_id: &ID, start_ts: i64, end_ts: i64| UserFreeEvents {
+            free_events: CompatibleInstances::new(vec![EventInstance {
+                busy: false,
+                start_ts,
+                end_ts,
+            }]),
+            user_id: user_id.clone(),
+            preferred_times: Vec::new(),
+        };
+
+        let slots = get_service_bookingslots(&[free(&host, 0, 40)], &options);
+        let resource_slots =
+            get_service_bookingslots(&[free(&room_1, 0, 20), free(&room_2, 10, 30)], &options);
+        let slots = apply_resource_pool(slots, resource_slots);
+
+        assert_eq!(
+            slots
+                .iter()
+                .map(|slot| (slot.start, slot.resource_ids.clone().unwrap()))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, vec![room_1.clone()]),
+                (10, vec![room_1, room_2.clone()]),
+                (20, vec![room_2]),
+            ]
+        );
+        assert!(slots.iter().all(|slot| slot.user_ids == vec![host.clone()]));
+    }
+
+    #[test]
+    fn service_chain_offsets() {
+        let step = |gap: i64| ServiceChainStep {
+            service_id: Default::default(),
+            duration: None,
+            gap,
+            host_user_ids: None,
+        };
+        let steps = vec![step(100), step(0), step(5)];
+        assert!(ServiceChainStep::is_valid_chain(&steps));
+        assert_eq!(
+            ServiceChainStep::offsets(&steps, &[30, 20, 10]),
+            vec![0, 30, 55]
+        );
+
+        assert!(!ServiceChainStep::is_valid_chain(&[]));
+        assert!(!ServiceChainStep::is_valid_chain(&[step(0), step(-1)]));
+        assert!(!ServiceChainStep::is_valid_chain(&vec![
+            step(0);
+            ServiceChainStep::MAX_STEPS
+                + 1
+        ]));
+    }
+
+    #[test]
+    fn get_service_chain_bookingslots_back_to_back() {
+        let first_step = (0..4).map(|i| slot(i * 10, 10)).collect::<Vec<_>>();
+        // The second step can not start at 20
+        let second_step = vec![slot(10, 20), slot(30, 20), slot(35, 20), slot(40, 20)];
+        let third_step = vec![slot(35, 5), slot(50, 5), slot(65, 5)];
+
+        let chain_slots =
+            get_service_chain_bookingslots(vec![first_step, second_step, third_step], &[0, 10, 35]);
+        assert_eq!(chain_slots.len(), 2);
+        assert_eq!(chain_slots[0].start, 0);
+        assert_eq!(
+            chain_slots[0]
+                .steps
+                .iter()
+                .map(|s| s.start)
+                .collect::<Vec<_>>(),
+            vec![0, 10, 35]
+        );
+        assert_eq!(chain_slots[1].start, 30);
+        assert_eq!(chain_slots[1].steps[2].start, 65);
+
+        assert!(get_service_chain_bookingslots(Vec::new(), &[]).is_empty());
+    }
+
+    #[test]
+    fn it_computes_bookingslots_pages() {
+        let page = BookingSlotsPage::new("2021-12-25", 7).unwrap();
+        assert_eq!(
+            page,
+            BookingSlotsPage {
+                start_date: "2021-12-25".into(),
+                end_date: "2021-12-31".into(),
+                next_start_date: "2022-1-1".into(),
+            }
+        );
+        assert_eq!(
+            page.next_start_ts(None),
+            Utc.ymd(2022, 1, 1).and_hms(0, 0, 0).timestamp_millis()
+        );
+
+        let page = BookingSlotsPage::new(&page.next_start_date, 1).unwrap();
+        assert_eq!(page.start_date, "2022-1-1");
+        assert_eq!(page.end_date, "2022-1-1");
+        assert_eq!(page.next_start_date, "2022-1-2");
+
+        assert!(BookingSlotsPage::new("2022-1-1", 0).is_err());
+        assert!(BookingSlotsPage::new("2022-1-1", BookingSlotsPage::MAX_DAYS + 1).is_err());
+        assert!(BookingSlotsPage::new("2022-13-1", 7).is_err());
+    }
 }
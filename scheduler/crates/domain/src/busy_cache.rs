@@ -0,0 +1,113 @@
+use crate::{shared::entity::ID, EventInstance, IntegrationProvider, TimeSpan};
+
+/// Busy intervals of an external Google or Outlook calendar as returned by
+/// the freebusy api of the provider at `fetched_at`, covering the time from
+/// `start_ts` to `end_ts`. A calendar can have entries for several timespans.
+#[derive(Debug, Clone)]
+pub struct ExternalBusyCacheEntry {
+    pub user_id: ID,
+    pub provider: IntegrationProvider,
+    pub ext_calendar_id: String,
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub busy: Vec<EventInstance>,
+    pub fetched_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BusyCacheFreshness {
+    /// Can be used as is
+    Fresh,
+    /// Can be used while it is being refetched from the provider
+    Stale,
+    /// Has to be refetched from the provider before it is used
+    Expired,
+}
+
+impl ExternalBusyCacheEntry {
+    /// How long a refetch of a stale entry may take before another one
+    /// is started, 1 minute
+    pub const REFRESH_TIMEOUT: i64 = 1000 * 60;
+
+    /// Whether the cached busy intervals include the whole `TimeSpan`
+    pub fn covers(&self, timespan: &TimeSpan) -> bool {
+        self.start_ts <= timespan.start() && timespan.end() <= self.end_ts
+    }
+
+    /// The entry is fresh for `ttl` *millis* after it was fetched, and then
+    /// stale for another `stale_ttl` *millis*
+    pub fn freshness(&self, now: i64, ttl: i64, stale_ttl: i64) -> BusyCacheFreshness {
+        let age = now - self.fetched_at;
+        if age < ttl {
+            BusyCacheFreshness::Fresh
+        } else if age < ttl + stale_ttl {
+            BusyCacheFreshness::Stale
+        } else {
+            BusyCacheFreshness::Expired
+        }
+    }
+
+    /// The cached busy intervals overlapping the `TimeSpan`
+    pub fn busy_in(&self, timespan: &TimeSpan) -> Vec<EventInstance> {
+        self.busy
+            .iter()
+            .filter(|instance| {
+                instance.start_ts < timespan.end() && instance.end_ts > timespan.start()
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entry() -> ExternalBusyCacheEntry {
+        ExternalBusyCacheEntry {
+            user_id: Default::default(),
+            provider: IntegrationProvider::Google,
+            ext_calendar_id: "primary".into(),
+            start_ts: 0,
+            end_ts: 100,
+            busy: vec![
+                EventInstance {
+                    start_ts: 10,
+                    end_ts: 20,
+                    busy: true,
+                },
+                EventInstance {
+                    start_ts: 60,
+                    end_ts: 70,
+                    busy: true,
+                },
+            ],
+            fetched_at: 1000,
+        }
+    }
+
+    fn timespan(start: i64, end: i64) -> TimeSpan {
+        TimeSpan::new(start, end)
+    }
+
+    #[test]
+    fn it_determines_freshness() {
+        let entry = entry();
+        assert_eq!(entry.freshness(1000, 50, 0), BusyCacheFreshness::Fresh);
+        assert_eq!(entry.freshness(1049, 50, 0), BusyCacheFreshness::Fresh);
+        assert_eq!(entry.freshness(1050, 50, 0), BusyCacheFreshness::Expired);
+        assert_eq!(entry.freshness(1050, 50, 100), BusyCacheFreshness::Stale);
+        assert_eq!(entry.freshness(1149, 50, 100), BusyCacheFreshness::Stale);
+        assert_eq!(entry.freshness(1150, 50, 100), BusyCacheFreshness::Expired);
+    }
+
+    #[test]
+    fn it_finds_busy_in_covered_timespan() {
+        let entry = entry();
+        assert!(entry.covers(&timespan(0, 100)));
+        assert!(!entry.covers(&timespan(0, 101)));
+        assert_eq!(entry.busy_in(&timespan(0, 100)).len(), 2);
+        assert_eq!(entry.busy_in(&timespan(15, 60)).len(), 1);
+        assert_eq!(entry.busy_in(&timespan(20, 60)).len(), 0);
+    }
+}
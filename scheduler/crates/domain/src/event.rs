@@ -16,6 +16,9 @@ pub struct CalendarEvent {
     pub start_ts: i64,
     pub duration: i64,
     pub busy: bool,
+    /// A tentative `CalendarEvent` is not confirmed yet, e.g. a `Booking`
+    /// waiting for approval. It is still busy if `busy` is set.
+    pub tentative: bool,
     pub end_ts: i64,
     pub created: i64,
     pub updated: i64,
@@ -0,0 +1,401 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// A question of a `Service` the invitee answers when booking it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IntakeQuestion {
+    /// The key of the answer in the `IntakeAnswers` of the invitee
+    pub key: String,
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Whether the invitee has to answer the question
+    #[serde(default)]
+    pub required: bool,
+    #[serde(flatten)]
+    pub kind: IntakeQuestionKind,
+}
+
+/// The type of the answer to an `IntakeQuestion`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum IntakeQuestionKind {
+    Text {
+        /// Maximum amount of characters in the answer
+        #[serde(default, rename = "maxLength")]
+        max_length: Option<usize>,
+    },
+    Number {
+        #[serde(default)]
+        min: Option<f64>,
+        #[serde(default)]
+        max: Option<f64>,
+    },
+    Boolean,
+    /// The answer is one of the `choices`, or a list of them if `multiple`
+    Choice {
+        choices: Vec<String>,
+        #[serde(default)]
+        multiple: bool,
+    },
+}
+
+/// The answer of an invitee to an `IntakeQuestion`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum IntakeAnswer {
+    Boolean(bool),
+    Number(f64),
+    Text(String),
+    Choices(Vec<String>),
+}
+
+impl IntakeAnswer {
+    /// Whether the answer is blank text or an empty selection, which does
+    /// not answer a required question
+    fn is_empty(&self) -> bool {
+        match self {
+            Self::Text(text) => text.trim().is_empty(),
+            Self::Choices(selected) => selected.is_empty(),
+            _ => false,
+        }
+    }
+}
+
+/// The answers of an invitee by the key of the `IntakeQuestion`
+pub type IntakeAnswers = HashMap<String, IntakeAnswer>;
+
+/// Why the answers of an invitee do not satisfy the `IntakeQuestion`s
+#[derive(Debug, Clone, PartialEq)]
+pub enum IntakeAnswerError {
+    /// A required question was not answered
+    Missing(String),
+    /// There is no question with the key of the answer
+    UnknownQuestion(String),
+    /// The answer is not of the type of the question
+    InvalidType(String),
+    /// The answer is not one of the choices of the question
+    InvalidChoice(String),
+    /// The answer is too long or outside of the allowed range of the question
+    OutOfRange(String),
+}
+
+impl std::fmt::Display for IntakeAnswerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing(key) => write!(f, "The required question: {} was not answered", key),
+            Self::UnknownQuestion(key) => write!(f, "There is no question: {}", key),
+            Self::InvalidType(key) => {
+                write!(
+                    f,
+                    "The answer to: {} is not of the type of the question",
+                    key
+                )
+            }
+            Self::InvalidChoice(key) => {
+                write!(f, "The answer to: {} is not one of the choices", key)
+            }
+            Self::OutOfRange(key) => write!(
+                f,
+                "The answer to: {} is outside of the range allowed by the question",
+                key
+            ),
+        }
+    }
+}
+
+impl IntakeQuestion {
+    pub fn is_valid(&self) -> bool {
+        if self.key.is_empty() {
+            return false;
+        }
+        match &self.kind {
+            IntakeQuestionKind::Number {
+                min: Some(min),
+                max: Some(max),
+            } => min <= max,
+            IntakeQuestionKind::Choice { choices, .. } => {
+                !choices.is_empty() && choices.iter().collect::<HashSet<_>>().len() == choices.len()
+            }
+            _ => true,
+        }
+    }
+
+    /// Checks that the answer satisfies this question
+    pub fn validate(&self, answer: &IntakeAnswer) -> Result<(), IntakeAnswerError> {
+        let key = || self.key.clone();
+        match (&self.kind, answer) {
+            (IntakeQuestionKind::Text { max_length }, IntakeAnswer::Text(text)) => match max_length
+            {
+                Some(max_length) if text.chars().count() > *max_length => {
+                    Err(IntakeAnswerError::OutOfRange(key()))
+                }
+                _ => Ok(()),
+            },
+            (IntakeQuestionKind::Number { min, max }, IntakeAnswer::Number(number)) => {
+                if matches!(min, Some(min) if number < min)
+                    || matches!(max, Some(max) if number > max)
+                {
+                    return Err(IntakeAnswerError::OutOfRange(key()));
+                }
+                Ok(())
+            }
+            (IntakeQuestionKind::Boolean, IntakeAnswer::Boolean(_)) => Ok(()),
+            (IntakeQuestionKind::Choice { choices, .. }, IntakeAnswer::Text(choice)) => {
+                if !choices.contains(choice) {
+                    return Err(IntakeAnswerError::InvalidChoice(key()));
+                }
+                Ok(())
+            }
+            (
+                IntakeQuestionKind::Choice {
+                    choices,
+                    multiple: true,
+                },
+                IntakeAnswer::Choices(selected),
+            ) => {
+                if !selected.iter().all(|choice| choices.contains(choice)) {
+                    return Err(IntakeAnswerError::InvalidChoice(key()));
+                }
+                Ok(())
+            }
+            _ => Err(IntakeAnswerError::InvalidType(key())),
+        }
+    }
+}
+
+/// Whether the keys of the questions are unique and every question is valid
+pub fn valid_intake_questions(questions: &[IntakeQuestion]) -> bool {
+    let keys = questions
+        .iter()
+        .map(|question| &question.key)
+        .collect::<HashSet<_>>();
+    keys.len() == questions.len() && questions.iter().all(|question| question.is_valid())
+}
+
+/// Checks that every required question is answered with a non-empty answer,
+/// that every answer belongs to one of the questions and that it satisfies
+/// that question
+pub fn validate_intake_answers(
+    questions: &[IntakeQuestion],
+    answers: &IntakeAnswers,
+) -> Result<(), IntakeAnswerError> {
+    for key in answers.keys() {
+        if !questions.iter().any(|question| &question.key == key) {
+            return Err(IntakeAnswerError::UnknownQuestion(key.clone()));
+        }
+    }
+    for question in questions {
+        match answers.get(&question.key) {
+            Some(answer) if question.required && answer.is_empty() => {
+                return Err(IntakeAnswerError::Missing(question.key.clone()))
+            }
+            Some(answer) => question.validate(answer)?,
+            None if question.required => {
+                return Err(IntakeAnswerError::Missing(question.key.clone()))
+            }
+            None => (),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn question(key: &str, required: bool, kind: IntakeQuestionKind) -> IntakeQuestion {
+        IntakeQuestion {
+            key: key.into(),
+            label: None,
+            required,
+            kind,
+        }
+    }
+
+    fn questions() -> Vec<IntakeQuestion> {
+        vec![
+            question(
+                "reason",
+                true,
+                IntakeQuestionKind::Text {
+                    max_length: Some(10),
+                },
+            ),
+            question(
+                "age",
+                false,
+                IntakeQuestionKind::Number {
+                    min: Some(18.0),
+                    max: None,
+                },
+            ),
+            question(
+                "language",
+                false,
+                IntakeQuestionKind::Choice {
+                    choices: vec!["en".into(), "no".into()],
+                    multiple: false,
+                },
+            ),
+        ]
+    }
+
+    fn answers(entries: Vec<(&str, IntakeAnswer)>) -> IntakeAnswers {
+        entries
+            .into_iter()
+            .map(|(key, answer)| (key.to_string(), answer))
+            .collect()
+    }
+
+    #[test]
+    fn it_validates_questions() {
+        assert!(valid_intake_questions(&questions()));
+        let mut duplicated = questions();
+        duplicated.push(questions()[0].clone());
+        assert!(!valid_intake_questions(&duplicated));
+        assert!(!question(
+            "language",
+            false,
+            IntakeQuestionKind::Choice {
+                choices: Vec::new(),
+                multiple: false
+            }
+        )
+        .is_valid());
+        assert!(!question(
+            "age",
+            false,
+            IntakeQuestionKind::Number {
+                min: Some(2.0),
+                max: Some(1.0)
+            }
+        )
+        .is_valid());
+        assert!(!question("", false, IntakeQuestionKind::Boolean).is_valid());
+    }
+
+    #[test]
+    fn it_accepts_valid_answers() {
+        let questions = questions();
+        assert!(validate_intake_answers(
+            &questions,
+            &answers(vec![("reason", IntakeAnswer::Text("Checkup".into()))])
+        )
+        .is_ok());
+        assert!(validate_intake_answers(
+            &questions,
+            &answers(vec![
+                ("reason", IntakeAnswer::Text("Checkup".into())),
+                ("age", IntakeAnswer::Number(30.0)),
+                ("language", IntakeAnswer::Text("no".into())),
+            ])
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn it_rejects_empty_answers_to_required_questions() {
+        let mut questions = questions();
+        questions.push(question(
+            "topics",
+            true,
+            IntakeQuestionKind::Choice {
+                choices: vec!["billing".into(), "support".into()],
+                multiple: true,
+            },
+        ));
+        let topics = ("topics", IntakeAnswer::Choices(vec!["billing".into()]));
+        for text in ["", "   "].iter() {
+            assert_eq!(
+                validate_intake_answers(
+                    &questions,
+                    &answers(vec![
+                        ("reason", IntakeAnswer::Text(text.to_string())),
+                        topics.clone()
+                    ])
+                ),
+                Err(IntakeAnswerError::Missing("reason".into()))
+            );
+        }
+        assert_eq!(
+            validate_intake_answers(
+                &questions,
+                &answers(vec![
+                    ("reason", IntakeAnswer::Text("Checkup".into())),
+                    ("topics", IntakeAnswer::Choices(Vec::new()))
+                ])
+            ),
+            Err(IntakeAnswerError::Missing("topics".into()))
+        );
+
+        // Optional questions may still be answered with an empty text
+        questions[0].required = false;
+        assert!(validate_intake_answers(
+            &questions,
+            &answers(vec![("reason", IntakeAnswer::Text("".into())), topics])
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn it_rejects_invalid_answers() {
+        let questions = questions();
+        let reason = ("reason", IntakeAnswer::Text("Checkup".into()));
+        assert_eq!(
+            validate_intake_answers(&questions, &answers(vec![])),
+            Err(IntakeAnswerError::Missing("reason".into()))
+        );
+        assert_eq!(
+            validate_intake_answers(
+                &questions,
+                &answers(vec![reason.clone(), ("email", IntakeAnswer::Boolean(true))])
+            ),
+            Err(IntakeAnswerError::UnknownQuestion("email".into()))
+        );
+        assert_eq!(
+            validate_intake_answers(
+                &questions,
+                &answers(vec![("reason", IntakeAnswer::Text("Way too long".into()))])
+            ),
+            Err(IntakeAnswerError::OutOfRange("reason".into()))
+        );
+        assert_eq!(
+            validate_intake_answers(
+                &questions,
+                &answers(vec![reason.clone(), ("age", IntakeAnswer::Number(17.0))])
+            ),
+            Err(IntakeAnswerError::OutOfRange("age".into()))
+        );
+        assert_eq!(
+            validate_intake_answers(
+                &questions,
+                &answers(vec![reason.clone(), ("age", IntakeAnswer::Boolean(true))])
+            ),
+            Err(IntakeAnswerError::InvalidType("age".into()))
+        );
+        assert_eq!(
+            validate_intake_answers(
+                &questions,
+                &answers(vec![
+                    reason.clone(),
+                    ("language", IntakeAnswer::Text("de".into()))
+                ])
+            ),
+            Err(IntakeAnswerError::InvalidChoice("language".into()))
+        );
+        assert_eq!(
+            validate_intake_answers(
+                &questions,
+                &answers(vec![
+                    reason,
+                    (
+                        "language",
+                        IntakeAnswer::Choices(vec!["en".into(), "no".into()])
+                    )
+                ])
+            ),
+            Err(IntakeAnswerError::InvalidType("language".into()))
+        );
+    }
+}
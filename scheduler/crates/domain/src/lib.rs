@@ -1,35 +1,51 @@
 mod account;
+mod booking;
 pub mod booking_slots;
+mod busy_cache;
 mod calendar;
 mod date;
 mod event;
 mod event_instance;
+mod intake;
 pub mod providers;
 mod reminder;
+mod reservation;
 mod schedule;
 pub mod scheduling;
 mod service;
 mod shared;
 mod timespan;
 mod user;
+mod vavailability;
+mod waitlist;
 
 pub use account::{Account, AccountIntegration, AccountSettings, AccountWebhookSettings, PEMKey};
+pub use booking::{Booking, BookingHold, BookingInvitee, BookingReschedule, BookingStatus};
+pub use busy_cache::{BusyCacheFreshness, ExternalBusyCacheEntry};
 pub use calendar::{Calendar, CalendarSettings, SyncedCalendar};
 pub use date::format_date;
 pub use event::{CalendarEvent, CalendarEventReminder, SyncedCalendarEvent};
 pub use event_instance::{
     get_free_busy, CompatibleInstances, EventInstance, EventWithInstances, FreeBusy,
 };
+pub use intake::{
+    valid_intake_questions, validate_intake_answers, IntakeAnswer, IntakeAnswerError,
+    IntakeAnswers, IntakeQuestion, IntakeQuestionKind,
+};
 pub use reminder::{EventRemindersExpansionJob, Reminder};
-pub use schedule::{Schedule, ScheduleRule};
+pub use reservation::Reservation;
+pub use schedule::{Schedule, ScheduleRule, ScheduleRuleInterval};
 pub use service::{
-    BusyCalendar, Service, ServiceMultiPersonOptions, ServiceResource, ServiceWithUsers, TimePlan,
+    BookingPolicyViolation, BusyCalendar, CollectiveQuorum, Service, ServiceBookingOptions,
+    ServiceMultiPersonOptions, ServiceResource, ServiceWithUsers, TimePlan,
 };
 pub use shared::entity::{Entity, ID};
 pub use shared::metadata::{Meta, Metadata};
 pub use shared::recurrence::{RRuleFrequency, RRuleOptions, WeekDay};
 pub use timespan::TimeSpan;
 pub use user::{IntegrationProvider, User, UserIntegration};
+pub use vavailability::{VAvailability, VAvailabilityError};
+pub use waitlist::WaitlistEntry;
 
 pub use chrono::{Month, Weekday};
 pub use chrono_tz::Tz;
@@ -0,0 +1,12 @@
+use crate::shared::entity::ID;
+
+/// A `Reservation` keeps track of how many seats have been taken at a
+/// given timestamp of a `Service` with `ServiceMultiPersonOptions::Group`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reservation {
+    pub service_id: ID,
+    /// The start of the booking slot the seats have been taken at
+    pub timestamp: i64,
+    /// The number of seats taken
+    pub count: usize,
+}
@@ -82,7 +82,7 @@ pub enum ScheduleRuleVariant {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
-struct Time {
+pub(crate) struct Time {
     pub hours: i64,
     pub minutes: i64,
 }
@@ -116,11 +116,33 @@ impl std::cmp::PartialOrd for Time {
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct ScheduleRuleInterval {
-    start: Time,
-    end: Time,
+    pub(crate) start: Time,
+    pub(crate) end: Time,
 }
 
 impl ScheduleRuleInterval {
+    pub fn new(start_hours: i64, start_minutes: i64, end_hours: i64, end_minutes: i64) -> Self {
+        Self {
+            start: Time {
+                hours: start_hours,
+                minutes: start_minutes,
+            },
+            end: Time {
+                hours: end_hours,
+                minutes: end_minutes,
+            },
+        }
+    }
+
+    /// An interval is valid if both times are valid times of the day and
+    /// the start is before the end
+    pub fn is_valid(&self) -> bool {
+        let valid_time = |time: &Time| {
+            time.hours >= 0 && time.hours < 24 && time.minutes >= 0 && time.minutes < 60
+        };
+        valid_time(&self.start) && valid_time(&self.end) && self.start < self.end
+    }
+
     /// Creates an `EventInstance` if the given timerange exists within
     /// that `Day` in the given timezone.
     /// If it is possible to create a timerange that is smaller but
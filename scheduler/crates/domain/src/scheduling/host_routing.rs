@@ -0,0 +1,101 @@
+use crate::Metadata;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A rule that the metadata of a host of a round robin `Service` has to
+/// satisfy for the host to be considered for a booking
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HostRoutingRule {
+    /// The key of the `User` metadata to match
+    pub key: String,
+    /// The value the metadata must have. If not set, the metadata must have
+    /// the value of the invitee attribute with the same key, and the rule is
+    /// ignored when the invitee does not have that attribute.
+    #[serde(default)]
+    pub value: Option<String>,
+}
+
+impl HostRoutingRule {
+    pub fn is_valid(&self) -> bool {
+        !self.key.is_empty()
+    }
+
+    /// Whether the metadata of a host satisfies this rule for an invitee
+    /// with the given attributes
+    pub fn matches(&self, metadata: &Metadata, attributes: &HashMap<String, String>) -> bool {
+        let expected = match self.value.as_ref().or_else(|| attributes.get(&self.key)) {
+            Some(expected) => expected,
+            None => return true,
+        };
+        metadata.inner.get(&self.key) == Some(expected)
+    }
+}
+
+/// Whether the metadata of a host satisfies all of the routing rules for an
+/// invitee with the given attributes
+pub fn matches_host_routing(
+    rules: &[HostRoutingRule],
+    metadata: &Metadata,
+    attributes: &HashMap<String, String>,
+) -> bool {
+    rules.iter().all(|rule| rule.matches(metadata, attributes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(key: &str, value: Option<&str>) -> HostRoutingRule {
+        HostRoutingRule {
+            key: key.into(),
+            value: value.map(String::from),
+        }
+    }
+
+    fn map(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn it_matches_fixed_values() {
+        let rules = vec![rule("certification", Some("level2"))];
+        let attributes = HashMap::new();
+        assert!(matches_host_routing(
+            &rules,
+            &map(&[("certification", "level2"), ("language", "en")]).into(),
+            &attributes
+        ));
+        assert!(!matches_host_routing(
+            &rules,
+            &map(&[("certification", "level1")]).into(),
+            &attributes
+        ));
+        assert!(!matches_host_routing(&rules, &Metadata::new(), &attributes));
+    }
+
+    #[test]
+    fn it_matches_invitee_attributes() {
+        let rules = vec![
+            rule("language", None),
+            rule("certification", Some("level2")),
+        ];
+        let host = map(&[("language", "de"), ("certification", "level2")]).into();
+        assert!(matches_host_routing(
+            &rules,
+            &host,
+            &map(&[("language", "de")])
+        ));
+        assert!(!matches_host_routing(
+            &rules,
+            &host,
+            &map(&[("language", "en")])
+        ));
+        // Rules for attributes the invitee did not provide are ignored
+        assert!(matches_host_routing(&rules, &host, &HashMap::new()));
+        assert!(matches_host_routing(&[], &Metadata::new(), &HashMap::new()));
+    }
+}
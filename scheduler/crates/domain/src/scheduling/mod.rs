@@ -1,2 +1,6 @@
+mod host_routing;
 mod round_robin;
+mod slot_scoring;
+pub use host_routing::*;
 pub use round_robin::*;
+pub use slot_scoring::*;
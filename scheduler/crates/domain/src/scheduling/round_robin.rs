@@ -1,10 +1,11 @@
-use crate::{CalendarEvent, ID};
-use itertools::Itertools;
-use rand::{thread_rng, Rng};
+use crate::{Booking, CalendarEvent, ServiceResource, TimeSpan, ID};
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 
 /// Round robin algorithm to decide which member should be assigned a
-/// `Service Event` when there are multiple members of a `Service`
+/// `Service Event` when there are multiple members of a `Service`.
+/// Only the available members with the highest priority are considered,
+/// and the assignments are shared between them by their weights.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum RoundRobinAlgorithm {
@@ -17,9 +18,8 @@ pub enum RoundRobinAlgorithm {
     /// Optimizes for equal distribution
     ///
     /// This assigns the `Service Event` to the member which was
-    /// least number of assigned `Service Event`s for the next
-    /// time period. Time period in this context is hard coded to be
-    /// two weeks.
+    /// least number of assigned `Service Event`s within the
+    /// `FairnessWindow` of the `Service`.
     EqualDistribution,
 }
 
@@ -29,39 +29,145 @@ impl Default for RoundRobinAlgorithm {
     }
 }
 
+/// What `RoundRobinAlgorithm::EqualDistribution` balances between the hosts
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum FairnessBalance {
+    /// The number of assigned bookings
+    #[default]
+    Count,
+    /// The amount of booked minutes
+    Minutes,
+}
+
+/// The period in which `RoundRobinAlgorithm::EqualDistribution` balances the
+/// assignments of the hosts
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FairnessWindow {
+    /// Time in *milliseconds* before now from which bookings are counted,
+    /// if `include_past_bookings` is set
+    pub lookback: i64,
+    /// Time in *milliseconds* after now until which bookings are counted
+    pub lookahead: i64,
+    /// Whether bookings that have already started are counted
+    pub include_past_bookings: bool,
+    pub balance: FairnessBalance,
+}
+
+impl Default for FairnessWindow {
+    fn default() -> Self {
+        Self {
+            lookback: 0,
+            lookahead: Self::DEFAULT_LOOKAHEAD,
+            include_past_bookings: false,
+            balance: FairnessBalance::default(),
+        }
+    }
+}
+
+impl FairnessWindow {
+    pub const DEFAULT_LOOKAHEAD: i64 = 1000 * 60 * 60 * 24 * 61;
+    pub const MAX_LENGTH: i64 = 1000 * 60 * 60 * 24 * 366;
+
+    pub fn is_valid(&self) -> bool {
+        let range = 0..=Self::MAX_LENGTH;
+        range.contains(&self.lookback) && range.contains(&self.lookahead)
+    }
+
+    /// The timespan in which bookings are counted at the given timestamp
+    pub fn timespan(&self, timestamp: i64) -> TimeSpan {
+        let start_ts = if self.include_past_bookings {
+            timestamp - self.lookback
+        } else {
+            timestamp
+        };
+        TimeSpan::new(start_ts, timestamp + self.lookahead)
+    }
+}
+
+/// A host of a `Service` that can be assigned by a round robin algorithm
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoundRobinHost {
+    pub user_id: ID,
+    /// Share of the assignments relative to the other hosts
+    pub weight: i64,
+    /// Hosts are only assigned when no host with a higher priority is available
+    pub priority: i64,
+}
+
+impl RoundRobinHost {
+    pub fn new(user_id: ID) -> Self {
+        Self {
+            user_id,
+            weight: ServiceResource::DEFAULT_WEIGHT,
+            priority: 0,
+        }
+    }
+}
+
+impl From<&ServiceResource> for RoundRobinHost {
+    fn from(resource: &ServiceResource) -> Self {
+        Self {
+            user_id: resource.user_id.clone(),
+            weight: resource.weight,
+            priority: resource.priority,
+        }
+    }
+}
+
+/// Keeps the members within the highest priority tier
+fn highest_priority_tier<T>(members: Vec<T>, host: impl Fn(&T) -> &RoundRobinHost) -> Vec<T> {
+    let max_priority = match members.iter().map(|m| host(m).priority).max() {
+        Some(priority) => priority,
+        None => return members,
+    };
+    members
+        .into_iter()
+        .filter(|m| host(m).priority == max_priority)
+        .collect()
+}
+
+/// Breaks ties between equally suited hosts by preferring the one with the
+/// highest weight and then the lowest user id, so that the assignment is
+/// deterministic
+fn tie_break(a: &RoundRobinHost, b: &RoundRobinHost) -> Ordering {
+    b.weight
+        .cmp(&a.weight)
+        .then_with(|| a.user_id.as_ref().cmp(b.user_id.as_ref()))
+}
+
 #[derive(Debug, Clone)]
 pub struct RoundRobinAvailabilityAssignment {
     /// List of members with a corresponding timestamp stating
     /// when the they were assigned a `Service Event` last time, if they have
     /// been assigned
-    pub members: Vec<(ID, Option<i64>)>,
+    pub members: Vec<(RoundRobinHost, Option<i64>)>,
+    /// The current timestamp. The time members have waited since their last
+    /// assignment is multiplied by their weight.
+    pub timestamp: i64,
 }
 
 impl RoundRobinAvailabilityAssignment {
-    pub fn assign(mut self) -> Option<ID> {
-        if self.members.is_empty() {
-            return None;
-        }
-        self.members.sort_by_key(|m| m.1);
-        let mut least_recently_booked_members: Vec<(ID, Option<i64>)> = Vec::new();
-        for member in self.members {
-            if least_recently_booked_members.is_empty()
-                || member.1 == least_recently_booked_members[0].1
-            {
-                least_recently_booked_members.push(member);
-            } else {
-                break;
-            }
-        }
-
-        if least_recently_booked_members.len() == 1 {
-            Some(least_recently_booked_members[0].0.clone())
-        } else {
-            // Just pick random
-            let mut rng = thread_rng();
-            let rand_user_index = rng.gen_range(0..least_recently_booked_members.len());
-            Some(least_recently_booked_members[rand_user_index].0.clone())
-        }
+    pub fn assign(self) -> Option<ID> {
+        let timestamp = self.timestamp;
+        // Members that have never been assigned have waited the longest
+        let weighted_wait = |member: &(RoundRobinHost, Option<i64>)| {
+            member
+                .1
+                .map(|assigned| (timestamp - assigned).max(0) * member.0.weight)
+        };
+        highest_priority_tier(self.members, |m| &m.0)
+            .into_iter()
+            .min_by(|a, b| match (weighted_wait(a), weighted_wait(b)) {
+                (None, None) => tie_break(&a.0, &b.0),
+                (None, Some(_)) => Ordering::Less,
+                (Some(_), None) => Ordering::Greater,
+                (Some(wait_a), Some(wait_b)) => {
+                    wait_b.cmp(&wait_a).then_with(|| tie_break(&a.0, &b.0))
+                }
+            })
+            .map(|member| member.0.user_id)
     }
 }
 
@@ -69,128 +175,223 @@ impl RoundRobinAvailabilityAssignment {
 pub struct RoundRobinEqualDistributionAssignment {
     /// List of upcoming `Service Event`s they are assigned for the given `Service`
     pub events: Vec<CalendarEvent>,
-    /// List of user that can be assigned the new `Service Event`
-    pub user_ids: Vec<ID>,
+    /// List of upcoming active `Booking`s they are assigned for the given `Service`.
+    /// `Service Event`s created for one of these `Booking`s are only counted once.
+    pub bookings: Vec<Booking>,
+    /// List of hosts that can be assigned the new `Service Event`
+    pub hosts: Vec<RoundRobinHost>,
+    pub balance: FairnessBalance,
 }
 
-#[derive(Debug)]
-struct UserWithEvents {
-    pub user_id: ID,
-    pub event_count: usize,
+/// The assignments of a host within the `FairnessWindow`
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostDistribution {
+    pub host: RoundRobinHost,
+    /// Number of assigned `Service Event`s and `Booking`s
+    pub assignments: i64,
+    /// Assigned time in *milliseconds*
+    pub booked_duration: i64,
+}
+
+impl HostDistribution {
+    fn load(&self, balance: FairnessBalance) -> i64 {
+        match balance {
+            FairnessBalance::Count => self.assignments,
+            FairnessBalance::Minutes => self.booked_duration,
+        }
+    }
+
+    /// Compares the assignments relative to the weights of the hosts
+    fn cmp_load(&self, other: &Self, balance: FairnessBalance) -> Ordering {
+        (self.load(balance) * other.host.weight).cmp(&(other.load(balance) * self.host.weight))
+    }
+
+    /// Share of the total load of all the hosts that is assigned to this host
+    pub fn share(&self, distribution: &[HostDistribution], balance: FairnessBalance) -> f64 {
+        let total: i64 = distribution.iter().map(|h| h.load(balance)).sum();
+        if total == 0 {
+            return 0.0;
+        }
+        self.load(balance) as f64 / total as f64
+    }
+
+    /// Share of the total load that this host should be assigned by its weight
+    pub fn target_share(&self, distribution: &[HostDistribution]) -> f64 {
+        let total: i64 = distribution.iter().map(|h| h.host.weight).sum();
+        if total == 0 {
+            return 0.0;
+        }
+        self.host.weight as f64 / total as f64
+    }
 }
 
 impl RoundRobinEqualDistributionAssignment {
-    pub fn assign(self) -> Option<ID> {
-        let mut prev: Option<usize> = None;
-        let users_with_least_upcoming_bookings = self
-            .user_ids
+    /// The assignments of every host, regardless of priority
+    pub fn distribution(&self) -> Vec<HostDistribution> {
+        let booking_event_ids = self
+            .bookings
             .iter()
-            .map(|user_id| UserWithEvents {
-                event_count: self.events.iter().filter(|e| &e.user_id == user_id).count(),
-                user_id: user_id.clone(),
-            })
-            .sorted_by_key(|u| u.event_count)
-            .take_while(|u| {
-                let take = match prev {
-                    Some(count) => count == u.event_count,
-                    None => true,
-                };
-                prev = Some(u.event_count);
-                take
-            })
+            .flat_map(|b| b.event_ids.iter())
             .collect::<Vec<_>>();
+        self.hosts
+            .iter()
+            .map(|host| {
+                let events = self
+                    .events
+                    .iter()
+                    .filter(|e| e.user_id == host.user_id && !booking_event_ids.contains(&&e.id));
+                let bookings = self
+                    .bookings
+                    .iter()
+                    .filter(|b| b.host_user_ids.contains(&host.user_id));
+                HostDistribution {
+                    host: host.clone(),
+                    assignments: (events.clone().count() + bookings.clone().count()) as i64,
+                    booked_duration: events.map(|e| e.end_ts - e.start_ts).sum::<i64>()
+                        + bookings.map(|b| b.duration).sum::<i64>(),
+                }
+            })
+            .collect()
+    }
 
-        if self.user_ids.is_empty() {
-            None
-        } else if users_with_least_upcoming_bookings.len() == 1 {
-            Some(users_with_least_upcoming_bookings[0].user_id.clone())
-        } else {
-            // Just pick random
-            let mut rng = thread_rng();
-            let rand_user_index = rng.gen_range(0..users_with_least_upcoming_bookings.len());
-            Some(
-                users_with_least_upcoming_bookings[rand_user_index]
-                    .user_id
-                    .clone(),
-            )
-        }
+    pub fn assign(self) -> Option<ID> {
+        let balance = self.balance;
+        highest_priority_tier(self.distribution(), |h| &h.host)
+            .into_iter()
+            .min_by(|a, b| {
+                a.cmp_load(b, balance)
+                    .then_with(|| tie_break(&a.host, &b.host))
+            })
+            .map(|h| h.host.user_id)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use rand::prelude::SliceRandom;
+    use rand::{prelude::SliceRandom, thread_rng};
 
     use super::*;
 
+    fn host(user_id: &ID) -> RoundRobinHost {
+        RoundRobinHost::new(user_id.clone())
+    }
+
+    /// Ids in ascending order
+    fn sorted_ids(count: usize) -> Vec<ID> {
+        let mut ids = (0..count).map(|_| ID::default()).collect::<Vec<_>>();
+        ids.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+        ids
+    }
+
     #[test]
     fn round_robin_availability_assignment_without_members() {
         let query = RoundRobinAvailabilityAssignment {
             members: Vec::new(),
+            timestamp: 0,
         };
         assert!(query.assign().is_none());
     }
 
     #[test]
     fn round_robin_availability_assignment() {
-        let none_user_1 = ID::default();
-        let none_user_2 = ID::default();
-        let none_user_3 = ID::default();
-        let none_user_4 = ID::default();
+        let none_user_ids = sorted_ids(4);
         let members = vec![
-            (none_user_4.clone(), None),
-            (ID::default(), Some(10)),
-            (none_user_1.clone(), None),
-            (ID::default(), Some(6)),
-            (ID::default(), Some(12)),
-            (ID::default(), Some(20)),
-            (ID::default(), Some(0)),
-            (ID::default(), Some(-28)),
-            (none_user_2.clone(), None),
-            (none_user_3.clone(), None),
+            (host(&none_user_ids[3]), None),
+            (host(&ID::default()), Some(10)),
+            (host(&none_user_ids[1]), None),
+            (host(&ID::default()), Some(6)),
+            (host(&ID::default()), Some(12)),
+            (host(&ID::default()), Some(20)),
+            (host(&ID::default()), Some(0)),
+            (host(&ID::default()), Some(-28)),
+            (host(&none_user_ids[0]), None),
+            (host(&none_user_ids[2]), None),
         ];
-        let none_user_ids = vec![none_user_1, none_user_2, none_user_3, none_user_4];
-        let query = RoundRobinAvailabilityAssignment { members };
-        assert!(query.clone().assign().is_some());
-        let selected_member = query.clone().assign().unwrap();
-        assert!(none_user_ids.contains(&selected_member));
-
-        // Check that random member is selected when there are multiple that are possible to select
-        let prev = selected_member;
-        let mut found_other = false;
-        for _ in 0..100 {
-            let selected_member = query.clone().assign().unwrap();
-            if selected_member != prev {
-                found_other = true;
-                break;
-            }
+        let query = RoundRobinAvailabilityAssignment {
+            members,
+            timestamp: 100,
+        };
+
+        // Ties are broken the same way every time
+        for _ in 0..10 {
+            assert_eq!(query.clone().assign(), Some(none_user_ids[0].clone()));
         }
-        assert!(found_other)
     }
 
     #[test]
     fn round_robin_availability_assignment_2() {
         let user_1 = ID::default();
         let members = vec![
-            (ID::default(), Some(10)),
-            (user_1.clone(), Some(4)),
-            (ID::default(), Some(6)),
-            (ID::default(), Some(12)),
-            (ID::default(), Some(20)),
-            (ID::default(), Some(100)),
-            (ID::default(), Some(28)),
+            (host(&ID::default()), Some(10)),
+            (host(&user_1), Some(4)),
+            (host(&ID::default()), Some(6)),
+            (host(&ID::default()), Some(12)),
+            (host(&ID::default()), Some(20)),
+            (host(&ID::default()), Some(100)),
+            (host(&ID::default()), Some(28)),
+        ];
+        let query = RoundRobinAvailabilityAssignment {
+            members,
+            timestamp: 100,
+        };
+        assert_eq!(query.assign(), Some(user_1));
+    }
+
+    #[test]
+    fn round_robin_availability_assignment_is_weighted() {
+        let junior = ID::default();
+        let senior = ID::default();
+        let members = vec![
+            (
+                RoundRobinHost {
+                    weight: 3,
+                    ..host(&junior)
+                },
+                Some(70),
+            ),
+            (host(&senior), Some(20)),
+        ];
+        // The junior has waited 30 * 3 and the senior 80 * 1
+        let query = RoundRobinAvailabilityAssignment {
+            members,
+            timestamp: 100,
+        };
+        assert_eq!(query.clone().assign(), Some(junior));
+        let query = RoundRobinAvailabilityAssignment {
+            timestamp: 30,
+            ..query
+        };
+        assert_eq!(query.assign(), Some(senior));
+    }
+
+    #[test]
+    fn round_robin_availability_assignment_prefers_priority() {
+        let junior = ID::default();
+        let members = vec![
+            (
+                RoundRobinHost {
+                    priority: 1,
+                    ..host(&junior)
+                },
+                Some(99),
+            ),
+            (host(&ID::default()), None),
+            (host(&ID::default()), Some(0)),
         ];
-        let query = RoundRobinAvailabilityAssignment { members };
-        assert!(query.clone().assign().is_some());
-        let selected_member = query.clone().assign().unwrap();
-        assert_eq!(selected_member, user_1);
+        let query = RoundRobinAvailabilityAssignment {
+            members,
+            timestamp: 100,
+        };
+        assert_eq!(query.assign(), Some(junior));
     }
 
     #[test]
     fn round_robin_eq_distribution_assignment_without_members() {
         let query = RoundRobinEqualDistributionAssignment {
             events: Vec::new(),
-            user_ids: Vec::new(),
+            bookings: Vec::new(),
+            balance: FairnessBalance::Count,
+            hosts: Vec::new(),
         };
         assert!(query.assign().is_none());
     }
@@ -202,6 +403,12 @@ mod tests {
         }
     }
 
+    fn generate_events(user_id: &ID, count: usize) -> Vec<CalendarEvent> {
+        (0..count)
+            .map(|_| generate_default_event(user_id))
+            .collect()
+    }
+
     struct UserWithEventsCount {
         pub user_id: ID,
         pub count: usize,
@@ -228,44 +435,207 @@ mod tests {
             UserWithEventsCount::new(least_bookings + 12),
             UserWithEventsCount::new(least_bookings),
         ];
-        let users_with_least_upcoming_bookings = user_with_events_count
+        let first_with_least_upcoming_bookings = user_with_events_count
             .iter()
             .filter(|u| u.count == least_bookings)
             .map(|u| u.user_id.clone())
-            .collect::<Vec<_>>();
+            .min_by(|a, b| a.as_ref().cmp(b.as_ref()));
 
-        let user_ids = user_with_events_count
+        let hosts = user_with_events_count
             .iter()
-            .map(|u| u.user_id.clone())
+            .map(|u| host(&u.user_id))
             .collect::<Vec<_>>();
         let mut events = user_with_events_count
             .iter()
-            .map(|u| {
-                let mut user_events = Vec::with_capacity(u.count);
-                for _ in 0..u.count {
-                    user_events.push(generate_default_event(&u.user_id));
-                }
-                user_events
-            })
+            .map(|u| generate_events(&u.user_id, u.count))
             .flatten()
             .collect::<Vec<_>>();
         events.shuffle(&mut thread_rng());
 
-        let query = RoundRobinEqualDistributionAssignment { events, user_ids };
-        assert!(query.clone().assign().is_some());
-        let selected_member = query.clone().assign().unwrap();
-        assert!(users_with_least_upcoming_bookings.contains(&selected_member));
-
-        // Check that random member is selected when there are multiple that are possible to select
-        let prev = selected_member;
-        let mut found_other = false;
-        for _ in 0..100 {
-            let selected_member = query.clone().assign().unwrap();
-            if selected_member != prev {
-                found_other = true;
-                break;
-            }
+        let query = RoundRobinEqualDistributionAssignment {
+            events,
+            bookings: Vec::new(),
+            balance: FairnessBalance::Count,
+            hosts,
+        };
+        // Ties are broken the same way every time
+        for _ in 0..10 {
+            assert_eq!(
+                query.clone().assign(),
+                first_with_least_upcoming_bookings.clone()
+            );
+        }
+    }
+
+    #[test]
+    fn round_robin_eq_distribution_assignment_is_weighted() {
+        let junior = ID::default();
+        let senior = ID::default();
+        let hosts = vec![
+            RoundRobinHost {
+                weight: 3,
+                ..host(&junior)
+            },
+            host(&senior),
+        ];
+
+        // The junior gets three bookings for every booking of the senior
+        let mut events = Vec::new();
+        let mut assigned = Vec::new();
+        for _ in 0..8 {
+            let query = RoundRobinEqualDistributionAssignment {
+                events: events.clone(),
+                bookings: Vec::new(),
+                hosts: hosts.clone(),
+                balance: FairnessBalance::Count,
+            };
+            let user_id = query.assign().unwrap();
+            events.push(generate_default_event(&user_id));
+            assigned.push(user_id);
         }
-        assert!(found_other)
+        assert_eq!(assigned.iter().filter(|id| **id == junior).count(), 6);
+        assert_eq!(assigned.iter().filter(|id| **id == senior).count(), 2);
+    }
+
+    #[test]
+    fn round_robin_eq_distribution_assignment_prefers_priority() {
+        let junior = ID::default();
+        let senior = ID::default();
+        let query = RoundRobinEqualDistributionAssignment {
+            events: generate_events(&junior, 10),
+            bookings: Vec::new(),
+            balance: FairnessBalance::Count,
+            hosts: vec![
+                RoundRobinHost {
+                    priority: 1,
+                    ..host(&junior)
+                },
+                host(&senior),
+            ],
+        };
+        assert_eq!(query.clone().assign(), Some(junior));
+
+        // The senior is only assigned when the junior is not available
+        let query = RoundRobinEqualDistributionAssignment {
+            hosts: vec![host(&senior)],
+            ..query
+        };
+        assert_eq!(query.assign(), Some(senior));
+    }
+
+    #[test]
+    fn round_robin_eq_distribution_assignment_counts_bookings() {
+        let user_ids = sorted_ids(2);
+        let user_1 = user_ids[0].clone();
+        let user_2 = user_ids[1].clone();
+        let booked_event = generate_default_event(&user_1);
+        let booking = Booking {
+            id: Default::default(),
+            account_id: Default::default(),
+            service_id: Default::default(),
+            host_user_ids: vec![user_1.clone()],
+            resource_id: None,
+            event_ids: vec![booked_event.id.clone()],
+            invitee: Default::default(),
+            start_ts: 0,
+            duration: 10,
+            status: crate::BookingStatus::Confirmed,
+            approval_deadline: None,
+            reschedules: Vec::new(),
+            created: 0,
+            updated: 0,
+            metadata: Default::default(),
+        };
+        let booking_without_event = Booking {
+            id: Default::default(),
+            host_user_ids: vec![user_2.clone()],
+            event_ids: Vec::new(),
+            ..booking.clone()
+        };
+
+        // The event of the booking is not counted twice, so the users are tied
+        let query = RoundRobinEqualDistributionAssignment {
+            events: vec![booked_event.clone(), generate_default_event(&user_2)],
+            bookings: vec![booking.clone()],
+            hosts: vec![host(&user_2), host(&user_1)],
+            balance: FairnessBalance::Count,
+        };
+        assert_eq!(query.assign(), Some(user_1.clone()));
+
+        // Bookings without events are counted as well
+        let query = RoundRobinEqualDistributionAssignment {
+            events: vec![booked_event],
+            bookings: vec![
+                booking,
+                booking_without_event.clone(),
+                booking_without_event,
+            ],
+            hosts: vec![host(&user_2), host(&user_1)],
+            balance: FairnessBalance::Count,
+        };
+        assert_eq!(query.assign(), Some(user_1));
+    }
+
+    #[test]
+    fn round_robin_eq_distribution_assignment_balances_minutes() {
+        let user_ids = sorted_ids(2);
+        let long_event = CalendarEvent {
+            start_ts: 0,
+            end_ts: 1000 * 60 * 90,
+            ..generate_default_event(&user_ids[0])
+        };
+        let short_events = (0..2)
+            .map(|_| CalendarEvent {
+                start_ts: 0,
+                end_ts: 1000 * 60 * 30,
+                ..generate_default_event(&user_ids[1])
+            })
+            .collect::<Vec<_>>();
+        let mut events = short_events;
+        events.push(long_event);
+
+        let query = RoundRobinEqualDistributionAssignment {
+            events,
+            bookings: Vec::new(),
+            hosts: vec![host(&user_ids[0]), host(&user_ids[1])],
+            balance: FairnessBalance::Count,
+        };
+        assert_eq!(query.clone().assign(), Some(user_ids[0].clone()));
+
+        let query = RoundRobinEqualDistributionAssignment {
+            balance: FairnessBalance::Minutes,
+            ..query
+        };
+        let distribution = query.distribution();
+        assert_eq!(distribution[0].assignments, 1);
+        assert_eq!(distribution[0].booked_duration, 1000 * 60 * 90);
+        assert_eq!(distribution[1].assignments, 2);
+        assert_eq!(distribution[1].booked_duration, 1000 * 60 * 60);
+        assert_eq!(
+            distribution[0].share(&distribution, FairnessBalance::Minutes),
+            0.6
+        );
+        assert_eq!(distribution[1].target_share(&distribution), 0.5);
+        assert_eq!(query.assign(), Some(user_ids[1].clone()));
+    }
+
+    #[test]
+    fn fairness_window_timespan() {
+        let mut window = FairnessWindow {
+            lookback: 100,
+            lookahead: 50,
+            include_past_bookings: false,
+            balance: FairnessBalance::Count,
+        };
+        assert!(window.is_valid());
+        let timespan = window.timespan(1000);
+        assert_eq!((timespan.start(), timespan.end()), (1000, 1050));
+        window.include_past_bookings = true;
+        let timespan = window.timespan(1000);
+        assert_eq!((timespan.start(), timespan.end()), (900, 1050));
+
+        window.lookback = -1;
+        assert!(!window.is_valid());
+        assert!(FairnessWindow::default().is_valid());
     }
 }
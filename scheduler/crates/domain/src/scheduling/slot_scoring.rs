@@ -0,0 +1,198 @@
+use crate::{
+    booking_slots::{ServiceBookingSlot, UserFreeEvents},
+    EventInstance,
+};
+
+/// Weight of placing a booking right next to another meeting or the edge
+/// of the availability
+const ADJACENCY_WEIGHT: f64 = 0.4;
+/// Weight of keeping the free time left around a booking in one large block
+const FREE_BLOCK_WEIGHT: f64 = 0.3;
+/// Weight of placing a booking within the preferred times of a host
+const PREFERRED_TIME_WEIGHT: f64 = 0.3;
+
+/// How the scores of the hosts available at a slot are combined into the
+/// score of that slot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SlotScoreAggregation {
+    /// The slot gets the score of the best suited host. Used when only one
+    /// host will be booked.
+    Best,
+    /// The slot gets the average score of the hosts. Used when all the
+    /// hosts will be booked.
+    Average,
+}
+
+/// Scores every `ServiceBookingSlot` by how little it fragments the free time of
+/// the available hosts
+pub fn score_slots(
+    slots: &mut [ServiceBookingSlot],
+    users_free: &[UserFreeEvents],
+    aggregation: SlotScoreAggregation,
+) {
+    for slot in slots.iter_mut() {
+        let scores = slot
+            .user_ids
+            .iter()
+            .filter_map(|user_id| users_free.iter().find(|user| user.user_id == *user_id))
+            .map(|user| score_slot_for_user(slot, user))
+            .collect::<Vec<_>>();
+        if scores.is_empty() {
+            slot.score = Some(0.0);
+            continue;
+        }
+
+        let score = match aggregation {
+            SlotScoreAggregation::Best => scores.iter().cloned().fold(0.0, f64::max),
+            SlotScoreAggregation::Average => scores.iter().sum::<f64>() / scores.len() as f64,
+        };
+        slot.score = Some((score * 1000.0).round() / 1000.0);
+    }
+}
+
+fn score_slot_for_user(slot: &ServiceBookingSlot, user: &UserFreeEvents) -> f64 {
+    let slot_end = slot.start + slot.duration;
+
+    let free_event = user
+        .free_events
+        .as_ref()
+        .iter()
+        .find(|event| event.start_ts <= slot.start && event.end_ts >= slot_end);
+    let (adjacency, free_block) = match free_event {
+        Some(event) => {
+            let gap_before = slot.start - event.start_ts;
+            let gap_after = event.end_ts - slot_end;
+            let adjacent_sides = [gap_before, gap_after]
+                .iter()
+                .filter(|gap| **gap == 0)
+                .count();
+            let leftover = gap_before + gap_after;
+            let free_block = if leftover == 0 {
+                1.0
+            } else {
+                gap_before.max(gap_after) as f64 / leftover as f64
+            };
+            (adjacent_sides as f64 / 2.0, free_block)
+        }
+        None => (0.0, 0.0),
+    };
+
+    let preferred = if slot.duration > 0 {
+        preferred_overlap(slot.start, slot_end, &user.preferred_times) as f64 / slot.duration as f64
+    } else {
+        0.0
+    };
+
+    ADJACENCY_WEIGHT * adjacency
+        + FREE_BLOCK_WEIGHT * free_block
+        + PREFERRED_TIME_WEIGHT * preferred
+}
+
+fn preferred_overlap(start: i64, end: i64, preferred_times: &[EventInstance]) -> i64 {
+    let overlap = preferred_times
+        .iter()
+        .map(|preferred| (end.min(preferred.end_ts) - start.max(preferred.start_ts)).max(0))
+        .sum::<i64>();
+    overlap.min(end - start)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CompatibleInstances, ID};
+
+    fn slot(start: i64, user_ids: Vec<ID>) -> ServiceBookingSlot {
+        ServiceBookingSlot {
+            start,
+            duration: 10,
+            user_ids,
+            score: None,
+            seats_taken: None,
+            seats_remaining: None,
+            optional_user_ids: None,
+            resource_ids: None,
+        }
+    }
+
+    fn free_events(instances: Vec<(i64, i64)>) -> CompatibleInstances {
+        CompatibleInstances::new(
+            instances
+                .into_iter()
+                .map(|(start_ts, end_ts)| EventInstance {
+                    start_ts,
+                    end_ts,
+                    busy: false,
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn it_prefers_slots_adjacent_to_meetings() {
+        let user_id = ID::default();
+        let users_free = vec![UserFreeEvents {
+            free_events: free_events(vec![(0, 60)]),
+            user_id: user_id.clone(),
+            preferred_times: Vec::new(),
+        }];
+        let mut slots = (0..6)
+            .map(|i| slot(i * 10, vec![user_id.clone()]))
+            .collect::<Vec<_>>();
+
+        score_slots(&mut slots, &users_free, SlotScoreAggregation::Best);
+        let scores = slots.iter().map(|s| s.score.unwrap()).collect::<Vec<_>>();
+        // Edges of the free block are best, the middle is worst
+        assert_eq!(scores[0], scores[5]);
+        assert!(scores[0] > scores[1]);
+        assert!(scores[1] > scores[2]);
+        assert_eq!(scores[2], scores[3]);
+        assert_eq!(scores[0], 0.5);
+    }
+
+    #[test]
+    fn it_prefers_slots_within_preferred_times() {
+        let user_id = ID::default();
+        let users_free = vec![UserFreeEvents {
+            free_events: free_events(vec![(0, 30)]),
+            user_id: user_id.clone(),
+            preferred_times: vec![EventInstance {
+                start_ts: 20,
+                end_ts: 100,
+                busy: false,
+            }],
+        }];
+        let mut slots = vec![
+            slot(0, vec![user_id.clone()]),
+            slot(20, vec![user_id.clone()]),
+        ];
+
+        score_slots(&mut slots, &users_free, SlotScoreAggregation::Best);
+        assert_eq!(slots[0].score, Some(0.5));
+        assert_eq!(slots[1].score, Some(0.8));
+    }
+
+    #[test]
+    fn it_aggregates_scores_of_hosts() {
+        let user_id_1 = ID::default();
+        let user_id_2 = ID::default();
+        let users_free = vec![
+            UserFreeEvents {
+                free_events: free_events(vec![(0, 10)]),
+                user_id: user_id_1.clone(),
+                preferred_times: Vec::new(),
+            },
+            UserFreeEvents {
+                free_events: free_events(vec![(0, 30)]),
+                user_id: user_id_2.clone(),
+                preferred_times: Vec::new(),
+            },
+        ];
+        let mut best = vec![slot(0, vec![user_id_1.clone(), user_id_2.clone()])];
+        score_slots(&mut best, &users_free, SlotScoreAggregation::Best);
+        assert_eq!(best[0].score, Some(0.7));
+
+        let mut average = vec![slot(0, vec![user_id_1, user_id_2])];
+        score_slots(&mut average, &users_free, SlotScoreAggregation::Average);
+        assert_eq!(average[0].score, Some(0.6));
+    }
+}
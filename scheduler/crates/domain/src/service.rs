@@ -1,8 +1,13 @@
-use crate::scheduling::RoundRobinAlgorithm;
+use crate::booking_slots::validate_slots_interval;
+use crate::schedule::{Day, ScheduleRuleInterval};
+use crate::scheduling::{FairnessWindow, HostRoutingRule, RoundRobinAlgorithm};
+use crate::valid_intake_questions;
 use crate::{
     shared::entity::{Entity, ID},
-    Meta, Metadata,
+    Booking, EventInstance, IntakeQuestion, Meta, Metadata, TimeSpan,
 };
+use chrono::{Date, Datelike, Duration, TimeZone};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 
 /// A type that describes a time plan and is either a `Calendar` or a `Schedule`
@@ -51,9 +56,34 @@ pub struct ServiceResource {
     /// time T then this `ServiceResource` will not have any available
     /// bookingslots after T + `furthest_booking_time`
     pub furthest_booking_time: Option<i64>,
+    /// Maximum amount of bookings this `ServiceResource` can receive on a single
+    /// day. Days are evaluated in the timezone of the `availability`.
+    /// This is best-effort: it is checked when booking slots are computed, but
+    /// not when a `Booking` is committed, so concurrent bookings at different
+    /// times of the same day can exceed it.
+    pub max_bookings_per_day: Option<i64>,
+    /// Maximum amount of booked time in *minutes* this `ServiceResource` can have
+    /// during a week (Monday to Sunday). Weeks are evaluated in the timezone of
+    /// the `availability`. Like `max_bookings_per_day` this is best-effort and
+    /// can be exceeded by concurrent bookings.
+    pub max_booked_minutes_per_week: Option<i64>,
+    /// Times of the day this `ServiceResource` prefers to be booked at. This is
+    /// used when ranking booking slots. Evaluated in the timezone of the
+    /// `availability`.
+    pub preferred_times: Vec<ScheduleRuleInterval>,
+    /// Share of the bookings assigned to this `ServiceResource` by round robin
+    /// relative to the other hosts of the `Service`. A host with weight 2 is
+    /// assigned twice as many bookings as a host with weight 1.
+    pub weight: i64,
+    /// Round robin only assigns bookings to this `ServiceResource` when no
+    /// available host of the `Service` has a higher priority
+    pub priority: i64,
 }
 
 impl ServiceResource {
+    pub const DEFAULT_WEIGHT: i64 = 1;
+    pub const MAX_WEIGHT: i64 = 100;
+
     pub fn new(user_id: ID, service_id: ID, availability: TimePlan) -> Self {
         Self {
             service_id,
@@ -63,24 +93,28 @@ impl ServiceResource {
             buffer_before: 0,
             closest_booking_time: 0,
             furthest_booking_time: None,
+            max_bookings_per_day: None,
+            max_booked_minutes_per_week: None,
+            preferred_times: Vec::new(),
+            weight: Self::DEFAULT_WEIGHT,
+            priority: 0,
         }
     }
 
-    pub fn set_availability(&mut self, availability: TimePlan) {
-        self.availability = availability;
-    }
-
-    fn valid_buffer(&self, buffer: i64) -> bool {
-        let min_buffer = 0;
-        let max_buffer = 60 * 12; // 12 Hours
-        if buffer < min_buffer || buffer > max_buffer {
+    pub fn set_weight(&mut self, weight: i64) -> bool {
+        if !(1..=Self::MAX_WEIGHT).contains(&weight) {
             return false;
         }
+        self.weight = weight;
         true
     }
 
+    pub fn set_availability(&mut self, availability: TimePlan) {
+        self.availability = availability;
+    }
+
     pub fn set_buffer_after(&mut self, buffer: i64) -> bool {
-        if self.valid_buffer(buffer) {
+        if valid_buffer(buffer) {
             self.buffer_after = buffer;
             return true;
         }
@@ -88,7 +122,7 @@ impl ServiceResource {
     }
 
     pub fn set_buffer_before(&mut self, buffer: i64) -> bool {
-        if self.valid_buffer(buffer) {
+        if valid_buffer(buffer) {
             self.buffer_before = buffer;
             return true;
         }
@@ -101,6 +135,133 @@ impl ServiceResource {
             _ => None,
         }
     }
+
+    /// The timespan of existing bookings that is needed to evaluate the booking
+    /// caps within the given timespan. Returns `None` if there are no booking caps.
+    pub fn booking_caps_timespan(&self, timespan: &TimeSpan, tz: &Tz) -> Option<TimeSpan> {
+        if self.max_bookings_per_day.is_none() && self.max_booked_minutes_per_week.is_none() {
+            return None;
+        }
+        let first_week = start_of_week(tz.timestamp_millis(timespan.start()).date());
+        let last_week = start_of_week(tz.timestamp_millis(timespan.end()).date());
+        Some(TimeSpan::new(
+            start_of_day(&first_week)?,
+            start_of_day(&(last_week + Duration::weeks(1)))?,
+        ))
+    }
+
+    /// Finds the days and weeks within the timespan where this `ServiceResource`
+    /// can not receive a new booking with the given duration (millis) because
+    /// it has reached its booking caps.
+    pub fn booking_caps_reached(
+        &self,
+        bookings: &[EventInstance],
+        timespan: &TimeSpan,
+        tz: &Tz,
+        duration: i64,
+    ) -> Vec<EventInstance> {
+        let mut reached = Vec::new();
+
+        let bookings_starting_within = |start_ts: i64, end_ts: i64| {
+            bookings
+                .iter()
+                .filter(move |booking| booking.start_ts >= start_ts && booking.start_ts < end_ts)
+        };
+
+        if let Some(max_bookings) = self.max_bookings_per_day {
+            let mut date = tz.timestamp_millis(timespan.start()).date();
+            while let (Some(start_ts), Some(end_ts)) =
+                (start_of_day(&date), start_of_day(&date.succ()))
+            {
+                if start_ts >= timespan.end() {
+                    break;
+                }
+                if bookings_starting_within(start_ts, end_ts).count() as i64 >= max_bookings {
+                    reached.push(EventInstance {
+                        start_ts,
+                        end_ts,
+                        busy: true,
+                    });
+                }
+                date = date.succ();
+            }
+        }
+
+        if let Some(max_minutes) = self.max_booked_minutes_per_week {
+            let mut week = start_of_week(tz.timestamp_millis(timespan.start()).date());
+            while let (Some(start_ts), Some(end_ts)) = (
+                start_of_day(&week),
+                start_of_day(&(week + Duration::weeks(1))),
+            ) {
+                if start_ts >= timespan.end() {
+                    break;
+                }
+                let booked: i64 = bookings_starting_within(start_ts, end_ts)
+                    .map(|booking| booking.end_ts - booking.start_ts)
+                    .sum();
+                if booked + duration > max_minutes * 60 * 1000 {
+                    reached.push(EventInstance {
+                        start_ts,
+                        end_ts,
+                        busy: true,
+                    });
+                }
+                week = week + Duration::weeks(1);
+            }
+        }
+
+        reached
+    }
+
+    /// Expands the `preferred_times` into instances within the given timespan
+    pub fn preferred_times_instances(&self, timespan: &TimeSpan, tz: &Tz) -> Vec<EventInstance> {
+        let mut instances = Vec::new();
+        if self.preferred_times.is_empty() {
+            return instances;
+        }
+
+        let mut date = tz.timestamp_millis(timespan.start()).date();
+        while matches!(start_of_day(&date), Some(start_ts) if start_ts < timespan.end()) {
+            let day = Day {
+                year: date.year(),
+                month: date.month(),
+                day: date.day(),
+            };
+            for interval in &self.preferred_times {
+                if let Some(instance) = interval.to_event(&day, tz) {
+                    instances.push(instance);
+                }
+            }
+            date = date.succ();
+        }
+
+        instances
+    }
+}
+
+/// The first instant of the date. Local midnight does not exist in some
+/// timezones on days where DST starts, e.g. America/Santiago, and then the
+/// day starts at the first hour that does exist.
+fn start_of_day(date: &Date<Tz>) -> Option<i64> {
+    (0..24).find_map(|hour| {
+        date.timezone()
+            .from_local_datetime(&date.naive_local().and_hms(hour, 0, 0))
+            .earliest()
+            .map(|datetime| datetime.timestamp_millis())
+    })
+}
+
+fn start_of_week(date: Date<Tz>) -> Date<Tz> {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+fn valid_buffer(buffer: i64) -> bool {
+    let min_buffer = 0;
+    let max_buffer = 60 * 12; // 12 Hours
+    if buffer < min_buffer || buffer > max_buffer {
+        return false;
+    }
+    true
 }
 
 impl Entity<String> for ServiceResource {
@@ -113,19 +274,217 @@ impl Entity<String> for ServiceResource {
 pub struct Service {
     pub id: ID,
     pub account_id: ID,
-    // interval: usize,
     pub multi_person: ServiceMultiPersonOptions,
+    pub booking_options: ServiceBookingOptions,
     pub metadata: Metadata,
 }
 
+/// Booking defaults for a `Service` which are enforced when querying
+/// booking slots and creating booking intends
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ServiceBookingOptions {
+    /// Durations in *milliseconds* that can be booked. The first one is used
+    /// when no duration is requested. If empty, any duration can be requested.
+    pub durations: Vec<i64>,
+    /// Interval in *milliseconds* between booking slots used when no interval
+    /// is requested. If set, only multiples of it can be requested.
+    pub default_interval: Option<i64>,
+    /// Overrides the `buffer_before` of every `ServiceResource` on this `Service`
+    pub buffer_before: Option<i64>,
+    /// Overrides the `buffer_after` of every `ServiceResource` on this `Service`
+    pub buffer_after: Option<i64>,
+    /// New `Booking`s are pending, with tentative `CalendarEvent`s, until a
+    /// host approves them
+    pub requires_approval: bool,
+    /// Time in *milliseconds* after which a pending `Booking` is declined
+    /// automatically. Defaults to 24 hours.
+    pub approval_timeout: Option<i64>,
+    /// Time in *milliseconds* before the start of a `Booking` from which it
+    /// can no longer be cancelled
+    pub cancellation_cutoff: Option<i64>,
+    /// Time in *milliseconds* before the start of a `Booking` from which it
+    /// can no longer be rescheduled
+    pub reschedule_cutoff: Option<i64>,
+    /// How many times a `Booking` can be rescheduled
+    pub max_reschedules: Option<usize>,
+    /// The period in which `RoundRobinAlgorithm::EqualDistribution` balances
+    /// the bookings of the hosts
+    pub fairness: FairnessWindow,
+    /// Returning invitees of a round robin `Service` are assigned the host of
+    /// their previous `Booking` whenever that host is available
+    pub sticky_hosts: bool,
+    /// Rules the metadata of the hosts of a round robin `Service` has to
+    /// satisfy to be considered for a booking
+    pub host_routing: Vec<HostRoutingRule>,
+    /// Questions the invitee answers when booking the `Service`
+    pub intake_questions: Vec<IntakeQuestion>,
+}
+
+/// Why the policy of a `Service` does not allow a change to a `Booking`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BookingPolicyViolation {
+    CancellationCutoff,
+    RescheduleCutoff,
+    MaxReschedules,
+}
+
+impl ServiceBookingOptions {
+    pub fn is_valid(&self) -> bool {
+        if self.durations.iter().any(|duration| *duration <= 0) {
+            return false;
+        }
+        if let Some(interval) = self.default_interval {
+            if !validate_slots_interval(interval) {
+                return false;
+            }
+        }
+        if matches!(self.approval_timeout, Some(timeout) if timeout <= 0) {
+            return false;
+        }
+        if !self.fairness.is_valid() {
+            return false;
+        }
+        if !self.host_routing.iter().all(|rule| rule.is_valid()) {
+            return false;
+        }
+        if !valid_intake_questions(&self.intake_questions) {
+            return false;
+        }
+        if [self.cancellation_cutoff, self.reschedule_cutoff]
+            .iter()
+            .flatten()
+            .any(|cutoff| *cutoff < 0)
+        {
+            return false;
+        }
+        [self.buffer_before, self.buffer_after]
+            .iter()
+            .flatten()
+            .all(|buffer| valid_buffer(*buffer))
+    }
+
+    /// Resolves the duration to use for a booking. Returns `None` if the
+    /// requested duration is not allowed or if no duration could be found.
+    pub fn duration(&self, requested: Option<i64>) -> Option<i64> {
+        match requested {
+            Some(duration) if self.durations.is_empty() || self.durations.contains(&duration) => {
+                Some(duration)
+            }
+            Some(_) => None,
+            None => self.durations.first().copied(),
+        }
+    }
+
+    /// Resolves the interval to use between booking slots. Returns `None` if the
+    /// requested interval is not allowed or if no interval could be found.
+    pub fn interval(&self, requested: Option<i64>) -> Option<i64> {
+        match (requested, self.default_interval) {
+            (Some(interval), Some(default)) if interval <= 0 || interval % default != 0 => None,
+            (Some(interval), _) => Some(interval),
+            (None, default) => default,
+        }
+    }
+
+    pub fn buffer_before(&self, resource: &ServiceResource) -> i64 {
+        self.buffer_before.unwrap_or(resource.buffer_before)
+    }
+
+    pub fn buffer_after(&self, resource: &ServiceResource) -> i64 {
+        self.buffer_after.unwrap_or(resource.buffer_after)
+    }
+
+    /// When a `Booking` created at the given timestamp is declined unless
+    /// it has been approved, if approval is required
+    pub fn approval_deadline(&self, timestamp: i64) -> Option<i64> {
+        if !self.requires_approval {
+            return None;
+        }
+        Some(timestamp + self.approval_timeout.unwrap_or(1000 * 60 * 60 * 24))
+    }
+
+    /// Checks that a `Booking` starting at the given time can be cancelled
+    /// at the given timestamp
+    pub fn check_cancellation(
+        &self,
+        start_ts: i64,
+        timestamp: i64,
+    ) -> Result<(), BookingPolicyViolation> {
+        match self.cancellation_cutoff {
+            Some(cutoff) if timestamp > start_ts - cutoff => {
+                Err(BookingPolicyViolation::CancellationCutoff)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that the `Booking` can be rescheduled at the given timestamp
+    pub fn check_reschedule(
+        &self,
+        booking: &Booking,
+        timestamp: i64,
+    ) -> Result<(), BookingPolicyViolation> {
+        if matches!(self.max_reschedules, Some(max) if booking.reschedules.len() >= max) {
+            return Err(BookingPolicyViolation::MaxReschedules);
+        }
+        match self.reschedule_cutoff {
+            Some(cutoff) if timestamp > booking.start_ts - cutoff => {
+                Err(BookingPolicyViolation::RescheduleCutoff)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "variant", content = "data", rename_all = "camelCase")]
 pub enum ServiceMultiPersonOptions {
     RoundRobinAlgorithm(RoundRobinAlgorithm),
     Collective,
+    /// Like `Collective`, but only some of the hosts have to attend
+    CollectiveQuorum(CollectiveQuorum),
     Group(usize),
 }
 
+/// Which hosts of a `Service` with `ServiceMultiPersonOptions::CollectiveQuorum`
+/// have to attend a booking. Every required host attends together with at
+/// least `min_optional` of the other hosts of the `Service`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectiveQuorum {
+    /// Hosts that have to attend every booking
+    pub required_user_ids: Vec<ID>,
+    /// How many of the optional hosts have to attend a booking
+    pub min_optional: usize,
+}
+
+impl CollectiveQuorum {
+    pub fn is_required(&self, user_id: &ID) -> bool {
+        self.required_user_ids.contains(user_id)
+    }
+
+    /// Finds the optional hosts among the given available hosts. Returns `None`
+    /// if a required host is not available or if too few optional hosts are.
+    pub fn available_optional(&self, user_ids: &[ID]) -> Option<Vec<ID>> {
+        if !self
+            .required_user_ids
+            .iter()
+            .all(|user_id| user_ids.contains(user_id))
+        {
+            return None;
+        }
+        let optional = user_ids
+            .iter()
+            .filter(|user_id| !self.is_required(user_id))
+            .cloned()
+            .collect::<Vec<_>>();
+        if optional.len() < self.min_optional {
+            return None;
+        }
+        Some(optional)
+    }
+}
+
 impl Default for ServiceMultiPersonOptions {
     fn default() -> Self {
         Self::RoundRobinAlgorithm(RoundRobinAlgorithm::default())
@@ -153,6 +512,7 @@ impl Service {
             id: Default::default(),
             account_id,
             multi_person: Default::default(),
+            booking_options: Default::default(),
             metadata: Default::default(),
         }
     }
@@ -162,11 +522,38 @@ impl Service {
 pub struct ServiceWithUsers {
     pub id: ID,
     pub account_id: ID,
+    /// The hosts of the `Service`
     pub users: Vec<ServiceResource>,
+    /// The resource pool of the `Service`. These are the registered `User`s
+    /// that are resources, and every `Booking` requires one of them
+    /// in addition to the hosts when the pool is not empty.
+    pub resources: Vec<ServiceResource>,
     pub multi_person: ServiceMultiPersonOptions,
+    pub booking_options: ServiceBookingOptions,
     pub metadata: Metadata,
 }
 
+impl ServiceWithUsers {
+    /// The furthest time any of the given hosts, or of all hosts if none are
+    /// given, can be booked at. `None` if one of them can be booked
+    /// arbitrarily far into the future.
+    pub fn last_bookable_ts(&self, host_user_ids: Option<&[ID]>, now: i64) -> Option<i64> {
+        let furthest_booking_time = self
+            .users
+            .iter()
+            .filter(|user| match host_user_ids {
+                Some(host_user_ids) => host_user_ids.contains(&user.user_id),
+                None => true,
+            })
+            .map(|user| user.furthest_booking_time)
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .max()?;
+
+        Some(now + furthest_booking_time * 60 * 1000)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "provider", content = "id")]
 pub enum BusyCalendar {
@@ -174,3 +561,335 @@ pub enum BusyCalendar {
     Outlook(String),
     Nettu(ID),
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::IntakeQuestionKind;
+    use chrono::{DateTime, Utc};
+
+    #[test]
+    fn it_validates_booking_options() {
+        assert!(ServiceBookingOptions::default().is_valid());
+        let options = ServiceBookingOptions {
+            durations: vec![1000 * 60 * 30, 1000 * 60 * 60],
+            default_interval: Some(1000 * 60 * 15),
+            buffer_before: Some(10),
+            buffer_after: Some(0),
+            requires_approval: true,
+            approval_timeout: Some(1000 * 60),
+            cancellation_cutoff: Some(0),
+            reschedule_cutoff: Some(1000 * 60 * 60),
+            max_reschedules: Some(0),
+            fairness: FairnessWindow {
+                lookback: 1000 * 60 * 60 * 24 * 30,
+                include_past_bookings: true,
+                ..Default::default()
+            },
+            sticky_hosts: true,
+            host_routing: vec![HostRoutingRule {
+                key: "language".into(),
+                value: None,
+            }],
+            intake_questions: vec![IntakeQuestion {
+                key: "reason".into(),
+                label: Some("Reason for the visit".into()),
+                required: true,
+                kind: IntakeQuestionKind::Text { max_length: None },
+            }],
+        };
+        assert!(options.is_valid());
+
+        let invalid_options = vec![
+            ServiceBookingOptions {
+                durations: vec![0],
+                ..Default::default()
+            },
+            ServiceBookingOptions {
+                default_interval: Some(1000),
+                ..Default::default()
+            },
+            ServiceBookingOptions {
+                buffer_after: Some(-1),
+                ..Default::default()
+            },
+            ServiceBookingOptions {
+                buffer_before: Some(60 * 13),
+                ..Default::default()
+            },
+            ServiceBookingOptions {
+                approval_timeout: Some(0),
+                ..Default::default()
+            },
+            ServiceBookingOptions {
+                cancellation_cutoff: Some(-1),
+                ..Default::default()
+            },
+            ServiceBookingOptions {
+                fairness: FairnessWindow {
+                    lookahead: FairnessWindow::MAX_LENGTH + 1,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ServiceBookingOptions {
+                host_routing: vec![HostRoutingRule {
+                    key: "".into(),
+                    value: Some("de".into()),
+                }],
+                ..Default::default()
+            },
+            ServiceBookingOptions {
+                intake_questions: vec![IntakeQuestion {
+                    key: "language".into(),
+                    label: None,
+                    required: false,
+                    kind: IntakeQuestionKind::Choice {
+                        choices: Vec::new(),
+                        multiple: false,
+                    },
+                }],
+                ..Default::default()
+            },
+        ];
+        for options in invalid_options {
+            assert!(!options.is_valid());
+        }
+    }
+
+    #[test]
+    fn it_resolves_booking_defaults() {
+        let any = ServiceBookingOptions::default();
+        assert_eq!(any.duration(Some(1234)), Some(1234));
+        assert_eq!(any.duration(None), None);
+        assert_eq!(any.interval(None), None);
+
+        let options = ServiceBookingOptions {
+            durations: vec![1000 * 60 * 30, 1000 * 60 * 60],
+            default_interval: Some(1000 * 60 * 15),
+            buffer_before: None,
+            buffer_after: Some(5),
+            requires_approval: false,
+            approval_timeout: None,
+            cancellation_cutoff: None,
+            reschedule_cutoff: None,
+            max_reschedules: None,
+            fairness: Default::default(),
+            sticky_hosts: false,
+            host_routing: Vec::new(),
+            intake_questions: Vec::new(),
+        };
+        assert_eq!(options.duration(None), Some(1000 * 60 * 30));
+        assert_eq!(options.duration(Some(1000 * 60 * 60)), Some(1000 * 60 * 60));
+        assert_eq!(options.duration(Some(1000 * 60 * 45)), None);
+        assert_eq!(options.interval(None), Some(1000 * 60 * 15));
+        assert_eq!(options.interval(Some(1000 * 60 * 30)), Some(1000 * 60 * 30));
+        assert_eq!(options.interval(Some(1000 * 60 * 5)), None);
+        assert_eq!(options.interval(Some(1000 * 60 * 20)), None);
+        assert_eq!(any.interval(Some(1000 * 60 * 5)), Some(1000 * 60 * 5));
+
+        let mut resource = ServiceResource::new(ID::default(), ID::default(), TimePlan::Empty);
+        resource.buffer_before = 20;
+        resource.buffer_after = 20;
+        assert_eq!(options.buffer_before(&resource), 20);
+        assert_eq!(options.buffer_after(&resource), 5);
+        assert_eq!(options.approval_deadline(100), None);
+
+        let options = ServiceBookingOptions {
+            requires_approval: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            options.approval_deadline(100),
+            Some(100 + 1000 * 60 * 60 * 24)
+        );
+    }
+
+    #[test]
+    fn it_enforces_booking_policy() {
+        let any = ServiceBookingOptions::default();
+        let mut booking = Booking {
+            id: Default::default(),
+            account_id: Default::default(),
+            service_id: Default::default(),
+            host_user_ids: Vec::new(),
+            resource_id: None,
+            event_ids: Vec::new(),
+            invitee: Default::default(),
+            start_ts: 1000,
+            duration: 10,
+            status: crate::BookingStatus::Confirmed,
+            approval_deadline: None,
+            reschedules: Vec::new(),
+            created: 0,
+            updated: 0,
+            metadata: Default::default(),
+        };
+        assert!(any.check_cancellation(booking.start_ts, 2000).is_ok());
+        assert!(any.check_reschedule(&booking, 2000).is_ok());
+
+        let options = ServiceBookingOptions {
+            cancellation_cutoff: Some(100),
+            reschedule_cutoff: Some(200),
+            max_reschedules: Some(1),
+            ..Default::default()
+        };
+        assert!(options.check_cancellation(booking.start_ts, 900).is_ok());
+        assert_eq!(
+            options.check_cancellation(booking.start_ts, 901),
+            Err(BookingPolicyViolation::CancellationCutoff)
+        );
+        assert!(options.check_reschedule(&booking, 800).is_ok());
+        assert_eq!(
+            options.check_reschedule(&booking, 801),
+            Err(BookingPolicyViolation::RescheduleCutoff)
+        );
+
+        assert!(booking.reschedule(1000, 10, Vec::new(), 0));
+        assert_eq!(
+            options.check_reschedule(&booking, 0),
+            Err(BookingPolicyViolation::MaxReschedules)
+        );
+    }
+
+    fn booking(start: DateTime<Tz>, minutes: i64) -> EventInstance {
+        EventInstance {
+            start_ts: start.timestamp_millis(),
+            end_ts: start.timestamp_millis() + minutes * 60 * 1000,
+            busy: true,
+        }
+    }
+
+    #[test]
+    fn it_finds_days_where_max_bookings_is_reached() {
+        let tz = chrono_tz::Europe::Oslo;
+        let mut resource = ServiceResource::new(ID::default(), ID::default(), TimePlan::Empty);
+        // Monday to Wednesday
+        let timespan = TimeSpan::new(
+            tz.ymd(2021, 9, 6).and_hms(0, 0, 0).timestamp_millis(),
+            tz.ymd(2021, 9, 9).and_hms(0, 0, 0).timestamp_millis(),
+        );
+        assert!(resource.booking_caps_timespan(&timespan, &tz).is_none());
+
+        resource.max_bookings_per_day = Some(2);
+        let caps_timespan = resource.booking_caps_timespan(&timespan, &tz).unwrap();
+        assert_eq!(
+            caps_timespan.start(),
+            tz.ymd(2021, 9, 6).and_hms(0, 0, 0).timestamp_millis()
+        );
+        assert_eq!(
+            caps_timespan.end(),
+            tz.ymd(2021, 9, 13).and_hms(0, 0, 0).timestamp_millis()
+        );
+
+        let bookings = vec![
+            booking(tz.ymd(2021, 9, 6).and_hms(9, 0, 0), 30),
+            booking(tz.ymd(2021, 9, 6).and_hms(10, 0, 0), 30),
+            booking(tz.ymd(2021, 9, 7).and_hms(9, 0, 0), 30),
+            // Monday in UTC, but tuesday in Oslo
+            booking(tz.ymd(2021, 9, 7).and_hms(0, 30, 0), 30),
+        ];
+        let reached = resource.booking_caps_reached(&bookings, &timespan, &tz, 1000 * 60 * 30);
+        assert_eq!(reached.len(), 2);
+        assert_eq!(
+            reached[0].start_ts,
+            tz.ymd(2021, 9, 6).and_hms(0, 0, 0).timestamp_millis()
+        );
+        assert_eq!(
+            reached[1].start_ts,
+            tz.ymd(2021, 9, 7).and_hms(0, 0, 0).timestamp_millis()
+        );
+        let reached_in_utc =
+            resource.booking_caps_reached(&bookings, &timespan, &Tz::UTC, 1000 * 60 * 30);
+        assert_eq!(reached_in_utc.len(), 1);
+        assert_eq!(
+            reached_in_utc[0].start_ts,
+            Utc.ymd(2021, 9, 6).and_hms(0, 0, 0).timestamp_millis()
+        );
+    }
+
+    #[test]
+    fn it_finds_booking_caps_on_days_without_midnight() {
+        // Midnight is skipped when DST starts on 2021-09-05 in Santiago
+        let tz = chrono_tz::America::Santiago;
+        let mut resource = ServiceResource::new(ID::default(), ID::default(), TimePlan::Empty);
+        resource.max_bookings_per_day = Some(1);
+        resource.max_booked_minutes_per_week = Some(60);
+        let timespan = TimeSpan::new(
+            tz.ymd(2021, 9, 4).and_hms(0, 0, 0).timestamp_millis(),
+            tz.ymd(2021, 9, 7).and_hms(0, 0, 0).timestamp_millis(),
+        );
+        assert!(resource.booking_caps_timespan(&timespan, &tz).is_some());
+
+        let bookings = [booking(tz.ymd(2021, 9, 5).and_hms(10, 0, 0), 30)];
+        let reached = resource.booking_caps_reached(&bookings, &timespan, &tz, 1000 * 60 * 30);
+        assert_eq!(reached.len(), 1);
+        assert_eq!(
+            reached[0].start_ts,
+            tz.ymd(2021, 9, 5).and_hms(1, 0, 0).timestamp_millis()
+        );
+        assert_eq!(
+            reached[0].end_ts,
+            tz.ymd(2021, 9, 6).and_hms(0, 0, 0).timestamp_millis()
+        );
+    }
+
+    #[test]
+    fn it_finds_weeks_where_max_booked_minutes_is_reached() {
+        let tz = chrono_tz::UTC;
+        let mut resource = ServiceResource::new(ID::default(), ID::default(), TimePlan::Empty);
+        resource.max_booked_minutes_per_week = Some(120);
+        // Wednesday to wednesday the week after
+        let timespan = TimeSpan::new(
+            tz.ymd(2021, 9, 8).and_hms(0, 0, 0).timestamp_millis(),
+            tz.ymd(2021, 9, 15).and_hms(0, 0, 0).timestamp_millis(),
+        );
+        let bookings = vec![
+            booking(tz.ymd(2021, 9, 6).and_hms(9, 0, 0), 60),
+            booking(tz.ymd(2021, 9, 10).and_hms(9, 0, 0), 30),
+            booking(tz.ymd(2021, 9, 13).and_hms(9, 0, 0), 60),
+        ];
+
+        let reached = resource.booking_caps_reached(&bookings, &timespan, &tz, 1000 * 60 * 30);
+        assert!(reached.is_empty());
+
+        let reached = resource.booking_caps_reached(&bookings, &timespan, &tz, 1000 * 60 * 60);
+        assert_eq!(reached.len(), 1);
+        assert_eq!(
+            reached[0].start_ts,
+            tz.ymd(2021, 9, 6).and_hms(0, 0, 0).timestamp_millis()
+        );
+        assert_eq!(
+            reached[0].end_ts,
+            tz.ymd(2021, 9, 13).and_hms(0, 0, 0).timestamp_millis()
+        );
+    }
+
+    #[test]
+    fn it_finds_available_optional_hosts_of_quorum() {
+        let manager = ID::default();
+        let interviewers = (0..3).map(|_| ID::default()).collect::<Vec<_>>();
+        let quorum = CollectiveQuorum {
+            required_user_ids: vec![manager.clone()],
+            min_optional: 2,
+        };
+
+        let mut available = interviewers.clone();
+        // The required host is missing
+        assert_eq!(quorum.available_optional(&available), None);
+        available.push(manager.clone());
+        assert_eq!(
+            quorum.available_optional(&available),
+            Some(interviewers.clone())
+        );
+        // Too few optional hosts
+        assert_eq!(
+            quorum.available_optional(&[manager.clone(), interviewers[0].clone()]),
+            None
+        );
+        assert_eq!(
+            quorum.available_optional(&[interviewers[2].clone(), manager, interviewers[1].clone()]),
+            Some(vec![interviewers[2].clone(), interviewers[1].clone()])
+        );
+    }
+}
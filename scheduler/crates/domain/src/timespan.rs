@@ -45,6 +45,10 @@ impl TimeSpan {
     pub fn end(&self) -> i64 {
         self.end_ts
     }
+
+    pub fn duration(&self) -> i64 {
+        self.duration
+    }
 }
 
 #[derive(Debug)]
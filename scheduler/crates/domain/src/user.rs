@@ -9,6 +9,11 @@ pub struct User {
     pub id: ID,
     pub account_id: ID,
     pub metadata: Metadata,
+    /// Set when the `User` is a non-human bookable resource, e.g. "room" or
+    /// "projector". Resources have their own `Calendar`s and `Schedule`s like
+    /// any other `User`, but are registered on a `Service` as part of its
+    /// resource pool instead of as a host.
+    pub resource_kind: Option<String>,
 }
 
 impl User {
@@ -18,6 +23,10 @@ impl User {
             ..Default::default()
         }
     }
+
+    pub fn is_resource(&self) -> bool {
+        self.resource_kind.is_some()
+    }
 }
 
 impl Entity<ID> for User {
@@ -0,0 +1,804 @@
+use crate::{
+    schedule::{Day, ScheduleRuleInterval, ScheduleRuleVariant, Time},
+    Schedule, ScheduleRule,
+};
+use chrono::{prelude::*, Duration};
+use chrono_tz::{Tz, UTC};
+use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
+
+const PRODID: &str = "-//Nettu//Nettu Scheduler//EN";
+/// Lines longer than this amount of octets should be folded (RFC 5545 section 3.1)
+const MAX_LINE_LENGTH: usize = 75;
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+#[derive(Error, Debug, PartialEq)]
+pub enum VAvailabilityError {
+    #[error("Malformed content line: {0}")]
+    MalformedLine(String),
+    #[error("Expected exactly one VAVAILABILITY component, but found: {0}")]
+    ComponentCount(usize),
+    #[error("AVAILABLE component is missing the property: {0}")]
+    MissingProperty(&'static str),
+    #[error("Invalid date or date-time value: {0}")]
+    InvalidDateTime(String),
+    #[error("Unknown timezone: {0}")]
+    InvalidTimezone(String),
+    #[error("All AVAILABLE components should be specified in the same timezone")]
+    MixedTimezones,
+    #[error("Unsupported recurrence: {0}")]
+    UnsupportedRecurrence(String),
+    #[error("AVAILABLE components spanning multiple days are not supported: {0}")]
+    MultiDayAvailability(String),
+}
+
+/// The `ScheduleRule`s and timezone parsed from a VAVAILABILITY
+/// component (RFC 7953)
+#[derive(Debug)]
+pub struct VAvailability {
+    /// `None` if the availability was only specified with floating times
+    pub timezone: Option<Tz>,
+    pub rules: Vec<ScheduleRule>,
+}
+
+impl Schedule {
+    /// Serializes the `ScheduleRule`s of this `Schedule` as an iCalendar object
+    /// with a single VAVAILABILITY component.
+    ///
+    /// `WDay` rules become weekly recurring AVAILABLE components and `Date` rules
+    /// become single AVAILABLE components. Because a `Date` rule replaces the `WDay`
+    /// rule for that day, the date is also added as an EXDATE to the weekly components.
+    pub fn to_vavailability(&self, dtstamp: i64) -> String {
+        let dtstamp = UTC
+            .timestamp_millis(dtstamp)
+            .format("%Y%m%dT%H%M%SZ")
+            .to_string();
+
+        let mut dates = Vec::new();
+        // Weekdays grouped by the interval they are available in
+        let mut weekly: BTreeMap<(i64, i64, i64, i64), Vec<Weekday>> = BTreeMap::new();
+        for rule in &self.rules {
+            match &rule.variant {
+                ScheduleRuleVariant::WDay(wday) => {
+                    for interval in &rule.intervals {
+                        weekly
+                            .entry(interval_key(interval))
+                            .or_default()
+                            .push(*wday);
+                    }
+                }
+                ScheduleRuleVariant::Date(datestr) => {
+                    if let Ok(day) = datestr.parse::<Day>() {
+                        dates.push((NaiveDate::from_ymd(day.year, day.month, day.day), rule));
+                    }
+                }
+            }
+        }
+        dates.sort_by_key(|(date, _)| *date);
+
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            format!("PRODID:{}", PRODID),
+            "BEGIN:VAVAILABILITY".to_string(),
+            format!("UID:{}", self.id),
+            format!("DTSTAMP:{}", dtstamp),
+        ];
+
+        let mut available_count = 0;
+        for ((start_h, start_m, end_h, end_m), mut wdays) in weekly {
+            wdays.sort_by_key(|wday| wday.num_days_from_monday());
+            wdays.dedup();
+            // First occurrence is in the week of monday 5. January 1970
+            let first_date = NaiveDate::from_ymd(1970, 1, 5)
+                + Duration::days(wdays[0].num_days_from_monday() as i64);
+            let start =
+                first_date.and_hms(0, 0, 0) + Duration::hours(start_h) + Duration::minutes(start_m);
+            let end =
+                first_date.and_hms(0, 0, 0) + Duration::hours(end_h) + Duration::minutes(end_m);
+            let byday = wdays
+                .iter()
+                .map(|wday| weekday_to_ical(*wday))
+                .collect::<Vec<_>>()
+                .join(",");
+
+            available_count += 1;
+            lines.push("BEGIN:AVAILABLE".into());
+            lines.push(format!("UID:{}-{}", self.id, available_count));
+            lines.push(format!("DTSTAMP:{}", dtstamp));
+            lines.push(self.date_time_property("DTSTART", &start));
+            lines.push(self.date_time_property("DTEND", &end));
+            lines.push(format!("RRULE:FREQ=WEEKLY;BYDAY={}", byday));
+            for (date, _) in dates
+                .iter()
+                .filter(|(date, _)| wdays.contains(&date.weekday()))
+            {
+                let exdate = date.and_time(start.time());
+                lines.push(self.date_time_property("EXDATE", &exdate));
+            }
+            lines.push("END:AVAILABLE".into());
+        }
+
+        for (date, rule) in &dates {
+            for interval in &rule.intervals {
+                let (start_h, start_m, end_h, end_m) = interval_key(interval);
+                let start =
+                    date.and_hms(0, 0, 0) + Duration::hours(start_h) + Duration::minutes(start_m);
+                let end = date.and_hms(0, 0, 0) + Duration::hours(end_h) + Duration::minutes(end_m);
+
+                available_count += 1;
+                lines.push("BEGIN:AVAILABLE".into());
+                lines.push(format!("UID:{}-{}", self.id, available_count));
+                lines.push(format!("DTSTAMP:{}", dtstamp));
+                lines.push(self.date_time_property("DTSTART", &start));
+                lines.push(self.date_time_property("DTEND", &end));
+                lines.push("END:AVAILABLE".into());
+            }
+        }
+
+        lines.push("END:VAVAILABILITY".into());
+        lines.push("END:VCALENDAR".into());
+
+        let mut ical = lines
+            .iter()
+            .map(|line| fold_line(line))
+            .collect::<Vec<_>>()
+            .join("\r\n");
+        ical.push_str("\r\n");
+        ical
+    }
+
+    fn date_time_property(&self, name: &str, datetime: &NaiveDateTime) -> String {
+        if self.timezone == UTC {
+            format!("{}:{}Z", name, datetime.format("%Y%m%dT%H%M%S"))
+        } else {
+            format!(
+                "{};TZID={}:{}",
+                name,
+                self.timezone.name(),
+                datetime.format("%Y%m%dT%H%M%S")
+            )
+        }
+    }
+}
+
+impl VAvailability {
+    /// Parses an iCalendar object containing a single VAVAILABILITY component.
+    ///
+    /// Only the AVAILABLE subcomponents are used. They should either be non
+    /// recurring or recur every week (or day), as that is what can be
+    /// described with `ScheduleRule`s.
+    pub fn parse(input: &str) -> Result<Self, VAvailabilityError> {
+        let mut vavailability_count = 0;
+        let mut stack: Vec<String> = Vec::new();
+        let mut components: Vec<Vec<ContentLine>> = Vec::new();
+        for line in unfold_lines(input) {
+            let line = ContentLine::parse(&line)?;
+            match line.name.as_str() {
+                "BEGIN" => {
+                    let component = line.value.to_uppercase();
+                    if component == "VAVAILABILITY" {
+                        vavailability_count += 1;
+                    }
+                    if component == "AVAILABLE" {
+                        components.push(Vec::new());
+                    }
+                    stack.push(component);
+                }
+                "END" => {
+                    stack.pop();
+                }
+                _ => {
+                    if stack.last().map(|c| c.as_str()) == Some("AVAILABLE") {
+                        if let Some(component) = components.last_mut() {
+                            component.push(line);
+                        }
+                    }
+                }
+            }
+        }
+        if vavailability_count != 1 {
+            return Err(VAvailabilityError::ComponentCount(vavailability_count));
+        }
+
+        let components = components
+            .iter()
+            .map(|c| AvailableComponent::parse(c))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut timezone = None;
+        for component in &components {
+            if let Some(tz) = component.start.1 {
+                match timezone {
+                    Some(existing) if existing != tz => {
+                        return Err(VAvailabilityError::MixedTimezones)
+                    }
+                    _ => timezone = Some(tz),
+                }
+            }
+        }
+
+        // Intervals for every weekday together with the dates that are excluded
+        let mut weekly: HashMap<Weekday, Vec<(ScheduleRuleInterval, Vec<NaiveDate>)>> =
+            HashMap::new();
+        let mut dates: BTreeMap<NaiveDate, Vec<ScheduleRuleInterval>> = BTreeMap::new();
+        for component in components {
+            let interval = component.interval()?;
+            let exdates = component
+                .exdates
+                .iter()
+                .map(|(datetime, tz)| match (tz, timezone) {
+                    (Some(from), Some(to)) => from
+                        .from_local_datetime(datetime)
+                        .earliest()
+                        .map(|dt| dt.with_timezone(&to).date().naive_local())
+                        .unwrap_or_else(|| datetime.date()),
+                    _ => datetime.date(),
+                })
+                .collect::<Vec<_>>();
+            match &component.weekdays {
+                Some(wdays) => {
+                    for wday in wdays {
+                        weekly
+                            .entry(*wday)
+                            .or_default()
+                            .push((interval.clone(), exdates.clone()));
+                    }
+                    for exdate in exdates {
+                        dates.entry(exdate).or_default();
+                    }
+                }
+                None => {
+                    dates
+                        .entry(component.start.0.date())
+                        .or_default()
+                        .push(interval);
+                }
+            }
+        }
+
+        let mut rules = WEEKDAYS
+            .iter()
+            .map(|wday| ScheduleRule {
+                variant: ScheduleRuleVariant::WDay(*wday),
+                intervals: weekly
+                    .get(wday)
+                    .map(|intervals| intervals.iter().map(|(i, _)| i.clone()).collect())
+                    .unwrap_or_default(),
+            })
+            .collect::<Vec<_>>();
+
+        for (date, mut intervals) in dates {
+            // A `Date` rule replaces the `WDay` rule for that day, so the weekly intervals
+            // that are not excluded on this date have to be included
+            if let Some(weekly_intervals) = weekly.get(&date.weekday()) {
+                for (interval, exdates) in weekly_intervals {
+                    if !exdates.contains(&date) {
+                        intervals.push(interval.clone());
+                    }
+                }
+            }
+            let day = Day {
+                year: date.year(),
+                month: date.month(),
+                day: date.day(),
+            };
+            rules.push(ScheduleRule {
+                variant: ScheduleRuleVariant::Date(day.to_string()),
+                intervals,
+            });
+        }
+
+        Ok(Self { timezone, rules })
+    }
+}
+
+fn interval_key(interval: &ScheduleRuleInterval) -> (i64, i64, i64, i64) {
+    (
+        interval.start.hours,
+        interval.start.minutes,
+        interval.end.hours,
+        interval.end.minutes,
+    )
+}
+
+fn weekday_to_ical(wday: Weekday) -> &'static str {
+    match wday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+fn weekday_from_ical(wday: &str) -> Option<Weekday> {
+    WEEKDAYS
+        .iter()
+        .find(|d| weekday_to_ical(**d) == wday)
+        .copied()
+}
+
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut line_length = 0;
+    for c in line.chars() {
+        if line_length + c.len_utf8() > MAX_LINE_LENGTH {
+            folded.push_str("\r\n ");
+            // The leading whitespace counts towards the line length
+            line_length = 1;
+        }
+        folded.push(c);
+        line_length += c.len_utf8();
+    }
+    folded
+}
+
+fn unfold_lines(input: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in input.lines() {
+        let line = line.trim_end_matches('\r');
+        if line.starts_with(' ') || line.starts_with('\t') {
+            if let Some(prev) = lines.last_mut() {
+                prev.push_str(&line[1..]);
+                continue;
+            }
+        }
+        if !line.is_empty() {
+            lines.push(line.to_string());
+        }
+    }
+    lines
+}
+
+#[derive(Debug)]
+struct ContentLine {
+    name: String,
+    params: HashMap<String, String>,
+    value: String,
+}
+
+impl ContentLine {
+    fn parse(line: &str) -> Result<Self, VAvailabilityError> {
+        // Colons inside quoted parameter values are not value delimiters
+        let mut in_quotes = false;
+        let value_start = line.char_indices().find(|(_, c)| {
+            if *c == '"' {
+                in_quotes = !in_quotes;
+            }
+            *c == ':' && !in_quotes
+        });
+        let (name_and_params, value) = match value_start {
+            Some((i, _)) => (&line[..i], &line[i + 1..]),
+            None => return Err(VAvailabilityError::MalformedLine(line.to_string())),
+        };
+
+        let mut parts = name_and_params.split(';');
+        let name = parts.next().unwrap_or_default().to_uppercase();
+        let mut params = HashMap::new();
+        for param in parts {
+            match param.split_once('=') {
+                Some((key, value)) => {
+                    params.insert(key.to_uppercase(), value.trim_matches('"').to_string());
+                }
+                None => return Err(VAvailabilityError::MalformedLine(line.to_string())),
+            }
+        }
+
+        Ok(Self {
+            name,
+            params,
+            value: value.to_string(),
+        })
+    }
+
+    /// Parses the value as a DATE or DATE-TIME together with the timezone
+    /// it was specified in. Floating times have no timezone.
+    fn date_times(&self) -> Result<Vec<(NaiveDateTime, Option<Tz>)>, VAvailabilityError> {
+        let tzid = match self.params.get("TZID") {
+            Some(tzid) => Some(
+                tzid.parse::<Tz>()
+                    .map_err(|_| VAvailabilityError::InvalidTimezone(tzid.clone()))?,
+            ),
+            None => None,
+        };
+
+        self.value
+            .split(',')
+            .map(|value| {
+                let invalid = || VAvailabilityError::InvalidDateTime(value.to_string());
+                if let Some(utc_value) = value.strip_suffix('Z') {
+                    NaiveDateTime::parse_from_str(utc_value, "%Y%m%dT%H%M%S")
+                        .map(|dt| (dt, Some(UTC)))
+                        .map_err(|_| invalid())
+                } else if value.contains('T') {
+                    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+                        .map(|dt| (dt, tzid))
+                        .map_err(|_| invalid())
+                } else {
+                    NaiveDate::parse_from_str(value, "%Y%m%d")
+                        .map(|date| (date.and_hms(0, 0, 0), tzid))
+                        .map_err(|_| invalid())
+                }
+            })
+            .collect()
+    }
+
+    fn date_time(&self) -> Result<(NaiveDateTime, Option<Tz>), VAvailabilityError> {
+        let mut values = self.date_times()?;
+        if values.len() != 1 {
+            return Err(VAvailabilityError::InvalidDateTime(self.value.clone()));
+        }
+        Ok(values.remove(0))
+    }
+}
+
+#[derive(Debug)]
+struct AvailableComponent {
+    start: (NaiveDateTime, Option<Tz>),
+    end: NaiveDateTime,
+    /// `None` if the component does not recur
+    weekdays: Option<Vec<Weekday>>,
+    exdates: Vec<(NaiveDateTime, Option<Tz>)>,
+}
+
+impl AvailableComponent {
+    fn parse(lines: &[ContentLine]) -> Result<Self, VAvailabilityError> {
+        let find = |name: &str| lines.iter().find(|line| line.name == name);
+
+        let dtstart = find("DTSTART").ok_or(VAvailabilityError::MissingProperty("DTSTART"))?;
+        let start = dtstart.date_time()?;
+        let is_date = !dtstart.value.contains('T');
+
+        let end = match (find("DTEND"), find("DURATION")) {
+            (Some(dtend), _) => {
+                let (end, end_tz) = dtend.date_time()?;
+                match (end_tz, start.1) {
+                    (Some(from), Some(to)) if from != to => from
+                        .from_local_datetime(&end)
+                        .earliest()
+                        .map(|dt| dt.with_timezone(&to).naive_local())
+                        .ok_or_else(|| VAvailabilityError::InvalidDateTime(dtend.value.clone()))?,
+                    _ => end,
+                }
+            }
+            (None, Some(duration)) => start.0 + parse_duration(&duration.value)?,
+            (None, None) if is_date => start.0 + Duration::days(1),
+            (None, None) => return Err(VAvailabilityError::MissingProperty("DTEND")),
+        };
+
+        if find("RDATE").is_some() {
+            return Err(VAvailabilityError::UnsupportedRecurrence("RDATE".into()));
+        }
+        let weekdays = match find("RRULE") {
+            Some(rrule) => Some(parse_rrule_weekdays(&rrule.value, start.0.weekday())?),
+            None => None,
+        };
+
+        let mut exdates = Vec::new();
+        for exdate in lines.iter().filter(|line| line.name == "EXDATE") {
+            exdates.append(&mut exdate.date_times()?);
+        }
+
+        Ok(Self {
+            start,
+            end,
+            weekdays,
+            exdates,
+        })
+    }
+
+    fn interval(&self) -> Result<ScheduleRuleInterval, VAvailabilityError> {
+        let start = self.start.0;
+        let end = if self.end.date() == start.date() {
+            Time {
+                hours: self.end.hour() as i64,
+                minutes: self.end.minute() as i64,
+            }
+        } else if self.end == start.date().succ().and_hms(0, 0, 0) {
+            // Available until midnight
+            Time {
+                hours: 23,
+                minutes: 59,
+            }
+        } else {
+            return Err(VAvailabilityError::MultiDayAvailability(format!(
+                "{} - {}",
+                start, self.end
+            )));
+        };
+
+        Ok(ScheduleRuleInterval {
+            start: Time {
+                hours: start.hour() as i64,
+                minutes: start.minute() as i64,
+            },
+            end,
+        })
+    }
+}
+
+/// Returns the weekdays on which the recurrence rule occurs
+fn parse_rrule_weekdays(rrule: &str, start: Weekday) -> Result<Vec<Weekday>, VAvailabilityError> {
+    let unsupported = || VAvailabilityError::UnsupportedRecurrence(rrule.to_string());
+
+    let mut freq = None;
+    let mut weekdays = None;
+    for part in rrule.split(';') {
+        let (key, value) = part.split_once('=').ok_or_else(unsupported)?;
+        match key.to_uppercase().as_str() {
+            "FREQ" => freq = Some(value.to_uppercase()),
+            "INTERVAL" if value == "1" => (),
+            "WKST" => (),
+            "BYDAY" => {
+                weekdays = Some(
+                    value
+                        .split(',')
+                        .map(|wday| weekday_from_ical(&wday.to_uppercase()))
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(unsupported)?,
+                )
+            }
+            // COUNT, UNTIL and the other rule parts would make the availability
+            // differ from week to week
+            _ => return Err(unsupported()),
+        }
+    }
+
+    match (freq.as_deref(), weekdays) {
+        (Some("WEEKLY"), Some(weekdays)) | (Some("DAILY"), Some(weekdays)) => Ok(weekdays),
+        (Some("WEEKLY"), None) => Ok(vec![start]),
+        (Some("DAILY"), None) => Ok(WEEKDAYS.to_vec()),
+        _ => Err(unsupported()),
+    }
+}
+
+/// Parses a DURATION value (RFC 5545 section 3.3.6), e.g. PT8H30M
+fn parse_duration(value: &str) -> Result<Duration, VAvailabilityError> {
+    let invalid = || VAvailabilityError::InvalidDateTime(value.to_string());
+    let value = value.strip_prefix('+').unwrap_or(value);
+    let value = value.strip_prefix('P').ok_or_else(invalid)?;
+
+    let mut duration = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in value.chars() {
+        match c {
+            'T' => in_time = true,
+            '0'..='9' => number.push(c),
+            unit => {
+                let amount = number.parse::<i64>().map_err(|_| invalid())?;
+                number.clear();
+                duration = duration
+                    + match (unit, in_time) {
+                        ('W', false) => Duration::weeks(amount),
+                        ('D', false) => Duration::days(amount),
+                        ('H', true) => Duration::hours(amount),
+                        ('M', true) => Duration::minutes(amount),
+                        ('S', true) => Duration::seconds(amount),
+                        _ => return Err(invalid()),
+                    };
+            }
+        }
+    }
+    if !number.is_empty() {
+        return Err(invalid());
+    }
+
+    Ok(duration)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::TimeSpan;
+
+    fn interval(start: (i64, i64), end: (i64, i64)) -> ScheduleRuleInterval {
+        ScheduleRuleInterval {
+            start: Time {
+                hours: start.0,
+                minutes: start.1,
+            },
+            end: Time {
+                hours: end.0,
+                minutes: end.1,
+            },
+        }
+    }
+
+    #[test]
+    fn exports_default_schedule() {
+        let schedule = Schedule::new(Default::default(), Default::default(), &chrono_tz::UTC);
+        let ical = schedule.to_vavailability(0);
+        assert!(ical.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ical.contains("BEGIN:VAVAILABILITY\r\n"));
+        assert!(ical.contains("DTSTART:19700105T090000Z\r\n"));
+        assert!(ical.contains("DTEND:19700105T173000Z\r\n"));
+        assert!(ical.contains("RRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR\r\n"));
+        assert_eq!(ical.matches("BEGIN:AVAILABLE").count(), 1);
+        for line in ical.split("\r\n") {
+            assert!(line.len() <= MAX_LINE_LENGTH);
+        }
+    }
+
+    #[test]
+    fn parses_rfc_7953_example() {
+        let ical = "BEGIN:VCALENDAR
+VERSION:2.0
+PRODID:-//example.com//iCalendar 2.0//EN
+BEGIN:VAVAILABILITY
+ORGANIZER:mailto:bernard@example.com
+UID:20061005T133225Z-00001-availability@example.com
+DTSTAMP:20061005T133225Z
+DTSTART;TZID=America/Montreal:20061002T000000
+BEGIN:AVAILABLE
+UID:20061005T133225Z-00001-A-availability@example.com
+SUMMARY:Monday to Friday from 9:00 to 17:00
+DTSTART;TZID=America/Montreal:20061002T090000
+DTEND;TZID=America/Montreal:20061002T170000
+RRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR
+END:AVAILABLE
+BEGIN:AVAILABLE
+UID:20061005T133225Z-00001-B-availability@example.com
+SUMMARY:Saturday
+DTSTART;TZID=America/Montreal:20061007T100000
+DURATION:PT4H
+RRULE:FREQ=WEEKLY
+END:AVAILABLE
+END:VAVAILABILITY
+END:VCALENDAR";
+
+        let res = VAvailability::parse(ical).expect("To parse vavailability");
+        assert_eq!(res.timezone, Some(chrono_tz::America::Montreal));
+        assert_eq!(res.rules.len(), 7);
+        for rule in &res.rules {
+            match &rule.variant {
+                ScheduleRuleVariant::WDay(Weekday::Sat) => {
+                    assert_eq!(rule.intervals, vec![interval((10, 0), (14, 0))]);
+                }
+                ScheduleRuleVariant::WDay(Weekday::Sun) => assert!(rule.intervals.is_empty()),
+                ScheduleRuleVariant::WDay(_) => {
+                    assert_eq!(rule.intervals, vec![interval((9, 0), (17, 0))]);
+                }
+                ScheduleRuleVariant::Date(_) => panic!("Did not expect any date rules"),
+            }
+        }
+    }
+
+    #[test]
+    fn roundtrip_preserves_freebusy() {
+        let mut schedule = Schedule::new(
+            Default::default(),
+            Default::default(),
+            &chrono_tz::Europe::Oslo,
+        );
+        let now = Utc::now();
+        let next_week = now + Duration::days(7);
+        let in_two_weeks = now + Duration::days(14);
+        let mut rules = schedule.rules.clone();
+        rules.push(ScheduleRule {
+            variant: ScheduleRuleVariant::WDay(Weekday::Sat),
+            intervals: vec![interval((8, 0), (10, 0)), interval((12, 0), (23, 59))],
+        });
+        // Day off
+        rules.push(ScheduleRule {
+            variant: ScheduleRuleVariant::Date(format!(
+                "{}-{}-{}",
+                next_week.year(),
+                next_week.month(),
+                next_week.day()
+            )),
+            intervals: Vec::new(),
+        });
+        // Overridden hours
+        rules.push(ScheduleRule {
+            variant: ScheduleRuleVariant::Date(format!(
+                "{}-{}-{}",
+                in_two_weeks.year(),
+                in_two_weeks.month(),
+                in_two_weeks.day()
+            )),
+            intervals: vec![interval((6, 0), (7, 15))],
+        });
+        schedule.set_rules(&rules);
+
+        let ical = schedule.to_vavailability(now.timestamp_millis());
+        let parsed = VAvailability::parse(&ical).expect("To parse exported vavailability");
+        assert_eq!(parsed.timezone, Some(chrono_tz::Europe::Oslo));
+
+        let mut imported = Schedule::new(Default::default(), Default::default(), &chrono_tz::UTC);
+        imported.timezone = parsed.timezone.unwrap();
+        imported.set_rules(&parsed.rules);
+
+        let timespan = TimeSpan::new(
+            now.timestamp_millis(),
+            (now + Duration::days(30)).timestamp_millis(),
+        );
+        assert_eq!(
+            schedule.freebusy(&timespan).inner(),
+            imported.freebusy(&timespan).inner()
+        );
+    }
+
+    #[test]
+    fn parses_exdates_and_single_occurrences() {
+        let ical = "BEGIN:VCALENDAR\r
+BEGIN:VAVAILABILITY\r
+BEGIN:AVAILABLE\r
+DTSTART:20300107T090000Z\r
+DTEND:20300107T120000Z\r
+RRULE:FREQ=DAILY\r
+EXDATE:20300108T090000Z,20300109T090000Z\r
+END:AVAILABLE\r
+BEGIN:AVAILABLE\r
+DTSTART:20300109T\r
+ 140000Z\r
+DTEND:20300110T000000Z\r
+END:AVAILABLE\r
+END:VAVAILABILITY\r
+END:VCALENDAR\r
+";
+        let res = VAvailability::parse(ical).expect("To parse vavailability");
+        assert_eq!(res.timezone, Some(chrono_tz::UTC));
+        let date_rules = res
+            .rules
+            .iter()
+            .filter_map(|r| match &r.variant {
+                ScheduleRuleVariant::Date(date) => Some((date.clone(), r.intervals.clone())),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            date_rules,
+            vec![
+                ("2030-1-8".to_string(), Vec::new()),
+                ("2030-1-9".to_string(), vec![interval((14, 0), (23, 59))]),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_vavailability() {
+        let wrap = |available: &str| {
+            format!(
+                "BEGIN:VCALENDAR\nBEGIN:VAVAILABILITY\nBEGIN:AVAILABLE\n{}\nEND:AVAILABLE\nEND:VAVAILABILITY\nEND:VCALENDAR",
+                available
+            )
+        };
+
+        assert_eq!(
+            VAvailability::parse("BEGIN:VCALENDAR\nEND:VCALENDAR").unwrap_err(),
+            VAvailabilityError::ComponentCount(0)
+        );
+        assert_eq!(
+            VAvailability::parse(&wrap("DTEND:20300107T120000Z")).unwrap_err(),
+            VAvailabilityError::MissingProperty("DTSTART")
+        );
+        assert!(matches!(
+            VAvailability::parse(&wrap(
+                "DTSTART:20300107T090000Z\nDTEND:20300107T120000Z\nRRULE:FREQ=WEEKLY;COUNT=2"
+            )),
+            Err(VAvailabilityError::UnsupportedRecurrence(_))
+        ));
+        assert!(matches!(
+            VAvailability::parse(&wrap("DTSTART:20300107T090000Z\nDTEND:20300109T120000Z")),
+            Err(VAvailabilityError::MultiDayAvailability(_))
+        ));
+        assert!(matches!(
+            VAvailability::parse(&wrap(
+                "DTSTART;TZID=Mars/Olympus:20300107T090000\nDTEND:20300107T120000Z"
+            )),
+            Err(VAvailabilityError::InvalidTimezone(_))
+        ));
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{
+    shared::entity::{Entity, ID},
+    BookingHold, BookingInvitee, Metadata,
+};
+
+/// A `WaitlistEntry` is an invitee waiting for a seat of a full booking slot
+/// of a `Service` with `ServiceMultiPersonOptions::Group`. Released seats are
+/// offered to the entries in the order they joined, by holding the seat for
+/// the invitee with a `BookingHold`.
+#[derive(Debug, Clone)]
+pub struct WaitlistEntry {
+    pub id: ID,
+    pub account_id: ID,
+    pub service_id: ID,
+    pub start_ts: i64,
+    pub duration: i64,
+    pub invitee: BookingInvitee,
+    pub created: i64,
+    /// The `BookingHold` keeping the seat offered to the invitee
+    pub hold_id: Option<ID>,
+    /// When the offered seat is released to the next entry
+    pub offer_expires_at: Option<i64>,
+    pub metadata: Metadata,
+}
+
+impl WaitlistEntry {
+    /// How long an offered seat is held for the invitee, 1 hour
+    pub const OFFER_TTL: i64 = BookingHold::MAX_TTL;
+
+    /// Creates the `BookingHold` offering a seat to the invitee
+    pub fn offer(&mut self, host_user_ids: Vec<ID>, timestamp: i64) -> BookingHold {
+        let hold = BookingHold {
+            id: Default::default(),
+            account_id: self.account_id.clone(),
+            service_id: self.service_id.clone(),
+            host_user_ids,
+            resource_id: None,
+            start_ts: self.start_ts,
+            duration: self.duration,
+            seat: true,
+            created: timestamp,
+            expires_at: timestamp + Self::OFFER_TTL,
+        };
+        self.hold_id = Some(hold.id.clone());
+        self.offer_expires_at = Some(hold.expires_at);
+        hold
+    }
+
+    pub fn is_offered(&self) -> bool {
+        self.hold_id.is_some()
+    }
+}
+
+impl Entity<ID> for WaitlistEntry {
+    fn id(&self) -> ID {
+        self.id.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_offers_seat_with_hold() {
+        let mut entry = WaitlistEntry {
+            id: Default::default(),
+            account_id: Default::default(),
+            service_id: Default::default(),
+            start_ts: 100,
+            duration: 10,
+            invitee: Default::default(),
+            created: 0,
+            hold_id: None,
+            offer_expires_at: None,
+            metadata: Default::default(),
+        };
+        assert!(!entry.is_offered());
+
+        let host_user_id = ID::default();
+        let hold = entry.offer(vec![host_user_id.clone()], 50);
+        assert!(entry.is_offered());
+        assert_eq!(entry.hold_id, Some(hold.id.clone()));
+        assert_eq!(entry.offer_expires_at, Some(50 + WaitlistEntry::OFFER_TTL));
+        assert!(hold.seat);
+        assert_eq!(hold.start_ts, 100);
+        assert_eq!(hold.end_ts(), 110);
+        assert_eq!(hold.host_user_ids, vec![host_user_id]);
+    }
+}
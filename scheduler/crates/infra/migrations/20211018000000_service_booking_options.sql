@@ -0,0 +1 @@
+ALTER TABLE services ADD COLUMN IF NOT EXISTS booking_options JSON NOT NULL DEFAULT '{}';
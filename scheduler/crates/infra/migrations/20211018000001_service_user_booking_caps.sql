@@ -0,0 +1,2 @@
+ALTER TABLE service_users ADD COLUMN IF NOT EXISTS max_bookings_per_day BIGINT;
+ALTER TABLE service_users ADD COLUMN IF NOT EXISTS max_booked_minutes_per_week BIGINT;
@@ -0,0 +1 @@
+ALTER TABLE service_users ADD COLUMN IF NOT EXISTS preferred_times JSON NOT NULL DEFAULT '[]';
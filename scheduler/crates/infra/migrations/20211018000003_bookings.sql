@@ -0,0 +1,29 @@
+CREATE DOMAIN booking_status AS TEXT
+    NOT NULL
+    CHECK (VALUE in ('pending', 'confirmed', 'cancelled', 'rescheduled', 'no_show'));
+COMMENT ON DOMAIN booking_status IS
+    'lifecycle states of a booking';
+
+CREATE TABLE IF NOT EXISTS bookings (
+                                        booking_uid uuid PRIMARY KEY NOT NULL,
+                                        account_uid uuid NOT NULL REFERENCES accounts(account_uid) ON DELETE CASCADE,
+                                        service_uid uuid NOT NULL REFERENCES services(service_uid) ON DELETE CASCADE,
+                                        host_user_uids uuid[] NOT NULL,
+                                        event_uids uuid[] NOT NULL,
+                                        invitee JSON NOT NULL,
+                                        start_ts BIGINT NOT NULL,
+                                        duration BIGINT NOT NULL,
+                                        end_ts BIGINT NOT NULL,
+                                        "status" booking_status NOT NULL,
+                                        created BIGINT NOT NULL,
+                                        updated BIGINT NOT NULL,
+                                        metadata jsonb NOT NULL
+);
+CREATE INDEX IF NOT EXISTS booking_service_start_ts ON bookings (service_uid, start_ts);
+CREATE INDEX IF NOT EXISTS booking_host_user_uids ON bookings USING GIN (host_user_uids);
+CREATE INDEX IF NOT EXISTS booking_metadata ON bookings USING GIN (metadata);
+create trigger
+    immutable_columns
+    before
+        update on bookings
+    for each row execute procedure immutable_columns('booking_uid', 'account_uid', 'service_uid', 'created');
@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS booking_holds (
+                                             hold_uid uuid PRIMARY KEY NOT NULL,
+                                             account_uid uuid NOT NULL REFERENCES accounts(account_uid) ON DELETE CASCADE,
+                                             service_uid uuid NOT NULL REFERENCES services(service_uid) ON DELETE CASCADE,
+                                             host_user_uids uuid[] NOT NULL,
+                                             start_ts BIGINT NOT NULL,
+                                             duration BIGINT NOT NULL,
+                                             end_ts BIGINT NOT NULL,
+                                             seat BOOLEAN NOT NULL,
+                                             created BIGINT NOT NULL,
+                                             expires_at BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS booking_hold_service_start_ts ON booking_holds (service_uid, start_ts);
+CREATE INDEX IF NOT EXISTS booking_hold_host_user_uids ON booking_holds USING GIN (host_user_uids);
+CREATE INDEX IF NOT EXISTS booking_hold_expires_at ON booking_holds (expires_at);
@@ -0,0 +1,9 @@
+ALTER TABLE calendar_events ADD COLUMN IF NOT EXISTS tentative BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER DOMAIN booking_status DROP CONSTRAINT IF EXISTS booking_status_check;
+ALTER DOMAIN booking_status ADD CONSTRAINT booking_status_check
+    CHECK (VALUE in ('pending', 'confirmed', 'cancelled', 'rescheduled', 'no_show', 'declined'));
+
+ALTER TABLE bookings ADD COLUMN IF NOT EXISTS approval_deadline BIGINT;
+CREATE INDEX IF NOT EXISTS booking_approval_deadline ON bookings (approval_deadline)
+    WHERE approval_deadline IS NOT NULL;
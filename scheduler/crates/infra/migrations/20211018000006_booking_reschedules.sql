@@ -0,0 +1 @@
+ALTER TABLE bookings ADD COLUMN IF NOT EXISTS reschedules JSONB NOT NULL DEFAULT '[]';
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS waitlist_entries (
+                                                entry_uid uuid PRIMARY KEY NOT NULL,
+                                                account_uid uuid NOT NULL REFERENCES accounts(account_uid) ON DELETE CASCADE,
+                                                service_uid uuid NOT NULL REFERENCES services(service_uid) ON DELETE CASCADE,
+                                                start_ts BIGINT NOT NULL,
+                                                duration BIGINT NOT NULL,
+                                                invitee JSONB NOT NULL,
+                                                created BIGINT NOT NULL,
+                                                hold_uid uuid,
+                                                offer_expires_at BIGINT,
+                                                metadata JSONB NOT NULL
+);
+CREATE INDEX IF NOT EXISTS waitlist_entry_service_start_ts ON waitlist_entries (service_uid, start_ts, created);
+CREATE INDEX IF NOT EXISTS waitlist_entry_hold_uid ON waitlist_entries (hold_uid);
+CREATE INDEX IF NOT EXISTS waitlist_entry_offer_expires_at ON waitlist_entries (offer_expires_at)
+    WHERE offer_expires_at IS NOT NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE service_users ADD COLUMN IF NOT EXISTS weight BIGINT NOT NULL DEFAULT 1;
+ALTER TABLE service_users ADD COLUMN IF NOT EXISTS priority BIGINT NOT NULL DEFAULT 0;
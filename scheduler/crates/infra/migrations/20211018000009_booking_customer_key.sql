@@ -0,0 +1,2 @@
+CREATE INDEX IF NOT EXISTS booking_service_customer_key ON bookings (service_uid, (invitee->>'customerKey'))
+    WHERE invitee->>'customerKey' IS NOT NULL;
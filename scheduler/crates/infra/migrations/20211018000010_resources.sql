@@ -0,0 +1,5 @@
+ALTER TABLE users ADD COLUMN IF NOT EXISTS resource_kind TEXT;
+ALTER TABLE bookings ADD COLUMN IF NOT EXISTS resource_uid uuid;
+ALTER TABLE booking_holds ADD COLUMN IF NOT EXISTS resource_uid uuid;
+CREATE INDEX IF NOT EXISTS booking_resource_uid ON bookings (resource_uid) WHERE resource_uid IS NOT NULL;
+CREATE INDEX IF NOT EXISTS booking_hold_resource_uid ON booking_holds (resource_uid) WHERE resource_uid IS NOT NULL;
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS external_busy_cache (
+                                                   user_uid uuid NOT NULL REFERENCES users(user_uid) ON DELETE CASCADE,
+                                                   provider TEXT NOT NULL,
+                                                   ext_calendar_id TEXT NOT NULL,
+                                                   start_ts BIGINT NOT NULL,
+                                                   end_ts BIGINT NOT NULL,
+                                                   busy JSONB NOT NULL,
+                                                   fetched_at BIGINT NOT NULL,
+                                                   PRIMARY KEY(user_uid, provider, ext_calendar_id)
+);
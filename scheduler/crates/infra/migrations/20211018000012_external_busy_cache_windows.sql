@@ -0,0 +1,4 @@
+ALTER TABLE external_busy_cache DROP CONSTRAINT IF EXISTS external_busy_cache_pkey;
+ALTER TABLE external_busy_cache ADD PRIMARY KEY(user_uid, provider, ext_calendar_id, start_ts, end_ts);
+
+ALTER TABLE external_busy_cache ADD COLUMN IF NOT EXISTS refreshing_at BIGINT;
@@ -17,6 +17,13 @@ pub struct Config {
     /// timespan of several years which will take a lot of time to compute
     /// and is also not very useful information to query about anyways.
     pub booking_slots_query_duration_limit: i64,
+    /// Time in millis the busy intervals fetched from external Google and
+    /// Outlook calendars are served from the cache before they are refetched
+    pub external_busy_cache_ttl: i64,
+    /// Time in millis after `external_busy_cache_ttl` during which expired
+    /// busy intervals are still served from the cache while they are
+    /// refetched in the background. Zero disables stale-while-revalidate.
+    pub external_busy_cache_stale_ttl: i64,
 }
 
 impl Config {
@@ -46,6 +53,11 @@ impl Config {
             }
         };
 
+        let external_busy_cache_ttl =
+            parse_secs_env_var("EXTERNAL_BUSY_CACHE_TTL_SECS", 60 * 5) * 1000;
+        let external_busy_cache_stale_ttl =
+            parse_secs_env_var("EXTERNAL_BUSY_CACHE_STALE_TTL_SECS", 0) * 1000;
+
         const DAYS_62: i64 = 1000 * 60 * 60 * 24 * 62;
         const DAYS_101: i64 = 1000 * 60 * 60 * 24 * 101;
 
@@ -54,10 +66,28 @@ impl Config {
             port,
             event_instances_query_duration_limit: DAYS_62,
             booking_slots_query_duration_limit: DAYS_101,
+            external_busy_cache_ttl,
+            external_busy_cache_stale_ttl,
         }
     }
 }
 
+fn parse_secs_env_var(name: &str, default: i64) -> i64 {
+    match std::env::var(name) {
+        Ok(secs) => match secs.parse::<i64>() {
+            Ok(secs) if secs >= 0 => secs,
+            _ => {
+                warn!(
+                    "The given {}: {} is not valid, falling back to the default: {}.",
+                    name, secs, default
+                );
+                default
+            }
+        },
+        Err(_) => default,
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self::new()
@@ -5,7 +5,10 @@ mod system;
 
 pub use config::Config;
 use repos::Repos;
-pub use repos::{BusyCalendarIdentifier, ExternalBusyCalendarIdentifier, MetadataFindQuery};
+pub use repos::{
+    BookingCommit, BookingCommitError, BookingRescheduleCommit, BusyCalendarIdentifier,
+    ExternalBusyCalendarIdentifier, MetadataFindQuery,
+};
 pub use services::*;
 use sqlx::migrate::MigrateError;
 use sqlx::postgres::PgPoolOptions;
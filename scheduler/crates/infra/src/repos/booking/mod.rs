@@ -0,0 +1,871 @@
+mod postgres;
+
+use nettu_scheduler_domain::{Booking, BookingStatus, CalendarEvent, ID};
+pub use postgres::PostgresBookingRepo;
+pub(crate) use postgres::{claim_slot, lock, lock_keys, SlotClaim};
+
+/// A `Booking` together with everything it occupies, which is stored atomically
+#[derive(Debug)]
+pub struct BookingCommit {
+    pub booking: Booking,
+    /// The `CalendarEvent`s to create for the hosts of the `Booking`
+    pub events: Vec<CalendarEvent>,
+    /// The number of seats of a `Service` with `ServiceMultiPersonOptions::Group`.
+    /// A seat is reserved for the `Booking` and the `events` are only created
+    /// when it books the last seat.
+    pub seats: Option<usize>,
+    /// The `BookingHold` the `Booking` is converted from. It is released
+    /// when the `Booking` is stored.
+    pub hold_id: Option<ID>,
+}
+
+/// A `Booking` moved to another time together with its `CalendarEvent`s,
+/// which is stored atomically
+#[derive(Debug)]
+pub struct BookingRescheduleCommit {
+    /// The `Booking` at its new time
+    pub booking: Booking,
+    /// When the `Booking` was last updated before it was moved. The reschedule
+    /// is rejected if it was changed in the meantime.
+    pub updated: i64,
+    /// The `CalendarEvent`s of the `Booking` moved to the new time
+    pub moved_events: Vec<CalendarEvent>,
+    /// The `CalendarEvent`s to create for hosts newly assigned to the `Booking`
+    pub new_events: Vec<CalendarEvent>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BookingCommitError {
+    /// The hosts or the seats were taken by another `Booking` or `BookingHold`
+    /// in the meantime, or the `BookingHold` to convert has expired
+    Conflict,
+    StorageError,
+}
+
+#[derive(Debug)]
+pub struct MostRecentCreatedBooking {
+    pub user_id: ID,
+    pub created: Option<i64>,
+}
+
+#[async_trait::async_trait]
+pub trait IBookingRepo: Send + Sync {
+    async fn insert(&self, booking: &Booking) -> anyhow::Result<()>;
+    /// Re-validates that the hosts and seats are still free and stores the
+    /// `Booking`, its `CalendarEvent`s and its reservation in one transaction.
+    /// Expired `BookingHold`s are ignored relative to when the `Booking` was created.
+    /// Concurrent commits for the same hosts and time are serialized with
+    /// advisory locks, so only one of them can succeed.
+    async fn commit(
+        &self,
+        commit: BookingCommit,
+    ) -> Result<(Booking, Vec<CalendarEvent>), BookingCommitError>;
+    /// Commits the `Booking`s of a chain of `Service`s in one transaction.
+    /// Either all of them are stored or none of them.
+    async fn commit_chain(
+        &self,
+        commits: Vec<BookingCommit>,
+    ) -> Result<Vec<(Booking, Vec<CalendarEvent>)>, BookingCommitError>;
+    /// Re-validates that the hosts are free at the new time of the `Booking`,
+    /// ignoring the time it currently occupies, and stores it together with
+    /// its moved and new `CalendarEvent`s in one transaction. The current time
+    /// of the `Booking` is locked as well, and it is only stored if it is still
+    /// active and was not updated since it was read.
+    async fn reschedule(
+        &self,
+        reschedule: BookingRescheduleCommit,
+    ) -> Result<Booking, BookingCommitError>;
+    /// Stores the new status of a cancelled or declined `Booking` if it is
+    /// still in one of the `from` statuses and gives back its seat of a group
+    /// `Service` in one transaction, under the same advisory locks as `commit`.
+    /// Returns the `CalendarEvent`s of the hosts that are no longer busy, or
+    /// `None` when the status of the `Booking` was changed in the meantime.
+    async fn release(
+        &self,
+        booking: &Booking,
+        from: &[BookingStatus],
+        seats: Option<usize>,
+    ) -> anyhow::Result<Option<Vec<ID>>>;
+    /// Stores the approved `Booking` together with its no longer tentative
+    /// `CalendarEvent`s in one transaction if it is still pending at the same
+    /// time. Returns false when the `Booking` was declined, approved or
+    /// rescheduled in the meantime.
+    async fn approve(&self, booking: &Booking, events: &[CalendarEvent]) -> anyhow::Result<bool>;
+    /// Stores the new status of the `Booking` if it is still in one of the
+    /// `from` statuses. Returns false when the status of the `Booking` was
+    /// changed in the meantime.
+    async fn set_status(&self, booking: &Booking, from: &[BookingStatus]) -> anyhow::Result<bool>;
+    async fn save(&self, booking: &Booking) -> anyhow::Result<()>;
+    async fn find(&self, booking_id: &ID) -> Option<Booking>;
+    /// Finds all the `Booking`s of a `Service` overlapping the given timespan
+    async fn find_by_service(
+        &self,
+        service_id: &ID,
+        min_ts: i64,
+        max_ts: i64,
+    ) -> anyhow::Result<Vec<Booking>>;
+    /// Finds the active `Booking`s of a `Service` assigned to any of the given
+    /// `User`s and overlapping the given timespan
+    async fn find_active_by_hosts(
+        &self,
+        service_id: &ID,
+        user_ids: &[ID],
+        min_ts: i64,
+        max_ts: i64,
+    ) -> Vec<Booking>;
+    /// Finds the pending `Booking`s which were not approved before their
+    /// approval deadline
+    async fn find_pending_past_deadline(&self, timestamp: i64) -> Vec<Booking>;
+    /// Finds when each of the given `User`s was most recently assigned an
+    /// active `Booking` of the `Service`
+    async fn find_most_recently_created(
+        &self,
+        service_id: &ID,
+        user_ids: &[ID],
+    ) -> Vec<MostRecentCreatedBooking>;
+    /// Finds the most recently created `Booking` of a `Service` made by the
+    /// invitee with the given customer key, which was not cancelled or declined
+    async fn find_last_by_customer(&self, service_id: &ID, customer_key: &str) -> Option<Booking>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BookingCommit, BookingCommitError, BookingRescheduleCommit};
+    use crate::{setup_context, NettuContext};
+    use nettu_scheduler_domain::{
+        Account, Booking, BookingInvitee, BookingStatus, Calendar, CalendarEvent, Entity,
+        IntakeAnswer, Service, User, ID,
+    };
+
+    struct TestContext {
+        ctx: NettuContext,
+        account: Account,
+        service: Service,
+        user: User,
+    }
+
+    async fn setup() -> TestContext {
+        let ctx = setup_context().await;
+        let account = Account::default();
+        ctx.repos.accounts.insert(&account).await.unwrap();
+        let user = User::new(account.id.clone());
+        ctx.repos.users.insert(&user).await.unwrap();
+        let service = Service::new(account.id.clone());
+        ctx.repos.services.insert(&service).await.unwrap();
+
+        TestContext {
+            ctx,
+            account,
+            service,
+            user,
+        }
+    }
+
+    fn generate_booking(account_id: &ID, service_id: &ID, user_id: &ID, start_ts: i64) -> Booking {
+        Booking {
+            id: Default::default(),
+            account_id: account_id.clone(),
+            service_id: service_id.clone(),
+            host_user_ids: vec![user_id.clone()],
+            resource_id: None,
+            event_ids: vec![ID::default()],
+            invitee: BookingInvitee {
+                name: Some("Invitee".into()),
+                email: Some("invitee@example.com".into()),
+                customer_key: None,
+                attributes: Default::default(),
+                answers: vec![("reason".to_string(), IntakeAnswer::Text("Checkup".into()))]
+                    .into_iter()
+                    .collect(),
+            },
+            start_ts,
+            duration: 1000 * 60 * 30,
+            status: BookingStatus::Confirmed,
+            approval_deadline: None,
+            reschedules: Vec::new(),
+            created: start_ts,
+            updated: start_ts,
+            metadata: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn crud() {
+        let TestContext {
+            ctx,
+            account,
+            service,
+            user,
+        } = setup().await;
+        let mut booking = generate_booking(&account.id, &service.id, &user.id, 0);
+
+        assert!(ctx.repos.bookings.insert(&booking).await.is_ok());
+        let res = ctx
+            .repos
+            .bookings
+            .find(&booking.id)
+            .await
+            .expect("To find booking");
+        assert!(res.eq(&booking));
+        assert_eq!(res.invitee, booking.invitee);
+        assert_eq!(res.event_ids.len(), 1);
+        assert_eq!(res.status, BookingStatus::Confirmed);
+
+        assert!(booking.cancel(10));
+        assert!(ctx.repos.bookings.save(&booking).await.is_ok());
+        let res = ctx
+            .repos
+            .bookings
+            .find(&booking.id)
+            .await
+            .expect("To find booking");
+        assert_eq!(res.status, BookingStatus::Cancelled);
+        assert_eq!(res.updated, 10);
+    }
+
+    #[tokio::test]
+    async fn find_pending_past_deadline() {
+        let TestContext {
+            ctx,
+            account,
+            service,
+            user,
+        } = setup().await;
+        let mut booking = generate_booking(&account.id, &service.id, &user.id, 0);
+        booking.status = BookingStatus::Pending;
+        booking.approval_deadline = Some(100);
+        assert!(ctx.repos.bookings.insert(&booking).await.is_ok());
+
+        let booking_id = booking.id.clone();
+        let is_found = |bookings: Vec<Booking>| bookings.iter().any(|b| b.id == booking_id);
+        assert!(!is_found(
+            ctx.repos.bookings.find_pending_past_deadline(99).await
+        ));
+        assert!(is_found(
+            ctx.repos.bookings.find_pending_past_deadline(100).await
+        ));
+
+        assert!(booking.approve(100));
+        assert!(ctx.repos.bookings.save(&booking).await.is_ok());
+        assert!(!is_found(
+            ctx.repos.bookings.find_pending_past_deadline(100).await
+        ));
+    }
+
+    #[tokio::test]
+    async fn approve_and_decline_only_pending() {
+        let TestContext {
+            ctx,
+            account,
+            service,
+            user,
+        } = setup().await;
+        let mut booking = generate_booking(&account.id, &service.id, &user.id, 0);
+        booking.status = BookingStatus::Pending;
+        booking.approval_deadline = Some(100);
+        assert!(ctx.repos.bookings.insert(&booking).await.is_ok());
+
+        let mut approved = booking.clone();
+        assert!(approved.approve(10));
+        let mut declined = booking.clone();
+        assert!(declined.decline(10));
+
+        assert!(ctx
+            .repos
+            .bookings
+            .approve(&approved, &[])
+            .await
+            .expect("To approve booking"));
+        // The booking is no longer pending, so neither a decline nor
+        // another approve takes effect
+        assert!(ctx
+            .repos
+            .bookings
+            .release(&declined, &[BookingStatus::Pending], None)
+            .await
+            .expect("To decline booking")
+            .is_none());
+        assert!(!ctx
+            .repos
+            .bookings
+            .approve(&approved, &[])
+            .await
+            .expect("To approve booking"));
+
+        let res = ctx
+            .repos
+            .bookings
+            .find(&booking.id)
+            .await
+            .expect("To find booking");
+        assert_eq!(res.status, BookingStatus::Confirmed);
+        assert_eq!(res.approval_deadline, None);
+    }
+
+    #[tokio::test]
+    async fn set_status_only_from_given_statuses() {
+        let TestContext {
+            ctx,
+            account,
+            service,
+            user,
+        } = setup().await;
+        let booking = generate_booking(&account.id, &service.id, &user.id, 0);
+        assert!(ctx.repos.bookings.insert(&booking).await.is_ok());
+
+        let mut no_show = booking.clone();
+        assert!(no_show.mark_no_show(100));
+        let mut cancelled = booking.clone();
+        assert!(cancelled.cancel(100));
+
+        assert!(ctx
+            .repos
+            .bookings
+            .set_status(&cancelled, &BookingStatus::ACTIVE)
+            .await
+            .expect("To set booking status"));
+        assert!(!ctx
+            .repos
+            .bookings
+            .set_status(&no_show, &[BookingStatus::Confirmed])
+            .await
+            .expect("To set booking status"));
+
+        let res = ctx
+            .repos
+            .bookings
+            .find(&booking.id)
+            .await
+            .expect("To find booking");
+        assert_eq!(res.status, BookingStatus::Cancelled);
+        assert_eq!(res.updated, 100);
+    }
+
+    #[tokio::test]
+    async fn find_by_service_and_hosts() {
+        let TestContext {
+            ctx,
+            account,
+            service,
+            user,
+        } = setup().await;
+        let booking_1 = generate_booking(&account.id, &service.id, &user.id, 0);
+        let booking_2 = generate_booking(&account.id, &service.id, &user.id, 1000 * 60 * 60);
+        let mut cancelled_booking =
+            generate_booking(&account.id, &service.id, &user.id, 1000 * 60 * 60 * 2);
+        cancelled_booking.cancel(0);
+        for booking in [&booking_1, &booking_2, &cancelled_booking] {
+            ctx.repos.bookings.insert(booking).await.unwrap();
+        }
+
+        let bookings = ctx
+            .repos
+            .bookings
+            .find_by_service(&service.id, 1000 * 60 * 45, 1000 * 60 * 60 * 3)
+            .await
+            .expect("To find bookings");
+        assert_eq!(bookings.len(), 2);
+        assert!(bookings[0].eq(&booking_2));
+        assert!(bookings[1].eq(&cancelled_booking));
+
+        let bookings = ctx
+            .repos
+            .bookings
+            .find_active_by_hosts(&service.id, &[user.id.clone()], 0, 1000 * 60 * 60 * 3)
+            .await;
+        assert_eq!(bookings.len(), 2);
+
+        let most_recent = ctx
+            .repos
+            .bookings
+            .find_most_recently_created(&service.id, &[user.id.clone()])
+            .await;
+        assert_eq!(most_recent.len(), 1);
+        assert_eq!(most_recent[0].created, Some(booking_2.created));
+    }
+
+    #[tokio::test]
+    async fn find_last_by_customer() {
+        let TestContext {
+            ctx,
+            account,
+            service,
+            user,
+        } = setup().await;
+        let customer_key = ID::default().to_string();
+        let mut bookings = (0..3)
+            .map(|i| {
+                let mut booking =
+                    generate_booking(&account.id, &service.id, &user.id, 1000 * 60 * 60 * i);
+                booking.invitee.customer_key = Some(customer_key.clone());
+                booking
+            })
+            .collect::<Vec<_>>();
+        bookings[2].cancel(0);
+        for booking in &bookings {
+            ctx.repos.bookings.insert(booking).await.unwrap();
+        }
+        let other_customer =
+            generate_booking(&account.id, &service.id, &user.id, 1000 * 60 * 60 * 3);
+        ctx.repos.bookings.insert(&other_customer).await.unwrap();
+
+        let res = ctx
+            .repos
+            .bookings
+            .find_last_by_customer(&service.id, &customer_key)
+            .await
+            .expect("To find booking");
+        assert!(res.eq(&bookings[1]));
+        assert!(ctx
+            .repos
+            .bookings
+            .find_last_by_customer(&service.id, "unknown")
+            .await
+            .is_none());
+    }
+
+    fn generate_event(calendar: &Calendar, service_id: &ID, start_ts: i64) -> CalendarEvent {
+        CalendarEvent {
+            calendar_id: calendar.id.clone(),
+            user_id: calendar.user_id.clone(),
+            account_id: calendar.account_id.clone(),
+            start_ts,
+            duration: 1000 * 60 * 30,
+            end_ts: start_ts + 1000 * 60 * 30,
+            busy: true,
+            service_id: Some(service_id.clone()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn release_gives_back_seat_once() {
+        let TestContext {
+            ctx,
+            account,
+            service,
+            user,
+        } = setup().await;
+        let calendar = Calendar::new(&user.id, &account.id);
+        ctx.repos.calendars.insert(&calendar).await.unwrap();
+
+        let mut bookings = Vec::new();
+        for _ in 0..2 {
+            let booking = generate_booking(&account.id, &service.id, &user.id, 0);
+            let (booking, _) = ctx
+                .repos
+                .bookings
+                .commit(BookingCommit {
+                    booking,
+                    events: vec![generate_event(&calendar, &service.id, 0)],
+                    seats: Some(2),
+                    hold_id: None,
+                })
+                .await
+                .expect("To commit booking");
+            bookings.push(booking);
+        }
+        // The last seat made the host busy
+        assert_eq!(bookings[1].event_ids.len(), 1);
+
+        let mut booking = bookings.remove(0);
+        assert!(booking.cancel(10));
+        let released = ctx
+            .repos
+            .bookings
+            .release(&booking, &BookingStatus::ACTIVE, Some(2))
+            .await
+            .expect("To release booking");
+        assert_eq!(released, Some(bookings[0].event_ids.clone()));
+        assert_eq!(
+            ctx.repos
+                .reservations
+                .count(&service.id, 0)
+                .await
+                .expect("To get reservations count"),
+            1
+        );
+
+        // A second release of the same booking gives back nothing
+        let released = ctx
+            .repos
+            .bookings
+            .release(&booking, &BookingStatus::ACTIVE, Some(2))
+            .await
+            .expect("To release booking");
+        assert!(released.is_none());
+        assert_eq!(
+            ctx.repos
+                .reservations
+                .count(&service.id, 0)
+                .await
+                .expect("To get reservations count"),
+            1
+        );
+        let res = ctx
+            .repos
+            .bookings
+            .find(&booking.id)
+            .await
+            .expect("To find booking");
+        assert_eq!(res.status, BookingStatus::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn commit_rejects_overlapping_bookings() {
+        let TestContext {
+            ctx,
+            account,
+            service,
+            user,
+        } = setup().await;
+        let calendar = Calendar::new(&user.id, &account.id);
+        ctx.repos.calendars.insert(&calendar).await.unwrap();
+
+        let booking = generate_booking(&account.id, &service.id, &user.id, 0);
+        let event = generate_event(&calendar, &service.id, 0);
+        let (booking, events) = ctx
+            .repos
+            .bookings
+            .commit(BookingCommit {
+                booking,
+                events: vec![event.clone()],
+                seats: None,
+                hold_id: None,
+            })
+            .await
+            .expect("To commit booking");
+        assert_eq!(booking.event_ids, vec![event.id.clone()]);
+        assert_eq!(events.len(), 1);
+        assert!(ctx.repos.events.find(&event.id).await.is_some());
+        assert!(ctx.repos.bookings.find(&booking.id).await.is_some());
+
+        let overlapping = generate_booking(&account.id, &service.id, &user.id, 1000 * 60 * 15);
+        let overlapping_event = generate_event(&calendar, &service.id, 1000 * 60 * 15);
+        let res = ctx
+            .repos
+            .bookings
+            .commit(BookingCommit {
+                booking: overlapping.clone(),
+                events: vec![overlapping_event.clone()],
+                seats: None,
+                hold_id: None,
+            })
+            .await;
+        assert_eq!(res.unwrap_err(), BookingCommitError::Conflict);
+        // Nothing of the rejected booking is stored
+        assert!(ctx.repos.events.find(&overlapping_event.id).await.is_none());
+        assert!(ctx.repos.bookings.find(&overlapping.id).await.is_none());
+
+        let adjacent = generate_booking(&account.id, &service.id, &user.id, 1000 * 60 * 30);
+        assert!(ctx
+            .repos
+            .bookings
+            .commit(BookingCommit {
+                booking: adjacent,
+                events: Vec::new(),
+                seats: None,
+                hold_id: None,
+            })
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn commit_rejects_bookings_of_taken_resource() {
+        let TestContext {
+            ctx,
+            account,
+            service,
+            user,
+        } = setup().await;
+        let mut room = User::new(account.id.clone());
+        room.resource_kind = Some("room".into());
+        ctx.repos.users.insert(&room).await.unwrap();
+        let other_host = User::new(account.id.clone());
+        ctx.repos.users.insert(&other_host).await.unwrap();
+
+        let mut booking = generate_booking(&account.id, &service.id, &user.id, 0);
+        booking.resource_id = Some(room.id.clone());
+        let (booking, _) = ctx
+            .repos
+            .bookings
+            .commit(BookingCommit {
+                booking,
+                events: Vec::new(),
+                seats: None,
+                hold_id: None,
+            })
+            .await
+            .expect("To commit booking");
+        let res = ctx
+            .repos
+            .bookings
+            .find(&booking.id)
+            .await
+            .expect("To find booking");
+        assert_eq!(res.resource_id, Some(room.id.clone()));
+
+        // Another host can not take the same room at the same time
+        let mut overlapping = generate_booking(&account.id, &service.id, &other_host.id, 0);
+        overlapping.resource_id = Some(room.id.clone());
+        let res = ctx
+            .repos
+            .bookings
+            .commit(BookingCommit {
+                booking: overlapping.clone(),
+                events: Vec::new(),
+                seats: None,
+                hold_id: None,
+            })
+            .await;
+        assert_eq!(res.unwrap_err(), BookingCommitError::Conflict);
+
+        overlapping.resource_id = None;
+        assert!(ctx
+            .repos
+            .bookings
+            .commit(BookingCommit {
+                booking: overlapping,
+                events: Vec::new(),
+                seats: None,
+                hold_id: None,
+            })
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn commit_chain_stores_all_or_nothing() {
+        let TestContext {
+            ctx,
+            account,
+            service,
+            user,
+        } = setup().await;
+        let other_user = User::new(account.id.clone());
+        ctx.repos.users.insert(&other_user).await.unwrap();
+        let existing = generate_booking(&account.id, &service.id, &other_user.id, 1000 * 60 * 60);
+        ctx.repos.bookings.insert(&existing).await.unwrap();
+
+        let chain = |second_step_start_ts: i64| {
+            vec![
+                BookingCommit {
+                    booking: generate_booking(&account.id, &service.id, &user.id, 0),
+                    events: Vec::new(),
+                    seats: None,
+                    hold_id: None,
+                },
+                BookingCommit {
+                    booking: generate_booking(
+                        &account.id,
+                        &service.id,
+                        &other_user.id,
+                        second_step_start_ts,
+                    ),
+                    events: Vec::new(),
+                    seats: None,
+                    hold_id: None,
+                },
+            ]
+        };
+
+        // The second step overlaps the existing booking
+        let commits = chain(1000 * 60 * 45);
+        let first_booking_id = commits[0].booking.id.clone();
+        let res = ctx.repos.bookings.commit_chain(commits).await;
+        assert_eq!(res.unwrap_err(), BookingCommitError::Conflict);
+        assert!(ctx.repos.bookings.find(&first_booking_id).await.is_none());
+
+        let res = ctx
+            .repos
+            .bookings
+            .commit_chain(chain(1000 * 60 * 30))
+            .await
+            .expect("To commit chain");
+        assert_eq!(res.len(), 2);
+        for (booking, _) in res {
+            assert!(ctx.repos.bookings.find(&booking.id).await.is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn reschedule_ignores_own_time() {
+        let TestContext {
+            ctx,
+            account,
+            service,
+            user,
+        } = setup().await;
+        let calendar = Calendar::new(&user.id, &account.id);
+        ctx.repos.calendars.insert(&calendar).await.unwrap();
+
+        let booking = generate_booking(&account.id, &service.id, &user.id, 0);
+        let event = generate_event(&calendar, &service.id, 0);
+        let (mut booking, mut events) = ctx
+            .repos
+            .bookings
+            .commit(BookingCommit {
+                booking,
+                events: vec![event],
+                seats: None,
+                hold_id: None,
+            })
+            .await
+            .expect("To commit booking");
+        let other = generate_booking(&account.id, &service.id, &user.id, 1000 * 60 * 60);
+        ctx.repos.bookings.insert(&other).await.unwrap();
+
+        // Overlaps the current time of the booking only
+        let start_ts = 1000 * 60 * 15;
+        assert!(booking.reschedule(start_ts, booking.duration, vec![user.id.clone()], 1));
+        events[0].start_ts = start_ts;
+        events[0].end_ts = start_ts + booking.duration;
+        let res = ctx
+            .repos
+            .bookings
+            .reschedule(BookingRescheduleCommit {
+                booking: booking.clone(),
+                updated: 0,
+                moved_events: events.clone(),
+                new_events: Vec::new(),
+            })
+            .await
+            .expect("To reschedule booking");
+        assert_eq!(res.event_ids, vec![events[0].id.clone()]);
+        let event = ctx.repos.events.find(&events[0].id).await.unwrap();
+        assert_eq!(event.start_ts, start_ts);
+        let res = ctx.repos.bookings.find(&booking.id).await.unwrap();
+        assert_eq!(res.start_ts, start_ts);
+        assert_eq!(res.reschedules.len(), 1);
+
+        // Overlaps the other booking
+        let start_ts = 1000 * 60 * 45;
+        assert!(booking.reschedule(start_ts, booking.duration, vec![user.id.clone()], 2));
+        let res = ctx
+            .repos
+            .bookings
+            .reschedule(BookingRescheduleCommit {
+                booking,
+                updated: 1,
+                moved_events: Vec::new(),
+                new_events: Vec::new(),
+            })
+            .await;
+        assert_eq!(res.unwrap_err(), BookingCommitError::Conflict);
+    }
+
+    #[tokio::test]
+    async fn reschedule_rejects_cancelled_booking() {
+        let TestContext {
+            ctx,
+            account,
+            service,
+            user,
+        } = setup().await;
+        let calendar = Calendar::new(&user.id, &account.id);
+        ctx.repos.calendars.insert(&calendar).await.unwrap();
+
+        let booking = generate_booking(&account.id, &service.id, &user.id, 0);
+        let event = generate_event(&calendar, &service.id, 0);
+        let (booking, mut events) = ctx
+            .repos
+            .bookings
+            .commit(BookingCommit {
+                booking,
+                events: vec![event],
+                seats: None,
+                hold_id: None,
+            })
+            .await
+            .expect("To commit booking");
+
+        // The booking is cancelled after it was read for the reschedule
+        let mut rescheduled = ctx.repos.bookings.find(&booking.id).await.unwrap();
+        let mut cancelled = rescheduled.clone();
+        assert!(cancelled.cancel(10));
+        let released = ctx
+            .repos
+            .bookings
+            .release(&cancelled, &BookingStatus::ACTIVE, None)
+            .await
+            .expect("To release booking");
+        assert_eq!(released, Some(booking.event_ids.clone()));
+
+        let updated = rescheduled.updated;
+        let start_ts = 1000 * 60 * 60;
+        assert!(rescheduled.reschedule(start_ts, booking.duration, vec![user.id.clone()], 20));
+        events[0].start_ts = start_ts;
+        events[0].end_ts = start_ts + booking.duration;
+        let res = ctx
+            .repos
+            .bookings
+            .reschedule(BookingRescheduleCommit {
+                booking: rescheduled,
+                updated,
+                moved_events: events.clone(),
+                new_events: Vec::new(),
+            })
+            .await;
+        assert_eq!(res.unwrap_err(), BookingCommitError::Conflict);
+
+        // Neither the booking nor its event were moved
+        let res = ctx.repos.bookings.find(&booking.id).await.unwrap();
+        assert_eq!(res.status, BookingStatus::Cancelled);
+        assert_eq!(res.start_ts, 0);
+        let event = ctx.repos.events.find(&events[0].id).await.unwrap();
+        assert_eq!(event.start_ts, 0);
+    }
+
+    #[tokio::test]
+    async fn commit_reserves_group_seats() {
+        let TestContext {
+            ctx,
+            account,
+            service,
+            user,
+        } = setup().await;
+        let calendar = Calendar::new(&user.id, &account.id);
+        ctx.repos.calendars.insert(&calendar).await.unwrap();
+
+        for seat in 0..2 {
+            let booking = generate_booking(&account.id, &service.id, &user.id, 0);
+            let event = generate_event(&calendar, &service.id, 0);
+            let (booking, events) = ctx
+                .repos
+                .bookings
+                .commit(BookingCommit {
+                    booking,
+                    events: vec![event],
+                    seats: Some(2),
+                    hold_id: None,
+                })
+                .await
+                .expect("To commit booking");
+            // Only the booking taking the last seat creates the events
+            assert_eq!(events.len(), seat);
+            assert_eq!(booking.event_ids.len(), seat);
+        }
+        assert_eq!(
+            ctx.repos.reservations.count(&service.id, 0).await.unwrap(),
+            2
+        );
+
+        let booking = generate_booking(&account.id, &service.id, &user.id, 0);
+        let res = ctx
+            .repos
+            .bookings
+            .commit(BookingCommit {
+                booking,
+                events: Vec::new(),
+                seats: Some(2),
+                hold_id: None,
+            })
+            .await;
+        assert_eq!(res.unwrap_err(), BookingCommitError::Conflict);
+        assert_eq!(
+            ctx.repos.reservations.count(&service.id, 0).await.unwrap(),
+            2
+        );
+    }
+}
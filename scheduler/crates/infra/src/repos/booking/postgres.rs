@@ -0,0 +1,941 @@
+use super::{
+    BookingCommit, BookingCommitError, BookingRescheduleCommit, IBookingRepo,
+    MostRecentCreatedBooking,
+};
+use crate::repos::{
+    event::{insert_event, save_event},
+    reservation::{decrement_reservation, increment_reservation},
+};
+use nettu_scheduler_domain::{Booking, BookingStatus, CalendarEvent, ID};
+use serde_json::Value;
+use sqlx::{
+    types::{Json, Uuid},
+    Executor, FromRow, PgPool, Postgres, Transaction,
+};
+use tracing::error;
+
+pub struct PostgresBookingRepo {
+    pool: PgPool,
+}
+
+impl PostgresBookingRepo {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct MostRecentCreatedBookingRaw {
+    user_uid: Uuid,
+    created: Option<i64>,
+}
+
+impl From<MostRecentCreatedBookingRaw> for MostRecentCreatedBooking {
+    fn from(e: MostRecentCreatedBookingRaw) -> Self {
+        Self {
+            user_id: e.user_uid.into(),
+            created: e.created,
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct BookingRaw {
+    booking_uid: Uuid,
+    account_uid: Uuid,
+    service_uid: Uuid,
+    host_user_uids: Vec<Uuid>,
+    resource_uid: Option<Uuid>,
+    event_uids: Vec<Uuid>,
+    invitee: Value,
+    start_ts: i64,
+    duration: i64,
+    status: String,
+    approval_deadline: Option<i64>,
+    reschedules: Value,
+    created: i64,
+    updated: i64,
+    metadata: Value,
+}
+
+impl From<BookingRaw> for Booking {
+    fn from(e: BookingRaw) -> Self {
+        Self {
+            id: e.booking_uid.into(),
+            account_id: e.account_uid.into(),
+            service_id: e.service_uid.into(),
+            host_user_ids: e.host_user_uids.into_iter().map(|id| id.into()).collect(),
+            resource_id: e.resource_uid.map(|id| id.into()),
+            event_ids: e.event_uids.into_iter().map(|id| id.into()).collect(),
+            invitee: serde_json::from_value(e.invitee).unwrap_or_default(),
+            start_ts: e.start_ts,
+            duration: e.duration,
+            status: e.status.into(),
+            approval_deadline: e.approval_deadline,
+            reschedules: serde_json::from_value(e.reschedules).unwrap_or_default(),
+            created: e.created,
+            updated: e.updated,
+            metadata: serde_json::from_value(e.metadata).unwrap(),
+        }
+    }
+}
+
+fn to_uuids(ids: &[ID]) -> Vec<Uuid> {
+    ids.iter().map(|id| *id.as_ref()).collect()
+}
+
+/// The size of the time ranges that are locked when committing a `Booking`
+const LOCK_RANGE_MILLIS: i64 = 1000 * 60 * 60;
+
+/// Advisory lock keys for every hour of the timespan for every resource.
+/// Overlapping timespans of the same resource always share at least one key.
+pub(crate) fn lock_keys(resource_ids: &[Uuid], start_ts: i64, end_ts: i64) -> Vec<i64> {
+    let first_range = start_ts.div_euclid(LOCK_RANGE_MILLIS);
+    let last_range = (end_ts - 1).div_euclid(LOCK_RANGE_MILLIS).max(first_range);
+    let mut keys = resource_ids
+        .iter()
+        .flat_map(|id| {
+            let id = id.as_u128();
+            let id = (id >> 64) as u64 ^ id as u64;
+            (first_range..=last_range).map(move |range| {
+                id.wrapping_add((range as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)) as i64
+            })
+        })
+        .collect::<Vec<_>>();
+    // Locks are always acquired in the same order to avoid deadlocks
+    keys.sort_unstable();
+    keys.dedup();
+    keys
+}
+
+fn commit_error(e: sqlx::Error) -> BookingCommitError {
+    error!("Unable to commit booking. DB returned error: {:?}", e);
+    BookingCommitError::StorageError
+}
+
+/// The time of the hosts and the resource, or a seat of a group `Service`,
+/// that a `Booking` or a `BookingHold` is about to take
+pub(crate) struct SlotClaim<'a> {
+    pub service_id: &'a ID,
+    pub host_user_ids: &'a [ID],
+    /// The resource from the resource pool of the `Service`
+    pub resource_id: Option<&'a ID>,
+    pub start_ts: i64,
+    pub end_ts: i64,
+    /// The number of seats when the `Service` is a group
+    pub seats: Option<usize>,
+    /// `BookingHold`s that expired before this time are ignored
+    pub timestamp: i64,
+    /// The `BookingHold` that is converted into the `Booking`, which is
+    /// released by the claim
+    pub hold_id: Option<&'a ID>,
+    /// The `Booking` that is rescheduled, whose current time and
+    /// `CalendarEvent`s do not conflict with the claim
+    pub booking_id: Option<&'a ID>,
+}
+
+/// The hosts and the resource whose time is claimed
+fn claimed_user_uids(claim: &SlotClaim<'_>) -> Vec<Uuid> {
+    let mut user_uids = to_uuids(claim.host_user_ids);
+    if let Some(resource_id) = claim.resource_id {
+        user_uids.push(*resource_id.as_ref());
+    }
+    user_uids
+}
+
+/// Advisory lock keys for the claimed time of the hosts and the resource,
+/// and the seats of a group `Service`
+fn claim_lock_keys(claim: &SlotClaim<'_>) -> Vec<i64> {
+    let mut locked_resources = claimed_user_uids(claim);
+    if claim.seats.is_some() {
+        locked_resources.push(*claim.service_id.as_ref());
+    }
+    lock_keys(&locked_resources, claim.start_ts, claim.end_ts)
+}
+
+/// Acquires the advisory locks until the transaction ends. Locks that are
+/// already held by the transaction are acquired again without waiting.
+pub(crate) async fn lock(
+    tx: &mut Transaction<'_, Postgres>,
+    keys: Vec<i64>,
+) -> Result<(), BookingCommitError> {
+    for key in keys {
+        sqlx::query!(
+            r#"SELECT 1 AS "locked!" FROM pg_advisory_xact_lock($1)"#,
+            key
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(commit_error)?;
+    }
+    Ok(())
+}
+
+/// Locks the claimed time of the hosts and the resource, and the seats of a group `Service`,
+/// until the transaction ends and checks that they are not taken by other
+/// `Booking`s or `BookingHold`s. Returns the number of seats already reserved
+/// by `Booking`s for a group `Service`.
+pub(crate) async fn claim_slot(
+    tx: &mut Transaction<'_, Postgres>,
+    claim: &SlotClaim<'_>,
+) -> Result<usize, BookingCommitError> {
+    let user_uids = claimed_user_uids(claim);
+    lock(tx, claim_lock_keys(claim)).await?;
+
+    if let Some(hold_id) = claim.hold_id {
+        let released = sqlx::query!(
+            r#"
+            DELETE FROM booking_holds AS h
+            WHERE h.hold_uid = $1 AND h.expires_at > $2
+            "#,
+            hold_id.as_ref(),
+            claim.timestamp,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(commit_error)?;
+        if released.rows_affected() == 0 {
+            // The hold expired or was converted by someone else
+            return Err(BookingCommitError::Conflict);
+        }
+    }
+
+    // Seats of the same group slot are shared, all other overlapping
+    // bookings, holds and service events of the hosts and the resource are conflicts
+    let conflict = sqlx::query_scalar!(
+        r#"
+        SELECT (EXISTS(
+            SELECT 1 FROM bookings AS b
+            WHERE (b.host_user_uids && $1 OR b.resource_uid = ANY($1)) AND
+            b.status IN ('pending', 'confirmed', 'rescheduled') AND
+            b.start_ts < $3 AND b.end_ts > $2 AND
+            NOT ($5 AND b.service_uid = $4 AND b.start_ts = $2) AND
+            b.booking_uid IS DISTINCT FROM $7
+        ) OR EXISTS(
+            SELECT 1 FROM booking_holds AS h
+            WHERE (h.host_user_uids && $1 OR h.resource_uid = ANY($1)) AND
+            NOT h.seat AND
+            h.expires_at > $6 AND
+            h.start_ts < $3 AND h.end_ts > $2
+        ) OR EXISTS(
+            SELECT 1 FROM calendar_events AS e
+            INNER JOIN calendars AS c
+                ON c.calendar_uid = e.calendar_uid
+            WHERE c.user_uid = ANY($1) AND
+            e.service_uid = $4 AND
+            e.busy AND
+            e.start_ts < $3 AND e.end_ts > $2 AND
+            NOT EXISTS(
+                SELECT 1 FROM bookings AS rb
+                WHERE rb.booking_uid = $7 AND e.event_uid = ANY(rb.event_uids)
+            )
+        )) AS "conflict!"
+        "#,
+        &user_uids,
+        claim.start_ts,
+        claim.end_ts,
+        claim.service_id.as_ref(),
+        claim.seats.is_some(),
+        claim.timestamp,
+        claim.booking_id.map(|id| *id.as_ref()),
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(commit_error)?;
+    if conflict {
+        return Err(BookingCommitError::Conflict);
+    }
+
+    let seats = match claim.seats {
+        Some(seats) => seats,
+        None => return Ok(0),
+    };
+    let taken = sqlx::query!(
+        r#"
+        SELECT COALESCE((
+            SELECT r.count FROM service_reservations AS r
+            WHERE r.service_uid = $1 AND r.timestamp = $2
+        ), 0) AS "reserved!", (
+            SELECT COUNT(*) FROM booking_holds AS h
+            WHERE h.service_uid = $1 AND h.start_ts = $2 AND
+            h.seat AND h.expires_at > $3
+        ) AS "held!"
+        "#,
+        claim.service_id.as_ref(),
+        claim.start_ts,
+        claim.timestamp,
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(commit_error)?;
+    if (taken.reserved + taken.held) as usize >= seats {
+        return Err(BookingCommitError::Conflict);
+    }
+
+    Ok(taken.reserved as usize)
+}
+
+async fn insert_booking<'c, E>(booking: &Booking, executor: E) -> anyhow::Result<()>
+where
+    E: Executor<'c, Database = Postgres>,
+{
+    let status: String = booking.status.into();
+    sqlx::query!(
+        r#"
+        INSERT INTO bookings(
+            booking_uid,
+            account_uid,
+            service_uid,
+            host_user_uids,
+            event_uids,
+            invitee,
+            start_ts,
+            duration,
+            end_ts,
+            status,
+            created,
+            updated,
+            metadata,
+            approval_deadline,
+            reschedules,
+            resource_uid
+        )
+        VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+        "#,
+        booking.id.as_ref(),
+        booking.account_id.as_ref(),
+        booking.service_id.as_ref(),
+        &to_uuids(&booking.host_user_ids),
+        &to_uuids(&booking.event_ids),
+        Json(&booking.invitee) as _,
+        booking.start_ts,
+        booking.duration,
+        booking.end_ts(),
+        status as _,
+        booking.created,
+        booking.updated,
+        Json(&booking.metadata) as _,
+        booking.approval_deadline,
+        Json(&booking.reschedules) as _,
+        booking.resource_id.as_ref().map(|id| *id.as_ref()),
+    )
+    .execute(executor)
+    .await
+    .map_err(|e| {
+        error!(
+            "Unable to insert booking: {:?}. DB returned error: {:?}",
+            booking, e
+        );
+        e
+    })?;
+
+    Ok(())
+}
+
+async fn save_booking<'c, E>(booking: &Booking, executor: E) -> anyhow::Result<()>
+where
+    E: Executor<'c, Database = Postgres>,
+{
+    let status: String = booking.status.into();
+    sqlx::query!(
+        r#"
+        UPDATE bookings SET
+            host_user_uids = $2,
+            event_uids = $3,
+            invitee = $4,
+            start_ts = $5,
+            duration = $6,
+            end_ts = $7,
+            status = $8,
+            updated = $9,
+            metadata = $10,
+            approval_deadline = $11,
+            reschedules = $12,
+            resource_uid = $13
+        WHERE booking_uid = $1
+        "#,
+        booking.id.as_ref(),
+        &to_uuids(&booking.host_user_ids),
+        &to_uuids(&booking.event_ids),
+        Json(&booking.invitee) as _,
+        booking.start_ts,
+        booking.duration,
+        booking.end_ts(),
+        status as _,
+        booking.updated,
+        Json(&booking.metadata) as _,
+        booking.approval_deadline,
+        Json(&booking.reschedules) as _,
+        booking.resource_id.as_ref().map(|id| *id.as_ref()),
+    )
+    .execute(executor)
+    .await
+    .map_err(|e| {
+        error!(
+            "Unable to save booking: {:?}. DB returned error: {:?}",
+            booking, e
+        );
+        e
+    })?;
+
+    Ok(())
+}
+
+/// The `SlotClaim` of a new `Booking`
+fn booking_claim(commit: &BookingCommit) -> SlotClaim<'_> {
+    SlotClaim {
+        service_id: &commit.booking.service_id,
+        host_user_ids: &commit.booking.host_user_ids,
+        resource_id: commit.booking.resource_id.as_ref(),
+        start_ts: commit.booking.start_ts,
+        end_ts: commit.booking.end_ts(),
+        seats: commit.seats,
+        timestamp: commit.booking.created,
+        hold_id: commit.hold_id.as_ref(),
+        booking_id: None,
+    }
+}
+
+/// Stores a new `Booking` together with its `CalendarEvent`s and reservation
+/// as part of the transaction
+async fn commit_booking(
+    tx: &mut Transaction<'_, Postgres>,
+    commit: BookingCommit,
+) -> Result<(Booking, Vec<CalendarEvent>), BookingCommitError> {
+    let seats_reserved = claim_slot(tx, &booking_claim(&commit)).await?;
+    let BookingCommit {
+        mut booking,
+        mut events,
+        seats,
+        ..
+    } = commit;
+
+    if let Some(seats) = seats {
+        increment_reservation(&booking.service_id, booking.start_ts, &mut *tx)
+            .await
+            .map_err(|_| BookingCommitError::StorageError)?;
+        if seats_reserved + 1 < seats {
+            // The hosts only become busy when the last seat is booked
+            events.clear();
+        }
+    }
+
+    for event in &events {
+        insert_event(event, &mut *tx)
+            .await
+            .map_err(|_| BookingCommitError::StorageError)?;
+    }
+    booking.event_ids = events.iter().map(|e| e.id.clone()).collect();
+    insert_booking(&booking, &mut *tx)
+        .await
+        .map_err(|_| BookingCommitError::StorageError)?;
+
+    Ok((booking, events))
+}
+
+#[async_trait::async_trait]
+impl IBookingRepo for PostgresBookingRepo {
+    async fn insert(&self, booking: &Booking) -> anyhow::Result<()> {
+        insert_booking(booking, &self.pool).await
+    }
+
+    async fn commit(
+        &self,
+        commit: BookingCommit,
+    ) -> Result<(Booking, Vec<CalendarEvent>), BookingCommitError> {
+        let mut tx = self.pool.begin().await.map_err(commit_error)?;
+        let committed = commit_booking(&mut tx, commit).await?;
+        tx.commit().await.map_err(commit_error)?;
+
+        Ok(committed)
+    }
+
+    async fn commit_chain(
+        &self,
+        commits: Vec<BookingCommit>,
+    ) -> Result<Vec<(Booking, Vec<CalendarEvent>)>, BookingCommitError> {
+        let mut tx = self.pool.begin().await.map_err(commit_error)?;
+
+        // All the locks of the chain are acquired up front and in order, so
+        // that concurrent chains can not deadlock
+        let mut keys = commits
+            .iter()
+            .flat_map(|commit| claim_lock_keys(&booking_claim(commit)))
+            .collect::<Vec<_>>();
+        keys.sort_unstable();
+        keys.dedup();
+        lock(&mut tx, keys).await?;
+
+        let mut committed = Vec::with_capacity(commits.len());
+        for commit in commits {
+            committed.push(commit_booking(&mut tx, commit).await?);
+        }
+        tx.commit().await.map_err(commit_error)?;
+
+        Ok(committed)
+    }
+
+    async fn reschedule(
+        &self,
+        reschedule: BookingRescheduleCommit,
+    ) -> Result<Booking, BookingCommitError> {
+        let BookingRescheduleCommit {
+            mut booking,
+            updated,
+            moved_events,
+            new_events,
+        } = reschedule;
+
+        let mut tx = self.pool.begin().await.map_err(commit_error)?;
+
+        let claim = SlotClaim {
+            service_id: &booking.service_id,
+            host_user_ids: &booking.host_user_ids,
+            resource_id: booking.resource_id.as_ref(),
+            start_ts: booking.start_ts,
+            end_ts: booking.end_ts(),
+            seats: None,
+            timestamp: booking.updated,
+            hold_id: None,
+            booking_id: Some(&booking.id),
+        };
+        // The current time of the booking is locked together with the new
+        // one, so that it is not released while it is moved
+        let mut keys = claim_lock_keys(&claim);
+        if let Some(previous) = booking.reschedules.last() {
+            keys.extend(lock_keys(
+                &to_uuids(&previous.host_user_ids),
+                previous.start_ts,
+                previous.start_ts + previous.duration,
+            ));
+        }
+        keys.sort_unstable();
+        keys.dedup();
+        lock(&mut tx, keys).await?;
+        claim_slot(&mut tx, &claim).await?;
+
+        booking.event_ids = moved_events
+            .iter()
+            .chain(new_events.iter())
+            .map(|e| e.id.clone())
+            .collect();
+        let status: String = booking.status.into();
+        let active = BookingStatus::ACTIVE
+            .iter()
+            .map(|status| String::from(*status))
+            .collect::<Vec<_>>();
+        let rescheduled = sqlx::query!(
+            r#"
+            UPDATE bookings SET
+                host_user_uids = $2,
+                event_uids = $3,
+                start_ts = $4,
+                duration = $5,
+                end_ts = $6,
+                status = $7,
+                updated = $8,
+                reschedules = $9,
+                resource_uid = $10
+            WHERE booking_uid = $1 AND status = ANY($11::text[]) AND updated = $12
+            "#,
+            booking.id.as_ref(),
+            &to_uuids(&booking.host_user_ids),
+            &to_uuids(&booking.event_ids),
+            booking.start_ts,
+            booking.duration,
+            booking.end_ts(),
+            status as _,
+            booking.updated,
+            Json(&booking.reschedules) as _,
+            booking.resource_id.as_ref().map(|id| *id.as_ref()),
+            &active,
+            updated,
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(commit_error)?;
+        if rescheduled.rows_affected() == 0 {
+            // The booking was cancelled, declined or approved in the meantime
+            return Err(BookingCommitError::Conflict);
+        }
+
+        for event in &moved_events {
+            save_event(event, &mut tx)
+                .await
+                .map_err(|_| BookingCommitError::StorageError)?;
+        }
+        for event in &new_events {
+            insert_event(event, &mut tx)
+                .await
+                .map_err(|_| BookingCommitError::StorageError)?;
+        }
+
+        tx.commit().await.map_err(commit_error)?;
+
+        Ok(booking)
+    }
+
+    async fn release(
+        &self,
+        booking: &Booking,
+        from: &[BookingStatus],
+        seats: Option<usize>,
+    ) -> anyhow::Result<Option<Vec<ID>>> {
+        let mut tx = self.pool.begin().await?;
+
+        let claim = SlotClaim {
+            service_id: &booking.service_id,
+            host_user_ids: &booking.host_user_ids,
+            resource_id: booking.resource_id.as_ref(),
+            start_ts: booking.start_ts,
+            end_ts: booking.end_ts(),
+            seats,
+            timestamp: booking.updated,
+            hold_id: None,
+            booking_id: Some(&booking.id),
+        };
+        lock(&mut tx, claim_lock_keys(&claim))
+            .await
+            .map_err(|e| anyhow::Error::msg(format!("{:?}", e)))?;
+
+        let status: String = booking.status.into();
+        let from = from
+            .iter()
+            .map(|status| String::from(*status))
+            .collect::<Vec<_>>();
+        // The events are read from the stored booking, as they may have been
+        // replaced by a reschedule since the booking was read
+        let released = sqlx::query_scalar!(
+            r#"
+            UPDATE bookings SET
+                status = $2,
+                updated = $3,
+                approval_deadline = NULL
+            WHERE booking_uid = $1 AND status = ANY($4::text[])
+            RETURNING event_uids AS "event_uids!"
+            "#,
+            booking.id.as_ref(),
+            status as _,
+            booking.updated,
+            &from,
+        )
+        .fetch_optional(&mut tx)
+        .await
+        .map_err(|e| {
+            error!(
+                "Unable to release booking: {:?}. DB returned error: {:?}",
+                booking, e
+            );
+            e
+        })?;
+        let event_uids = match released {
+            Some(event_uids) => event_uids,
+            // The booking was cancelled, declined or approved by someone else
+            None => return Ok(None),
+        };
+
+        let event_ids = match seats {
+            Some(seats) => {
+                let reserved =
+                    decrement_reservation(&booking.service_id, booking.start_ts, &mut tx).await?;
+                if reserved >= seats {
+                    // A seat of a full slot was released so the hosts are no longer busy
+                    sqlx::query_scalar!(
+                        r#"
+                        SELECT e.event_uid AS "event_uid!" FROM calendar_events AS e
+                        INNER JOIN calendars AS c
+                            ON c.calendar_uid = e.calendar_uid
+                        WHERE c.user_uid = ANY($1) AND
+                        e.service_uid = $2 AND
+                        e.start_ts = $3
+                        "#,
+                        &to_uuids(&booking.host_user_ids),
+                        booking.service_id.as_ref(),
+                        booking.start_ts,
+                    )
+                    .fetch_all(&mut tx)
+                    .await?
+                } else {
+                    Vec::new()
+                }
+            }
+            None => event_uids,
+        };
+
+        tx.commit().await?;
+
+        Ok(Some(
+            event_ids.into_iter().map(|id: Uuid| id.into()).collect(),
+        ))
+    }
+
+    async fn approve(&self, booking: &Booking, events: &[CalendarEvent]) -> anyhow::Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let status: String = booking.status.into();
+        let approved = sqlx::query!(
+            r#"
+            UPDATE bookings SET
+                status = $2,
+                updated = $3,
+                approval_deadline = NULL
+            WHERE booking_uid = $1 AND status = 'pending' AND
+            start_ts = $4 AND event_uids = $5
+            "#,
+            booking.id.as_ref(),
+            status as _,
+            booking.updated,
+            booking.start_ts,
+            &to_uuids(&booking.event_ids),
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|e| {
+            error!(
+                "Unable to approve booking: {:?}. DB returned error: {:?}",
+                booking, e
+            );
+            e
+        })?;
+        if approved.rows_affected() == 0 {
+            // The booking was declined, approved or rescheduled by someone else
+            return Ok(false);
+        }
+
+        for event in events {
+            save_event(event, &mut tx).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    async fn set_status(&self, booking: &Booking, from: &[BookingStatus]) -> anyhow::Result<bool> {
+        let status: String = booking.status.into();
+        let from = from
+            .iter()
+            .map(|status| String::from(*status))
+            .collect::<Vec<_>>();
+        let res = sqlx::query!(
+            r#"
+            UPDATE bookings SET
+                status = $2,
+                updated = $3
+            WHERE booking_uid = $1 AND status = ANY($4::text[])
+            "#,
+            booking.id.as_ref(),
+            status as _,
+            booking.updated,
+            &from,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Unable to set status of booking: {:?}. DB returned error: {:?}",
+                booking, e
+            );
+            e
+        })?;
+
+        Ok(res.rows_affected() > 0)
+    }
+
+    async fn save(&self, booking: &Booking) -> anyhow::Result<()> {
+        save_booking(booking, &self.pool).await
+    }
+
+    async fn find(&self, booking_id: &ID) -> Option<Booking> {
+        let res: Option<BookingRaw> = sqlx::query_as!(
+            BookingRaw,
+            r#"
+            SELECT b.booking_uid, b.account_uid, b.service_uid, b.host_user_uids, b.resource_uid,
+            b.event_uids, b.invitee, b.start_ts, b.duration, b.status, b.approval_deadline,
+            b.reschedules, b.created, b.updated, b.metadata FROM bookings AS b
+            WHERE b.booking_uid = $1
+            "#,
+            booking_id.as_ref(),
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Find booking with id: {:?} failed. DB returned error: {:?}",
+                booking_id, e
+            );
+            e
+        })
+        .ok()?;
+
+        res.map(|booking| booking.into())
+    }
+
+    async fn find_by_service(
+        &self,
+        service_id: &ID,
+        min_ts: i64,
+        max_ts: i64,
+    ) -> anyhow::Result<Vec<Booking>> {
+        let bookings: Vec<BookingRaw> = sqlx::query_as!(
+            BookingRaw,
+            r#"
+            SELECT b.booking_uid, b.account_uid, b.service_uid, b.host_user_uids, b.resource_uid,
+            b.event_uids, b.invitee, b.start_ts, b.duration, b.status, b.approval_deadline,
+            b.reschedules, b.created, b.updated, b.metadata FROM bookings AS b
+            WHERE b.service_uid = $1 AND
+            b.start_ts < $3 AND b.end_ts > $2
+            ORDER BY b.start_ts
+            "#,
+            service_id.as_ref(),
+            min_ts,
+            max_ts,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Find bookings for service id: {}, min_ts: {}, max_ts: {} failed. DB returned error: {:?}",
+                service_id, min_ts, max_ts, e
+            );
+            e
+        })?;
+
+        Ok(bookings.into_iter().map(|b| b.into()).collect())
+    }
+
+    async fn find_active_by_hosts(
+        &self,
+        service_id: &ID,
+        user_ids: &[ID],
+        min_ts: i64,
+        max_ts: i64,
+    ) -> Vec<Booking> {
+        let bookings: Vec<BookingRaw> = match sqlx::query_as!(
+            BookingRaw,
+            r#"
+            SELECT b.booking_uid, b.account_uid, b.service_uid, b.host_user_uids, b.resource_uid,
+            b.event_uids, b.invitee, b.start_ts, b.duration, b.status, b.approval_deadline,
+            b.reschedules, b.created, b.updated, b.metadata FROM bookings AS b
+            WHERE b.service_uid = $1 AND
+            b.host_user_uids && $2 AND
+            b.status IN ('pending', 'confirmed', 'rescheduled') AND
+            b.start_ts < $4 AND b.end_ts > $3
+            ORDER BY b.start_ts
+            "#,
+            service_id.as_ref(),
+            &to_uuids(user_ids),
+            min_ts,
+            max_ts,
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(bookings) => bookings,
+            Err(e) => {
+                error!(
+                    "Find active bookings for service id: {}, user_ids: {:?}, min_ts: {}, max_ts: {} failed. DB returned error: {:?}",
+                    service_id, user_ids, min_ts, max_ts, e
+                );
+                return Vec::new();
+            }
+        };
+
+        bookings.into_iter().map(|b| b.into()).collect()
+    }
+
+    async fn find_pending_past_deadline(&self, timestamp: i64) -> Vec<Booking> {
+        let bookings: Vec<BookingRaw> = match sqlx::query_as!(
+            BookingRaw,
+            r#"
+            SELECT b.booking_uid, b.account_uid, b.service_uid, b.host_user_uids, b.resource_uid,
+            b.event_uids, b.invitee, b.start_ts, b.duration, b.status, b.approval_deadline,
+            b.reschedules, b.created, b.updated, b.metadata FROM bookings AS b
+            WHERE b.status = 'pending' AND
+            b.approval_deadline <= $1
+            "#,
+            timestamp,
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(bookings) => bookings,
+            Err(e) => {
+                error!(
+                    "Find pending bookings past deadline: {} failed. DB returned error: {:?}",
+                    timestamp, e
+                );
+                return Vec::new();
+            }
+        };
+
+        bookings.into_iter().map(|b| b.into()).collect()
+    }
+
+    async fn find_most_recently_created(
+        &self,
+        service_id: &ID,
+        user_ids: &[ID],
+    ) -> Vec<MostRecentCreatedBooking> {
+        let bookings: Vec<MostRecentCreatedBookingRaw> = match sqlx::query_as!(
+            MostRecentCreatedBookingRaw,
+            r#"
+            SELECT DISTINCT ON (h.user_uid) h.user_uid AS "user_uid!", b.created AS "created?"
+            FROM bookings AS b
+            CROSS JOIN UNNEST(b.host_user_uids) AS h(user_uid)
+            WHERE b.service_uid = $1 AND
+            h.user_uid = ANY($2) AND
+            b.status IN ('pending', 'confirmed', 'rescheduled')
+            ORDER BY h.user_uid, b.created DESC
+            "#,
+            service_id.as_ref(),
+            &to_uuids(user_ids),
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(bookings) => bookings,
+            Err(e) => {
+                error!(
+                    "Find most recently created bookings for service id: {} failed. DB returned error: {:?}",
+                    service_id, e
+                );
+                return Vec::new();
+            }
+        };
+
+        bookings.into_iter().map(|b| b.into()).collect()
+    }
+
+    async fn find_last_by_customer(&self, service_id: &ID, customer_key: &str) -> Option<Booking> {
+        let booking: Option<BookingRaw> = match sqlx::query_as!(
+            BookingRaw,
+            r#"
+            SELECT b.booking_uid, b.account_uid, b.service_uid, b.host_user_uids, b.resource_uid,
+            b.event_uids, b.invitee, b.start_ts, b.duration, b.status, b.approval_deadline,
+            b.reschedules, b.created, b.updated, b.metadata FROM bookings AS b
+            WHERE b.service_uid = $1 AND
+            b.invitee->>'customerKey' = $2 AND
+            b.status NOT IN ('cancelled', 'declined')
+            ORDER BY b.created DESC
+            LIMIT 1
+            "#,
+            service_id.as_ref(),
+            customer_key,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        {
+            Ok(booking) => booking,
+            Err(e) => {
+                error!(
+                    "Find last booking for service id: {}, customer key: {} failed. DB returned error: {:?}",
+                    service_id, customer_key, e
+                );
+                return None;
+            }
+        };
+
+        booking.map(|b| b.into())
+    }
+}
@@ -0,0 +1,170 @@
+mod postgres;
+
+use super::booking::BookingCommitError;
+use nettu_scheduler_domain::{BookingHold, Reservation, ID};
+pub(crate) use postgres::commit_hold;
+pub use postgres::PostgresBookingHoldRepo;
+
+#[async_trait::async_trait]
+pub trait IBookingHoldRepo: Send + Sync {
+    /// Re-validates that the hosts, or a seat when `seats` is given, are still
+    /// free and stores the `BookingHold`. It is serialized with concurrent
+    /// `Booking`s and `BookingHold`s for the same hosts and time.
+    async fn commit(
+        &self,
+        hold: &BookingHold,
+        seats: Option<usize>,
+    ) -> Result<(), BookingCommitError>;
+    async fn find(&self, hold_id: &ID) -> Option<BookingHold>;
+    async fn delete(&self, hold_id: &ID) -> anyhow::Result<()>;
+    async fn delete_expired(&self, timestamp: i64) -> anyhow::Result<()>;
+    /// Finds the `BookingHold`s that have not expired at the given timestamp,
+    /// are not for a seat and are holding the `User`, as a host or as a resource,
+    /// within the given timespan
+    async fn find_active_by_host(
+        &self,
+        user_id: &ID,
+        min_ts: i64,
+        max_ts: i64,
+        timestamp: i64,
+    ) -> Vec<BookingHold>;
+    /// Counts the seats held at every timestamp of a `Service` within the
+    /// given timespan by `BookingHold`s that have not expired at `timestamp`
+    async fn find_seats_by_timespan(
+        &self,
+        service_id: &ID,
+        min_ts: i64,
+        max_ts: i64,
+        timestamp: i64,
+    ) -> anyhow::Result<Vec<Reservation>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BookingCommitError;
+    use crate::{setup_context, NettuContext};
+    use nettu_scheduler_domain::{Account, BookingHold, Service, User};
+
+    struct TestContext {
+        ctx: NettuContext,
+        hold: BookingHold,
+    }
+
+    async fn setup() -> TestContext {
+        let ctx = setup_context().await;
+        let account = Account::default();
+        ctx.repos.accounts.insert(&account).await.unwrap();
+        let user = User::new(account.id.clone());
+        ctx.repos.users.insert(&user).await.unwrap();
+        let service = Service::new(account.id.clone());
+        ctx.repos.services.insert(&service).await.unwrap();
+        let hold = BookingHold {
+            id: Default::default(),
+            account_id: account.id.clone(),
+            service_id: service.id.clone(),
+            host_user_ids: vec![user.id.clone()],
+            resource_id: None,
+            start_ts: 1000 * 60 * 60,
+            duration: 1000 * 60 * 30,
+            seat: false,
+            created: 0,
+            expires_at: 1000 * 60 * 10,
+        };
+
+        TestContext { ctx, hold }
+    }
+
+    #[tokio::test]
+    async fn holds_hosts_until_expiry() {
+        let TestContext { ctx, hold } = setup().await;
+        let user_id = hold.host_user_ids[0].clone();
+
+        assert!(ctx.repos.booking_holds.commit(&hold, None).await.is_ok());
+        assert!(ctx.repos.booking_holds.find(&hold.id).await.is_some());
+        let holds = ctx
+            .repos
+            .booking_holds
+            .find_active_by_host(&user_id, 0, 1000 * 60 * 60 * 2, 0)
+            .await;
+        assert_eq!(holds.len(), 1);
+
+        // The hosts are taken by the hold
+        let mut overlapping = hold.clone();
+        overlapping.id = Default::default();
+        overlapping.start_ts += 1000 * 60 * 15;
+        assert_eq!(
+            ctx.repos.booking_holds.commit(&overlapping, None).await,
+            Err(BookingCommitError::Conflict)
+        );
+
+        // Until the hold expires
+        overlapping.created = hold.expires_at;
+        overlapping.expires_at = hold.expires_at * 2;
+        assert!(ctx
+            .repos
+            .booking_holds
+            .commit(&overlapping, None)
+            .await
+            .is_ok());
+        let holds = ctx
+            .repos
+            .booking_holds
+            .find_active_by_host(&user_id, 0, 1000 * 60 * 60 * 2, hold.expires_at)
+            .await;
+        assert_eq!(holds.len(), 1);
+        assert_eq!(holds[0].id, overlapping.id);
+
+        assert!(ctx
+            .repos
+            .booking_holds
+            .delete_expired(hold.expires_at)
+            .await
+            .is_ok());
+        assert!(ctx.repos.booking_holds.find(&hold.id).await.is_none());
+        assert!(ctx
+            .repos
+            .booking_holds
+            .delete(&overlapping.id)
+            .await
+            .is_ok());
+        assert!(ctx
+            .repos
+            .booking_holds
+            .find(&overlapping.id)
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn holds_seats() {
+        let TestContext { ctx, mut hold } = setup().await;
+        hold.seat = true;
+
+        for _ in 0..2 {
+            hold.id = Default::default();
+            assert!(ctx.repos.booking_holds.commit(&hold, Some(2)).await.is_ok());
+        }
+        hold.id = Default::default();
+        assert_eq!(
+            ctx.repos.booking_holds.commit(&hold, Some(2)).await,
+            Err(BookingCommitError::Conflict)
+        );
+
+        let seats = ctx
+            .repos
+            .booking_holds
+            .find_seats_by_timespan(&hold.service_id, 0, 1000 * 60 * 60 * 2, 0)
+            .await
+            .expect("To find held seats");
+        assert_eq!(seats.len(), 1);
+        assert_eq!(seats[0].timestamp, hold.start_ts);
+        assert_eq!(seats[0].count, 2);
+        // Seat holds do not take the time of the hosts
+        let holds = ctx
+            .repos
+            .booking_holds
+            .find_active_by_host(&hold.host_user_ids[0], 0, 1000 * 60 * 60 * 2, 0)
+            .await;
+        assert!(holds.is_empty());
+    }
+}
@@ -0,0 +1,291 @@
+use super::IBookingHoldRepo;
+use crate::repos::booking::{claim_slot, BookingCommitError, SlotClaim};
+use nettu_scheduler_domain::{BookingHold, Reservation, ID};
+use sqlx::{types::Uuid, FromRow, PgPool, Postgres, Transaction};
+use tracing::error;
+
+pub struct PostgresBookingHoldRepo {
+    pool: PgPool,
+}
+
+impl PostgresBookingHoldRepo {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct BookingHoldRaw {
+    hold_uid: Uuid,
+    account_uid: Uuid,
+    service_uid: Uuid,
+    host_user_uids: Vec<Uuid>,
+    resource_uid: Option<Uuid>,
+    start_ts: i64,
+    duration: i64,
+    seat: bool,
+    created: i64,
+    expires_at: i64,
+}
+
+impl From<BookingHoldRaw> for BookingHold {
+    fn from(e: BookingHoldRaw) -> Self {
+        Self {
+            id: e.hold_uid.into(),
+            account_id: e.account_uid.into(),
+            service_id: e.service_uid.into(),
+            host_user_ids: e.host_user_uids.into_iter().map(|id| id.into()).collect(),
+            resource_id: e.resource_uid.map(|id| id.into()),
+            start_ts: e.start_ts,
+            duration: e.duration,
+            seat: e.seat,
+            created: e.created,
+            expires_at: e.expires_at,
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct HeldSeatsRaw {
+    start_ts: i64,
+    count: i64,
+}
+
+/// Re-validates that the hosts, or a seat when `seats` is given, are still
+/// free and stores the `BookingHold` as part of the transaction
+pub(crate) async fn commit_hold(
+    tx: &mut Transaction<'_, Postgres>,
+    hold: &BookingHold,
+    seats: Option<usize>,
+) -> Result<(), BookingCommitError> {
+    let claim = SlotClaim {
+        service_id: &hold.service_id,
+        host_user_ids: &hold.host_user_ids,
+        resource_id: hold.resource_id.as_ref(),
+        start_ts: hold.start_ts,
+        end_ts: hold.end_ts(),
+        seats,
+        timestamp: hold.created,
+        hold_id: None,
+        booking_id: None,
+    };
+    claim_slot(tx, &claim).await?;
+
+    let host_user_uids = hold
+        .host_user_ids
+        .iter()
+        .map(|id| *id.as_ref())
+        .collect::<Vec<_>>();
+    sqlx::query!(
+        r#"
+        INSERT INTO booking_holds(
+            hold_uid,
+            account_uid,
+            service_uid,
+            host_user_uids,
+            start_ts,
+            duration,
+            end_ts,
+            seat,
+            created,
+            expires_at,
+            resource_uid
+        )
+        VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        "#,
+        hold.id.as_ref(),
+        hold.account_id.as_ref(),
+        hold.service_id.as_ref(),
+        &host_user_uids,
+        hold.start_ts,
+        hold.duration,
+        hold.end_ts(),
+        hold.seat,
+        hold.created,
+        hold.expires_at,
+        hold.resource_id.as_ref().map(|id| *id.as_ref()),
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| {
+        error!(
+            "Unable to commit booking hold: {:?}. DB returned error: {:?}",
+            hold, e
+        );
+        BookingCommitError::StorageError
+    })?;
+
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl IBookingHoldRepo for PostgresBookingHoldRepo {
+    async fn commit(
+        &self,
+        hold: &BookingHold,
+        seats: Option<usize>,
+    ) -> Result<(), BookingCommitError> {
+        let storage_error = |e: sqlx::Error| {
+            error!(
+                "Unable to commit booking hold: {:?}. DB returned error: {:?}",
+                hold, e
+            );
+            BookingCommitError::StorageError
+        };
+
+        let mut tx = self.pool.begin().await.map_err(storage_error)?;
+        commit_hold(&mut tx, hold, seats).await?;
+        tx.commit().await.map_err(storage_error)?;
+
+        Ok(())
+    }
+
+    async fn find(&self, hold_id: &ID) -> Option<BookingHold> {
+        let res: Option<BookingHoldRaw> = sqlx::query_as!(
+            BookingHoldRaw,
+            r#"
+            SELECT hold_uid, account_uid, service_uid, host_user_uids, resource_uid,
+            start_ts, duration, seat, created, expires_at FROM booking_holds AS h
+            WHERE h.hold_uid = $1
+            "#,
+            hold_id.as_ref(),
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Find booking hold with id: {:?} failed. DB returned error: {:?}",
+                hold_id, e
+            );
+            e
+        })
+        .ok()?;
+
+        res.map(|hold| hold.into())
+    }
+
+    async fn delete(&self, hold_id: &ID) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM booking_holds AS h
+            WHERE h.hold_uid = $1
+            "#,
+            hold_id.as_ref(),
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Delete booking hold with id: {:?} failed. DB returned error: {:?}",
+                hold_id, e
+            );
+            e
+        })?;
+
+        Ok(())
+    }
+
+    async fn delete_expired(&self, timestamp: i64) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM booking_holds AS h
+            WHERE h.expires_at <= $1
+            "#,
+            timestamp,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Delete booking holds expired at: {} failed. DB returned error: {:?}",
+                timestamp, e
+            );
+            e
+        })?;
+
+        Ok(())
+    }
+
+    async fn find_active_by_host(
+        &self,
+        user_id: &ID,
+        min_ts: i64,
+        max_ts: i64,
+        timestamp: i64,
+    ) -> Vec<BookingHold> {
+        let holds: Vec<BookingHoldRaw> = match sqlx::query_as!(
+            BookingHoldRaw,
+            r#"
+            SELECT hold_uid, account_uid, service_uid, host_user_uids, resource_uid,
+            start_ts, duration, seat, created, expires_at FROM booking_holds AS h
+            WHERE ($1 = ANY(h.host_user_uids) OR h.resource_uid = $1) AND
+            NOT h.seat AND
+            h.expires_at > $4 AND
+            h.start_ts < $3 AND h.end_ts > $2
+            ORDER BY h.start_ts
+            "#,
+            user_id.as_ref(),
+            min_ts,
+            max_ts,
+            timestamp,
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(holds) => holds,
+            Err(e) => {
+                error!(
+                    "Find active booking holds for user id: {}, min_ts: {}, max_ts: {} failed. DB returned error: {:?}",
+                    user_id, min_ts, max_ts, e
+                );
+                return Vec::new();
+            }
+        };
+
+        holds.into_iter().map(|h| h.into()).collect()
+    }
+
+    async fn find_seats_by_timespan(
+        &self,
+        service_id: &ID,
+        min_ts: i64,
+        max_ts: i64,
+        timestamp: i64,
+    ) -> anyhow::Result<Vec<Reservation>> {
+        let seats: Vec<HeldSeatsRaw> = sqlx::query_as!(
+            HeldSeatsRaw,
+            r#"
+            SELECT h.start_ts, COUNT(*) AS "count!" FROM booking_holds AS h
+            WHERE h.service_uid = $1 AND
+            h.seat AND
+            h.expires_at > $4 AND
+            h.start_ts >= $2 AND
+            h.start_ts < $3
+            GROUP BY h.start_ts
+            ORDER BY h.start_ts
+            "#,
+            service_id.as_ref(),
+            min_ts,
+            max_ts,
+            timestamp,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Find held seats for service id: {} between {} and {} failed. DB returned error: {:?}",
+                service_id, min_ts, max_ts, e
+            );
+            e
+        })?;
+
+        Ok(seats
+            .into_iter()
+            .map(|seats| Reservation {
+                service_id: service_id.clone(),
+                timestamp: seats.start_ts,
+                count: seats.count as usize,
+            })
+            .collect())
+    }
+}
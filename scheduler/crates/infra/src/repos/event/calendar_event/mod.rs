@@ -3,6 +3,7 @@ mod postgres;
 use crate::repos::shared::query_structs::MetadataFindQuery;
 use nettu_scheduler_domain::{CalendarEvent, TimeSpan, ID};
 pub use postgres::PostgresEventRepo;
+pub(crate) use postgres::{insert_event, save_event};
 
 #[derive(Debug)]
 pub struct MostRecentCreatedServiceEvents {
@@ -4,7 +4,7 @@ use nettu_scheduler_domain::{CalendarEvent, CalendarEventReminder, RRuleOptions,
 use serde_json::Value;
 use sqlx::{
     types::{Json, Uuid},
-    FromRow, PgPool,
+    Executor, FromRow, PgPool, Postgres,
 };
 use tracing::error;
 
@@ -42,6 +42,7 @@ struct EventRaw {
     start_ts: i64,
     duration: i64,
     busy: bool,
+    tentative: bool,
     end_ts: i64,
     created: i64,
     updated: i64,
@@ -71,6 +72,7 @@ impl From<EventRaw> for CalendarEvent {
             start_ts: e.start_ts,
             duration: e.duration,
             busy: e.busy,
+            tentative: e.tentative,
             end_ts: e.end_ts,
             created: e.created,
             updated: e.updated,
@@ -83,96 +85,116 @@ impl From<EventRaw> for CalendarEvent {
     }
 }
 
+/// Inserts a `CalendarEvent` with the given executor, which allows it to be
+/// part of a transaction
+pub(crate) async fn insert_event<'c, E>(e: &CalendarEvent, executor: E) -> anyhow::Result<()>
+where
+    E: Executor<'c, Database = Postgres>,
+{
+    sqlx::query!(
+        r#"
+        INSERT INTO calendar_events(
+            event_uid,
+            calendar_uid,
+            start_ts,
+            duration,
+            end_ts,
+            busy,
+            created,
+            updated,
+            recurrence,
+            exdates,
+            reminders,
+            service_uid,
+            metadata,
+            tentative
+        )
+        VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+        "#,
+        e.id.as_ref(),
+        e.calendar_id.as_ref(),
+        e.start_ts,
+        e.duration,
+        e.end_ts,
+        e.busy,
+        e.created,
+        e.updated,
+        Json(&e.recurrence) as _,
+        &e.exdates,
+        Json(&e.reminders) as _,
+        e.service_id.as_ref().map(|id| id.as_ref()),
+        Json(&e.metadata) as _,
+        e.tentative,
+    )
+    .execute(executor)
+    .await
+    .map_err(|err| {
+        error!(
+            "Unable to insert calendar_event: {:?}. DB returned error: {:?}",
+            e, err
+        );
+        err
+    })?;
+
+    Ok(())
+}
+
+pub(crate) async fn save_event<'c, E>(e: &CalendarEvent, executor: E) -> anyhow::Result<()>
+where
+    E: Executor<'c, Database = Postgres>,
+{
+    sqlx::query!(
+        r#"
+        UPDATE calendar_events SET
+            start_ts = $2,
+            duration = $3,
+            end_ts = $4,
+            busy = $5,
+            created = $6,
+            updated = $7,
+            recurrence = $8,
+            exdates = $9,
+            reminders = $10,
+            service_uid = $11,
+            metadata = $12,
+            tentative = $13
+        WHERE event_uid = $1
+        "#,
+        e.id.as_ref(),
+        e.start_ts,
+        e.duration,
+        e.end_ts,
+        e.busy,
+        e.created,
+        e.updated,
+        Json(&e.recurrence) as _,
+        &e.exdates,
+        Json(&e.reminders) as _,
+        e.service_id.as_ref().map(|id| id.as_ref()),
+        Json(&e.metadata) as _,
+        e.tentative,
+    )
+    .execute(executor)
+    .await
+    .map_err(|err| {
+        error!(
+            "Unable to save calendar_event: {:?}. DB returned error: {:?}",
+            e, err
+        );
+        err
+    })?;
+
+    Ok(())
+}
+
 #[async_trait::async_trait]
 impl IEventRepo for PostgresEventRepo {
     async fn insert(&self, e: &CalendarEvent) -> anyhow::Result<()> {
-        sqlx::query!(
-            r#"
-            INSERT INTO calendar_events(
-                event_uid,
-                calendar_uid,
-                start_ts,
-                duration,
-                end_ts,
-                busy,
-                created,
-                updated,
-                recurrence,
-                exdates,
-                reminders,
-                service_uid,
-                metadata
-            )
-            VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
-            "#,
-            e.id.as_ref(),
-            e.calendar_id.as_ref(),
-            e.start_ts,
-            e.duration,
-            e.end_ts,
-            e.busy,
-            e.created,
-            e.updated,
-            Json(&e.recurrence) as _,
-            &e.exdates,
-            Json(&e.reminders) as _,
-            e.service_id.as_ref().map(|id| id.as_ref()),
-            Json(&e.metadata) as _,
-        )
-        .execute(&self.pool)
-        .await
-        .map_err(|err| {
-            error!(
-                "Unable to insert calendar_event: {:?}. DB returned error: {:?}",
-                e, err
-            );
-            err
-        })?;
-
-        Ok(())
+        insert_event(e, &self.pool).await
     }
 
     async fn save(&self, e: &CalendarEvent) -> anyhow::Result<()> {
-        sqlx::query!(
-            r#"
-            UPDATE calendar_events SET
-                start_ts = $2,
-                duration = $3,
-                end_ts = $4,
-                busy = $5,
-                created = $6,
-                updated = $7,
-                recurrence = $8,
-                exdates = $9,
-                reminders = $10,
-                service_uid = $11,
-                metadata = $12
-            WHERE event_uid = $1
-            "#,
-            e.id.as_ref(),
-            e.start_ts,
-            e.duration,
-            e.end_ts,
-            e.busy,
-            e.created,
-            e.updated,
-            Json(&e.recurrence) as _,
-            &e.exdates,
-            Json(&e.reminders) as _,
-            e.service_id.as_ref().map(|id| id.as_ref()),
-            Json(&e.metadata) as _,
-        )
-        .execute(&self.pool)
-        .await
-        .map_err(|err| {
-            error!(
-                "Unable to save calendar_event: {:?}. DB returned error: {:?}",
-                e, err
-            );
-            err
-        })?;
-
-        Ok(())
+        save_event(e, &self.pool).await
     }
 
     async fn find(&self, event_id: &ID) -> Option<CalendarEvent> {
@@ -5,6 +5,7 @@ mod reminder;
 
 pub use calendar_event::IEventRepo;
 pub use calendar_event::PostgresEventRepo;
+pub(crate) use calendar_event::{insert_event, save_event};
 pub use event_reminders_expansion_jobs::IEventRemindersGenerationJobsRepo;
 pub use event_reminders_expansion_jobs::PostgresEventReminderGenerationJobsRepo;
 pub use event_synced::IEventSyncedRepo;
@@ -0,0 +1,263 @@
+mod postgres;
+
+use nettu_scheduler_domain::{ExternalBusyCacheEntry, IntegrationProvider, TimeSpan, ID};
+pub use postgres::PostgresExternalBusyCacheRepo;
+
+#[async_trait::async_trait]
+pub trait IExternalBusyCacheRepo: Send + Sync {
+    /// Inserts the entry or replaces the cached entry of the same external
+    /// calendar and timespan. The entries of the calendar within the timespan
+    /// of the entry, and the ones fetched before `prune_fetched_before`, are
+    /// removed as they are no longer needed.
+    async fn save(
+        &self,
+        entry: &ExternalBusyCacheEntry,
+        prune_fetched_before: i64,
+    ) -> anyhow::Result<()>;
+    /// Finds the most recently fetched entry of the external calendar which
+    /// covers the whole `TimeSpan`
+    async fn find(
+        &self,
+        user_id: &ID,
+        provider: &IntegrationProvider,
+        ext_calendar_id: &str,
+        timespan: &TimeSpan,
+    ) -> Option<ExternalBusyCacheEntry>;
+    /// Marks the entry as being refetched from the provider. Returns false when
+    /// it was refetched already, or another refetch of it started less than
+    /// `ExternalBusyCacheEntry::REFRESH_TIMEOUT` before `timestamp`.
+    async fn start_refresh(
+        &self,
+        entry: &ExternalBusyCacheEntry,
+        timestamp: i64,
+    ) -> anyhow::Result<bool>;
+    /// Removes the cached entries so that the busy intervals of the external
+    /// calendar are refetched from the provider the next time they are needed
+    async fn invalidate(
+        &self,
+        user_id: &ID,
+        provider: &IntegrationProvider,
+        ext_calendar_id: &str,
+    ) -> anyhow::Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{setup_context, NettuContext};
+    use nettu_scheduler_domain::{
+        Account, EventInstance, ExternalBusyCacheEntry, IntegrationProvider, TimeSpan, User,
+    };
+
+    async fn find(
+        ctx: &NettuContext,
+        user: &User,
+        start_ts: i64,
+        end_ts: i64,
+    ) -> Option<ExternalBusyCacheEntry> {
+        ctx.repos
+            .external_busy_cache
+            .find(
+                &user.id,
+                &IntegrationProvider::Google,
+                "primary",
+                &TimeSpan::new(start_ts, end_ts),
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn crud() {
+        let ctx = setup_context().await;
+        let account = Account::default();
+        ctx.repos.accounts.insert(&account).await.unwrap();
+        let user = User::new(account.id.clone());
+        ctx.repos.users.insert(&user).await.unwrap();
+
+        let mut entry = ExternalBusyCacheEntry {
+            user_id: user.id.clone(),
+            provider: IntegrationProvider::Google,
+            ext_calendar_id: "primary".into(),
+            start_ts: 0,
+            end_ts: 1000,
+            busy: vec![EventInstance {
+                start_ts: 100,
+                end_ts: 200,
+                busy: true,
+            }],
+            fetched_at: 10,
+        };
+        assert!(ctx.repos.external_busy_cache.save(&entry, 0).await.is_ok());
+
+        let found = ctx
+            .repos
+            .external_busy_cache
+            .find(
+                &user.id,
+                &IntegrationProvider::Google,
+                "primary",
+                &TimeSpan::new(0, 1000),
+            )
+            .await
+            .expect("To find cached busy intervals");
+        assert_eq!(found.busy, entry.busy);
+        assert_eq!(found.fetched_at, 10);
+        assert!(ctx
+            .repos
+            .external_busy_cache
+            .find(
+                &user.id,
+                &IntegrationProvider::Outlook,
+                "primary",
+                &TimeSpan::new(0, 1000)
+            )
+            .await
+            .is_none());
+        // The timespan is not covered
+        assert!(ctx
+            .repos
+            .external_busy_cache
+            .find(
+                &user.id,
+                &IntegrationProvider::Google,
+                "primary",
+                &TimeSpan::new(0, 1001)
+            )
+            .await
+            .is_none());
+
+        // Replaces the cached entry
+        entry.busy = Vec::new();
+        entry.fetched_at = 20;
+        assert!(ctx.repos.external_busy_cache.save(&entry, 0).await.is_ok());
+        let found = ctx
+            .repos
+            .external_busy_cache
+            .find(
+                &user.id,
+                &IntegrationProvider::Google,
+                "primary",
+                &TimeSpan::new(0, 1000),
+            )
+            .await
+            .expect("To find cached busy intervals");
+        assert!(found.busy.is_empty());
+        assert_eq!(found.fetched_at, 20);
+
+        assert!(ctx
+            .repos
+            .external_busy_cache
+            .invalidate(&user.id, &IntegrationProvider::Google, "primary")
+            .await
+            .is_ok());
+        assert!(ctx
+            .repos
+            .external_busy_cache
+            .find(
+                &user.id,
+                &IntegrationProvider::Google,
+                "primary",
+                &TimeSpan::new(0, 1000)
+            )
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn keeps_entries_of_different_timespans() {
+        let ctx = setup_context().await;
+        let account = Account::default();
+        ctx.repos.accounts.insert(&account).await.unwrap();
+        let user = User::new(account.id.clone());
+        ctx.repos.users.insert(&user).await.unwrap();
+
+        let entry = |start_ts: i64, end_ts: i64, fetched_at: i64| ExternalBusyCacheEntry {
+            user_id: user.id.clone(),
+            provider: IntegrationProvider::Google,
+            ext_calendar_id: "primary".into(),
+            start_ts,
+            end_ts,
+            busy: Vec::new(),
+            fetched_at,
+        };
+        let long = entry(0, 1000, 10);
+        assert!(ctx.repos.external_busy_cache.save(&long, 0).await.is_ok());
+        // A shorter query does not evict the longer one
+        assert!(ctx
+            .repos
+            .external_busy_cache
+            .save(&entry(2000, 2100, 20), 0)
+            .await
+            .is_ok());
+        assert_eq!(
+            find(&ctx, &user, 0, 1000)
+                .await
+                .expect("To find entry")
+                .fetched_at,
+            10
+        );
+        assert_eq!(
+            find(&ctx, &user, 2000, 2100)
+                .await
+                .expect("To find entry")
+                .fetched_at,
+            20
+        );
+
+        // Only one request starts to refresh a stale entry
+        assert!(ctx
+            .repos
+            .external_busy_cache
+            .start_refresh(&long, 30)
+            .await
+            .expect("To start refresh"));
+        assert!(!ctx
+            .repos
+            .external_busy_cache
+            .start_refresh(&long, 31)
+            .await
+            .expect("To start refresh"));
+        assert!(ctx
+            .repos
+            .external_busy_cache
+            .start_refresh(&long, 30 + ExternalBusyCacheEntry::REFRESH_TIMEOUT)
+            .await
+            .expect("To start refresh"));
+
+        // Entries within the timespan of a refetched entry are superseded by it,
+        // and entries which can no longer be served are pruned
+        assert!(ctx
+            .repos
+            .external_busy_cache
+            .save(&entry(0, 1000, 40), 15)
+            .await
+            .is_ok());
+        assert_eq!(
+            find(&ctx, &user, 0, 1000)
+                .await
+                .expect("To find entry")
+                .fetched_at,
+            40
+        );
+        assert!(find(&ctx, &user, 2000, 2100).await.is_some());
+        assert!(ctx
+            .repos
+            .external_busy_cache
+            .save(&entry(0, 3000, 50), 25)
+            .await
+            .is_ok());
+        assert_eq!(
+            find(&ctx, &user, 2000, 2100)
+                .await
+                .expect("To find entry")
+                .fetched_at,
+            50
+        );
+        assert_eq!(
+            find(&ctx, &user, 0, 1000)
+                .await
+                .expect("To find entry")
+                .fetched_at,
+            50
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use super::IExternalBusyCacheRepo;
+use nettu_scheduler_domain::{ExternalBusyCacheEntry, IntegrationProvider, TimeSpan, ID};
+use serde_json::Value;
+use sqlx::{
+    types::{Json, Uuid},
+    FromRow, PgPool,
+};
+use tracing::error;
+
+pub struct PostgresExternalBusyCacheRepo {
+    pool: PgPool,
+}
+
+impl PostgresExternalBusyCacheRepo {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct ExternalBusyCacheEntryRaw {
+    user_uid: Uuid,
+    provider: String,
+    ext_calendar_id: String,
+    start_ts: i64,
+    end_ts: i64,
+    busy: Value,
+    fetched_at: i64,
+}
+
+impl From<ExternalBusyCacheEntryRaw> for ExternalBusyCacheEntry {
+    fn from(e: ExternalBusyCacheEntryRaw) -> Self {
+        Self {
+            user_id: e.user_uid.into(),
+            provider: e.provider.into(),
+            ext_calendar_id: e.ext_calendar_id,
+            start_ts: e.start_ts,
+            end_ts: e.end_ts,
+            busy: serde_json::from_value(e.busy).unwrap_or_default(),
+            fetched_at: e.fetched_at,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl IExternalBusyCacheRepo for PostgresExternalBusyCacheRepo {
+    async fn save(
+        &self,
+        entry: &ExternalBusyCacheEntry,
+        prune_fetched_before: i64,
+    ) -> anyhow::Result<()> {
+        let provider: String = entry.provider.clone().into();
+        let log_error = |e: &sqlx::Error| {
+            error!(
+                "Unable to save external busy cache entry for user: {}, calendar: {}. DB returned error: {:?}",
+                entry.user_id, entry.ext_calendar_id, e
+            );
+        };
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            DELETE FROM external_busy_cache AS c
+            WHERE c.user_uid = $1 AND
+            c.provider = $2 AND
+            c.ext_calendar_id = $3 AND
+            ((c.start_ts >= $4 AND c.end_ts <= $5) OR c.fetched_at < $6)
+            "#,
+        )
+        .bind(entry.user_id.as_ref())
+        .bind(&provider)
+        .bind(&entry.ext_calendar_id)
+        .bind(entry.start_ts)
+        .bind(entry.end_ts)
+        .bind(prune_fetched_before)
+        .execute(&mut tx)
+        .await
+        .inspect_err(log_error)?;
+        sqlx::query(
+            r#"
+            INSERT INTO external_busy_cache(
+                user_uid,
+                provider,
+                ext_calendar_id,
+                start_ts,
+                end_ts,
+                busy,
+                fetched_at
+            )
+            VALUES($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (user_uid, provider, ext_calendar_id, start_ts, end_ts) DO UPDATE SET
+                busy = EXCLUDED.busy,
+                fetched_at = EXCLUDED.fetched_at,
+                refreshing_at = NULL
+            WHERE external_busy_cache.fetched_at <= EXCLUDED.fetched_at
+            "#,
+        )
+        .bind(entry.user_id.as_ref())
+        .bind(&provider)
+        .bind(&entry.ext_calendar_id)
+        .bind(entry.start_ts)
+        .bind(entry.end_ts)
+        .bind(Json(&entry.busy))
+        .bind(entry.fetched_at)
+        .execute(&mut tx)
+        .await
+        .inspect_err(log_error)?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn find(
+        &self,
+        user_id: &ID,
+        provider: &IntegrationProvider,
+        ext_calendar_id: &str,
+        timespan: &TimeSpan,
+    ) -> Option<ExternalBusyCacheEntry> {
+        let provider: String = provider.clone().into();
+        let res: Option<ExternalBusyCacheEntryRaw> = sqlx::query_as(
+            r#"
+            SELECT
+                c.user_uid,
+                c.provider,
+                c.ext_calendar_id,
+                c.start_ts,
+                c.end_ts,
+                c.busy,
+                c.fetched_at
+            FROM external_busy_cache AS c
+            WHERE c.user_uid = $1 AND
+            c.provider = $2 AND
+            c.ext_calendar_id = $3 AND
+            c.start_ts <= $4 AND c.end_ts >= $5
+            ORDER BY c.fetched_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(user_id.as_ref())
+        .bind(provider)
+        .bind(ext_calendar_id)
+        .bind(timespan.start())
+        .bind(timespan.end())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Find external busy cache entry for user: {}, calendar: {} failed. DB returned error: {:?}",
+                user_id, ext_calendar_id, e
+            );
+            e
+        })
+        .ok()?;
+
+        res.map(|entry| entry.into())
+    }
+
+    async fn start_refresh(
+        &self,
+        entry: &ExternalBusyCacheEntry,
+        timestamp: i64,
+    ) -> anyhow::Result<bool> {
+        let provider: String = entry.provider.clone().into();
+        let started = sqlx::query(
+            r#"
+            UPDATE external_busy_cache AS c
+            SET refreshing_at = $7
+            WHERE c.user_uid = $1 AND
+            c.provider = $2 AND
+            c.ext_calendar_id = $3 AND
+            c.start_ts = $4 AND
+            c.end_ts = $5 AND
+            c.fetched_at = $6 AND
+            (c.refreshing_at IS NULL OR c.refreshing_at <= $8)
+            "#,
+        )
+        .bind(entry.user_id.as_ref())
+        .bind(provider)
+        .bind(&entry.ext_calendar_id)
+        .bind(entry.start_ts)
+        .bind(entry.end_ts)
+        .bind(entry.fetched_at)
+        .bind(timestamp)
+        .bind(timestamp - ExternalBusyCacheEntry::REFRESH_TIMEOUT)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Unable to start refresh of external busy cache entry for user: {}, calendar: {}. DB returned error: {:?}",
+                entry.user_id, entry.ext_calendar_id, e
+            );
+            e
+        })?;
+
+        // Only the request that marked the entry refetches it
+        Ok(started.rows_affected() > 0)
+    }
+
+    async fn invalidate(
+        &self,
+        user_id: &ID,
+        provider: &IntegrationProvider,
+        ext_calendar_id: &str,
+    ) -> anyhow::Result<()> {
+        let provider: String = provider.clone().into();
+        sqlx::query!(
+            r#"
+            DELETE FROM external_busy_cache AS c
+            WHERE c.user_uid = $1 AND
+            c.provider = $2 AND
+            c.ext_calendar_id = $3
+            "#,
+            user_id.as_ref(),
+            provider,
+            ext_calendar_id,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Unable to invalidate external busy cache entry for user: {}, calendar: {}. DB returned error: {:?}",
+                user_id, ext_calendar_id, e
+            );
+            e
+        })?;
+
+        Ok(())
+    }
+}
@@ -1,8 +1,11 @@
 mod account;
 mod account_integrations;
+mod booking;
+mod booking_hold;
 mod calendar;
 mod calendar_synced;
 mod event;
+mod external_busy_cache;
 // mod kv;
 mod reservation;
 mod schedule;
@@ -12,9 +15,13 @@ mod service_user_busy_calendars;
 mod shared;
 pub(crate) mod user;
 mod user_integrations;
+mod waitlist;
 
 use account::{IAccountRepo, PostgresAccountRepo};
 use account_integrations::{IAccountIntegrationRepo, PostgresAccountIntegrationRepo};
+pub use booking::{BookingCommit, BookingCommitError, BookingRescheduleCommit};
+use booking::{IBookingRepo, PostgresBookingRepo};
+use booking_hold::{IBookingHoldRepo, PostgresBookingHoldRepo};
 use calendar::{ICalendarRepo, PostgresCalendarRepo};
 use calendar_synced::{ICalendarSyncedRepo, PostgresCalendarSyncedRepo};
 use event::{
@@ -22,6 +29,7 @@ use event::{
     PostgresEventReminderGenerationJobsRepo, PostgresEventRepo, PostgresEventSyncedRepo,
     PostgresReminderRepo,
 };
+use external_busy_cache::{IExternalBusyCacheRepo, PostgresExternalBusyCacheRepo};
 use reservation::{IReservationRepo, PostgresReservationRepo};
 use schedule::{IScheduleRepo, PostgresScheduleRepo};
 use service::{IServiceRepo, PostgresServiceRepo};
@@ -36,16 +44,20 @@ use std::sync::Arc;
 use tracing::info;
 use user::{IUserRepo, PostgresUserRepo};
 use user_integrations::{IUserIntegrationRepo, PostgresUserIntegrationRepo};
+use waitlist::{IWaitlistRepo, PostgresWaitlistRepo};
 
 #[derive(Clone)]
 pub struct Repos {
     pub accounts: Arc<dyn IAccountRepo>,
     pub account_integrations: Arc<dyn IAccountIntegrationRepo>,
+    pub bookings: Arc<dyn IBookingRepo>,
+    pub booking_holds: Arc<dyn IBookingHoldRepo>,
     pub calendars: Arc<dyn ICalendarRepo>,
     pub calendar_synced: Arc<dyn ICalendarSyncedRepo>,
     pub events: Arc<dyn IEventRepo>,
     pub event_reminders_generation_jobs: Arc<dyn IEventRemindersGenerationJobsRepo>,
     pub event_synced: Arc<dyn IEventSyncedRepo>,
+    pub external_busy_cache: Arc<dyn IExternalBusyCacheRepo>,
     pub schedules: Arc<dyn IScheduleRepo>,
     pub reminders: Arc<dyn IReminderRepo>,
     pub reservations: Arc<dyn IReservationRepo>,
@@ -54,6 +66,7 @@ pub struct Repos {
     pub service_user_busy_calendars: Arc<dyn IServiceUserBusyCalendarRepo>,
     pub users: Arc<dyn IUserRepo>,
     pub user_integrations: Arc<dyn IUserIntegrationRepo>,
+    pub waitlist: Arc<dyn IWaitlistRepo>,
 }
 
 impl Repos {
@@ -71,10 +84,13 @@ impl Repos {
         Ok(Self {
             accounts: Arc::new(PostgresAccountRepo::new(pool.clone())),
             account_integrations: Arc::new(PostgresAccountIntegrationRepo::new(pool.clone())),
+            bookings: Arc::new(PostgresBookingRepo::new(pool.clone())),
+            booking_holds: Arc::new(PostgresBookingHoldRepo::new(pool.clone())),
             calendars: Arc::new(PostgresCalendarRepo::new(pool.clone())),
             calendar_synced: Arc::new(PostgresCalendarSyncedRepo::new(pool.clone())),
             events: Arc::new(PostgresEventRepo::new(pool.clone())),
             event_synced: Arc::new(PostgresEventSyncedRepo::new(pool.clone())),
+            external_busy_cache: Arc::new(PostgresExternalBusyCacheRepo::new(pool.clone())),
             users: Arc::new(PostgresUserRepo::new(pool.clone())),
             user_integrations: Arc::new(PostgresUserIntegrationRepo::new(pool.clone())),
             services: Arc::new(PostgresServiceRepo::new(pool.clone())),
@@ -85,6 +101,7 @@ impl Repos {
             schedules: Arc::new(PostgresScheduleRepo::new(pool.clone())),
             reminders: Arc::new(PostgresReminderRepo::new(pool.clone())),
             reservations: Arc::new(PostgresReservationRepo::new(pool.clone())),
+            waitlist: Arc::new(PostgresWaitlistRepo::new(pool.clone())),
             event_reminders_generation_jobs: Arc::new(
                 PostgresEventReminderGenerationJobsRepo::new(pool),
             ),
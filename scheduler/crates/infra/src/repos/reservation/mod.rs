@@ -1,19 +1,44 @@
 mod postgres;
 
-use nettu_scheduler_domain::ID;
+use super::booking::BookingCommitError;
+use nettu_scheduler_domain::{Reservation, TimeSpan, ID};
 pub use postgres::PostgresReservationRepo;
+pub(crate) use postgres::{decrement_reservation, increment_reservation};
 
 #[async_trait::async_trait]
 pub trait IReservationRepo: Send + Sync {
     async fn increment(&self, service_id: &ID, timestamp: i64) -> anyhow::Result<()>;
-    async fn decrement(&self, service_id: &ID, timestamp: i64) -> anyhow::Result<()>;
+    /// Reserves a seat of the booking slot of a group `Service` if the hosts
+    /// and a seat are still free. It is serialized with concurrent `Booking`s
+    /// and `BookingHold`s for the same hosts and time, and seats held by
+    /// `BookingHold`s that have not expired at `timestamp` are not free.
+    /// Returns the number of seats that were reserved before.
+    async fn reserve_seat(
+        &self,
+        service_id: &ID,
+        host_user_ids: &[ID],
+        timespan: &TimeSpan,
+        seats: usize,
+        timestamp: i64,
+    ) -> Result<usize, BookingCommitError>;
+    /// Gives back a reserved seat of the booking slot of a group `Service`,
+    /// serialized with concurrent claims of its seats. Returns false when no
+    /// seat was reserved.
+    async fn decrement(&self, service_id: &ID, timestamp: i64) -> anyhow::Result<bool>;
     async fn count(&self, service_id: &ID, timestamp: i64) -> anyhow::Result<usize>;
+    /// Finds the `Reservation`s of a `Service` with a timestamp within the given timespan
+    async fn find_by_timespan(
+        &self,
+        service_id: &ID,
+        start_ts: i64,
+        end_ts: i64,
+    ) -> anyhow::Result<Vec<Reservation>>;
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::setup_context;
-    use nettu_scheduler_domain::{Account, Service};
+    use crate::{repos::booking::BookingCommitError, setup_context};
+    use nettu_scheduler_domain::{Account, Service, TimeSpan};
 
     #[tokio::test]
     async fn test_reservations_repo() {
@@ -78,6 +103,17 @@ mod tests {
             .await
             .expect("To get reservations count");
         assert_eq!(count, 1);
+
+        let reservations = ctx
+            .repos
+            .reservations
+            .find_by_timespan(&service.id, 1, 3)
+            .await
+            .expect("To find reservations");
+        assert_eq!(reservations.len(), 2);
+        assert_eq!(reservations[0].timestamp, 1);
+        assert_eq!(reservations[1].timestamp, 2);
+        assert!(reservations.iter().all(|r| r.count == 1));
     }
 
     #[tokio::test]
@@ -137,7 +173,7 @@ mod tests {
             .reservations
             .decrement(&service.id, timestamp)
             .await
-            .is_ok());
+            .expect("To decrement reservation"));
 
         // Now there should only be three
         let count = ctx
@@ -148,5 +184,71 @@ mod tests {
             .expect("To get reservations count");
 
         assert_eq!(count, 3);
+
+        // The count never drops below zero
+        for released in [true, true, true, false].iter() {
+            let res = ctx
+                .repos
+                .reservations
+                .decrement(&service.id, timestamp)
+                .await
+                .expect("To decrement reservation");
+            assert_eq!(res, *released);
+        }
+        let count = ctx
+            .repos
+            .reservations
+            .count(&service.id, timestamp)
+            .await
+            .expect("To get reservations count");
+        assert_eq!(count, 0);
+        assert!(!ctx
+            .repos
+            .reservations
+            .decrement(&service.id, timestamp + 1)
+            .await
+            .expect("To decrement reservation"));
+    }
+
+    #[tokio::test]
+    async fn test_reserve_seat() {
+        let ctx = setup_context().await;
+
+        let account = Account::new();
+        ctx.repos
+            .accounts
+            .insert(&account)
+            .await
+            .expect("To insert account");
+        let service = Service::new(account.id.clone());
+        ctx.repos
+            .services
+            .insert(&service)
+            .await
+            .expect("To insert service");
+
+        let timespan = TimeSpan::new(1000 * 60 * 60, 1000 * 60 * 90);
+        for reserved in 0..2 {
+            let res = ctx
+                .repos
+                .reservations
+                .reserve_seat(&service.id, &[], &timespan, 2, 0)
+                .await;
+            assert_eq!(res, Ok(reserved));
+        }
+        // The slot is full
+        let res = ctx
+            .repos
+            .reservations
+            .reserve_seat(&service.id, &[], &timespan, 2, 0)
+            .await;
+        assert_eq!(res, Err(BookingCommitError::Conflict));
+        let count = ctx
+            .repos
+            .reservations
+            .count(&service.id, timespan.start())
+            .await
+            .expect("To get reservations count");
+        assert_eq!(count, 2);
     }
 }
@@ -1,6 +1,7 @@
 use super::IReservationRepo;
-use nettu_scheduler_domain::ID;
-use sqlx::{types::Uuid, FromRow, PgPool};
+use crate::repos::booking::{claim_slot, lock, lock_keys, BookingCommitError, SlotClaim};
+use nettu_scheduler_domain::{Reservation, TimeSpan, ID};
+use sqlx::{types::Uuid, Executor, FromRow, PgPool, Postgres};
 use tracing::error;
 
 pub struct PostgresReservationRepo {
@@ -20,76 +21,196 @@ struct ReservationRaw {
     service_uid: Uuid,
 }
 
+impl From<ReservationRaw> for Reservation {
+    fn from(e: ReservationRaw) -> Self {
+        Self {
+            service_id: e.service_uid.into(),
+            timestamp: e.timestamp,
+            count: e.count as usize,
+        }
+    }
+}
+
+/// Increments the reservation count with the given executor, which allows it
+/// to be part of a transaction
+pub(crate) async fn increment_reservation<'c, E>(
+    service_id: &ID,
+    timestamp: i64,
+    executor: E,
+) -> anyhow::Result<()>
+where
+    E: Executor<'c, Database = Postgres>,
+{
+    sqlx::query!(
+        r#"
+        INSERT INTO service_reservations(service_uid, timestamp)
+        VALUES($1, $2)
+        ON CONFLICT(service_uid, timestamp) DO UPDATE SET count = service_reservations.count + 1
+        "#,
+        service_id.as_ref(),
+        timestamp
+    )
+    .execute(executor)
+    .await
+    .map_err(|err| {
+        error!(
+            "Unable to increment reservation count for service id: {} at timestamp {}. DB returned error: {:?}",
+            service_id, timestamp, err
+        );
+        err
+    })?;
+
+    Ok(())
+}
+
+/// Decrements the reservation count with the given executor, which allows it
+/// to be part of a transaction. Returns the count before it was decremented,
+/// which is 0 when there was no reservation to decrement.
+pub(crate) async fn decrement_reservation<'c, E>(
+    service_id: &ID,
+    timestamp: i64,
+    executor: E,
+) -> anyhow::Result<usize>
+where
+    E: Executor<'c, Database = Postgres>,
+{
+    let count = sqlx::query_scalar!(
+        r#"
+        UPDATE service_reservations as r
+        SET count = count - 1
+        WHERE r.service_uid = $1 AND r.timestamp = $2 AND r.count > 0
+        RETURNING r.count + 1 AS "count!"
+        "#,
+        service_id.as_ref(),
+        timestamp,
+    )
+    .fetch_optional(executor)
+    .await
+    .map_err(|err| {
+        error!(
+            "Unable to decrement reservation count for service id: {} at timestamp {}. DB returned error: {:?}",
+            service_id, timestamp, err
+        );
+        err
+    })?;
+
+    Ok(count.unwrap_or(0) as usize)
+}
+
 #[async_trait::async_trait]
 impl IReservationRepo for PostgresReservationRepo {
     async fn increment(&self, service_id: &ID, timestamp: i64) -> anyhow::Result<()> {
-        sqlx::query!(
-            r#"
-            INSERT INTO service_reservations(service_uid, timestamp)
-            VALUES($1, $2)
-            ON CONFLICT(service_uid, timestamp) DO UPDATE SET count = service_reservations.count + 1
-            "#,
-            service_id.as_ref(),
-            timestamp
-        )
-        .execute(&self.pool)
-        .await
-        .map_err(|err| {
+        increment_reservation(service_id, timestamp, &self.pool).await
+    }
+
+    async fn reserve_seat(
+        &self,
+        service_id: &ID,
+        host_user_ids: &[ID],
+        timespan: &TimeSpan,
+        seats: usize,
+        timestamp: i64,
+    ) -> Result<usize, BookingCommitError> {
+        let storage_error = |e: sqlx::Error| {
             error!(
-                "Unable to increment reservation count for service id: {} at timestamp {}. DB returned error: {:?}",
-                service_id, timestamp, err
+                "Unable to reserve seat for service id: {} at timestamp {}. DB returned error: {:?}",
+                service_id,
+                timespan.start(),
+                e
             );
-            err
-        })?;
+            BookingCommitError::StorageError
+        };
+
+        let mut tx = self.pool.begin().await.map_err(storage_error)?;
+        let claim = SlotClaim {
+            service_id,
+            host_user_ids,
+            resource_id: None,
+            start_ts: timespan.start(),
+            end_ts: timespan.end(),
+            seats: Some(seats),
+            timestamp,
+            hold_id: None,
+            booking_id: None,
+        };
+        let reserved = claim_slot(&mut tx, &claim).await?;
+        increment_reservation(service_id, timespan.start(), &mut tx)
+            .await
+            .map_err(|_| BookingCommitError::StorageError)?;
+        tx.commit().await.map_err(storage_error)?;
 
-        Ok(())
+        Ok(reserved)
     }
 
-    async fn decrement(&self, service_id: &ID, timestamp: i64) -> anyhow::Result<()> {
-        sqlx::query_as!(
+    async fn decrement(&self, service_id: &ID, timestamp: i64) -> anyhow::Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        // Every claim of a seat of the booking slot takes the lock of its start
+        lock(
+            &mut tx,
+            lock_keys(&[*service_id.as_ref()], timestamp, timestamp + 1),
+        )
+        .await
+        .map_err(|e| anyhow::Error::msg(format!("{:?}", e)))?;
+        let reserved = decrement_reservation(service_id, timestamp, &mut tx).await?;
+        tx.commit().await?;
+
+        Ok(reserved > 0)
+    }
+
+    async fn count(&self, service_id: &ID, timestamp: i64) -> anyhow::Result<usize> {
+        let reservation: Option<ReservationRaw> = sqlx::query_as!(
             ReservationRaw,
             r#"
-            UPDATE service_reservations as r
-            SET count = count - 1
-            WHERE r.service_uid = $1 AND r.timestamp = $2
+            SELECT * FROM service_reservations as r
+            WHERE r.service_uid = $1 AND
+            r.timestamp = $2
             "#,
             service_id.as_ref(),
             timestamp,
         )
-        .execute(&self.pool)
+        .fetch_optional(&self.pool)
         .await
         .map_err(|err| {
             error!(
-                "Unable to decrement reservation count for service id: {} at timestamp {}. DB returned error: {:?}",
+                "Unable to retrieve reservation count for service id: {} at timestamp {}. DB returned error: {:?}",
                 service_id, timestamp, err
             );
             err
         })?;
-        Ok(())
+
+        let count = reservation.map(|r| r.count).unwrap_or(0);
+        Ok(count as usize)
     }
 
-    async fn count(&self, service_id: &ID, timestamp: i64) -> anyhow::Result<usize> {
-        let reservation: Option<ReservationRaw> = sqlx::query_as!(
+    async fn find_by_timespan(
+        &self,
+        service_id: &ID,
+        start_ts: i64,
+        end_ts: i64,
+    ) -> anyhow::Result<Vec<Reservation>> {
+        let reservations: Vec<ReservationRaw> = sqlx::query_as!(
             ReservationRaw,
             r#"
             SELECT * FROM service_reservations as r
             WHERE r.service_uid = $1 AND
-            r.timestamp = $2
+            r.timestamp >= $2 AND
+            r.timestamp < $3
+            ORDER BY r.timestamp
             "#,
             service_id.as_ref(),
-            timestamp,
+            start_ts,
+            end_ts,
         )
-        .fetch_optional(&self.pool)
+        .fetch_all(&self.pool)
         .await
         .map_err(|err| {
             error!(
-                "Unable to retrieve reservation count for service id: {} at timestamp {}. DB returned error: {:?}",
-                service_id, timestamp, err
+                "Unable to retrieve reservations for service id: {} between {} and {}. DB returned error: {:?}",
+                service_id, start_ts, end_ts, err
             );
             err
         })?;
 
-        let count = reservation.map(|r| r.count).unwrap_or(0);
-        Ok(count as usize)
+        Ok(reservations.into_iter().map(|r| r.into()).collect())
     }
 }
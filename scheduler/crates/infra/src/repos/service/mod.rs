@@ -9,6 +9,7 @@ pub trait IServiceRepo: Send + Sync {
     async fn insert(&self, service: &Service) -> anyhow::Result<()>;
     async fn save(&self, service: &Service) -> anyhow::Result<()>;
     async fn find(&self, service_id: &ID) -> Option<Service>;
+    async fn find_many(&self, service_ids: &[ID]) -> Vec<Service>;
     async fn find_with_users(&self, service_id: &ID) -> Option<ServiceWithUsers>;
     async fn delete(&self, service_id: &ID) -> anyhow::Result<()>;
     async fn find_by_metadata(&self, query: MetadataFindQuery) -> Vec<Service>;
@@ -41,6 +42,15 @@ mod tests {
             .await
             .expect("To get service");
 
+        // Get many by ids
+        let services = ctx
+            .repos
+            .services
+            .find_many(&[service.id.clone(), Default::default()])
+            .await;
+        assert_eq!(services.len(), 1);
+        assert_eq!(services[0].id, service.id);
+
         let user = User::new(account.id.clone());
         ctx.repos.users.insert(&user).await.unwrap();
 
@@ -24,6 +24,7 @@ struct ServiceRaw {
     account_uid: Uuid,
     multi_person: Value,
     metadata: Value,
+    booking_options: Value,
 }
 
 #[derive(Debug, FromRow)]
@@ -31,8 +32,10 @@ struct ServiceWithUsersRaw {
     service_uid: Uuid,
     account_uid: Uuid,
     users: Option<Value>,
+    resources: Option<Value>,
     multi_person: Value,
     metadata: Value,
+    booking_options: Value,
 }
 
 impl From<ServiceRaw> for Service {
@@ -41,6 +44,7 @@ impl From<ServiceRaw> for Service {
             id: e.service_uid.into(),
             account_id: e.account_uid.into(),
             multi_person: serde_json::from_value(e.multi_person).unwrap(),
+            booking_options: serde_json::from_value(e.booking_options).unwrap(),
             metadata: serde_json::from_value(e.metadata).unwrap(),
         }
     }
@@ -52,11 +56,17 @@ impl From<ServiceWithUsersRaw> for ServiceWithUsers {
             Some(json) => serde_json::from_value(json).unwrap_or_default(),
             None => Vec::new(),
         };
+        let resources: Vec<ServiceUserRaw> = match e.resources {
+            Some(json) => serde_json::from_value(json).unwrap_or_default(),
+            None => Vec::new(),
+        };
         Self {
             id: e.service_uid.into(),
             account_id: e.account_uid.into(),
             users: users.into_iter().map(|u| u.into()).collect(),
+            resources: resources.into_iter().map(|u| u.into()).collect(),
             multi_person: serde_json::from_value(e.multi_person).unwrap(),
+            booking_options: serde_json::from_value(e.booking_options).unwrap(),
             metadata: serde_json::from_value(e.metadata).unwrap(),
         }
     }
@@ -67,13 +77,14 @@ impl IServiceRepo for PostgresServiceRepo {
     async fn insert(&self, service: &Service) -> anyhow::Result<()> {
         sqlx::query!(
             r#"
-            INSERT INTO services(service_uid, account_uid, multi_person, metadata)
-            VALUES($1, $2, $3, $4)
+            INSERT INTO services(service_uid, account_uid, multi_person, metadata, booking_options)
+            VALUES($1, $2, $3, $4, $5)
             "#,
             service.id.as_ref(),
             service.account_id.as_ref(),
             Json(&service.multi_person) as _,
             Json(&service.metadata) as _,
+            Json(&service.booking_options) as _,
         )
         .execute(&self.pool)
         .await
@@ -93,12 +104,14 @@ impl IServiceRepo for PostgresServiceRepo {
             r#"
             UPDATE services SET
                 multi_person = $2,
-                metadata = $3
+                metadata = $3,
+                booking_options = $4
             WHERE service_uid = $1
             "#,
             service.id.as_ref(),
             Json(&service.multi_person) as _,
             Json(&service.metadata) as _,
+            Json(&service.booking_options) as _,
         )
         .execute(&self.pool)
         .await
@@ -136,12 +149,45 @@ impl IServiceRepo for PostgresServiceRepo {
         res.map(|service| service.into())
     }
 
+    async fn find_many(&self, service_ids: &[ID]) -> Vec<Service> {
+        let service_ids = service_ids
+            .iter()
+            .map(|id| *id.as_ref())
+            .collect::<Vec<_>>();
+
+        let services: Vec<ServiceRaw> = sqlx::query_as!(
+            ServiceRaw,
+            r#"
+            SELECT * FROM services AS s
+            WHERE s.service_uid = ANY($1)
+            "#,
+            &service_ids
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Find services with service_ids: {:?} failed. DB returned error: {:?}",
+                service_ids, e
+            );
+            e
+        })
+        .unwrap_or_default();
+
+        services.into_iter().map(|s| s.into()).collect()
+    }
+
     async fn find_with_users(&self, service_id: &ID) -> Option<ServiceWithUsers> {
         let res: Option<ServiceWithUsersRaw> = sqlx::query_as(
             r#"
-            SELECT s.*, jsonb_agg((su.*)) AS users FROM services AS s
+            SELECT s.*,
+            jsonb_agg((su.*)) FILTER (WHERE u.resource_kind IS NULL) AS users,
+            jsonb_agg((su.*)) FILTER (WHERE u.resource_kind IS NOT NULL) AS resources
+            FROM services AS s
             LEFT JOIN service_users AS su
             ON su.service_uid = s.service_uid
+            LEFT JOIN users AS u
+            ON u.user_uid = su.user_uid
             WHERE s.service_uid = $1
             GROUP BY s.service_uid
             "#,
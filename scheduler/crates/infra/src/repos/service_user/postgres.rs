@@ -1,7 +1,11 @@
 use super::IServiceUserRepo;
 use nettu_scheduler_domain::{ServiceResource, TimePlan, ID};
 use serde::Deserialize;
-use sqlx::{types::Uuid, FromRow, PgPool};
+use serde_json::Value;
+use sqlx::{
+    types::{Json, Uuid},
+    FromRow, PgPool,
+};
 use tracing::error;
 
 pub struct PostgresServiceUserRepo {
@@ -24,6 +28,12 @@ pub struct ServiceUserRaw {
     buffer_before: i64,
     closest_booking_time: i64,
     furthest_booking_time: Option<i64>,
+    max_bookings_per_day: Option<i64>,
+    max_booked_minutes_per_week: Option<i64>,
+    #[serde(default)]
+    preferred_times: Value,
+    weight: i64,
+    priority: i64,
 }
 
 impl From<ServiceUserRaw> for ServiceResource {
@@ -44,6 +54,11 @@ impl From<ServiceUserRaw> for ServiceResource {
             buffer_before: e.buffer_before,
             closest_booking_time: e.closest_booking_time,
             furthest_booking_time: e.furthest_booking_time,
+            max_bookings_per_day: e.max_bookings_per_day,
+            max_booked_minutes_per_week: e.max_booked_minutes_per_week,
+            preferred_times: serde_json::from_value(e.preferred_times).unwrap_or_default(),
+            weight: e.weight,
+            priority: e.priority,
         }
     }
 }
@@ -59,8 +74,8 @@ impl IServiceUserRepo for PostgresServiceUserRepo {
 
         sqlx::query!(
             r#"
-            INSERT INTO service_users(service_uid, user_uid, available_calendar_uid, available_schedule_uid, buffer_after, buffer_before, closest_booking_time, furthest_booking_time)
-            VALUES($1, $2, $3, $4, $5, $6, $7, $8)
+            INSERT INTO service_users(service_uid, user_uid, available_calendar_uid, available_schedule_uid, buffer_after, buffer_before, closest_booking_time, furthest_booking_time, max_bookings_per_day, max_booked_minutes_per_week, preferred_times, weight, priority)
+            VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
             "#,
             user.service_id.as_ref(),
             user.user_id.as_ref(),
@@ -70,6 +85,11 @@ impl IServiceUserRepo for PostgresServiceUserRepo {
             user.buffer_before,
             user.closest_booking_time,
             user.furthest_booking_time,
+            user.max_bookings_per_day,
+            user.max_booked_minutes_per_week,
+            Json(&user.preferred_times) as _,
+            user.weight,
+            user.priority,
         )
         .execute(&self.pool)
         .await
@@ -99,7 +119,12 @@ impl IServiceUserRepo for PostgresServiceUserRepo {
                 buffer_after = $5,
                 buffer_before = $6,
                 closest_booking_time = $7,
-                furthest_booking_time = $8
+                furthest_booking_time = $8,
+                max_bookings_per_day = $9,
+                max_booked_minutes_per_week = $10,
+                preferred_times = $11,
+                weight = $12,
+                priority = $13
             WHERE service_uid = $1 AND user_uid = $2
             "#,
             user.service_id.as_ref(),
@@ -110,6 +135,11 @@ impl IServiceUserRepo for PostgresServiceUserRepo {
             user.buffer_before,
             user.closest_booking_time,
             user.furthest_booking_time,
+            user.max_bookings_per_day,
+            user.max_booked_minutes_per_week,
+            Json(&user.preferred_times) as _,
+            user.weight,
+            user.priority,
         )
         .execute(&self.pool)
         .await
@@ -23,6 +23,7 @@ struct UserRaw {
     user_uid: Uuid,
     account_uid: Uuid,
     metadata: Value,
+    resource_kind: Option<String>,
 }
 
 impl From<UserRaw> for User {
@@ -31,6 +32,7 @@ impl From<UserRaw> for User {
             id: e.user_uid.into(),
             account_id: e.account_uid.into(),
             metadata: serde_json::from_value(e.metadata).unwrap(),
+            resource_kind: e.resource_kind,
         }
     }
 }
@@ -40,12 +42,13 @@ impl IUserRepo for PostgresUserRepo {
     async fn insert(&self, user: &User) -> anyhow::Result<()> {
         sqlx::query!(
             r#"
-            INSERT INTO users(user_uid, account_uid, metadata)
-            VALUES($1, $2, $3)
+            INSERT INTO users(user_uid, account_uid, metadata, resource_kind)
+            VALUES($1, $2, $3, $4)
             "#,
             user.id.as_ref(),
             user.account_id.as_ref(),
             Json(&user.metadata) as _,
+            user.resource_kind,
         )
         .execute(&self.pool)
         .await
@@ -65,12 +68,14 @@ impl IUserRepo for PostgresUserRepo {
             r#"
             UPDATE users
             SET account_uid = $2,
-            metadata = $3
+            metadata = $3,
+            resource_kind = $4
             WHERE user_uid = $1
             "#,
             user.id.as_ref(),
             user.account_id.as_ref(),
             Json(&user.metadata) as _,
+            user.resource_kind,
         )
         .execute(&self.pool)
         .await
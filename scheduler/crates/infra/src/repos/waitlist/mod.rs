@@ -0,0 +1,166 @@
+mod postgres;
+
+use super::booking::BookingCommitError;
+use nettu_scheduler_domain::{BookingHold, WaitlistEntry, ID};
+pub use postgres::PostgresWaitlistRepo;
+
+#[async_trait::async_trait]
+pub trait IWaitlistRepo: Send + Sync {
+    async fn insert(&self, entry: &WaitlistEntry) -> anyhow::Result<()>;
+    async fn save(&self, entry: &WaitlistEntry) -> anyhow::Result<()>;
+    async fn find(&self, entry_id: &ID) -> Option<WaitlistEntry>;
+    /// Finds the entries waiting for the booking slot of a `Service` starting
+    /// at `start_ts` in the order they joined the waitlist
+    async fn find_by_slot(&self, service_id: &ID, start_ts: i64) -> Vec<WaitlistEntry>;
+    /// Finds the first entry waiting for the booking slot that has not been
+    /// offered a seat yet
+    async fn find_next(&self, service_id: &ID, start_ts: i64) -> Option<WaitlistEntry>;
+    /// Claims the first entry waiting for the booking slot that has not been
+    /// offered a seat yet and holds a seat of the hosts for it in one transaction.
+    /// Concurrent offers for the same booking slot skip the entries claimed by
+    /// each other, so an entry is never offered more than one seat.
+    /// Returns `None` when nobody is waiting.
+    async fn offer_next(
+        &self,
+        service_id: &ID,
+        start_ts: i64,
+        host_user_ids: Vec<ID>,
+        seats: usize,
+        timestamp: i64,
+    ) -> Result<Option<(WaitlistEntry, BookingHold)>, BookingCommitError>;
+    async fn find_by_hold(&self, hold_id: &ID) -> Option<WaitlistEntry>;
+    /// Finds the entries whose offered seat has expired at the given timestamp
+    async fn find_expired_offers(&self, timestamp: i64) -> Vec<WaitlistEntry>;
+    async fn delete(&self, entry_id: &ID) -> anyhow::Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::setup_context;
+    use nettu_scheduler_domain::{Account, Service, WaitlistEntry};
+
+    #[tokio::test]
+    async fn crud() {
+        let ctx = setup_context().await;
+        let account = Account::default();
+        ctx.repos.accounts.insert(&account).await.unwrap();
+        let service = Service::new(account.id.clone());
+        ctx.repos.services.insert(&service).await.unwrap();
+
+        let entry = |created: i64| WaitlistEntry {
+            id: Default::default(),
+            account_id: account.id.clone(),
+            service_id: service.id.clone(),
+            start_ts: 1000 * 60 * 60,
+            duration: 1000 * 60 * 30,
+            invitee: Default::default(),
+            created,
+            hold_id: None,
+            offer_expires_at: None,
+            metadata: Default::default(),
+        };
+        let mut first = entry(1);
+        let second = entry(2);
+        assert!(ctx.repos.waitlist.insert(&second).await.is_ok());
+        assert!(ctx.repos.waitlist.insert(&first).await.is_ok());
+
+        let entries = ctx
+            .repos
+            .waitlist
+            .find_by_slot(&service.id, first.start_ts)
+            .await;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id, first.id);
+        assert_eq!(entries[1].id, second.id);
+        let next = ctx
+            .repos
+            .waitlist
+            .find_next(&service.id, first.start_ts)
+            .await
+            .expect("To find next entry");
+        assert_eq!(next.id, first.id);
+
+        // Offered entries are skipped
+        let hold = first.offer(Vec::new(), 10);
+        assert!(ctx.repos.waitlist.save(&first).await.is_ok());
+        let next = ctx
+            .repos
+            .waitlist
+            .find_next(&service.id, first.start_ts)
+            .await
+            .expect("To find next entry");
+        assert_eq!(next.id, second.id);
+        let offered = ctx
+            .repos
+            .waitlist
+            .find_by_hold(&hold.id)
+            .await
+            .expect("To find offered entry");
+        assert_eq!(offered.id, first.id);
+        assert_eq!(offered.offer_expires_at, Some(hold.expires_at));
+
+        let expired = ctx
+            .repos
+            .waitlist
+            .find_expired_offers(hold.expires_at - 1)
+            .await;
+        assert!(expired.iter().all(|e| e.id != first.id));
+        let expired = ctx
+            .repos
+            .waitlist
+            .find_expired_offers(hold.expires_at)
+            .await;
+        assert!(expired.iter().any(|e| e.id == first.id));
+        assert!(expired.iter().all(|e| e.id != second.id));
+
+        assert!(ctx.repos.waitlist.delete(&first.id).await.is_ok());
+        assert!(ctx.repos.waitlist.find(&first.id).await.is_none());
+        assert!(ctx.repos.waitlist.find(&second.id).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn offer_next_offers_entry_once() {
+        let ctx = setup_context().await;
+        let account = Account::default();
+        ctx.repos.accounts.insert(&account).await.unwrap();
+        let service = Service::new(account.id.clone());
+        ctx.repos.services.insert(&service).await.unwrap();
+
+        let entry = WaitlistEntry {
+            id: Default::default(),
+            account_id: account.id.clone(),
+            service_id: service.id.clone(),
+            start_ts: 1000 * 60 * 60,
+            duration: 1000 * 60 * 30,
+            invitee: Default::default(),
+            created: 1,
+            hold_id: None,
+            offer_expires_at: None,
+            metadata: Default::default(),
+        };
+        assert!(ctx.repos.waitlist.insert(&entry).await.is_ok());
+
+        // Two seats are released at the same time
+        let (first, second) = tokio::join!(
+            ctx.repos
+                .waitlist
+                .offer_next(&service.id, entry.start_ts, Vec::new(), 2, 10),
+            ctx.repos
+                .waitlist
+                .offer_next(&service.id, entry.start_ts, Vec::new(), 2, 10),
+        );
+        let offers = [first.expect("To offer"), second.expect("To offer")];
+        let offers = offers.iter().flatten().collect::<Vec<_>>();
+        assert_eq!(offers.len(), 1);
+        let (offered, hold) = offers[0];
+        assert_eq!(offered.id, entry.id);
+        assert!(ctx.repos.booking_holds.find(&hold.id).await.is_some());
+        let res = ctx
+            .repos
+            .waitlist
+            .find(&entry.id)
+            .await
+            .expect("To find entry");
+        assert_eq!(res.hold_id, Some(hold.id.clone()));
+    }
+}
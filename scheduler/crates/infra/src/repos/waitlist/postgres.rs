@@ -0,0 +1,333 @@
+use super::IWaitlistRepo;
+use crate::repos::{booking::BookingCommitError, booking_hold::commit_hold};
+use nettu_scheduler_domain::{BookingHold, WaitlistEntry, ID};
+use serde_json::Value;
+use sqlx::{
+    types::{Json, Uuid},
+    FromRow, PgPool,
+};
+use tracing::error;
+
+pub struct PostgresWaitlistRepo {
+    pool: PgPool,
+}
+
+impl PostgresWaitlistRepo {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct WaitlistEntryRaw {
+    entry_uid: Uuid,
+    account_uid: Uuid,
+    service_uid: Uuid,
+    start_ts: i64,
+    duration: i64,
+    invitee: Value,
+    created: i64,
+    hold_uid: Option<Uuid>,
+    offer_expires_at: Option<i64>,
+    metadata: Value,
+}
+
+impl From<WaitlistEntryRaw> for WaitlistEntry {
+    fn from(e: WaitlistEntryRaw) -> Self {
+        Self {
+            id: e.entry_uid.into(),
+            account_id: e.account_uid.into(),
+            service_id: e.service_uid.into(),
+            start_ts: e.start_ts,
+            duration: e.duration,
+            invitee: serde_json::from_value(e.invitee).unwrap_or_default(),
+            created: e.created,
+            hold_id: e.hold_uid.map(|id| id.into()),
+            offer_expires_at: e.offer_expires_at,
+            metadata: serde_json::from_value(e.metadata).unwrap(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl IWaitlistRepo for PostgresWaitlistRepo {
+    async fn insert(&self, entry: &WaitlistEntry) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO waitlist_entries(
+                entry_uid,
+                account_uid,
+                service_uid,
+                start_ts,
+                duration,
+                invitee,
+                created,
+                hold_uid,
+                offer_expires_at,
+                metadata
+            )
+            VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#,
+            entry.id.as_ref(),
+            entry.account_id.as_ref(),
+            entry.service_id.as_ref(),
+            entry.start_ts,
+            entry.duration,
+            Json(&entry.invitee) as _,
+            entry.created,
+            entry.hold_id.as_ref().map(|id| *id.as_ref()),
+            entry.offer_expires_at,
+            Json(&entry.metadata) as _,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Unable to insert waitlist entry: {:?}. DB returned error: {:?}",
+                entry, e
+            );
+            e
+        })?;
+
+        Ok(())
+    }
+
+    async fn save(&self, entry: &WaitlistEntry) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE waitlist_entries SET
+                invitee = $2,
+                hold_uid = $3,
+                offer_expires_at = $4,
+                metadata = $5
+            WHERE entry_uid = $1
+            "#,
+            entry.id.as_ref(),
+            Json(&entry.invitee) as _,
+            entry.hold_id.as_ref().map(|id| *id.as_ref()),
+            entry.offer_expires_at,
+            Json(&entry.metadata) as _,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Unable to save waitlist entry: {:?}. DB returned error: {:?}",
+                entry, e
+            );
+            e
+        })?;
+
+        Ok(())
+    }
+
+    async fn find(&self, entry_id: &ID) -> Option<WaitlistEntry> {
+        let res: Option<WaitlistEntryRaw> = sqlx::query_as!(
+            WaitlistEntryRaw,
+            r#"
+            SELECT * FROM waitlist_entries AS w
+            WHERE w.entry_uid = $1
+            "#,
+            entry_id.as_ref(),
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Find waitlist entry with id: {:?} failed. DB returned error: {:?}",
+                entry_id, e
+            );
+            e
+        })
+        .ok()?;
+
+        res.map(|entry| entry.into())
+    }
+
+    async fn find_by_slot(&self, service_id: &ID, start_ts: i64) -> Vec<WaitlistEntry> {
+        let entries: Vec<WaitlistEntryRaw> = match sqlx::query_as!(
+            WaitlistEntryRaw,
+            r#"
+            SELECT * FROM waitlist_entries AS w
+            WHERE w.service_uid = $1 AND
+            w.start_ts = $2
+            ORDER BY w.created, w.entry_uid
+            "#,
+            service_id.as_ref(),
+            start_ts,
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(entries) => entries,
+            Err(e) => {
+                error!(
+                    "Find waitlist entries for service id: {}, start_ts: {} failed. DB returned error: {:?}",
+                    service_id, start_ts, e
+                );
+                return Vec::new();
+            }
+        };
+
+        entries.into_iter().map(|e| e.into()).collect()
+    }
+
+    async fn find_next(&self, service_id: &ID, start_ts: i64) -> Option<WaitlistEntry> {
+        let res: Option<WaitlistEntryRaw> = sqlx::query_as!(
+            WaitlistEntryRaw,
+            r#"
+            SELECT * FROM waitlist_entries AS w
+            WHERE w.service_uid = $1 AND
+            w.start_ts = $2 AND
+            w.hold_uid IS NULL
+            ORDER BY w.created, w.entry_uid
+            LIMIT 1
+            "#,
+            service_id.as_ref(),
+            start_ts,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Find next waitlist entry for service id: {}, start_ts: {} failed. DB returned error: {:?}",
+                service_id, start_ts, e
+            );
+            e
+        })
+        .ok()?;
+
+        res.map(|entry| entry.into())
+    }
+
+    async fn offer_next(
+        &self,
+        service_id: &ID,
+        start_ts: i64,
+        host_user_ids: Vec<ID>,
+        seats: usize,
+        timestamp: i64,
+    ) -> Result<Option<(WaitlistEntry, BookingHold)>, BookingCommitError> {
+        let storage_error = |e: sqlx::Error| {
+            error!(
+                "Unable to offer seat to next waitlist entry for service id: {}, start_ts: {}. DB returned error: {:?}",
+                service_id, start_ts, e
+            );
+            BookingCommitError::StorageError
+        };
+
+        let mut tx = self.pool.begin().await.map_err(storage_error)?;
+
+        let entry: Option<WaitlistEntryRaw> = sqlx::query_as(
+            r#"
+            SELECT * FROM waitlist_entries AS w
+            WHERE w.service_uid = $1 AND
+            w.start_ts = $2 AND
+            w.hold_uid IS NULL
+            ORDER BY w.created, w.entry_uid
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(service_id.as_ref())
+        .bind(start_ts)
+        .fetch_optional(&mut tx)
+        .await
+        .map_err(storage_error)?;
+        let mut entry: WaitlistEntry = match entry {
+            Some(entry) => entry.into(),
+            None => return Ok(None),
+        };
+
+        let hold = entry.offer(host_user_ids, timestamp);
+        commit_hold(&mut tx, &hold, Some(seats)).await?;
+        sqlx::query(
+            r#"
+            UPDATE waitlist_entries SET
+                hold_uid = $2,
+                offer_expires_at = $3
+            WHERE entry_uid = $1
+            "#,
+        )
+        .bind(entry.id.as_ref())
+        .bind(hold.id.as_ref())
+        .bind(entry.offer_expires_at)
+        .execute(&mut tx)
+        .await
+        .map_err(storage_error)?;
+
+        tx.commit().await.map_err(storage_error)?;
+
+        Ok(Some((entry, hold)))
+    }
+
+    async fn find_by_hold(&self, hold_id: &ID) -> Option<WaitlistEntry> {
+        let res: Option<WaitlistEntryRaw> = sqlx::query_as!(
+            WaitlistEntryRaw,
+            r#"
+            SELECT * FROM waitlist_entries AS w
+            WHERE w.hold_uid = $1
+            "#,
+            hold_id.as_ref(),
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Find waitlist entry with hold id: {:?} failed. DB returned error: {:?}",
+                hold_id, e
+            );
+            e
+        })
+        .ok()?;
+
+        res.map(|entry| entry.into())
+    }
+
+    async fn find_expired_offers(&self, timestamp: i64) -> Vec<WaitlistEntry> {
+        let entries: Vec<WaitlistEntryRaw> = match sqlx::query_as!(
+            WaitlistEntryRaw,
+            r#"
+            SELECT * FROM waitlist_entries AS w
+            WHERE w.offer_expires_at <= $1
+            ORDER BY w.offer_expires_at
+            "#,
+            timestamp,
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(entries) => entries,
+            Err(e) => {
+                error!(
+                    "Find waitlist entries with offers expired at: {} failed. DB returned error: {:?}",
+                    timestamp, e
+                );
+                return Vec::new();
+            }
+        };
+
+        entries.into_iter().map(|e| e.into()).collect()
+    }
+
+    async fn delete(&self, entry_id: &ID) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM waitlist_entries AS w
+            WHERE w.entry_uid = $1
+            "#,
+            entry_id.as_ref(),
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(
+                "Delete waitlist entry with id: {:?} failed. DB returned error: {:?}",
+                entry_id, e
+            );
+            e
+        })?;
+
+        Ok(())
+    }
+}
@@ -44,6 +44,7 @@ pub struct GoogleCalendarEventAttributes {
     pub summary: String,
     pub description: String,
     pub transparency: String,
+    pub status: String,
     pub recurrence: Vec<String>,
 }
 
@@ -67,6 +68,11 @@ impl From<CalendarEvent> for GoogleCalendarEventAttributes {
         } else {
             "transparent".to_string()
         };
+        let status = if e.tentative {
+            "tentative".to_string()
+        } else {
+            "confirmed".to_string()
+        };
         Self {
             description,
             summary,
@@ -77,6 +83,7 @@ impl From<CalendarEvent> for GoogleCalendarEventAttributes {
             recurrence: Vec::new(),
             // Whether it blocks calendar time or not
             transparency,
+            status,
         }
     }
 }
@@ -31,6 +31,14 @@ impl GoogleCalendarProvider {
     }
 
     pub async fn freebusy(&self, query: FreeBusyProviderQuery) -> CompatibleInstances {
+        self.try_freebusy(query).await.unwrap_or_default()
+    }
+
+    /// Like `freebusy`, but fails if the busy intervals could not be fetched
+    pub async fn try_freebusy(
+        &self,
+        query: FreeBusyProviderQuery,
+    ) -> Result<CompatibleInstances, ()> {
         let body = FreeBusyRequest {
             time_min: GoogleDateTime::from_timestamp_millis(query.start),
             time_max: GoogleDateTime::from_timestamp_millis(query.end),
@@ -41,22 +49,21 @@ impl GoogleCalendarProvider {
                 .map(FreeBusyCalendar::new)
                 .collect(),
         };
+        let res = self.api.freebusy(&body).await.map_err(|_| {
+            error!("Unable to get freebusy info from google calendar");
+        })?;
         let mut instances = Vec::new();
-        if let Ok(res) = self.api.freebusy(&body).await {
-            for (_, calendar_busy) in res.calendars {
-                for instance in calendar_busy.busy {
-                    let instance = EventInstance {
-                        start_ts: instance.start.get_timestamp_millis(),
-                        end_ts: instance.end.get_timestamp_millis(),
-                        busy: true,
-                    };
-                    instances.push(instance);
-                }
+        for (_, calendar_busy) in res.calendars {
+            for instance in calendar_busy.busy {
+                let instance = EventInstance {
+                    start_ts: instance.start.get_timestamp_millis(),
+                    end_ts: instance.end.get_timestamp_millis(),
+                    busy: true,
+                };
+                instances.push(instance);
             }
-        } else {
-            error!("Unable to get freebusy info from google calendar");
         }
-        CompatibleInstances::new(instances)
+        Ok(CompatibleInstances::new(instances))
     }
 
     pub async fn create_event(
@@ -30,7 +30,9 @@ pub struct OutlookCalendarEventAttributes {
 
 impl From<CalendarEvent> for OutlookCalendarEventAttributes {
     fn from(e: CalendarEvent) -> Self {
-        let show_as = if e.busy {
+        let show_as = if e.busy && e.tentative {
+            OutlookCalendarEventShowAs::Tentative
+        } else if e.busy {
             OutlookCalendarEventShowAs::Busy
         } else {
             OutlookCalendarEventShowAs::Free
@@ -259,36 +261,46 @@ impl OutlookCalendarRestApi {
         let cal_futures = body
             .calendars
             .iter()
-            .map(|calendar_id| {
-                self.get::<CalendarViewResponse>(format!(
-                    "me/calendars/{}/calendarView?startDateTime={}&endDateTime={}",
-                    calendar_id,
-                    // format!("{}", Utc.timestamp_millis(body.time_min).format("%+")),
-                    // format!("{}", Utc.timestamp_millis(body.time_max).format("%+"))
-                    Utc.timestamp_millis(body.time_min)
-                        .to_rfc3339_opts(SecondsFormat::Secs, true),
-                    Utc.timestamp_millis(body.time_max)
-                        .to_rfc3339_opts(SecondsFormat::Secs, true),
-                ))
-            })
+            .map(|calendar_id| self.calendar_busy(calendar_id, body.time_min, body.time_max))
             .collect::<Vec<_>>();
         let calendar_views = join_all(cal_futures)
             .await
             .into_iter()
             .filter_map(|res| res.ok())
-            .map(|view| {
-                view.value
-                    .into_iter()
-                    .filter(|e| matches!(e.show_as, OutlookCalendarEventShowAs::Busy))
-                    .map(|e| EventInstance {
-                        busy: true,
-                        start_ts: e.start.get_timestamp_millis(),
-                        end_ts: e.end.get_timestamp_millis(),
-                    })
-                    .collect::<Vec<_>>()
-            })
             .flatten()
             .collect::<Vec<_>>();
         Ok(CompatibleInstances::new(calendar_views))
     }
+
+    /// Finds the busy `EventInstance`s of a single outlook calendar
+    pub async fn calendar_busy(
+        &self,
+        calendar_id: &str,
+        time_min: i64,
+        time_max: i64,
+    ) -> Result<Vec<EventInstance>, ()> {
+        let view = self
+            .get::<CalendarViewResponse>(format!(
+                "me/calendars/{}/calendarView?startDateTime={}&endDateTime={}",
+                calendar_id,
+                // format!("{}", Utc.timestamp_millis(body.time_min).format("%+")),
+                // format!("{}", Utc.timestamp_millis(body.time_max).format("%+"))
+                Utc.timestamp_millis(time_min)
+                    .to_rfc3339_opts(SecondsFormat::Secs, true),
+                Utc.timestamp_millis(time_max)
+                    .to_rfc3339_opts(SecondsFormat::Secs, true),
+            ))
+            .await
+            .map_err(|_| ())?;
+        Ok(view
+            .value
+            .into_iter()
+            .filter(|e| matches!(e.show_as, OutlookCalendarEventShowAs::Busy))
+            .map(|e| EventInstance {
+                busy: true,
+                start_ts: e.start.get_timestamp_millis(),
+                end_ts: e.end.get_timestamp_millis(),
+            })
+            .collect())
+    }
 }
@@ -5,6 +5,7 @@ mod calendar_api;
 use self::calendar_api::{FreeBusyRequest, ListCalendarsResponse, OutlookCalendarEventAttributes};
 use super::FreeBusyProviderQuery;
 use calendar_api::OutlookCalendarRestApi;
+use futures::future::join_all;
 use nettu_scheduler_domain::{
     providers::outlook::{OutlookCalendarAccessRole, OutlookCalendarEvent},
     CalendarEvent, CompatibleInstances, User,
@@ -37,6 +38,26 @@ impl OutlookCalendarProvider {
         self.api.freebusy(&body).await.unwrap_or_default()
     }
 
+    /// Like `freebusy`, but fails if the busy intervals of any of the
+    /// calendars could not be fetched
+    pub async fn try_freebusy(
+        &self,
+        query: FreeBusyProviderQuery,
+    ) -> Result<CompatibleInstances, ()> {
+        let calendars = join_all(
+            query
+                .calendar_ids
+                .iter()
+                .map(|calendar_id| self.api.calendar_busy(calendar_id, query.start, query.end)),
+        )
+        .await;
+        let mut instances = Vec::new();
+        for calendar_busy in calendars {
+            instances.append(&mut calendar_busy?);
+        }
+        Ok(CompatibleInstances::new(instances))
+    }
+
     pub async fn create_event(
         &self,
         calendar_id: String,
@@ -1,13 +1,17 @@
 mod helpers;
 
+use chrono::{Datelike, Utc};
 use helpers::setup::spawn_app;
-use nettu_scheduler_domain::{PEMKey, Weekday};
+use nettu_scheduler_domain::{booking_slots::UnavailabilityReason, PEMKey, VAvailability, Weekday};
 use nettu_scheduler_sdk::{
-    AddServiceUserInput, CreateCalendarInput, CreateEventInput, CreateScheduleInput,
-    CreateServiceInput, CreateUserInput, GetCalendarEventsInput, GetEventsInstancesInput,
-    GetServiceBookingSlotsInput, GetUserFreeBusyInput, KVMetadata, MetadataFindInput, NettuSDK,
-    RemoveServiceUserInput, UpdateCalendarInput, UpdateEventInput, UpdateScheduleInput,
-    UpdateServiceUserInput,
+    AddServiceUserInput, CreateBookingIntendInput, CreateCalendarInput, CreateEventInput,
+    CreateScheduleFromVAvailabilityInput, CreateScheduleInput, CreateServiceInput, CreateUserInput,
+    ExplainServiceBookingSlotInput, GetCalendarEventsInput, GetEventsInstancesInput,
+    GetServiceBookingSlotsInput, GetServiceBookingSlotsPageInput, GetServiceNextBookingSlotInput,
+    GetUserFreeBusyInput, KVMetadata, MetadataFindInput, NettuSDK, PreviewScheduleInput,
+    RemoveServiceUserInput, ScheduleRuleInterval, ServiceBookingOptions, TimePlan,
+    UpdateCalendarInput, UpdateEventInput, UpdateScheduleFromVAvailabilityInput,
+    UpdateScheduleInput, UpdateServiceInput, UpdateServiceUserInput,
 };
 use std::collections::HashMap;
 
@@ -63,6 +67,7 @@ async fn test_crud_user() {
         .user
         .create(CreateUserInput {
             metadata: Some(metadata.into()),
+            resource_kind: None,
         })
         .await
         .expect("Expected to create user");
@@ -131,7 +136,10 @@ async fn test_crud_schedule() {
     let admin_client = NettuSDK::new(address, res.secret_api_key);
     let create_user_res = admin_client
         .user
-        .create(CreateUserInput { metadata: None })
+        .create(CreateUserInput {
+            metadata: None,
+            resource_kind: None,
+        })
         .await
         .expect("Expected to create user");
 
@@ -185,6 +193,153 @@ async fn test_crud_schedule() {
         .is_err());
 }
 
+#[actix_web::main]
+#[test]
+async fn test_schedule_vavailability() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+    let user = admin_client
+        .user
+        .create(CreateUserInput {
+            metadata: None,
+            resource_kind: None,
+        })
+        .await
+        .expect("Expected to create user")
+        .user;
+
+    let schedule = admin_client
+        .schedule
+        .create(CreateScheduleInput {
+            user_id: user.id.clone(),
+            rules: None,
+            timezone: chrono_tz::Europe::Oslo,
+            metadata: None,
+        })
+        .await
+        .expect("Expected to create schedule")
+        .schedule;
+
+    let vavailability = admin_client
+        .schedule
+        .get_vavailability(schedule.id.clone())
+        .await
+        .expect("Expected to export schedule")
+        .vavailability;
+    assert!(vavailability.contains("DTSTART;TZID=Europe/Oslo:19700105T090000"));
+    assert!(vavailability.contains("RRULE:FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR"));
+
+    let imported_schedule = admin_client
+        .schedule
+        .create_from_vavailability(CreateScheduleFromVAvailabilityInput {
+            user_id: user.id.clone(),
+            vavailability,
+            timezone: None,
+            metadata: None,
+        })
+        .await
+        .expect("Expected to import schedule")
+        .schedule;
+    assert_eq!(imported_schedule.timezone, chrono_tz::Europe::Oslo);
+    assert_eq!(imported_schedule.rules.len(), 7);
+
+    let vavailability = "BEGIN:VCALENDAR
+BEGIN:VAVAILABILITY
+BEGIN:AVAILABLE
+DTSTART:20300107T100000Z
+DTEND:20300107T120000Z
+RRULE:FREQ=WEEKLY;BYDAY=MO
+END:AVAILABLE
+END:VAVAILABILITY
+END:VCALENDAR";
+    let updated_schedule = admin_client
+        .schedule
+        .update_from_vavailability(UpdateScheduleFromVAvailabilityInput {
+            schedule_id: imported_schedule.id.clone(),
+            vavailability: vavailability.into(),
+        })
+        .await
+        .expect("Expected to update schedule")
+        .schedule;
+    assert_eq!(updated_schedule.timezone, chrono_tz::UTC);
+    let rules_with_intervals = updated_schedule
+        .rules
+        .iter()
+        .filter(|rule| !rule.intervals.is_empty())
+        .count();
+    assert_eq!(rules_with_intervals, 1);
+
+    assert!(admin_client
+        .schedule
+        .update_from_vavailability(UpdateScheduleFromVAvailabilityInput {
+            schedule_id: imported_schedule.id.clone(),
+            vavailability: "BEGIN:VCALENDAR\nEND:VCALENDAR".into(),
+        })
+        .await
+        .is_err());
+}
+
+#[actix_web::main]
+#[test]
+async fn test_preview_schedule() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+
+    let rules = VAvailability::parse(
+        "BEGIN:VCALENDAR
+BEGIN:VAVAILABILITY
+BEGIN:AVAILABLE
+DTSTART:20300107T100000Z
+DTEND:20300107T120000Z
+RRULE:FREQ=WEEKLY;BYDAY=MO
+END:AVAILABLE
+END:VAVAILABILITY
+END:VCALENDAR",
+    )
+    .expect("Expected valid vavailability")
+    .rules;
+
+    // Monday 7th of January 2030
+    let start_ts = 1893974400000;
+    let week = 1000 * 60 * 60 * 24 * 7;
+    let instances = admin_client
+        .schedule
+        .preview(PreviewScheduleInput {
+            timezone: chrono_tz::UTC,
+            rules: rules.clone(),
+            start_ts,
+            end_ts: start_ts + week,
+        })
+        .await
+        .expect("Expected to preview schedule")
+        .instances;
+    assert_eq!(instances.len(), 1);
+    assert_eq!(instances[0].start_ts, start_ts + 1000 * 60 * 60 * 10);
+    assert_eq!(instances[0].end_ts, start_ts + 1000 * 60 * 60 * 12);
+
+    assert!(admin_client
+        .schedule
+        .preview(PreviewScheduleInput {
+            timezone: chrono_tz::UTC,
+            rules,
+            start_ts,
+            end_ts: start_ts + 100 * week,
+        })
+        .await
+        .is_err());
+}
+
 #[actix_web::main]
 #[test]
 async fn test_create_user() {
@@ -197,7 +352,10 @@ async fn test_create_user() {
     let admin_client = NettuSDK::new(address, res.secret_api_key);
     let create_user_res = admin_client
         .user
-        .create(CreateUserInput { metadata: None })
+        .create(CreateUserInput {
+            metadata: None,
+            resource_kind: None,
+        })
         .await
         .expect("Expected to create user");
     let get_user_res = admin_client
@@ -285,7 +443,10 @@ async fn test_crud_calendars() {
     let admin_client = NettuSDK::new(address, res.secret_api_key);
     let user = admin_client
         .user
-        .create(CreateUserInput { metadata: None })
+        .create(CreateUserInput {
+            metadata: None,
+            resource_kind: None,
+        })
         .await
         .unwrap()
         .user;
@@ -364,7 +525,10 @@ async fn test_crud_events() {
     let admin_client = NettuSDK::new(address, res.secret_api_key);
     let user = admin_client
         .user
-        .create(CreateUserInput { metadata: None })
+        .create(CreateUserInput {
+            metadata: None,
+            resource_kind: None,
+        })
         .await
         .unwrap()
         .user;
@@ -457,7 +621,7 @@ async fn test_crud_events() {
 
 #[actix_web::main]
 #[test]
-async fn test_crud_service() {
+async fn test_service_user_booking_caps() {
     let (app, sdk, address) = spawn_app().await;
     let res = sdk
         .account
@@ -467,45 +631,109 @@ async fn test_crud_service() {
     let admin_client = NettuSDK::new(address, res.secret_api_key);
     let user = admin_client
         .user
-        .create(CreateUserInput { metadata: None })
+        .create(CreateUserInput {
+            metadata: None,
+            resource_kind: None,
+        })
         .await
         .unwrap()
         .user;
-
-    let create_service_input = CreateServiceInput {
-        metadata: None,
-        multi_person: None,
-    };
+    let schedule = admin_client
+        .schedule
+        .create(CreateScheduleInput {
+            user_id: user.id.clone(),
+            rules: None,
+            timezone: chrono_tz::UTC,
+            metadata: None,
+        })
+        .await
+        .unwrap()
+        .schedule;
+    let calendar = admin_client
+        .calendar
+        .create(CreateCalendarInput {
+            user_id: user.id.clone(),
+            timezone: chrono_tz::UTC,
+            week_start: Weekday::Mon,
+            metadata: None,
+        })
+        .await
+        .unwrap()
+        .calendar;
     let service = admin_client
         .service
-        .create(create_service_input)
+        .create(CreateServiceInput {
+            metadata: None,
+            multi_person: None,
+            booking_options: None,
+        })
         .await
         .unwrap()
         .service;
 
-    let add_user_res = admin_client
+    let service_user = admin_client
         .service
         .add_user(AddServiceUserInput {
             service_id: service.id.clone(),
             user_id: user.id.clone(),
-            availability: None,
+            availability: Some(TimePlan::Schedule(schedule.id.clone())),
             buffer_after: None,
             buffer_before: None,
             closest_booking_time: None,
             furthest_booking_time: None,
+            max_bookings_per_day: Some(1),
+            max_booked_minutes_per_week: None,
+            preferred_times: None,
+            weight: None,
+            priority: None,
         })
-        .await;
-    assert!(add_user_res.is_ok());
-    let added_service_resource = add_user_res.unwrap();
-    assert_eq!(added_service_resource.user_id, user.id.clone());
-    assert_eq!(added_service_resource.service_id, service.id.clone());
+        .await
+        .unwrap();
+    assert_eq!(service_user.max_bookings_per_day, Some(1));
 
-    let service = admin_client.service.get(service.id.clone()).await.unwrap();
+    // Monday 7th of January 2030 at 10:00
+    let half_hour = 1000 * 60 * 30;
+    let booking_start = 1893974400000 + 1000 * 60 * 60 * 10;
+    admin_client
+        .event
+        .create(CreateEventInput {
+            user_id: user.id.clone(),
+            calendar_id: calendar.id.clone(),
+            start_ts: booking_start,
+            duration: half_hour,
+            busy: None,
+            recurrence: None,
+            reminders: Vec::new(),
+            service_id: Some(service.id.clone()),
+            metadata: None,
+        })
+        .await
+        .unwrap();
 
-    assert_eq!(service.users.len(), 1);
-    let new_closest_booking_time = service.users[0].closest_booking_time + 1000 * 60 * 60;
+    let bookingslots_input = GetServiceBookingSlotsInput {
+        start_date: "2030-1-7".to_string(),
+        end_date: "2030-1-8".to_string(),
+        duration: Some(half_hour),
+        timezone: Some(chrono_tz::UTC),
+        interval: Some(half_hour),
+        host_user_ids: None,
+        service_id: service.id.clone(),
+        rank: false,
+        best_per_day: None,
+        attributes: None,
+        bypass_busy_cache: false,
+    };
+    let booking_slots = admin_client
+        .service
+        .bookingslots(bookingslots_input.clone())
+        .await
+        .unwrap()
+        .dates;
+    // Monday has reached the cap
+    assert_eq!(booking_slots.len(), 1);
+    assert_eq!(booking_slots[0].date, "2030-1-8");
 
-    let service_resource = admin_client
+    admin_client
         .service
         .update_user(UpdateServiceUserInput {
             service_id: service.id.clone(),
@@ -513,68 +741,1063 @@ async fn test_crud_service() {
             availability: None,
             buffer_after: None,
             buffer_before: None,
-            closest_booking_time: Some(new_closest_booking_time),
+            closest_booking_time: None,
             furthest_booking_time: None,
+            max_bookings_per_day: None,
+            max_booked_minutes_per_week: Some(60),
+            preferred_times: None,
+            weight: None,
+            priority: None,
         })
         .await
         .unwrap();
-
-    assert_eq!(
-        service_resource.closest_booking_time,
-        new_closest_booking_time
-    );
-    let remove_user_res = admin_client
+    let booking_slots = admin_client
         .service
-        .remove_user(RemoveServiceUserInput {
-            service_id: service.id.clone(),
-            user_id: user.id.clone(),
-        })
-        .await;
-    assert!(remove_user_res.is_ok());
-
-    let service = admin_client.service.get(service.id.clone()).await.unwrap();
-    assert!(service.users.is_empty());
-
+        .bookingslots(bookingslots_input.clone())
+        .await
+        .unwrap()
+        .dates;
+    assert_eq!(booking_slots.len(), 2);
+    // Another hour would exceed the weekly cap
     let booking_slots = admin_client
         .service
         .bookingslots(GetServiceBookingSlotsInput {
-            start_date: "2030-1-1".to_string(),
-            end_date: "2030-1-2".to_string(),
-            duration: 1000 * 60 * 30,
-            timezone: Some(chrono_tz::UTC),
-            interval: 1000 * 60 * 15,
-            host_user_ids: None,
-            service_id: service.id.clone(),
+            duration: Some(half_hour * 2),
+            ..bookingslots_input
         })
         .await
         .unwrap()
         .dates;
     assert!(booking_slots.is_empty());
 
-    // About 100 days timespan
-    let booking_slots = admin_client
+    assert!(admin_client
         .service
-        .bookingslots(GetServiceBookingSlotsInput {
-            start_date: "2030-1-1".to_string(),
-            end_date: "2030-4-1".to_string(),
-            duration: 1000 * 60 * 30,
-            timezone: Some(chrono_tz::UTC),
-            interval: 1000 * 60 * 15,
-            host_user_ids: None,
+        .update_user(UpdateServiceUserInput {
             service_id: service.id.clone(),
+            user_id: user.id.clone(),
+            availability: None,
+            buffer_after: None,
+            buffer_before: None,
+            closest_booking_time: None,
+            furthest_booking_time: None,
+            max_bookings_per_day: Some(-1),
+            max_booked_minutes_per_week: None,
+            preferred_times: None,
+            weight: None,
+            priority: None,
+        })
+        .await
+        .is_err());
+}
+
+#[actix_web::main]
+#[test]
+async fn test_ranked_service_bookingslots() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+    let user = admin_client
+        .user
+        .create(CreateUserInput {
+            metadata: None,
+            resource_kind: None,
         })
         .await
         .unwrap()
-        .dates;
-    assert!(booking_slots.is_empty());
+        .user;
+    let schedule = admin_client
+        .schedule
+        .create(CreateScheduleInput {
+            user_id: user.id.clone(),
+            rules: None,
+            timezone: chrono_tz::UTC,
+            metadata: None,
+        })
+        .await
+        .unwrap()
+        .schedule;
+    let service = admin_client
+        .service
+        .create(CreateServiceInput {
+            metadata: None,
+            multi_person: None,
+            booking_options: None,
+        })
+        .await
+        .unwrap()
+        .service;
 
-    // Delete service
-    assert!(admin_client
+    let service_user = admin_client
         .service
-        .delete(service.id.clone())
+        .add_user(AddServiceUserInput {
+            service_id: service.id.clone(),
+            user_id: user.id.clone(),
+            availability: Some(TimePlan::Schedule(schedule.id.clone())),
+            buffer_after: None,
+            buffer_before: None,
+            closest_booking_time: None,
+            furthest_booking_time: None,
+            max_bookings_per_day: None,
+            max_booked_minutes_per_week: None,
+            preferred_times: Some(vec![ScheduleRuleInterval::new(17, 0, 17, 30)]),
+            weight: None,
+            priority: None,
+        })
         .await
-        .is_ok());
+        .unwrap();
+    assert_eq!(service_user.preferred_times.len(), 1);
 
-    // Get now returns 404
-    assert!(admin_client.service.get(service.id.clone()).await.is_err());
+    // Preferred times that end before they start are rejected
+    assert!(admin_client
+        .service
+        .update_user(UpdateServiceUserInput {
+            service_id: service.id.clone(),
+            user_id: user.id.clone(),
+            availability: None,
+            buffer_after: None,
+            buffer_before: None,
+            closest_booking_time: None,
+            furthest_booking_time: None,
+            max_bookings_per_day: None,
+            max_booked_minutes_per_week: None,
+            preferred_times: Some(vec![ScheduleRuleInterval::new(17, 30, 17, 0)]),
+            weight: None,
+            priority: None,
+        })
+        .await
+        .is_err());
+
+    let half_hour = 1000 * 60 * 30;
+    // Monday 7th of January 2030
+    let monday = 1893974400000;
+    let bookingslots_input = GetServiceBookingSlotsInput {
+        start_date: "2030-1-7".to_string(),
+        end_date: "2030-1-7".to_string(),
+        duration: Some(half_hour),
+        timezone: Some(chrono_tz::UTC),
+        interval: Some(half_hour),
+        host_user_ids: None,
+        service_id: service.id.clone(),
+        rank: false,
+        best_per_day: None,
+        attributes: None,
+        bypass_busy_cache: false,
+    };
+    let booking_slots = admin_client
+        .service
+        .bookingslots(bookingslots_input.clone())
+        .await
+        .unwrap()
+        .dates;
+    assert_eq!(booking_slots.len(), 1);
+    assert_eq!(booking_slots[0].slots.len(), 17);
+    assert!(booking_slots[0].slots.iter().all(|s| s.score.is_none()));
+
+    let booking_slots = admin_client
+        .service
+        .bookingslots(GetServiceBookingSlotsInput {
+            rank: true,
+            ..bookingslots_input.clone()
+        })
+        .await
+        .unwrap()
+        .dates;
+    assert_eq!(booking_slots[0].slots.len(), 17);
+    let best_slot = booking_slots[0]
+        .slots
+        .iter()
+        .max_by(|s1, s2| s1.score.partial_cmp(&s2.score).unwrap())
+        .unwrap();
+    // The last slot of the day is both adjacent to the end of the
+    // availability and within the preferred times
+    assert_eq!(best_slot.start, monday + 1000 * 60 * 60 * 17);
+    assert_eq!(best_slot.score, Some(0.8));
+
+    let booking_slots = admin_client
+        .service
+        .bookingslots(GetServiceBookingSlotsInput {
+            best_per_day: Some(3),
+            attributes: None,
+            ..bookingslots_input
+        })
+        .await
+        .unwrap()
+        .dates;
+    let starts = booking_slots[0]
+        .slots
+        .iter()
+        .map(|s| s.start)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        starts,
+        vec![
+            monday + 1000 * 60 * 60 * 9,
+            monday + 1000 * 60 * 60 * 9 + half_hour,
+            monday + 1000 * 60 * 60 * 17,
+        ]
+    );
+}
+
+#[actix_web::main]
+#[test]
+async fn test_crud_service() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+    let user = admin_client
+        .user
+        .create(CreateUserInput {
+            metadata: None,
+            resource_kind: None,
+        })
+        .await
+        .unwrap()
+        .user;
+
+    let create_service_input = CreateServiceInput {
+        metadata: None,
+        multi_person: None,
+        booking_options: None,
+    };
+    let service = admin_client
+        .service
+        .create(create_service_input)
+        .await
+        .unwrap()
+        .service;
+
+    let add_user_res = admin_client
+        .service
+        .add_user(AddServiceUserInput {
+            service_id: service.id.clone(),
+            user_id: user.id.clone(),
+            availability: None,
+            buffer_after: None,
+            buffer_before: None,
+            closest_booking_time: None,
+            furthest_booking_time: None,
+            max_bookings_per_day: None,
+            max_booked_minutes_per_week: None,
+            preferred_times: None,
+            weight: None,
+            priority: None,
+        })
+        .await;
+    assert!(add_user_res.is_ok());
+    let added_service_resource = add_user_res.unwrap();
+    assert_eq!(added_service_resource.user_id, user.id.clone());
+    assert_eq!(added_service_resource.service_id, service.id.clone());
+
+    let service = admin_client.service.get(service.id.clone()).await.unwrap();
+
+    assert_eq!(service.users.len(), 1);
+    let new_closest_booking_time = service.users[0].closest_booking_time + 1000 * 60 * 60;
+
+    let service_resource = admin_client
+        .service
+        .update_user(UpdateServiceUserInput {
+            service_id: service.id.clone(),
+            user_id: user.id.clone(),
+            availability: None,
+            buffer_after: None,
+            buffer_before: None,
+            closest_booking_time: Some(new_closest_booking_time),
+            furthest_booking_time: None,
+            max_bookings_per_day: None,
+            max_booked_minutes_per_week: None,
+            preferred_times: None,
+            weight: None,
+            priority: None,
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(
+        service_resource.closest_booking_time,
+        new_closest_booking_time
+    );
+    let remove_user_res = admin_client
+        .service
+        .remove_user(RemoveServiceUserInput {
+            service_id: service.id.clone(),
+            user_id: user.id.clone(),
+        })
+        .await;
+    assert!(remove_user_res.is_ok());
+
+    let service = admin_client.service.get(service.id.clone()).await.unwrap();
+    assert!(service.users.is_empty());
+
+    let booking_slots = admin_client
+        .service
+        .bookingslots(GetServiceBookingSlotsInput {
+            start_date: "2030-1-1".to_string(),
+            end_date: "2030-1-2".to_string(),
+            duration: Some(1000 * 60 * 30),
+            timezone: Some(chrono_tz::UTC),
+            interval: Some(1000 * 60 * 15),
+            host_user_ids: None,
+            service_id: service.id.clone(),
+            rank: false,
+            best_per_day: None,
+            attributes: None,
+            bypass_busy_cache: false,
+        })
+        .await
+        .unwrap()
+        .dates;
+    assert!(booking_slots.is_empty());
+
+    // About 100 days timespan
+    let booking_slots = admin_client
+        .service
+        .bookingslots(GetServiceBookingSlotsInput {
+            start_date: "2030-1-1".to_string(),
+            end_date: "2030-4-1".to_string(),
+            duration: Some(1000 * 60 * 30),
+            timezone: Some(chrono_tz::UTC),
+            interval: Some(1000 * 60 * 15),
+            host_user_ids: None,
+            service_id: service.id.clone(),
+            rank: false,
+            best_per_day: None,
+            attributes: None,
+            bypass_busy_cache: false,
+        })
+        .await
+        .unwrap()
+        .dates;
+    assert!(booking_slots.is_empty());
+
+    // Delete service
+    assert!(admin_client
+        .service
+        .delete(service.id.clone())
+        .await
+        .is_ok());
+
+    // Get now returns 404
+    assert!(admin_client.service.get(service.id.clone()).await.is_err());
+}
+#[actix_web::main]
+#[test]
+async fn test_service_booking_options() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+    let user = admin_client
+        .user
+        .create(CreateUserInput {
+            metadata: None,
+            resource_kind: None,
+        })
+        .await
+        .unwrap()
+        .user;
+    let schedule = admin_client
+        .schedule
+        .create(CreateScheduleInput {
+            user_id: user.id.clone(),
+            rules: None,
+            timezone: chrono_tz::UTC,
+            metadata: None,
+        })
+        .await
+        .unwrap()
+        .schedule;
+
+    let half_hour = 1000 * 60 * 30;
+    let booking_options = ServiceBookingOptions {
+        durations: vec![half_hour, half_hour * 2],
+        default_interval: Some(half_hour),
+        buffer_before: None,
+        buffer_after: Some(10),
+        requires_approval: false,
+        approval_timeout: None,
+        cancellation_cutoff: Some(1000 * 60 * 60 * 24),
+        reschedule_cutoff: None,
+        max_reschedules: Some(2),
+        fairness: Default::default(),
+        sticky_hosts: false,
+        host_routing: Vec::new(),
+        intake_questions: Vec::new(),
+    };
+    let service = admin_client
+        .service
+        .create(CreateServiceInput {
+            metadata: None,
+            multi_person: None,
+            booking_options: Some(booking_options.clone()),
+        })
+        .await
+        .unwrap()
+        .service;
+    assert_eq!(service.booking_options, booking_options);
+
+    admin_client
+        .service
+        .add_user(AddServiceUserInput {
+            service_id: service.id.clone(),
+            user_id: user.id.clone(),
+            availability: Some(TimePlan::Schedule(schedule.id.clone())),
+            buffer_after: None,
+            buffer_before: None,
+            closest_booking_time: None,
+            furthest_booking_time: None,
+            max_bookings_per_day: None,
+            max_booked_minutes_per_week: None,
+            preferred_times: None,
+            weight: None,
+            priority: None,
+        })
+        .await
+        .unwrap();
+
+    let bookingslots_input = GetServiceBookingSlotsInput {
+        // Monday
+        start_date: "2030-1-7".to_string(),
+        end_date: "2030-1-7".to_string(),
+        duration: None,
+        timezone: Some(chrono_tz::UTC),
+        interval: None,
+        host_user_ids: None,
+        service_id: service.id.clone(),
+        rank: false,
+        best_per_day: None,
+        attributes: None,
+        bypass_busy_cache: false,
+    };
+    let booking_slots = admin_client
+        .service
+        .bookingslots(bookingslots_input.clone())
+        .await
+        .unwrap()
+        .dates;
+    assert_eq!(booking_slots.len(), 1);
+    // Default schedule is available from 09:00 to 17:30
+    let slots = &booking_slots[0].slots;
+    assert_eq!(slots.len(), 17);
+    for (i, slot) in slots.iter().enumerate() {
+        assert_eq!(slot.duration, half_hour);
+        if i > 0 {
+            assert_eq!(slot.start - slots[i - 1].start, half_hour);
+        }
+    }
+
+    // Durations that are not allowed by the service are rejected
+    assert!(admin_client
+        .service
+        .bookingslots(GetServiceBookingSlotsInput {
+            duration: Some(half_hour * 3),
+            ..bookingslots_input.clone()
+        })
+        .await
+        .is_err());
+    // Intervals finer than the default of the service are rejected
+    assert!(admin_client
+        .service
+        .bookingslots(GetServiceBookingSlotsInput {
+            interval: Some(half_hour / 2),
+            ..bookingslots_input.clone()
+        })
+        .await
+        .is_err());
+    let booking_slots = admin_client
+        .service
+        .bookingslots(GetServiceBookingSlotsInput {
+            duration: Some(half_hour * 2),
+            interval: Some(half_hour * 2),
+            ..bookingslots_input.clone()
+        })
+        .await
+        .unwrap()
+        .dates;
+    assert_eq!(booking_slots[0].slots.len(), 8);
+    assert_eq!(booking_slots[0].slots[0].duration, half_hour * 2);
+
+    let intend = admin_client
+        .service
+        .create_booking_intend(CreateBookingIntendInput {
+            service_id: service.id.clone(),
+            host_user_ids: None,
+            timestamp: slots[0].start,
+            duration: None,
+            interval: None,
+            attributes: None,
+            answers: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(intend.selected_hosts.len(), 1);
+    assert!(admin_client
+        .service
+        .create_booking_intend(CreateBookingIntendInput {
+            service_id: service.id.clone(),
+            host_user_ids: None,
+            timestamp: slots[0].start,
+            duration: Some(half_hour * 3),
+            interval: None,
+            attributes: None,
+            answers: None,
+        })
+        .await
+        .is_err());
+
+    // Invalid booking options are rejected
+    assert!(admin_client
+        .service
+        .update(UpdateServiceInput {
+            service_id: service.id.clone(),
+            metadata: None,
+            multi_person: None,
+            booking_options: Some(ServiceBookingOptions {
+                default_interval: Some(1),
+                ..Default::default()
+            }),
+        })
+        .await
+        .is_err());
+
+    // Removing the allowed durations makes any duration bookable again
+    let service = admin_client
+        .service
+        .update(UpdateServiceInput {
+            service_id: service.id.clone(),
+            metadata: None,
+            multi_person: None,
+            booking_options: Some(Default::default()),
+        })
+        .await
+        .unwrap()
+        .service;
+    assert!(service.booking_options.durations.is_empty());
+    assert!(admin_client
+        .service
+        .bookingslots(bookingslots_input.clone())
+        .await
+        .is_err());
+    assert!(admin_client
+        .service
+        .bookingslots(GetServiceBookingSlotsInput {
+            duration: Some(half_hour * 3),
+            interval: Some(half_hour),
+            ..bookingslots_input
+        })
+        .await
+        .is_ok());
+}
+
+#[actix_web::main]
+#[test]
+async fn test_paginated_service_bookingslots() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+    let user = admin_client
+        .user
+        .create(CreateUserInput {
+            metadata: None,
+            resource_kind: None,
+        })
+        .await
+        .unwrap()
+        .user;
+    let schedule = admin_client
+        .schedule
+        .create(CreateScheduleInput {
+            user_id: user.id.clone(),
+            rules: None,
+            timezone: chrono_tz::UTC,
+            metadata: None,
+        })
+        .await
+        .unwrap()
+        .schedule;
+    let service = admin_client
+        .service
+        .create(CreateServiceInput {
+            metadata: None,
+            multi_person: None,
+            booking_options: None,
+        })
+        .await
+        .unwrap()
+        .service;
+    admin_client
+        .service
+        .add_user(AddServiceUserInput {
+            service_id: service.id.clone(),
+            user_id: user.id.clone(),
+            availability: Some(TimePlan::Schedule(schedule.id.clone())),
+            buffer_after: None,
+            buffer_before: None,
+            closest_booking_time: None,
+            furthest_booking_time: None,
+            max_bookings_per_day: None,
+            max_booked_minutes_per_week: None,
+            preferred_times: None,
+            weight: None,
+            priority: None,
+        })
+        .await
+        .unwrap();
+
+    let page_input = GetServiceBookingSlotsPageInput {
+        service_id: service.id.clone(),
+        timezone: Some(chrono_tz::UTC),
+        duration: Some(1000 * 60 * 30),
+        interval: Some(1000 * 60 * 30),
+        // Monday 7th of January 2030
+        start_date: Some("2030-1-7".into()),
+        cursor: None,
+        days: Some(7),
+        host_user_ids: None,
+        attributes: None,
+        bypass_busy_cache: false,
+    };
+    let page = admin_client
+        .service
+        .bookingslots_page(page_input.clone())
+        .await
+        .unwrap();
+    assert_eq!(page.dates[0].date, "2030-1-7");
+    assert_eq!(page.next_cursor, Some("2030-1-14".to_string()));
+
+    // The cursor continues with the following dates
+    let next_page = admin_client
+        .service
+        .bookingslots_page(GetServiceBookingSlotsPageInput {
+            cursor: page.next_cursor,
+            ..page_input.clone()
+        })
+        .await
+        .unwrap();
+    assert_eq!(next_page.dates[0].date, "2030-1-14");
+    assert!(next_page.dates.iter().all(|d| d.date != "2030-1-13"));
+
+    // Pages are small
+    assert!(admin_client
+        .service
+        .bookingslots_page(GetServiceBookingSlotsPageInput {
+            days: Some(100),
+            ..page_input.clone()
+        })
+        .await
+        .is_err());
+    assert!(admin_client
+        .service
+        .bookingslots_page(GetServiceBookingSlotsPageInput {
+            start_date: None,
+            ..page_input.clone()
+        })
+        .await
+        .is_err());
+
+    // There are no pages after the furthest booking time of the host
+    admin_client
+        .service
+        .update_user(UpdateServiceUserInput {
+            service_id: service.id.clone(),
+            user_id: user.id.clone(),
+            availability: None,
+            buffer_after: None,
+            buffer_before: None,
+            closest_booking_time: None,
+            furthest_booking_time: Some(60 * 24 * 10),
+            max_bookings_per_day: None,
+            max_booked_minutes_per_week: None,
+            preferred_times: None,
+            weight: None,
+            priority: None,
+        })
+        .await
+        .unwrap();
+    let today = Utc::now();
+    let first_page = GetServiceBookingSlotsPageInput {
+        start_date: Some(format!(
+            "{}-{}-{}",
+            today.year(),
+            today.month(),
+            today.day()
+        )),
+        ..page_input
+    };
+    let page = admin_client
+        .service
+        .bookingslots_page(first_page.clone())
+        .await
+        .unwrap();
+    assert!(page.next_cursor.is_some());
+    let page = admin_client
+        .service
+        .bookingslots_page(GetServiceBookingSlotsPageInput {
+            cursor: page.next_cursor,
+            ..first_page
+        })
+        .await
+        .unwrap();
+    assert!(page.next_cursor.is_none());
+}
+
+#[actix_web::main]
+#[test]
+async fn test_next_available_service_bookingslot() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+    let user = admin_client
+        .user
+        .create(CreateUserInput {
+            metadata: None,
+            resource_kind: None,
+        })
+        .await
+        .unwrap()
+        .user;
+    let schedule = admin_client
+        .schedule
+        .create(CreateScheduleInput {
+            user_id: user.id.clone(),
+            rules: None,
+            timezone: chrono_tz::UTC,
+            metadata: None,
+        })
+        .await
+        .unwrap()
+        .schedule;
+    let service = admin_client
+        .service
+        .create(CreateServiceInput {
+            metadata: None,
+            multi_person: None,
+            booking_options: None,
+        })
+        .await
+        .unwrap()
+        .service;
+    admin_client
+        .service
+        .add_user(AddServiceUserInput {
+            service_id: service.id.clone(),
+            user_id: user.id.clone(),
+            availability: Some(TimePlan::Schedule(schedule.id.clone())),
+            buffer_after: None,
+            buffer_before: None,
+            closest_booking_time: None,
+            furthest_booking_time: Some(60 * 24 * 30),
+            max_bookings_per_day: None,
+            max_booked_minutes_per_week: None,
+            preferred_times: None,
+            weight: None,
+            priority: None,
+        })
+        .await
+        .unwrap();
+
+    let half_hour = 1000 * 60 * 30;
+    let today = Utc::now();
+    let week_from_today = today + chrono::Duration::days(7);
+    let booking_slots = admin_client
+        .service
+        .bookingslots(GetServiceBookingSlotsInput {
+            start_date: format!("{}-{}-{}", today.year(), today.month(), today.day()),
+            end_date: format!(
+                "{}-{}-{}",
+                week_from_today.year(),
+                week_from_today.month(),
+                week_from_today.day()
+            ),
+            duration: Some(half_hour),
+            timezone: Some(chrono_tz::UTC),
+            interval: Some(half_hour),
+            host_user_ids: None,
+            service_id: service.id.clone(),
+            rank: false,
+            best_per_day: None,
+            attributes: None,
+            bypass_busy_cache: false,
+        })
+        .await
+        .unwrap()
+        .dates;
+    let first_slot = booking_slots[0].slots[0].start;
+    let second_slot = booking_slots[0].slots[1].start;
+
+    let next_input = GetServiceNextBookingSlotInput {
+        service_id: service.id.clone(),
+        after: None,
+        duration: Some(half_hour),
+        interval: Some(half_hour),
+        host_user_ids: None,
+        attributes: None,
+        bypass_busy_cache: false,
+    };
+    let slot = admin_client
+        .service
+        .next_bookingslot(next_input.clone())
+        .await
+        .unwrap()
+        .slot
+        .expect("To find the next available slot");
+    assert_eq!(slot.start, first_slot);
+    let slot = admin_client
+        .service
+        .next_bookingslot(GetServiceNextBookingSlotInput {
+            after: Some(first_slot + 1),
+            ..next_input.clone()
+        })
+        .await
+        .unwrap()
+        .slot
+        .expect("To find the next available slot");
+    assert_eq!(slot.start, second_slot);
+
+    // Searches start now at the earliest
+    let slot = admin_client
+        .service
+        .next_bookingslot(GetServiceNextBookingSlotInput {
+            after: Some(0),
+            ..next_input.clone()
+        })
+        .await
+        .unwrap()
+        .slot
+        .expect("To find the next available slot");
+    assert_eq!(slot.start, first_slot);
+    assert!(admin_client
+        .service
+        .next_bookingslot(GetServiceNextBookingSlotInput {
+            after: Some(i64::MAX),
+            ..next_input.clone()
+        })
+        .await
+        .is_err());
+
+    // Searches beyond the first weeks
+    admin_client
+        .service
+        .update_user(UpdateServiceUserInput {
+            service_id: service.id.clone(),
+            user_id: user.id.clone(),
+            availability: None,
+            buffer_after: None,
+            buffer_before: None,
+            closest_booking_time: Some(60 * 24 * 20),
+            furthest_booking_time: Some(60 * 24 * 30),
+            max_bookings_per_day: None,
+            max_booked_minutes_per_week: None,
+            preferred_times: None,
+            weight: None,
+            priority: None,
+        })
+        .await
+        .unwrap();
+    let slot = admin_client
+        .service
+        .next_bookingslot(next_input.clone())
+        .await
+        .unwrap()
+        .slot
+        .expect("To find the next available slot");
+    assert!(slot.start >= today.timestamp_millis() + 1000 * 60 * 60 * 24 * 20);
+
+    // Searches until the furthest booking time of the host
+    assert!(admin_client
+        .service
+        .next_bookingslot(GetServiceNextBookingSlotInput {
+            after: Some(today.timestamp_millis() + 1000 * 60 * 60 * 24 * 31),
+            ..next_input
+        })
+        .await
+        .unwrap()
+        .slot
+        .is_none());
+}
+
+#[actix_web::main]
+#[test]
+async fn test_explain_unavailable_service_bookingslot() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+    let user = admin_client
+        .user
+        .create(CreateUserInput {
+            metadata: None,
+            resource_kind: None,
+        })
+        .await
+        .unwrap()
+        .user;
+    let schedule = admin_client
+        .schedule
+        .create(CreateScheduleInput {
+            user_id: user.id.clone(),
+            rules: None,
+            timezone: chrono_tz::UTC,
+            metadata: None,
+        })
+        .await
+        .unwrap()
+        .schedule;
+    let calendar = admin_client
+        .calendar
+        .create(CreateCalendarInput {
+            user_id: user.id.clone(),
+            timezone: chrono_tz::UTC,
+            week_start: Weekday::Mon,
+            metadata: None,
+        })
+        .await
+        .unwrap()
+        .calendar;
+    let mut services = Vec::new();
+    // Only the other service has a buffer of 30 minutes after its bookings
+    for buffer_after in [None, Some(30)].iter() {
+        let service = admin_client
+            .service
+            .create(CreateServiceInput {
+                metadata: None,
+                multi_person: None,
+                booking_options: None,
+            })
+            .await
+            .unwrap()
+            .service;
+        admin_client
+            .service
+            .add_user(AddServiceUserInput {
+                service_id: service.id.clone(),
+                user_id: user.id.clone(),
+                availability: Some(TimePlan::Schedule(schedule.id.clone())),
+                buffer_after: *buffer_after,
+                buffer_before: None,
+                closest_booking_time: None,
+                furthest_booking_time: None,
+                max_bookings_per_day: None,
+                max_booked_minutes_per_week: None,
+                preferred_times: None,
+                weight: None,
+                priority: None,
+            })
+            .await
+            .unwrap();
+        services.push(service);
+    }
+    let service = &services[0];
+
+    // Monday 7th of January 2030 at 10:00, booked on the other service
+    let hour = 1000 * 60 * 60;
+    let booking_start = 1893974400000 + hour * 10;
+    admin_client
+        .event
+        .create(CreateEventInput {
+            user_id: user.id.clone(),
+            calendar_id: calendar.id.clone(),
+            start_ts: booking_start,
+            duration: hour,
+            busy: None,
+            recurrence: None,
+            reminders: Vec::new(),
+            service_id: Some(services[1].id.clone()),
+            metadata: None,
+        })
+        .await
+        .unwrap();
+
+    let explain_input = ExplainServiceBookingSlotInput {
+        service_id: service.id.clone(),
+        user_id: user.id.clone(),
+        timestamp: booking_start,
+        duration: Some(hour),
+        bypass_busy_cache: false,
+    };
+    let res = admin_client
+        .service
+        .explain_bookingslot(explain_input.clone())
+        .await
+        .unwrap();
+    assert!(!res.available);
+    assert_eq!(res.reasons, vec![UnavailabilityReason::BusyEvent]);
+
+    // The booking ends at 11:00, but its buffer lasts until 11:30
+    let res = admin_client
+        .service
+        .explain_bookingslot(ExplainServiceBookingSlotInput {
+            timestamp: booking_start + hour,
+            ..explain_input.clone()
+        })
+        .await
+        .unwrap();
+    assert!(!res.available);
+    assert_eq!(res.reasons, vec![UnavailabilityReason::Buffer]);
+
+    let res = admin_client
+        .service
+        .explain_bookingslot(ExplainServiceBookingSlotInput {
+            timestamp: booking_start + hour * 2,
+            ..explain_input.clone()
+        })
+        .await
+        .unwrap();
+    assert!(res.available);
+    assert!(res.reasons.is_empty());
+
+    // Monday 7th of January 2030 at 03:00
+    let res = admin_client
+        .service
+        .explain_bookingslot(ExplainServiceBookingSlotInput {
+            timestamp: booking_start - hour * 7,
+            ..explain_input.clone()
+        })
+        .await
+        .unwrap();
+    assert_eq!(res.reasons, vec![UnavailabilityReason::OutsideSchedule]);
+
+    // Monday 6th of January 2020 at 10:00
+    let res = admin_client
+        .service
+        .explain_bookingslot(ExplainServiceBookingSlotInput {
+            timestamp: 1578304800000,
+            ..explain_input.clone()
+        })
+        .await
+        .unwrap();
+    assert!(res
+        .reasons
+        .contains(&UnavailabilityReason::ClosestBookingTime));
+
+    // Not a host of the service
+    assert!(admin_client
+        .service
+        .explain_bookingslot(ExplainServiceBookingSlotInput {
+            user_id: Default::default(),
+            ..explain_input
+        })
+        .await
+        .is_err());
 }
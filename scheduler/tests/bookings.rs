@@ -0,0 +1,1607 @@
+mod helpers;
+
+use chrono::{Duration, Utc, Weekday};
+use futures::future::join_all;
+use helpers::setup::spawn_app;
+use helpers::utils::format_datetime;
+use nettu_scheduler_domain::{BusyCalendar, ServiceMultiPersonOptions, TimePlan, ID};
+use nettu_scheduler_sdk::{
+    APIErrorVariant, AddBusyCalendar, AddServiceUserInput, BookingInvitee, BookingStatus, Calendar,
+    CancelBookingInput, CreateBookingChainInput, CreateBookingFromHoldInput,
+    CreateBookingHoldInput, CreateBookingInput, CreateBookingIntendInput, CreateCalendarInput,
+    CreateScheduleInput, CreateServiceInput, CreateUserInput, GetCalendarEventsInput,
+    GetServiceBookingSlotsInput, GetServiceBookingsInput, GetServiceChainBookingSlotsInput,
+    IntakeAnswer, IntakeAnswers, IntakeQuestion, IntakeQuestionKind, JoinWaitlistInput, NettuSDK,
+    RescheduleBookingInput, RoundRobinAlgorithm, ServiceBookingOptions, ServiceChainStep, User,
+};
+
+async fn create_default_service_host(admin_client: &NettuSDK, service_id: &ID) -> (User, Calendar) {
+    create_default_service_user(admin_client, service_id, None).await
+}
+
+/// Registers a new `User` on the service, which is a resource if it has a
+/// resource kind, with a default schedule and a busy calendar
+async fn create_default_service_user(
+    admin_client: &NettuSDK,
+    service_id: &ID,
+    resource_kind: Option<String>,
+) -> (User, Calendar) {
+    let input = CreateUserInput {
+        metadata: None,
+        resource_kind,
+    };
+    let host = admin_client
+        .user
+        .create(input)
+        .await
+        .expect("To create user")
+        .user;
+
+    let input = CreateScheduleInput {
+        metadata: None,
+        rules: None,
+        timezone: chrono_tz::UTC,
+        user_id: host.id.clone(),
+    };
+    let schedule = admin_client
+        .schedule
+        .create(input)
+        .await
+        .expect("To create schedule")
+        .schedule;
+    let input = CreateCalendarInput {
+        metadata: None,
+        timezone: chrono_tz::UTC,
+        user_id: host.id.clone(),
+        week_start: Weekday::Mon,
+    };
+    let busy_calendar = admin_client
+        .calendar
+        .create(input)
+        .await
+        .expect("To create calendar")
+        .calendar;
+
+    let input = AddServiceUserInput {
+        availability: Some(TimePlan::Schedule(schedule.id.clone())),
+        buffer_after: None,
+        buffer_before: None,
+        closest_booking_time: None,
+        furthest_booking_time: None,
+        service_id: service_id.clone(),
+        user_id: host.id.clone(),
+        max_bookings_per_day: None,
+        max_booked_minutes_per_week: None,
+        preferred_times: None,
+        weight: None,
+        priority: None,
+    };
+    admin_client
+        .service
+        .add_user(input)
+        .await
+        .expect("To add host to service");
+    let input = AddBusyCalendar {
+        user_id: host.id.clone(),
+        service_id: service_id.clone(),
+        calendar: BusyCalendar::Nettu(busy_calendar.id.clone()),
+    };
+    admin_client
+        .service
+        .add_busy_calendar(input)
+        .await
+        .expect("To add busy calendar to service user");
+    (host, busy_calendar)
+}
+
+fn bookingslots_input(service_id: &ID) -> GetServiceBookingSlotsInput {
+    let tomorrow = Utc::now() + Duration::days(1);
+    let next_week = tomorrow + Duration::days(7);
+    GetServiceBookingSlotsInput {
+        duration: Some(1000 * 60 * 30),
+        interval: Some(1000 * 60 * 30),
+        service_id: service_id.clone(),
+        timezone: Some(chrono_tz::UTC),
+        end_date: format_datetime(&next_week),
+        start_date: format_datetime(&tomorrow),
+        host_user_ids: None,
+        rank: false,
+        best_per_day: None,
+        attributes: None,
+        bypass_busy_cache: false,
+    }
+}
+
+fn create_booking_input(service_id: &ID, start_ts: i64) -> CreateBookingInput {
+    CreateBookingInput {
+        service_id: service_id.clone(),
+        host_user_ids: None,
+        start_ts,
+        duration: Some(1000 * 60 * 30),
+        interval: Some(1000 * 60 * 30),
+        invitee: BookingInvitee {
+            name: Some("Invitee".into()),
+            email: Some("invitee@example.com".into()),
+            customer_key: None,
+            attributes: Default::default(),
+            answers: Default::default(),
+        },
+        metadata: None,
+    }
+}
+
+#[actix_web::main]
+#[test]
+async fn test_create_and_cancel_booking() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+
+    let input = CreateServiceInput {
+        metadata: None,
+        multi_person: None,
+        booking_options: None,
+    };
+    let service = admin_client
+        .service
+        .create(input)
+        .await
+        .expect("To create service")
+        .service;
+    let (host, busy_calendar) = create_default_service_host(&admin_client, &service.id).await;
+
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    let available_slot = bookingslots[0].slots[0].start;
+
+    let booking = admin_client
+        .booking
+        .create(create_booking_input(&service.id, available_slot))
+        .await
+        .expect("To create booking")
+        .booking;
+    assert_eq!(booking.status, BookingStatus::Confirmed);
+    assert_eq!(booking.host_user_ids, vec![host.id.clone()]);
+    assert_eq!(booking.invitee.name, Some("Invitee".into()));
+    assert_eq!(booking.start_ts, available_slot);
+    assert_eq!(booking.event_ids.len(), 1);
+
+    // The event was created in the busy calendar of the host
+    let event = admin_client
+        .event
+        .get(booking.event_ids[0].clone())
+        .await
+        .expect("To get booking event")
+        .event;
+    assert_eq!(event.calendar_id, busy_calendar.id);
+    assert_eq!(event.start_ts, available_slot);
+
+    let res = admin_client
+        .booking
+        .get(booking.id.clone())
+        .await
+        .expect("To get booking")
+        .booking;
+    assert_eq!(res.id, booking.id);
+
+    let bookings = admin_client
+        .booking
+        .get_by_service(GetServiceBookingsInput {
+            service_id: service.id.clone(),
+            start_ts: available_slot,
+            end_ts: available_slot + 1000 * 60 * 60,
+            status: None,
+        })
+        .await
+        .expect("To get service bookings")
+        .bookings;
+    assert_eq!(bookings.len(), 1);
+
+    // The booked slot is no longer available
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    assert_ne!(bookingslots[0].slots[0].start, available_slot);
+
+    let booking = admin_client
+        .booking
+        .cancel(CancelBookingInput {
+            booking_id: booking.id.clone(),
+            ignore_policy: false,
+        })
+        .await
+        .expect("To cancel booking")
+        .booking;
+    assert_eq!(booking.status, BookingStatus::Cancelled);
+    assert!(admin_client
+        .event
+        .get(booking.event_ids[0].clone())
+        .await
+        .is_err());
+    assert!(admin_client
+        .booking
+        .cancel(CancelBookingInput {
+            booking_id: booking.id.clone(),
+            ignore_policy: false,
+        })
+        .await
+        .is_err());
+
+    // The slot is available again
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    assert_eq!(bookingslots[0].slots[0].start, available_slot);
+
+    for (status, count) in [(BookingStatus::Cancelled, 1), (BookingStatus::Confirmed, 0)] {
+        let bookings = admin_client
+            .booking
+            .get_by_service(GetServiceBookingsInput {
+                service_id: service.id.clone(),
+                start_ts: available_slot,
+                end_ts: available_slot + 1000 * 60 * 60,
+                status: Some(status),
+            })
+            .await
+            .expect("To get service bookings")
+            .bookings;
+        assert_eq!(bookings.len(), count);
+    }
+}
+
+#[actix_web::main]
+#[test]
+async fn test_round_robin_includes_bookings() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+
+    let input = CreateServiceInput {
+        metadata: None,
+        multi_person: Some(ServiceMultiPersonOptions::RoundRobinAlgorithm(
+            RoundRobinAlgorithm::EqualDistribution,
+        )),
+        booking_options: None,
+    };
+    let service = admin_client
+        .service
+        .create(input)
+        .await
+        .expect("To create service")
+        .service;
+    for _ in 0..2 {
+        create_default_service_host(&admin_client, &service.id).await;
+    }
+
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    let slots = &bookingslots[0].slots;
+
+    let booking_1 = admin_client
+        .booking
+        .create(create_booking_input(&service.id, slots[0].start))
+        .await
+        .expect("To create booking")
+        .booking;
+    // The host with the fewest upcoming bookings is assigned the next booking
+    let booking_2 = admin_client
+        .booking
+        .create(create_booking_input(&service.id, slots[1].start))
+        .await
+        .expect("To create booking")
+        .booking;
+    assert_eq!(booking_1.host_user_ids.len(), 1);
+    assert_eq!(booking_2.host_user_ids.len(), 1);
+    assert_ne!(booking_1.host_user_ids, booking_2.host_user_ids);
+}
+
+#[actix_web::main]
+#[test]
+async fn test_sticky_host_for_returning_invitee() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+
+    let input = CreateServiceInput {
+        metadata: None,
+        multi_person: Some(ServiceMultiPersonOptions::RoundRobinAlgorithm(
+            RoundRobinAlgorithm::EqualDistribution,
+        )),
+        booking_options: Some(ServiceBookingOptions {
+            sticky_hosts: true,
+            ..Default::default()
+        }),
+    };
+    let service = admin_client
+        .service
+        .create(input)
+        .await
+        .expect("To create service")
+        .service;
+    for _ in 0..2 {
+        create_default_service_host(&admin_client, &service.id).await;
+    }
+
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    let slots = &bookingslots[0].slots;
+    let returning_booking_input = |start_ts: i64| {
+        let mut input = create_booking_input(&service.id, start_ts);
+        input.invitee.customer_key = Some("customer".into());
+        input
+    };
+
+    let booking_1 = admin_client
+        .booking
+        .create(returning_booking_input(slots[0].start))
+        .await
+        .expect("To create booking")
+        .booking;
+    assert_eq!(booking_1.invitee.customer_key, Some("customer".into()));
+    // The returning invitee keeps the host even though the other host has
+    // fewer bookings
+    let booking_2 = admin_client
+        .booking
+        .create(returning_booking_input(slots[1].start))
+        .await
+        .expect("To create booking")
+        .booking;
+    assert_eq!(booking_2.host_user_ids, booking_1.host_user_ids);
+
+    // Other invitees are assigned by the round robin algorithm
+    let booking_3 = admin_client
+        .booking
+        .create(create_booking_input(&service.id, slots[2].start))
+        .await
+        .expect("To create booking")
+        .booking;
+    assert_ne!(booking_3.host_user_ids, booking_1.host_user_ids);
+
+    // Falls back to the other host when the previous host is not available
+    let mut input = create_booking_input(&service.id, slots[3].start);
+    input.host_user_ids = Some(booking_1.host_user_ids.clone());
+    admin_client
+        .booking
+        .create(input)
+        .await
+        .expect("To create booking");
+    let booking_4 = admin_client
+        .booking
+        .create(returning_booking_input(slots[3].start))
+        .await
+        .expect("To create booking")
+        .booking;
+    assert_eq!(booking_4.host_user_ids, booking_3.host_user_ids);
+}
+
+#[actix_web::main]
+#[test]
+async fn test_book_service_chain() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+
+    let mut services = Vec::new();
+    let mut hosts = Vec::new();
+    for _ in 0..2 {
+        let input = CreateServiceInput {
+            metadata: None,
+            multi_person: None,
+            booking_options: None,
+        };
+        let service = admin_client
+            .service
+            .create(input)
+            .await
+            .expect("To create service")
+            .service;
+        let (host, _) = create_default_service_host(&admin_client, &service.id).await;
+        services.push(service);
+        hosts.push(host);
+    }
+
+    let duration = 1000 * 60 * 30;
+    let steps = services
+        .iter()
+        .map(|service| ServiceChainStep {
+            service_id: service.id.clone(),
+            duration: Some(duration),
+            gap: 0,
+            host_user_ids: None,
+        })
+        .collect::<Vec<_>>();
+    let tomorrow = Utc::now() + Duration::days(1);
+    let chain_slots_input = GetServiceChainBookingSlotsInput {
+        steps: steps.clone(),
+        timezone: Some(chrono_tz::UTC),
+        interval: Some(duration),
+        start_date: format_datetime(&tomorrow),
+        end_date: format_datetime(&(tomorrow + Duration::days(7))),
+        attributes: None,
+    };
+    let chain_slots = admin_client
+        .service
+        .chain_bookingslots(chain_slots_input.clone())
+        .await
+        .expect("To get chain bookingslots")
+        .slots;
+    let chain_start = chain_slots[0].start;
+    assert_eq!(chain_slots[0].steps.len(), 2);
+    assert_eq!(chain_slots[0].steps[0].service_id, services[0].id);
+    assert_eq!(
+        chain_slots[0].steps[0].slot.user_ids,
+        vec![hosts[0].id.clone()]
+    );
+    assert_eq!(chain_slots[0].steps[1].service_id, services[1].id);
+    assert_eq!(chain_slots[0].steps[1].slot.start, chain_start + duration);
+    assert_eq!(
+        chain_slots[0].steps[1].slot.user_ids,
+        vec![hosts[1].id.clone()]
+    );
+
+    // Once the second step is taken the chain can no longer start there
+    admin_client
+        .booking
+        .create(create_booking_input(
+            &services[1].id,
+            chain_start + duration,
+        ))
+        .await
+        .expect("To create booking");
+    let chain_slots = admin_client
+        .service
+        .chain_bookingslots(chain_slots_input)
+        .await
+        .expect("To get chain bookingslots")
+        .slots;
+    assert!(chain_slots.iter().all(|slot| slot.start != chain_start));
+
+    let chain_booking_input = |start_ts: i64| CreateBookingChainInput {
+        steps: steps.clone(),
+        start_ts,
+        interval: Some(duration),
+        invitee: create_booking_input(&services[0].id, start_ts).invitee,
+        metadata: None,
+    };
+    let res = admin_client
+        .booking
+        .create_chain(chain_booking_input(chain_start))
+        .await;
+    assert!(res.is_err());
+    // None of the steps were booked
+    let bookings = admin_client
+        .booking
+        .get_by_service(GetServiceBookingsInput {
+            service_id: services[0].id.clone(),
+            start_ts: chain_start,
+            end_ts: chain_start + duration,
+            status: None,
+        })
+        .await
+        .expect("To get service bookings")
+        .bookings;
+    assert!(bookings.is_empty());
+
+    let bookings = admin_client
+        .booking
+        .create_chain(chain_booking_input(chain_slots[0].start))
+        .await
+        .expect("To create chain bookings")
+        .bookings;
+    assert_eq!(bookings.len(), 2);
+    for (i, booking) in bookings.iter().enumerate() {
+        assert_eq!(booking.service_id, services[i].id);
+        assert_eq!(booking.host_user_ids, vec![hosts[i].id.clone()]);
+        assert_eq!(booking.duration, duration);
+    }
+    assert_eq!(bookings[1].start_ts, bookings[0].start_ts + duration);
+}
+
+#[actix_web::main]
+#[test]
+async fn test_booking_requires_resource_from_pool() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+
+    let input = CreateServiceInput {
+        metadata: None,
+        multi_person: Some(ServiceMultiPersonOptions::RoundRobinAlgorithm(
+            RoundRobinAlgorithm::EqualDistribution,
+        )),
+        booking_options: None,
+    };
+    let service = admin_client
+        .service
+        .create(input)
+        .await
+        .expect("To create service")
+        .service;
+    for _ in 0..2 {
+        create_default_service_host(&admin_client, &service.id).await;
+    }
+    let (room, room_calendar) =
+        create_default_service_user(&admin_client, &service.id, Some("room".into())).await;
+    assert_eq!(room.resource_kind, Some("room".into()));
+
+    let service = admin_client
+        .service
+        .get(service.id.clone())
+        .await
+        .expect("To get service");
+    assert_eq!(service.users.len(), 2);
+    assert_eq!(service.resources.len(), 1);
+    assert_eq!(service.resources[0].user_id, room.id);
+
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    let slot = &bookingslots[0].slots[0];
+    assert_eq!(slot.user_ids.len(), 2);
+    assert_eq!(slot.resource_ids, Some(vec![room.id.clone()]));
+
+    let booking = admin_client
+        .booking
+        .create(create_booking_input(&service.id, slot.start))
+        .await
+        .expect("To create booking")
+        .booking;
+    assert_eq!(booking.resource_id, Some(room.id.clone()));
+    // The room is busy during the booking just like the host
+    assert_eq!(booking.event_ids.len(), 2);
+    let room_events = admin_client
+        .calendar
+        .get_events(GetCalendarEventsInput {
+            calendar_id: room_calendar.id.clone(),
+            start_ts: slot.start,
+            end_ts: slot.start + slot.duration,
+        })
+        .await
+        .expect("To get room events")
+        .events;
+    assert_eq!(room_events.len(), 1);
+
+    // The other host is free, but the only room is taken
+    let remaining_bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    assert!(remaining_bookingslots[0]
+        .slots
+        .iter()
+        .all(|s| s.start != slot.start));
+    assert!(admin_client
+        .booking
+        .create(create_booking_input(&service.id, slot.start))
+        .await
+        .is_err());
+}
+
+#[actix_web::main]
+#[test]
+async fn test_cancel_group_booking_releases_seat() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+
+    let input = CreateServiceInput {
+        metadata: None,
+        multi_person: Some(ServiceMultiPersonOptions::Group(2)),
+        booking_options: None,
+    };
+    let service = admin_client
+        .service
+        .create(input)
+        .await
+        .expect("To create service")
+        .service;
+    let (host, busy_calendar) = create_default_service_host(&admin_client, &service.id).await;
+
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    let available_slot = bookingslots[0].slots[0].start;
+
+    let booking_1 = admin_client
+        .booking
+        .create(create_booking_input(&service.id, available_slot))
+        .await
+        .expect("To create booking")
+        .booking;
+    assert!(booking_1.event_ids.is_empty());
+    // The last seat creates the events for the hosts
+    let booking_2 = admin_client
+        .booking
+        .create(create_booking_input(&service.id, available_slot))
+        .await
+        .expect("To create booking")
+        .booking;
+    assert_eq!(booking_2.event_ids.len(), 1);
+    assert!(admin_client
+        .booking
+        .create(create_booking_input(&service.id, available_slot))
+        .await
+        .is_err());
+
+    admin_client
+        .booking
+        .cancel(CancelBookingInput {
+            booking_id: booking_1.id.clone(),
+            ignore_policy: false,
+        })
+        .await
+        .expect("To cancel booking");
+
+    // The host is no longer busy and the seat can be booked again
+    let events = admin_client
+        .calendar
+        .get_events(GetCalendarEventsInput {
+            calendar_id: busy_calendar.id.clone(),
+            start_ts: available_slot,
+            end_ts: available_slot + 1000 * 60 * 30,
+        })
+        .await
+        .expect("To get calendar events")
+        .events;
+    assert!(events.is_empty());
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    let slot = &bookingslots[0].slots[0];
+    assert_eq!(slot.start, available_slot);
+    assert_eq!(slot.seats_remaining, Some(1));
+    assert_eq!(slot.user_ids, vec![host.id]);
+}
+
+#[actix_web::main]
+#[test]
+async fn test_concurrent_bookings_of_same_slot() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+
+    let input = CreateServiceInput {
+        metadata: None,
+        multi_person: None,
+        booking_options: None,
+    };
+    let service = admin_client
+        .service
+        .create(input)
+        .await
+        .expect("To create service")
+        .service;
+    create_default_service_host(&admin_client, &service.id).await;
+
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    let available_slot = bookingslots[0].slots[0].start;
+
+    let results = join_all((0..5).map(|_| {
+        admin_client
+            .booking
+            .create(create_booking_input(&service.id, available_slot))
+    }))
+    .await;
+    let bookings = results.iter().filter(|res| res.is_ok()).count();
+    assert_eq!(bookings, 1);
+    // The losers are either told that the slot was just booked or that the host is no longer available
+    for err in results.into_iter().filter_map(|res| res.err()) {
+        assert!(
+            matches!(err.variant, APIErrorVariant::Conflict)
+                || err.message.contains("not available")
+        );
+    }
+
+    let bookings = admin_client
+        .booking
+        .get_by_service(GetServiceBookingsInput {
+            service_id: service.id.clone(),
+            start_ts: available_slot,
+            end_ts: available_slot + 1000 * 60 * 30,
+            status: None,
+        })
+        .await
+        .expect("To get service bookings")
+        .bookings;
+    assert_eq!(bookings.len(), 1);
+}
+
+fn create_hold_input(service_id: &ID, start_ts: i64) -> CreateBookingHoldInput {
+    CreateBookingHoldInput {
+        service_id: service_id.clone(),
+        host_user_ids: None,
+        start_ts,
+        duration: Some(1000 * 60 * 30),
+        interval: Some(1000 * 60 * 30),
+        ttl: None,
+        attributes: Default::default(),
+        customer_key: None,
+    }
+}
+
+fn create_from_hold_input(hold_id: &ID) -> CreateBookingFromHoldInput {
+    CreateBookingFromHoldInput {
+        hold_id: hold_id.clone(),
+        invitee: BookingInvitee {
+            name: Some("Invitee".into()),
+            email: None,
+            customer_key: None,
+            attributes: Default::default(),
+            answers: Default::default(),
+        },
+        metadata: None,
+    }
+}
+
+#[actix_web::main]
+#[test]
+async fn test_hold_and_convert_booking_slot() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+
+    let input = CreateServiceInput {
+        metadata: None,
+        multi_person: None,
+        booking_options: None,
+    };
+    let service = admin_client
+        .service
+        .create(input)
+        .await
+        .expect("To create service")
+        .service;
+    let (host, _) = create_default_service_host(&admin_client, &service.id).await;
+
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    let available_slot = bookingslots[0].slots[0].start;
+
+    let mut invalid_ttl = create_hold_input(&service.id, available_slot);
+    invalid_ttl.ttl = Some(0);
+    assert!(admin_client.booking.hold(invalid_ttl).await.is_err());
+
+    let hold = admin_client
+        .booking
+        .hold(create_hold_input(&service.id, available_slot))
+        .await
+        .expect("To hold booking slot")
+        .hold;
+    assert_eq!(hold.host_user_ids, vec![host.id.clone()]);
+    assert_eq!(hold.expires_at - hold.created, 1000 * 60 * 10);
+
+    // The held slot is not available to anyone else
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    assert_ne!(bookingslots[0].slots[0].start, available_slot);
+    assert!(admin_client
+        .booking
+        .create(create_booking_input(&service.id, available_slot))
+        .await
+        .is_err());
+    assert!(admin_client
+        .booking
+        .hold(create_hold_input(&service.id, available_slot))
+        .await
+        .is_err());
+
+    let booking = admin_client
+        .booking
+        .create_from_hold(create_from_hold_input(&hold.id))
+        .await
+        .expect("To convert hold into booking")
+        .booking;
+    assert_eq!(booking.status, BookingStatus::Confirmed);
+    assert_eq!(booking.start_ts, available_slot);
+    assert_eq!(booking.host_user_ids, vec![host.id.clone()]);
+    assert_eq!(booking.event_ids.len(), 1);
+    // The hold is used up
+    assert!(admin_client
+        .booking
+        .create_from_hold(create_from_hold_input(&hold.id))
+        .await
+        .is_err());
+
+    // A released hold frees the slot again
+    let next_slot = available_slot + 1000 * 60 * 30;
+    let hold = admin_client
+        .booking
+        .hold(create_hold_input(&service.id, next_slot))
+        .await
+        .expect("To hold booking slot")
+        .hold;
+    admin_client
+        .booking
+        .release_hold(hold.id.clone())
+        .await
+        .expect("To release hold");
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    assert_eq!(bookingslots[0].slots[0].start, next_slot);
+}
+
+#[actix_web::main]
+#[test]
+async fn test_holds_count_against_group_capacity() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+
+    let input = CreateServiceInput {
+        metadata: None,
+        multi_person: Some(ServiceMultiPersonOptions::Group(2)),
+        booking_options: None,
+    };
+    let service = admin_client
+        .service
+        .create(input)
+        .await
+        .expect("To create service")
+        .service;
+    create_default_service_host(&admin_client, &service.id).await;
+
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    let available_slot = bookingslots[0].slots[0].start;
+
+    let hold_1 = admin_client
+        .booking
+        .hold(create_hold_input(&service.id, available_slot))
+        .await
+        .expect("To hold seat")
+        .hold;
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    let slot = &bookingslots[0].slots[0];
+    assert_eq!(slot.start, available_slot);
+    assert_eq!(slot.seats_taken, Some(1));
+    assert_eq!(slot.seats_remaining, Some(1));
+
+    let hold_2 = admin_client
+        .booking
+        .hold(create_hold_input(&service.id, available_slot))
+        .await
+        .expect("To hold seat")
+        .hold;
+    // Both seats are held
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    assert_ne!(bookingslots[0].slots[0].start, available_slot);
+    assert!(admin_client
+        .booking
+        .create(create_booking_input(&service.id, available_slot))
+        .await
+        .is_err());
+
+    let booking_1 = admin_client
+        .booking
+        .create_from_hold(create_from_hold_input(&hold_1.id))
+        .await
+        .expect("To convert hold into booking")
+        .booking;
+    assert!(booking_1.event_ids.is_empty());
+    // Only the last booked seat makes the hosts busy
+    let booking_2 = admin_client
+        .booking
+        .create_from_hold(create_from_hold_input(&hold_2.id))
+        .await
+        .expect("To convert hold into booking")
+        .booking;
+    assert_eq!(booking_2.event_ids.len(), 1);
+}
+
+#[actix_web::main]
+#[test]
+async fn test_approve_and_decline_pending_booking() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+
+    let input = CreateServiceInput {
+        metadata: None,
+        multi_person: None,
+        booking_options: Some(ServiceBookingOptions {
+            requires_approval: true,
+            ..Default::default()
+        }),
+    };
+    let service = admin_client
+        .service
+        .create(input)
+        .await
+        .expect("To create service")
+        .service;
+    create_default_service_host(&admin_client, &service.id).await;
+
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    let available_slot = bookingslots[0].slots[0].start;
+
+    let booking = admin_client
+        .booking
+        .create(create_booking_input(&service.id, available_slot))
+        .await
+        .expect("To create booking")
+        .booking;
+    assert_eq!(booking.status, BookingStatus::Pending);
+    assert!(booking.approval_deadline.is_some());
+    let event = admin_client
+        .event
+        .get(booking.event_ids[0].clone())
+        .await
+        .expect("To get booking event")
+        .event;
+    assert!(event.tentative);
+    assert!(event.busy);
+
+    // The pending booking keeps the slot
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    assert_ne!(bookingslots[0].slots[0].start, available_slot);
+
+    let booking = admin_client
+        .booking
+        .approve(booking.id.clone())
+        .await
+        .expect("To approve booking")
+        .booking;
+    assert_eq!(booking.status, BookingStatus::Confirmed);
+    assert_eq!(booking.approval_deadline, None);
+    let event = admin_client
+        .event
+        .get(booking.event_ids[0].clone())
+        .await
+        .expect("To get booking event")
+        .event;
+    assert!(!event.tentative);
+    assert!(admin_client
+        .booking
+        .decline(booking.id.clone())
+        .await
+        .is_err());
+    // The booking has not ended yet, so the invitee can not have missed it
+    assert!(admin_client
+        .booking
+        .mark_no_show(booking.id.clone())
+        .await
+        .is_err());
+
+    let next_slot = bookingslots[0].slots[0].start;
+    let booking = admin_client
+        .booking
+        .create(create_booking_input(&service.id, next_slot))
+        .await
+        .expect("To create booking")
+        .booking;
+    let booking = admin_client
+        .booking
+        .decline(booking.id.clone())
+        .await
+        .expect("To decline booking")
+        .booking;
+    assert_eq!(booking.status, BookingStatus::Declined);
+    assert!(admin_client
+        .event
+        .get(booking.event_ids[0].clone())
+        .await
+        .is_err());
+
+    // The declined slot is available again
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    assert_eq!(bookingslots[0].slots[0].start, next_slot);
+}
+
+#[actix_web::main]
+#[test]
+async fn test_reschedule_booking() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+
+    let input = CreateServiceInput {
+        metadata: None,
+        multi_person: None,
+        booking_options: None,
+    };
+    let service = admin_client
+        .service
+        .create(input)
+        .await
+        .expect("To create service")
+        .service;
+    let (host, _) = create_default_service_host(&admin_client, &service.id).await;
+
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    let first_slot = bookingslots[0].slots[0].start;
+    let later_slot = bookingslots[0].slots[4].start;
+
+    let booking = admin_client
+        .booking
+        .create(create_booking_input(&service.id, first_slot))
+        .await
+        .expect("To create booking")
+        .booking;
+    let other_booking = admin_client
+        .booking
+        .create(create_booking_input(&service.id, later_slot))
+        .await
+        .expect("To create booking")
+        .booking;
+
+    // Extending the booking overlaps the time it already occupies
+    let rescheduled = admin_client
+        .booking
+        .reschedule(RescheduleBookingInput {
+            booking_id: booking.id.clone(),
+            start_ts: first_slot,
+            duration: Some(1000 * 60 * 60),
+            interval: Some(1000 * 60 * 30),
+            ignore_policy: false,
+        })
+        .await
+        .expect("To reschedule booking")
+        .booking;
+    assert_eq!(rescheduled.id, booking.id);
+    assert_eq!(rescheduled.status, BookingStatus::Rescheduled);
+    assert_eq!(rescheduled.duration, 1000 * 60 * 60);
+    assert_eq!(rescheduled.host_user_ids, vec![host.id.clone()]);
+    assert_eq!(rescheduled.event_ids, booking.event_ids);
+    assert_eq!(rescheduled.reschedules.len(), 1);
+    assert_eq!(rescheduled.reschedules[0].start_ts, first_slot);
+    assert_eq!(rescheduled.reschedules[0].duration, 1000 * 60 * 30);
+
+    // The time of another booking can not be taken
+    let res = admin_client
+        .booking
+        .reschedule(RescheduleBookingInput {
+            booking_id: booking.id.clone(),
+            start_ts: later_slot,
+            duration: None,
+            interval: Some(1000 * 60 * 30),
+            ignore_policy: false,
+        })
+        .await;
+    assert!(res.is_err());
+
+    let new_start = other_booking.start_ts + 1000 * 60 * 60;
+    let rescheduled = admin_client
+        .booking
+        .reschedule(RescheduleBookingInput {
+            booking_id: booking.id.clone(),
+            start_ts: new_start,
+            duration: None,
+            interval: Some(1000 * 60 * 30),
+            ignore_policy: false,
+        })
+        .await
+        .expect("To reschedule booking")
+        .booking;
+    assert_eq!(rescheduled.start_ts, new_start);
+    assert_eq!(rescheduled.reschedules.len(), 2);
+    let event = admin_client
+        .event
+        .get(rescheduled.event_ids[0].clone())
+        .await
+        .expect("To get booking event")
+        .event;
+    assert_eq!(event.id, booking.event_ids[0]);
+    assert_eq!(event.start_ts, new_start);
+    assert_eq!(event.duration, 1000 * 60 * 60);
+
+    // The previous time is available again
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    assert_eq!(bookingslots[0].slots[0].start, first_slot);
+
+    admin_client
+        .booking
+        .cancel(CancelBookingInput {
+            booking_id: rescheduled.id.clone(),
+            ignore_policy: false,
+        })
+        .await
+        .expect("To cancel booking");
+    assert!(admin_client
+        .booking
+        .reschedule(RescheduleBookingInput {
+            booking_id: booking.id.clone(),
+            start_ts: first_slot,
+            duration: None,
+            interval: Some(1000 * 60 * 30),
+            ignore_policy: false,
+        })
+        .await
+        .is_err());
+}
+
+#[actix_web::main]
+#[test]
+async fn test_booking_policy_of_service() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+
+    let input = CreateServiceInput {
+        metadata: None,
+        multi_person: None,
+        booking_options: Some(ServiceBookingOptions {
+            // Every bookable slot is within the cutoff
+            cancellation_cutoff: Some(1000 * 60 * 60 * 24 * 30),
+            max_reschedules: Some(1),
+            ..Default::default()
+        }),
+    };
+    let service = admin_client
+        .service
+        .create(input)
+        .await
+        .expect("To create service")
+        .service;
+    create_default_service_host(&admin_client, &service.id).await;
+
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    let slots = &bookingslots[0].slots;
+
+    let booking = admin_client
+        .booking
+        .create(create_booking_input(&service.id, slots[0].start))
+        .await
+        .expect("To create booking")
+        .booking;
+
+    let res = admin_client
+        .booking
+        .cancel(CancelBookingInput {
+            booking_id: booking.id.clone(),
+            ignore_policy: false,
+        })
+        .await;
+    assert!(matches!(
+        res.map(|_| ()).unwrap_err().variant,
+        APIErrorVariant::Conflict
+    ));
+
+    let reschedule_input = |start_ts: i64, ignore_policy: bool| RescheduleBookingInput {
+        booking_id: booking.id.clone(),
+        start_ts,
+        duration: None,
+        interval: Some(1000 * 60 * 30),
+        ignore_policy,
+    };
+    assert!(admin_client
+        .booking
+        .reschedule(reschedule_input(slots[1].start, false))
+        .await
+        .is_ok());
+    let res = admin_client
+        .booking
+        .reschedule(reschedule_input(slots[2].start, false))
+        .await;
+    assert!(matches!(
+        res.map(|_| ()).unwrap_err().variant,
+        APIErrorVariant::Conflict
+    ));
+
+    // The account can override the policy
+    let booking = admin_client
+        .booking
+        .reschedule(reschedule_input(slots[2].start, true))
+        .await
+        .expect("To reschedule booking")
+        .booking;
+    assert_eq!(booking.reschedules.len(), 2);
+    let booking = admin_client
+        .booking
+        .cancel(CancelBookingInput {
+            booking_id: booking.id.clone(),
+            ignore_policy: true,
+        })
+        .await
+        .expect("To cancel booking")
+        .booking;
+    assert_eq!(booking.status, BookingStatus::Cancelled);
+}
+
+#[actix_web::main]
+#[test]
+async fn test_validate_intake_answers_of_booking() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+
+    let input = CreateServiceInput {
+        metadata: None,
+        multi_person: None,
+        booking_options: Some(ServiceBookingOptions {
+            intake_questions: vec![
+                IntakeQuestion {
+                    key: "reason".into(),
+                    label: Some("Reason for the visit".into()),
+                    required: true,
+                    kind: IntakeQuestionKind::Text {
+                        max_length: Some(100),
+                    },
+                },
+                IntakeQuestion {
+                    key: "language".into(),
+                    label: None,
+                    required: false,
+                    kind: IntakeQuestionKind::Choice {
+                        choices: vec!["en".into(), "no".into()],
+                        multiple: false,
+                    },
+                },
+            ],
+            ..Default::default()
+        }),
+    };
+    let service = admin_client
+        .service
+        .create(input)
+        .await
+        .expect("To create service")
+        .service;
+    let (host, _) = create_default_service_host(&admin_client, &service.id).await;
+
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    let start_ts = bookingslots[0].slots[0].start;
+
+    let booking_input = |answers: Vec<(&str, IntakeAnswer)>| {
+        let mut input = create_booking_input(&service.id, start_ts);
+        input.invitee.answers = answers
+            .into_iter()
+            .map(|(key, answer)| (key.to_string(), answer))
+            .collect();
+        input
+    };
+    let invalid_answers = vec![
+        // The reason is required
+        vec![("language", IntakeAnswer::Text("en".into()))],
+        vec![
+            ("reason", IntakeAnswer::Text("Checkup".into())),
+            ("language", IntakeAnswer::Text("de".into())),
+        ],
+        vec![("reason", IntakeAnswer::Number(1.0))],
+        vec![
+            ("reason", IntakeAnswer::Text("Checkup".into())),
+            ("email", IntakeAnswer::Text("invitee@example.com".into())),
+        ],
+    ];
+    for answers in invalid_answers {
+        assert!(admin_client
+            .booking
+            .create(booking_input(answers))
+            .await
+            .is_err());
+    }
+
+    // The intend endpoint validates the answers as well
+    let intend_input = |answers: Option<IntakeAnswers>| CreateBookingIntendInput {
+        service_id: service.id.clone(),
+        host_user_ids: None,
+        timestamp: start_ts,
+        duration: Some(1000 * 60 * 30),
+        interval: Some(1000 * 60 * 30),
+        attributes: None,
+        answers,
+    };
+    assert!(admin_client
+        .service
+        .create_booking_intend(intend_input(None))
+        .await
+        .is_err());
+    let answers = booking_input(vec![("reason", IntakeAnswer::Text("Checkup".into()))])
+        .invitee
+        .answers;
+    let intend = admin_client
+        .service
+        .create_booking_intend(intend_input(Some(answers)))
+        .await
+        .expect("To create booking intend");
+    assert_eq!(intend.selected_hosts[0].id, host.id);
+
+    let input = booking_input(vec![
+        ("reason", IntakeAnswer::Text("Checkup".into())),
+        ("language", IntakeAnswer::Text("no".into())),
+    ]);
+    let answers = input.invitee.answers.clone();
+    let booking = admin_client
+        .booking
+        .create(input)
+        .await
+        .expect("To create booking")
+        .booking;
+    assert_eq!(booking.invitee.answers, answers);
+    // The answers are stored with the booking
+    let booking = admin_client
+        .booking
+        .get(booking.id.clone())
+        .await
+        .expect("To get booking")
+        .booking;
+    assert_eq!(booking.invitee.answers, answers);
+}
+
+#[actix_web::main]
+#[test]
+async fn test_waitlist_of_full_group_slot() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+
+    let input = CreateServiceInput {
+        metadata: None,
+        multi_person: Some(ServiceMultiPersonOptions::Group(1)),
+        booking_options: None,
+    };
+    let service = admin_client
+        .service
+        .create(input)
+        .await
+        .expect("To create service")
+        .service;
+    create_default_service_host(&admin_client, &service.id).await;
+
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(&service.id))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    let available_slot = bookingslots[0].slots[0].start;
+    let join_input = |name: &str| JoinWaitlistInput {
+        service_id: service.id.clone(),
+        start_ts: available_slot,
+        duration: Some(1000 * 60 * 30),
+        invitee: BookingInvitee {
+            name: Some(name.into()),
+            email: None,
+            customer_key: None,
+            attributes: Default::default(),
+            answers: Default::default(),
+        },
+        metadata: None,
+    };
+
+    // Only full slots have a waitlist
+    let res = admin_client
+        .booking
+        .join_waitlist(join_input("first"))
+        .await;
+    assert!(matches!(
+        res.map(|_| ()).unwrap_err().variant,
+        APIErrorVariant::Conflict
+    ));
+
+    let booking = admin_client
+        .booking
+        .create(create_booking_input(&service.id, available_slot))
+        .await
+        .expect("To create booking")
+        .booking;
+    let first = admin_client
+        .booking
+        .join_waitlist(join_input("first"))
+        .await
+        .expect("To join waitlist")
+        .entry;
+    let second = admin_client
+        .booking
+        .join_waitlist(join_input("second"))
+        .await
+        .expect("To join waitlist")
+        .entry;
+    assert!(first.hold_id.is_none());
+    let entries = admin_client
+        .booking
+        .get_waitlist(service.id.clone(), available_slot)
+        .await
+        .expect("To get waitlist")
+        .entries;
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].id, first.id);
+    assert_eq!(entries[1].id, second.id);
+
+    // The released seat is held for the first one in line
+    admin_client
+        .booking
+        .cancel(CancelBookingInput {
+            booking_id: booking.id.clone(),
+            ignore_policy: false,
+        })
+        .await
+        .expect("To cancel booking");
+    let entries = admin_client
+        .booking
+        .get_waitlist(service.id.clone(), available_slot)
+        .await
+        .expect("To get waitlist")
+        .entries;
+    assert_eq!(entries[0].id, first.id);
+    let hold_id = entries[0].hold_id.clone().expect("To offer seat");
+    assert!(entries[0].offer_expires_at.is_some());
+    assert!(entries[1].hold_id.is_none());
+    assert!(admin_client
+        .booking
+        .create(create_booking_input(&service.id, available_slot))
+        .await
+        .is_err());
+
+    // Leaving the waitlist passes the seat on to the next one in line
+    admin_client
+        .booking
+        .leave_waitlist(first.id.clone())
+        .await
+        .expect("To leave waitlist");
+    let entries = admin_client
+        .booking
+        .get_waitlist(service.id.clone(), available_slot)
+        .await
+        .expect("To get waitlist")
+        .entries;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].id, second.id);
+    let second_hold_id = entries[0].hold_id.clone().expect("To offer seat");
+    assert_ne!(second_hold_id, hold_id);
+
+    let booking = admin_client
+        .booking
+        .create_from_hold(create_from_hold_input(&second_hold_id))
+        .await
+        .expect("To book offered seat")
+        .booking;
+    assert_eq!(booking.start_ts, available_slot);
+    let entries = admin_client
+        .booking
+        .get_waitlist(service.id.clone(), available_slot)
+        .await
+        .expect("To get waitlist")
+        .entries;
+    assert!(entries.is_empty());
+}
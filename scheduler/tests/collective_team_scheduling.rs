@@ -11,7 +11,10 @@ use nettu_scheduler_sdk::{
 };
 
 async fn create_default_service_host(admin_client: &NettuSDK, service_id: &ID) -> (User, Calendar) {
-    let input = CreateUserInput { metadata: None };
+    let input = CreateUserInput {
+        metadata: None,
+        resource_kind: None,
+    };
     let host = admin_client
         .user
         .create(input)
@@ -52,6 +55,11 @@ async fn create_default_service_host(admin_client: &NettuSDK, service_id: &ID) -
         furthest_booking_time: None,
         service_id: service_id.clone(),
         user_id: host.id.clone(),
+        max_bookings_per_day: None,
+        max_booked_minutes_per_week: None,
+        preferred_times: None,
+        weight: None,
+        priority: None,
     };
     admin_client
         .service
@@ -89,6 +97,7 @@ async fn test_collective_team_scheduling() {
         let input = CreateServiceInput {
             metadata: None,
             multi_person: Some(ServiceMultiPersonOptions::Collective),
+            booking_options: None,
         };
         let service = admin_client
             .service
@@ -110,13 +119,17 @@ async fn test_collective_team_scheduling() {
         let duration = 1000 * 60 * 30;
         let interval = 1000 * 60 * 30;
         let input = GetServiceBookingSlotsInput {
-            duration,
-            interval,
+            duration: Some(duration),
+            interval: Some(interval),
             service_id: service.id.clone(),
             timezone: Some(chrono_tz::UTC),
             end_date: format_datetime(&next_week),
             start_date: format_datetime(&tomorrow),
             host_user_ids: None,
+            rank: false,
+            best_per_day: None,
+            attributes: None,
+            bypass_busy_cache: false,
         };
         let bookingslots = admin_client
             .service
@@ -134,8 +147,10 @@ async fn test_collective_team_scheduling() {
             service_id: service.id.clone(),
             host_user_ids: None,
             timestamp: available_slot,
-            duration,
-            interval,
+            duration: Some(duration),
+            interval: Some(interval),
+            attributes: None,
+            answers: None,
         };
         let booking_intend = admin_client
             .service
@@ -169,8 +184,10 @@ async fn test_collective_team_scheduling() {
             service_id: service.id.clone(),
             host_user_ids: None,
             timestamp: available_slot,
-            duration,
-            interval,
+            duration: Some(duration),
+            interval: Some(interval),
+            attributes: None,
+            answers: None,
         };
         admin_client
             .service
@@ -180,13 +197,17 @@ async fn test_collective_team_scheduling() {
 
         // And bookingslots query also no longer shows that time
         let input = GetServiceBookingSlotsInput {
-            duration,
-            interval,
+            duration: Some(duration),
+            interval: Some(interval),
             service_id: service.id.clone(),
             timezone: Some(chrono_tz::UTC),
             end_date: format_datetime(&next_week),
             start_date: format_datetime(&tomorrow),
             host_user_ids: None,
+            rank: false,
+            best_per_day: None,
+            attributes: None,
+            bypass_busy_cache: false,
         };
         let bookingslots = admin_client
             .service
@@ -214,6 +235,7 @@ async fn test_collective_team_scheduling_is_collective() {
     let input = CreateServiceInput {
         metadata: None,
         multi_person: Some(ServiceMultiPersonOptions::Collective),
+        booking_options: None,
     };
     let service = admin_client
         .service
@@ -222,14 +244,20 @@ async fn test_collective_team_scheduling_is_collective() {
         .expect("To create service")
         .service;
 
-    let input = CreateUserInput { metadata: None };
+    let input = CreateUserInput {
+        metadata: None,
+        resource_kind: None,
+    };
     let host1 = admin_client
         .user
         .create(input)
         .await
         .expect("To create user")
         .user;
-    let input = CreateUserInput { metadata: None };
+    let input = CreateUserInput {
+        metadata: None,
+        resource_kind: None,
+    };
     let host2 = admin_client
         .user
         .create(input)
@@ -259,6 +287,11 @@ async fn test_collective_team_scheduling_is_collective() {
         furthest_booking_time: None,
         service_id: service.id.clone(),
         user_id: host1.id.clone(),
+        max_bookings_per_day: None,
+        max_booked_minutes_per_week: None,
+        preferred_times: None,
+        weight: None,
+        priority: None,
     };
     admin_client
         .service
@@ -275,6 +308,11 @@ async fn test_collective_team_scheduling_is_collective() {
         furthest_booking_time: None,
         service_id: service.id.clone(),
         user_id: host2.id.clone(),
+        max_bookings_per_day: None,
+        max_booked_minutes_per_week: None,
+        preferred_times: None,
+        weight: None,
+        priority: None,
     };
     admin_client
         .service
@@ -287,13 +325,17 @@ async fn test_collective_team_scheduling_is_collective() {
     let duration = 1000 * 60 * 30;
     let interval = 1000 * 60 * 30;
     let input = GetServiceBookingSlotsInput {
-        duration,
-        interval,
+        duration: Some(duration),
+        interval: Some(interval),
         service_id: service.id.clone(),
         timezone: Some(chrono_tz::UTC),
         end_date: format_datetime(&next_week),
         start_date: format_datetime(&tomorrow),
         host_user_ids: None,
+        rank: false,
+        best_per_day: None,
+        attributes: None,
+        bypass_busy_cache: false,
     };
     let bookingslots = admin_client
         .service
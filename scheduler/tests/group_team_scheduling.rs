@@ -5,13 +5,16 @@ use helpers::setup::spawn_app;
 use helpers::utils::{assert_equal_user_lists, format_datetime};
 use nettu_scheduler_domain::{BusyCalendar, ServiceMultiPersonOptions, TimePlan, ID};
 use nettu_scheduler_sdk::{
-    AddBusyCalendar, AddServiceUserInput, Calendar, CreateBookingIntendInput, CreateCalendarInput,
-    CreateEventInput, CreateScheduleInput, CreateServiceInput, CreateUserInput,
-    GetServiceBookingSlotsInput, NettuSDK, UpdateServiceInput, User,
+    AddBusyCalendar, AddServiceUserInput, Calendar, CollectiveQuorum, CreateBookingIntendInput,
+    CreateCalendarInput, CreateEventInput, CreateScheduleInput, CreateServiceInput,
+    CreateUserInput, GetServiceBookingSlotsInput, NettuSDK, UpdateServiceInput, User,
 };
 
 async fn create_default_service_host(admin_client: &NettuSDK, service_id: &ID) -> (User, Calendar) {
-    let input = CreateUserInput { metadata: None };
+    let input = CreateUserInput {
+        metadata: None,
+        resource_kind: None,
+    };
     let host = admin_client
         .user
         .create(input)
@@ -52,6 +55,11 @@ async fn create_default_service_host(admin_client: &NettuSDK, service_id: &ID) -
         furthest_booking_time: None,
         service_id: service_id.clone(),
         user_id: host.id.clone(),
+        max_bookings_per_day: None,
+        max_booked_minutes_per_week: None,
+        preferred_times: None,
+        weight: None,
+        priority: None,
     };
     admin_client
         .service
@@ -90,6 +98,7 @@ async fn test_group_team_scheduling() {
             let input = CreateServiceInput {
                 metadata: None,
                 multi_person: Some(ServiceMultiPersonOptions::Group(max_booking_spots)),
+                booking_options: None,
             };
             let service = admin_client
                 .service
@@ -111,13 +120,17 @@ async fn test_group_team_scheduling() {
             let duration = 1000 * 60 * 30;
             let interval = 1000 * 60 * 30;
             let input = GetServiceBookingSlotsInput {
-                duration,
-                interval,
+                duration: Some(duration),
+                interval: Some(interval),
                 service_id: service.id.clone(),
                 timezone: Some(chrono_tz::UTC),
                 end_date: format_datetime(&next_week),
                 start_date: format_datetime(&tomorrow),
                 host_user_ids: None,
+                rank: false,
+                best_per_day: None,
+                attributes: None,
+                bypass_busy_cache: false,
             };
             let bookingslots = admin_client
                 .service
@@ -136,8 +149,10 @@ async fn test_group_team_scheduling() {
                     service_id: service.id.clone(),
                     host_user_ids: None,
                     timestamp: available_slot,
-                    duration,
-                    interval,
+                    duration: Some(duration),
+                    interval: Some(interval),
+                    attributes: None,
+                    answers: None,
                 };
                 let booking_intend = admin_client
                     .service
@@ -152,8 +167,10 @@ async fn test_group_team_scheduling() {
                 service_id: service.id.clone(),
                 host_user_ids: None,
                 timestamp: available_slot,
-                duration,
-                interval,
+                duration: Some(duration),
+                interval: Some(interval),
+                attributes: None,
+                answers: None,
             };
             let booking_intend = admin_client
                 .service
@@ -186,8 +203,10 @@ async fn test_group_team_scheduling() {
                 service_id: service.id.clone(),
                 host_user_ids: None,
                 timestamp: available_slot,
-                duration,
-                interval,
+                duration: Some(duration),
+                interval: Some(interval),
+                attributes: None,
+                answers: None,
             };
             admin_client
                 .service
@@ -197,13 +216,17 @@ async fn test_group_team_scheduling() {
 
             // And bookingslots query also no longer shows that time
             let input = GetServiceBookingSlotsInput {
-                duration,
-                interval,
+                duration: Some(duration),
+                interval: Some(interval),
                 service_id: service.id.clone(),
                 timezone: Some(chrono_tz::UTC),
                 end_date: format_datetime(&next_week),
                 start_date: format_datetime(&tomorrow),
                 host_user_ids: None,
+                rank: false,
+                best_per_day: None,
+                attributes: None,
+                bypass_busy_cache: false,
             };
             let bookingslots = admin_client
                 .service
@@ -233,6 +256,7 @@ async fn test_group_team_scheduling_is_collective() {
     let input = CreateServiceInput {
         metadata: None,
         multi_person: Some(ServiceMultiPersonOptions::Group(max_booking_spots)),
+        booking_options: None,
     };
     let service = admin_client
         .service
@@ -241,14 +265,20 @@ async fn test_group_team_scheduling_is_collective() {
         .expect("To create service")
         .service;
 
-    let input = CreateUserInput { metadata: None };
+    let input = CreateUserInput {
+        metadata: None,
+        resource_kind: None,
+    };
     let host1 = admin_client
         .user
         .create(input)
         .await
         .expect("To create user")
         .user;
-    let input = CreateUserInput { metadata: None };
+    let input = CreateUserInput {
+        metadata: None,
+        resource_kind: None,
+    };
     let host2 = admin_client
         .user
         .create(input)
@@ -278,6 +308,11 @@ async fn test_group_team_scheduling_is_collective() {
         furthest_booking_time: None,
         service_id: service.id.clone(),
         user_id: host1.id.clone(),
+        max_bookings_per_day: None,
+        max_booked_minutes_per_week: None,
+        preferred_times: None,
+        weight: None,
+        priority: None,
     };
     admin_client
         .service
@@ -294,6 +329,11 @@ async fn test_group_team_scheduling_is_collective() {
         furthest_booking_time: None,
         service_id: service.id.clone(),
         user_id: host2.id.clone(),
+        max_bookings_per_day: None,
+        max_booked_minutes_per_week: None,
+        preferred_times: None,
+        weight: None,
+        priority: None,
     };
     admin_client
         .service
@@ -306,13 +346,17 @@ async fn test_group_team_scheduling_is_collective() {
     let duration = 1000 * 60 * 30;
     let interval = 1000 * 60 * 30;
     let input = GetServiceBookingSlotsInput {
-        duration,
-        interval,
+        duration: Some(duration),
+        interval: Some(interval),
         service_id: service.id.clone(),
         timezone: Some(chrono_tz::UTC),
         end_date: format_datetime(&next_week),
         start_date: format_datetime(&tomorrow),
         host_user_ids: None,
+        rank: false,
+        best_per_day: None,
+        attributes: None,
+        bypass_busy_cache: false,
     };
     let bookingslots = admin_client
         .service
@@ -342,6 +386,7 @@ async fn test_group_team_scheduling_increase_max_count() {
         let input = CreateServiceInput {
             metadata: None,
             multi_person: Some(ServiceMultiPersonOptions::Group(max_booking_spots)),
+            booking_options: None,
         };
         let service = admin_client
             .service
@@ -350,7 +395,10 @@ async fn test_group_team_scheduling_increase_max_count() {
             .expect("To create service")
             .service;
 
-        let input = CreateUserInput { metadata: None };
+        let input = CreateUserInput {
+            metadata: None,
+            resource_kind: None,
+        };
         let host = admin_client
             .user
             .create(input)
@@ -391,6 +439,11 @@ async fn test_group_team_scheduling_increase_max_count() {
             furthest_booking_time: None,
             service_id: service.id.clone(),
             user_id: host.id.clone(),
+            max_bookings_per_day: None,
+            max_booked_minutes_per_week: None,
+            preferred_times: None,
+            weight: None,
+            priority: None,
         };
         admin_client
             .service
@@ -413,13 +466,17 @@ async fn test_group_team_scheduling_increase_max_count() {
         let duration = 1000 * 60 * 30;
         let interval = 1000 * 60 * 30;
         let input = GetServiceBookingSlotsInput {
-            duration,
-            interval,
+            duration: Some(duration),
+            interval: Some(interval),
             service_id: service.id.clone(),
             timezone: Some(chrono_tz::UTC),
             end_date: format_datetime(&next_week),
             start_date: format_datetime(&tomorrow),
             host_user_ids: None,
+            rank: false,
+            best_per_day: None,
+            attributes: None,
+            bypass_busy_cache: false,
         };
         let bookingslots = admin_client
             .service
@@ -435,8 +492,10 @@ async fn test_group_team_scheduling_increase_max_count() {
                 service_id: service.id.clone(),
                 host_user_ids: None,
                 timestamp: available_slot,
-                duration,
-                interval,
+                duration: Some(duration),
+                interval: Some(interval),
+                attributes: None,
+                answers: None,
             };
             let booking_intend = admin_client
                 .service
@@ -458,8 +517,10 @@ async fn test_group_team_scheduling_increase_max_count() {
             service_id: service.id.clone(),
             host_user_ids: None,
             timestamp: available_slot,
-            duration,
-            interval,
+            duration: Some(duration),
+            interval: Some(interval),
+            attributes: None,
+            answers: None,
         };
         let booking_intend = admin_client
             .service
@@ -501,6 +562,7 @@ async fn test_group_team_scheduling_increase_max_count() {
             multi_person: Some(ServiceMultiPersonOptions::Group(
                 max_booking_spots + booking_spots_inc,
             )),
+            booking_options: None,
         };
         admin_client
             .service
@@ -520,8 +582,10 @@ async fn test_group_team_scheduling_increase_max_count() {
                 service_id: service.id.clone(),
                 host_user_ids: None,
                 timestamp: available_slot,
-                duration,
-                interval,
+                duration: Some(duration),
+                interval: Some(interval),
+                attributes: None,
+                answers: None,
             };
             let booking_intend = admin_client
                 .service
@@ -543,8 +607,10 @@ async fn test_group_team_scheduling_increase_max_count() {
             service_id: service.id.clone(),
             host_user_ids: None,
             timestamp: available_slot,
-            duration,
-            interval,
+            duration: Some(duration),
+            interval: Some(interval),
+            attributes: None,
+            answers: None,
         };
         let booking_intend = admin_client
             .service
@@ -568,6 +634,7 @@ async fn test_group_team_scheduling_increase_max_count() {
         let input = CreateServiceInput {
             metadata: None,
             multi_person: Some(ServiceMultiPersonOptions::Group(max_booking_spots)),
+            booking_options: None,
         };
         let service = admin_client
             .service
@@ -576,7 +643,10 @@ async fn test_group_team_scheduling_increase_max_count() {
             .expect("To create service")
             .service;
 
-        let input = CreateUserInput { metadata: None };
+        let input = CreateUserInput {
+            metadata: None,
+            resource_kind: None,
+        };
         let host = admin_client
             .user
             .create(input)
@@ -617,6 +687,11 @@ async fn test_group_team_scheduling_increase_max_count() {
             furthest_booking_time: None,
             service_id: service.id.clone(),
             user_id: host.id.clone(),
+            max_bookings_per_day: None,
+            max_booked_minutes_per_week: None,
+            preferred_times: None,
+            weight: None,
+            priority: None,
         };
         admin_client
             .service
@@ -639,13 +714,17 @@ async fn test_group_team_scheduling_increase_max_count() {
         let duration = 1000 * 60 * 30;
         let interval = 1000 * 60 * 30;
         let input = GetServiceBookingSlotsInput {
-            duration,
-            interval,
+            duration: Some(duration),
+            interval: Some(interval),
             service_id: service.id.clone(),
             timezone: Some(chrono_tz::UTC),
             end_date: format_datetime(&next_week),
             start_date: format_datetime(&tomorrow),
             host_user_ids: None,
+            rank: false,
+            best_per_day: None,
+            attributes: None,
+            bypass_busy_cache: false,
         };
         let bookingslots = admin_client
             .service
@@ -664,6 +743,7 @@ async fn test_group_team_scheduling_increase_max_count() {
             multi_person: Some(ServiceMultiPersonOptions::Group(
                 max_booking_spots + booking_spots_inc,
             )),
+            booking_options: None,
         };
         admin_client
             .service
@@ -672,13 +752,17 @@ async fn test_group_team_scheduling_increase_max_count() {
             .expect("To update service");
 
         let input = GetServiceBookingSlotsInput {
-            duration,
-            interval,
+            duration: Some(duration),
+            interval: Some(interval),
             service_id: service.id.clone(),
             timezone: Some(chrono_tz::UTC),
             end_date: format_datetime(&next_week),
             start_date: format_datetime(&tomorrow),
             host_user_ids: None,
+            rank: false,
+            best_per_day: None,
+            attributes: None,
+            bypass_busy_cache: false,
         };
         let bookingslots = admin_client
             .service
@@ -693,8 +777,10 @@ async fn test_group_team_scheduling_increase_max_count() {
                 service_id: service.id.clone(),
                 host_user_ids: None,
                 timestamp: available_slot,
-                duration,
-                interval,
+                duration: Some(duration),
+                interval: Some(interval),
+                attributes: None,
+                answers: None,
             };
             let booking_intend = admin_client
                 .service
@@ -716,8 +802,10 @@ async fn test_group_team_scheduling_increase_max_count() {
             service_id: service.id.clone(),
             host_user_ids: None,
             timestamp: available_slot,
-            duration,
-            interval,
+            duration: Some(duration),
+            interval: Some(interval),
+            attributes: None,
+            answers: None,
         };
         let booking_intend = admin_client
             .service
@@ -753,6 +841,7 @@ async fn test_group_team_scheduling_decrease_max_count() {
         let input = CreateServiceInput {
             metadata: None,
             multi_person: Some(ServiceMultiPersonOptions::Group(max_booking_spots)),
+            booking_options: None,
         };
         let service = admin_client
             .service
@@ -761,7 +850,10 @@ async fn test_group_team_scheduling_decrease_max_count() {
             .expect("To create service")
             .service;
 
-        let input = CreateUserInput { metadata: None };
+        let input = CreateUserInput {
+            metadata: None,
+            resource_kind: None,
+        };
         let host = admin_client
             .user
             .create(input)
@@ -802,6 +894,11 @@ async fn test_group_team_scheduling_decrease_max_count() {
             furthest_booking_time: None,
             service_id: service.id.clone(),
             user_id: host.id.clone(),
+            max_bookings_per_day: None,
+            max_booked_minutes_per_week: None,
+            preferred_times: None,
+            weight: None,
+            priority: None,
         };
         admin_client
             .service
@@ -824,13 +921,17 @@ async fn test_group_team_scheduling_decrease_max_count() {
         let duration = 1000 * 60 * 30;
         let interval = 1000 * 60 * 30;
         let input = GetServiceBookingSlotsInput {
-            duration,
-            interval,
+            duration: Some(duration),
+            interval: Some(interval),
             service_id: service.id.clone(),
             timezone: Some(chrono_tz::UTC),
             end_date: format_datetime(&next_week),
             start_date: format_datetime(&tomorrow),
             host_user_ids: None,
+            rank: false,
+            best_per_day: None,
+            attributes: None,
+            bypass_busy_cache: false,
         };
         let bookingslots = admin_client
             .service
@@ -846,8 +947,10 @@ async fn test_group_team_scheduling_decrease_max_count() {
                 service_id: service.id.clone(),
                 host_user_ids: None,
                 timestamp: available_slot,
-                duration,
-                interval,
+                duration: Some(duration),
+                interval: Some(interval),
+                attributes: None,
+                answers: None,
             };
             let booking_intend = admin_client
                 .service
@@ -869,8 +972,10 @@ async fn test_group_team_scheduling_decrease_max_count() {
             service_id: service.id.clone(),
             host_user_ids: None,
             timestamp: available_slot,
-            duration,
-            interval,
+            duration: Some(duration),
+            interval: Some(interval),
+            attributes: None,
+            answers: None,
         };
         let booking_intend = admin_client
             .service
@@ -912,6 +1017,7 @@ async fn test_group_team_scheduling_decrease_max_count() {
             multi_person: Some(ServiceMultiPersonOptions::Group(
                 max_booking_spots - booking_spots_dec,
             )),
+            booking_options: None,
         };
         admin_client
             .service
@@ -929,8 +1035,10 @@ async fn test_group_team_scheduling_decrease_max_count() {
             service_id: service.id.clone(),
             host_user_ids: None,
             timestamp: available_slot,
-            duration,
-            interval,
+            duration: Some(duration),
+            interval: Some(interval),
+            attributes: None,
+            answers: None,
         };
         admin_client
             .service
@@ -958,6 +1066,7 @@ async fn test_combination_of_services() {
     let input = CreateServiceInput {
         metadata: None,
         multi_person: Some(ServiceMultiPersonOptions::Group(10)),
+        booking_options: None,
     };
     let group_service = admin_client
         .service
@@ -970,6 +1079,7 @@ async fn test_combination_of_services() {
         multi_person: Some(ServiceMultiPersonOptions::RoundRobinAlgorithm(
             Default::default(),
         )),
+        booking_options: None,
     };
     let round_robin_service = admin_client
         .service
@@ -978,7 +1088,10 @@ async fn test_combination_of_services() {
         .expect("To create service")
         .service;
 
-    let input = CreateUserInput { metadata: None };
+    let input = CreateUserInput {
+        metadata: None,
+        resource_kind: None,
+    };
     let host = admin_client
         .user
         .create(input)
@@ -1021,6 +1134,11 @@ async fn test_combination_of_services() {
             furthest_booking_time: None,
             service_id: service_id.clone(),
             user_id: host.id.clone(),
+            max_bookings_per_day: None,
+            max_booked_minutes_per_week: None,
+            preferred_times: None,
+            weight: None,
+            priority: None,
         };
         admin_client
             .service
@@ -1044,13 +1162,17 @@ async fn test_combination_of_services() {
     let duration = 1000 * 60 * 30;
     let interval = 1000 * 60 * 30;
     let get_bookingslots_input = GetServiceBookingSlotsInput {
-        duration,
-        interval,
+        duration: Some(duration),
+        interval: Some(interval),
         service_id: group_service.id.clone(),
         timezone: Some(chrono_tz::UTC),
         end_date: format_datetime(&next_week),
         start_date: format_datetime(&tomorrow),
         host_user_ids: None,
+        rank: false,
+        best_per_day: None,
+        attributes: None,
+        bypass_busy_cache: false,
     };
     let bookingslots = admin_client
         .service
@@ -1075,8 +1197,10 @@ async fn test_combination_of_services() {
         service_id: group_service.id.clone(),
         host_user_ids: None,
         timestamp: available_slot,
-        duration,
-        interval,
+        duration: Some(duration),
+        interval: Some(interval),
+        attributes: None,
+        answers: None,
     };
     admin_client
         .service
@@ -1121,3 +1245,252 @@ async fn test_combination_of_services() {
         .dates;
     assert_eq!(available_slot, bookingslots[0].slots[0].start);
 }
+
+#[actix_web::main]
+#[test]
+async fn test_group_team_scheduling_remaining_seats() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+
+    let max_booking_spots = 3;
+    let input = CreateServiceInput {
+        metadata: None,
+        multi_person: Some(ServiceMultiPersonOptions::Group(max_booking_spots)),
+        booking_options: None,
+    };
+    let service = admin_client
+        .service
+        .create(input)
+        .await
+        .expect("To create service")
+        .service;
+    create_default_service_host(&admin_client, &service.id).await;
+
+    let tomorrow = Utc::now() + Duration::days(1);
+    let next_week = tomorrow + Duration::days(7);
+    let duration = 1000 * 60 * 30;
+    let interval = 1000 * 60 * 30;
+    let bookingslots_input = GetServiceBookingSlotsInput {
+        duration: Some(duration),
+        interval: Some(interval),
+        service_id: service.id.clone(),
+        timezone: Some(chrono_tz::UTC),
+        end_date: format_datetime(&next_week),
+        start_date: format_datetime(&tomorrow),
+        host_user_ids: None,
+        rank: false,
+        best_per_day: None,
+        attributes: None,
+        bypass_busy_cache: false,
+    };
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input.clone())
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    let available_slot = &bookingslots[0].slots[0];
+    assert_eq!(available_slot.seats_taken, Some(0));
+    assert_eq!(available_slot.seats_remaining, Some(max_booking_spots));
+    let available_slot = available_slot.start;
+
+    for seats_taken in 1..=max_booking_spots {
+        let input = CreateBookingIntendInput {
+            service_id: service.id.clone(),
+            host_user_ids: None,
+            timestamp: available_slot,
+            duration: Some(duration),
+            interval: Some(interval),
+            attributes: None,
+            answers: None,
+        };
+        admin_client
+            .service
+            .create_booking_intend(input)
+            .await
+            .expect("To create booking intend");
+
+        let bookingslots = admin_client
+            .service
+            .bookingslots(bookingslots_input.clone())
+            .await
+            .expect("To get bookingslots")
+            .dates;
+        let slot = &bookingslots[0].slots[0];
+        if seats_taken < max_booking_spots {
+            assert_eq!(slot.start, available_slot);
+            assert_eq!(slot.seats_taken, Some(seats_taken));
+            assert_eq!(slot.seats_remaining, Some(max_booking_spots - seats_taken));
+        } else {
+            // The full slot is hidden even before the service event is created
+            assert_ne!(slot.start, available_slot);
+        }
+    }
+
+    let input = CreateBookingIntendInput {
+        service_id: service.id.clone(),
+        host_user_ids: None,
+        timestamp: available_slot,
+        duration: Some(duration),
+        interval: Some(interval),
+        attributes: None,
+        answers: None,
+    };
+    admin_client
+        .service
+        .create_booking_intend(input)
+        .await
+        .expect_err("Expected timestamp to be full booked");
+}
+
+#[actix_web::main]
+#[test]
+async fn test_collective_quorum_scheduling() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+
+    let input = CreateServiceInput {
+        metadata: None,
+        multi_person: None,
+        booking_options: None,
+    };
+    let service = admin_client
+        .service
+        .create(input)
+        .await
+        .expect("To create service")
+        .service;
+    let (manager, _) = create_default_service_host(&admin_client, &service.id).await;
+    let mut interviewers = Vec::new();
+    for _ in 0..3 {
+        interviewers.push(create_default_service_host(&admin_client, &service.id).await);
+    }
+
+    // The manager and any two of the interviewers have to attend
+    let input = UpdateServiceInput {
+        metadata: None,
+        service_id: service.id.clone(),
+        multi_person: Some(ServiceMultiPersonOptions::CollectiveQuorum(
+            CollectiveQuorum {
+                required_user_ids: vec![manager.id.clone()],
+                min_optional: 2,
+            },
+        )),
+        booking_options: None,
+    };
+    admin_client
+        .service
+        .update(input)
+        .await
+        .expect("To update service");
+
+    let tomorrow = Utc::now() + Duration::days(1);
+    let next_week = tomorrow + Duration::days(7);
+    let duration = 1000 * 60 * 30;
+    let interval = 1000 * 60 * 30;
+    let bookingslots_input = || GetServiceBookingSlotsInput {
+        duration: Some(duration),
+        interval: Some(interval),
+        service_id: service.id.clone(),
+        timezone: Some(chrono_tz::UTC),
+        end_date: format_datetime(&next_week),
+        start_date: format_datetime(&tomorrow),
+        host_user_ids: None,
+        rank: false,
+        best_per_day: None,
+        attributes: None,
+        bypass_busy_cache: false,
+    };
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input())
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    let slot = &bookingslots[0].slots[0];
+    let available_slot = slot.start;
+    assert_eq!(slot.user_ids.len(), 4);
+    assert_eq!(
+        slot.optional_user_ids.as_ref().map(|ids| ids.len()),
+        Some(3)
+    );
+
+    // One busy interviewer still leaves a quorum
+    let busy_event = |(host, calendar): &(User, Calendar)| CreateEventInput {
+        user_id: host.id.clone(),
+        busy: Some(true),
+        calendar_id: calendar.id.clone(),
+        duration,
+        metadata: None,
+        recurrence: None,
+        reminders: Vec::new(),
+        service_id: None,
+        start_ts: available_slot,
+    };
+    admin_client
+        .event
+        .create(busy_event(&interviewers[0]))
+        .await
+        .expect("To create event");
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input())
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    let slot = &bookingslots[0].slots[0];
+    assert_eq!(slot.start, available_slot);
+    let optional_user_ids = slot.optional_user_ids.clone().unwrap_or_default();
+    assert_eq!(optional_user_ids.len(), 2);
+    assert!(!optional_user_ids.contains(&interviewers[0].0.id));
+
+    let input = CreateBookingIntendInput {
+        service_id: service.id.clone(),
+        host_user_ids: None,
+        timestamp: available_slot,
+        duration: Some(duration),
+        interval: Some(interval),
+        attributes: None,
+        answers: None,
+    };
+    let booking_intend = admin_client
+        .service
+        .create_booking_intend(input)
+        .await
+        .expect("To create booking intend");
+    let selected_host_ids = booking_intend
+        .selected_hosts
+        .iter()
+        .map(|host| host.id.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(selected_host_ids.len(), 3);
+    assert!(selected_host_ids.contains(&manager.id));
+    assert!(selected_host_ids.contains(&interviewers[1].0.id));
+    assert!(selected_host_ids.contains(&interviewers[2].0.id));
+
+    // Two busy interviewers break the quorum
+    admin_client
+        .event
+        .create(busy_event(&interviewers[1]))
+        .await
+        .expect("To create event");
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input())
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    assert_ne!(bookingslots[0].slots[0].start, available_slot);
+}
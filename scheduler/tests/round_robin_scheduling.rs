@@ -5,13 +5,19 @@ use helpers::setup::spawn_app;
 use helpers::utils::{assert_equal_user_lists, format_datetime};
 use nettu_scheduler_domain::{BusyCalendar, ServiceMultiPersonOptions, TimePlan, ID};
 use nettu_scheduler_sdk::{
-    AddBusyCalendar, AddServiceUserInput, Calendar, CreateBookingIntendInput, CreateCalendarInput,
-    CreateEventInput, CreateScheduleInput, CreateServiceInput, CreateUserInput,
-    GetServiceBookingSlotsInput, NettuSDK, RoundRobinAlgorithm, User,
+    AddBusyCalendar, AddServiceUserInput, Calendar, CreateBookingHoldInput,
+    CreateBookingIntendInput, CreateCalendarInput, CreateEventInput, CreateScheduleInput,
+    CreateServiceInput, CreateUserInput, FairnessBalance, FairnessWindow,
+    GetServiceBookingSlotsInput, HostRoutingRule, Metadata, NettuSDK, RoundRobinAlgorithm,
+    ServiceBookingOptions, UpdateServiceInput, UpdateServiceUserInput, UpdateUserInput, User,
 };
+use std::collections::HashMap;
 
 async fn create_default_service_host(admin_client: &NettuSDK, service_id: &ID) -> (User, Calendar) {
-    let input = CreateUserInput { metadata: None };
+    let input = CreateUserInput {
+        metadata: None,
+        resource_kind: None,
+    };
     let host = admin_client
         .user
         .create(input)
@@ -52,6 +58,11 @@ async fn create_default_service_host(admin_client: &NettuSDK, service_id: &ID) -
         furthest_booking_time: None,
         service_id: service_id.clone(),
         user_id: host.id.clone(),
+        max_bookings_per_day: None,
+        max_booked_minutes_per_week: None,
+        preferred_times: None,
+        weight: None,
+        priority: None,
     };
     admin_client
         .service
@@ -93,6 +104,7 @@ async fn test_round_robin_scheduling_simple_test() {
             let input = CreateServiceInput {
                 metadata: None,
                 multi_person: Some(ServiceMultiPersonOptions::RoundRobinAlgorithm(alg)),
+                booking_options: None,
             };
             let service = admin_client
                 .service
@@ -114,13 +126,17 @@ async fn test_round_robin_scheduling_simple_test() {
             let duration = 1000 * 60 * 30;
             let interval = 1000 * 60 * 30;
             let input = GetServiceBookingSlotsInput {
-                duration,
-                interval,
+                duration: Some(duration),
+                interval: Some(interval),
                 service_id: service.id.clone(),
                 timezone: Some(chrono_tz::UTC),
                 end_date: format_datetime(&next_week),
                 start_date: format_datetime(&tomorrow),
                 host_user_ids: None,
+                rank: false,
+                best_per_day: None,
+                attributes: None,
+                bypass_busy_cache: false,
             };
             let bookingslots = admin_client
                 .service
@@ -149,8 +165,10 @@ async fn test_round_robin_scheduling_simple_test() {
                     service_id: service.id.clone(),
                     host_user_ids: None,
                     timestamp: available_slot,
-                    duration,
-                    interval,
+                    duration: Some(duration),
+                    interval: Some(interval),
+                    attributes: None,
+                    answers: None,
                 };
                 let booking_intend = admin_client
                     .service
@@ -230,6 +248,7 @@ async fn test_round_robin_equal_distribution_scheduling() {
             multi_person: Some(ServiceMultiPersonOptions::RoundRobinAlgorithm(
                 RoundRobinAlgorithm::EqualDistribution,
             )),
+            booking_options: None,
         };
         let service = admin_client
             .service
@@ -252,13 +271,17 @@ async fn test_round_robin_equal_distribution_scheduling() {
         let tomorrow = Utc::now() + Duration::days(1);
         let next_week = tomorrow + Duration::days(7);
         let input = GetServiceBookingSlotsInput {
-            duration,
-            interval,
+            duration: Some(duration),
+            interval: Some(interval),
             service_id: service.id.clone(),
             timezone: Some(chrono_tz::UTC),
             end_date: format_datetime(&next_week),
             start_date: format_datetime(&tomorrow),
             host_user_ids: None,
+            rank: false,
+            best_per_day: None,
+            attributes: None,
+            bypass_busy_cache: false,
         };
         let bookingslots = admin_client
             .service
@@ -313,8 +336,10 @@ async fn test_round_robin_equal_distribution_scheduling() {
                 service_id: service.id.clone(),
                 host_user_ids: None,
                 timestamp: available_slot,
-                duration,
-                interval,
+                duration: Some(duration),
+                interval: Some(interval),
+                attributes: None,
+                answers: None,
             };
             let booking_intend = admin_client
                 .service
@@ -384,6 +409,7 @@ async fn test_round_robin_availability_scheduling() {
             multi_person: Some(ServiceMultiPersonOptions::RoundRobinAlgorithm(
                 RoundRobinAlgorithm::Availability,
             )),
+            booking_options: None,
         };
         let service = admin_client
             .service
@@ -406,13 +432,17 @@ async fn test_round_robin_availability_scheduling() {
         let tomorrow = Utc::now() + Duration::days(1);
         let next_week = tomorrow + Duration::days(7);
         let input = GetServiceBookingSlotsInput {
-            duration,
-            interval,
+            duration: Some(duration),
+            interval: Some(interval),
             service_id: service.id.clone(),
             timezone: Some(chrono_tz::UTC),
             end_date: format_datetime(&next_week),
             start_date: format_datetime(&tomorrow),
             host_user_ids: None,
+            rank: false,
+            best_per_day: None,
+            attributes: None,
+            bypass_busy_cache: false,
         };
         let bookingslots = admin_client
             .service
@@ -483,8 +513,10 @@ async fn test_round_robin_availability_scheduling() {
                 service_id: service.id.clone(),
                 host_user_ids: None,
                 timestamp: available_slot,
-                duration,
-                interval,
+                duration: Some(duration),
+                interval: Some(interval),
+                attributes: None,
+                answers: None,
             };
             let booking_intend = admin_client
                 .service
@@ -525,3 +557,502 @@ async fn test_round_robin_availability_scheduling() {
         }
     }
 }
+
+#[actix_web::main]
+#[test]
+async fn test_round_robin_weights_and_priorities() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+
+    let input = CreateServiceInput {
+        metadata: None,
+        multi_person: Some(ServiceMultiPersonOptions::RoundRobinAlgorithm(
+            RoundRobinAlgorithm::EqualDistribution,
+        )),
+        booking_options: None,
+    };
+    let service = admin_client
+        .service
+        .create(input)
+        .await
+        .expect("To create service")
+        .service;
+    let (senior, senior_calendar) = create_default_service_host(&admin_client, &service.id).await;
+    let (junior_1, junior_1_calendar) =
+        create_default_service_host(&admin_client, &service.id).await;
+    let (junior_2, junior_2_calendar) =
+        create_default_service_host(&admin_client, &service.id).await;
+
+    // The juniors are assigned first and the first junior gets twice as many bookings
+    for (user_id, weight) in [(&junior_1.id, 2), (&junior_2.id, 1)] {
+        let input = UpdateServiceUserInput {
+            service_id: service.id.clone(),
+            user_id: user_id.clone(),
+            availability: None,
+            buffer_after: None,
+            buffer_before: None,
+            closest_booking_time: None,
+            furthest_booking_time: None,
+            max_bookings_per_day: None,
+            max_booked_minutes_per_week: None,
+            preferred_times: None,
+            weight: Some(weight),
+            priority: Some(1),
+        };
+        let service_user = admin_client
+            .service
+            .update_user(input)
+            .await
+            .expect("To update service user");
+        assert_eq!(service_user.weight, weight);
+        assert_eq!(service_user.priority, 1);
+    }
+    let input = UpdateServiceUserInput {
+        service_id: service.id.clone(),
+        user_id: senior.id.clone(),
+        availability: None,
+        buffer_after: None,
+        buffer_before: None,
+        closest_booking_time: None,
+        furthest_booking_time: None,
+        max_bookings_per_day: None,
+        max_booked_minutes_per_week: None,
+        preferred_times: None,
+        weight: Some(0),
+        priority: None,
+    };
+    assert!(admin_client.service.update_user(input).await.is_err());
+
+    let duration = 1000 * 60 * 30;
+    let interval = 1000 * 60 * 30;
+    let tomorrow = Utc::now() + Duration::days(1);
+    let next_week = tomorrow + Duration::days(7);
+    let input = GetServiceBookingSlotsInput {
+        duration: Some(duration),
+        interval: Some(interval),
+        service_id: service.id.clone(),
+        timezone: Some(chrono_tz::UTC),
+        end_date: format_datetime(&next_week),
+        start_date: format_datetime(&tomorrow),
+        host_user_ids: None,
+        rank: false,
+        best_per_day: None,
+        attributes: None,
+        bypass_busy_cache: false,
+    };
+    let bookingslots = admin_client
+        .service
+        .bookingslots(input)
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    let slots = &bookingslots[0].slots;
+
+    let mut assigned = Vec::new();
+    for slot in slots.iter().take(6) {
+        let input = CreateBookingIntendInput {
+            service_id: service.id.clone(),
+            host_user_ids: None,
+            timestamp: slot.start,
+            duration: Some(duration),
+            interval: Some(interval),
+            attributes: None,
+            answers: None,
+        };
+        let selected_host = admin_client
+            .service
+            .create_booking_intend(input)
+            .await
+            .expect("To create booking intend")
+            .selected_hosts
+            .remove(0);
+        let calendar = if selected_host.id == junior_1.id {
+            &junior_1_calendar
+        } else if selected_host.id == junior_2.id {
+            &junior_2_calendar
+        } else {
+            &senior_calendar
+        };
+        let service_event = CreateEventInput {
+            user_id: selected_host.id.clone(),
+            busy: Some(true),
+            calendar_id: calendar.id.clone(),
+            duration,
+            metadata: None,
+            recurrence: None,
+            reminders: Vec::new(),
+            service_id: Some(service.id.clone()),
+            start_ts: slot.start,
+        };
+        admin_client
+            .event
+            .create(service_event)
+            .await
+            .expect("To create service event");
+        assigned.push(selected_host.id);
+    }
+    assert_eq!(assigned.iter().filter(|id| **id == junior_1.id).count(), 4);
+    assert_eq!(assigned.iter().filter(|id| **id == junior_2.id).count(), 2);
+
+    // The senior is assigned when no junior is available
+    let slot = slots[6].start;
+    for (junior, calendar) in [
+        (&junior_1, &junior_1_calendar),
+        (&junior_2, &junior_2_calendar),
+    ] {
+        let busy_event = CreateEventInput {
+            user_id: junior.id.clone(),
+            busy: Some(true),
+            calendar_id: calendar.id.clone(),
+            duration,
+            metadata: None,
+            recurrence: None,
+            reminders: Vec::new(),
+            service_id: None,
+            start_ts: slot,
+        };
+        admin_client
+            .event
+            .create(busy_event)
+            .await
+            .expect("To create event");
+    }
+    let input = CreateBookingIntendInput {
+        service_id: service.id.clone(),
+        host_user_ids: None,
+        timestamp: slot,
+        duration: Some(duration),
+        interval: Some(interval),
+        attributes: None,
+        answers: None,
+    };
+    let booking_intend = admin_client
+        .service
+        .create_booking_intend(input)
+        .await
+        .expect("To create booking intend");
+    assert_eq!(booking_intend.selected_hosts[0].id, senior.id);
+}
+
+#[actix_web::main]
+#[test]
+async fn test_round_robin_fairness_window() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+
+    let day = 1000 * 60 * 60 * 24;
+    let fairness = FairnessWindow {
+        lookback: day * 7,
+        lookahead: day * 7,
+        include_past_bookings: true,
+        balance: FairnessBalance::Minutes,
+    };
+    let input = CreateServiceInput {
+        metadata: None,
+        multi_person: Some(ServiceMultiPersonOptions::RoundRobinAlgorithm(
+            RoundRobinAlgorithm::EqualDistribution,
+        )),
+        booking_options: Some(ServiceBookingOptions {
+            fairness: fairness.clone(),
+            ..Default::default()
+        }),
+    };
+    let service = admin_client
+        .service
+        .create(input)
+        .await
+        .expect("To create service")
+        .service;
+    let mut hosts = [
+        create_default_service_host(&admin_client, &service.id).await,
+        create_default_service_host(&admin_client, &service.id).await,
+    ];
+    hosts.sort_by(|a, b| a.0.id.as_ref().cmp(b.0.id.as_ref()));
+    let (host_1, host_1_calendar) = &hosts[0];
+    let (host_2, host_2_calendar) = &hosts[1];
+
+    let now = Utc::now().timestamp_millis();
+    let minutes = 1000 * 60;
+    // The first host had a long meeting and the second host has two short ones
+    // coming up and a long one outside of the window
+    let service_events = [
+        (host_1, host_1_calendar, now - day * 2, 90 * minutes),
+        (host_2, host_2_calendar, now + day * 3, 30 * minutes),
+        (host_2, host_2_calendar, now + day * 4, 30 * minutes),
+        (host_2, host_2_calendar, now + day * 10, 120 * minutes),
+    ];
+    for (host, calendar, start_ts, duration) in service_events.iter() {
+        let service_event = CreateEventInput {
+            user_id: host.id.clone(),
+            busy: Some(true),
+            calendar_id: calendar.id.clone(),
+            duration: *duration,
+            metadata: None,
+            recurrence: None,
+            reminders: Vec::new(),
+            service_id: Some(service.id.clone()),
+            start_ts: *start_ts,
+        };
+        admin_client
+            .event
+            .create(service_event)
+            .await
+            .expect("To create service event");
+    }
+
+    let distribution = admin_client
+        .service
+        .distribution(service.id.clone())
+        .await
+        .expect("To get distribution");
+    assert_eq!(distribution.balance, FairnessBalance::Minutes);
+    assert_eq!(distribution.end_ts - distribution.start_ts, day * 14);
+    assert_eq!(distribution.hosts.len(), 2);
+    let host_1_distribution = distribution
+        .hosts
+        .iter()
+        .find(|h| h.user_id == host_1.id)
+        .unwrap();
+    assert_eq!(host_1_distribution.assignments, 1);
+    assert_eq!(host_1_distribution.booked_minutes, 90);
+    assert!((host_1_distribution.share - 0.6).abs() < f64::EPSILON);
+    assert!((host_1_distribution.target_share - 0.5).abs() < f64::EPSILON);
+    let host_2_distribution = distribution
+        .hosts
+        .iter()
+        .find(|h| h.user_id == host_2.id)
+        .unwrap();
+    assert_eq!(host_2_distribution.assignments, 2);
+    assert_eq!(host_2_distribution.booked_minutes, 60);
+
+    let duration = 1000 * 60 * 30;
+    let tomorrow = Utc::now() + Duration::days(1);
+    let input = GetServiceBookingSlotsInput {
+        duration: Some(duration),
+        interval: Some(duration),
+        service_id: service.id.clone(),
+        timezone: Some(chrono_tz::UTC),
+        end_date: format_datetime(&tomorrow),
+        start_date: format_datetime(&tomorrow),
+        host_user_ids: None,
+        rank: false,
+        best_per_day: None,
+        attributes: None,
+        bypass_busy_cache: false,
+    };
+    let bookingslots = admin_client
+        .service
+        .bookingslots(input)
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    let slot = bookingslots[0].slots[0].start;
+    let create_booking_intend = || async {
+        let input = CreateBookingIntendInput {
+            service_id: service.id.clone(),
+            host_user_ids: None,
+            timestamp: slot,
+            duration: Some(duration),
+            interval: Some(duration),
+            attributes: None,
+            answers: None,
+        };
+        admin_client
+            .service
+            .create_booking_intend(input)
+            .await
+            .expect("To create booking intend")
+            .selected_hosts
+            .remove(0)
+            .id
+    };
+
+    // The second host has fewer booked minutes
+    assert_eq!(create_booking_intend().await, host_2.id);
+
+    // Past bookings are ignored
+    let input = UpdateServiceInput {
+        service_id: service.id.clone(),
+        metadata: None,
+        multi_person: None,
+        booking_options: Some(ServiceBookingOptions {
+            fairness: FairnessWindow {
+                include_past_bookings: false,
+                ..fairness.clone()
+            },
+            ..Default::default()
+        }),
+    };
+    admin_client
+        .service
+        .update(input)
+        .await
+        .expect("To update service");
+    assert_eq!(create_booking_intend().await, host_1.id);
+
+    // Bookings are counted instead of minutes
+    let input = UpdateServiceInput {
+        service_id: service.id.clone(),
+        metadata: None,
+        multi_person: None,
+        booking_options: Some(ServiceBookingOptions {
+            fairness: FairnessWindow {
+                balance: FairnessBalance::Count,
+                ..fairness
+            },
+            ..Default::default()
+        }),
+    };
+    admin_client
+        .service
+        .update(input)
+        .await
+        .expect("To update service");
+    assert_eq!(create_booking_intend().await, host_1.id);
+}
+
+#[actix_web::main]
+#[test]
+async fn test_round_robin_host_routing() {
+    let (app, sdk, address) = spawn_app().await;
+    let res = sdk
+        .account
+        .create(&app.config.create_account_secret_code)
+        .await
+        .expect("Expected to create account");
+
+    let admin_client = NettuSDK::new(address, res.secret_api_key);
+    let input = CreateServiceInput {
+        metadata: None,
+        multi_person: Some(ServiceMultiPersonOptions::RoundRobinAlgorithm(
+            RoundRobinAlgorithm::EqualDistribution,
+        )),
+        booking_options: Some(ServiceBookingOptions {
+            host_routing: vec![
+                HostRoutingRule {
+                    key: "language".into(),
+                    value: None,
+                },
+                HostRoutingRule {
+                    key: "certification".into(),
+                    value: Some("level2".into()),
+                },
+            ],
+            ..Default::default()
+        }),
+    };
+    let service = admin_client
+        .service
+        .create(input)
+        .await
+        .expect("To create service")
+        .service;
+
+    let mut hosts = Vec::new();
+    for (language, certification) in [("de", "level2"), ("en", "level2"), ("de", "level1")] {
+        let (host, _) = create_default_service_host(&admin_client, &service.id).await;
+        let mut metadata = Metadata::new_kv("language".into(), language.into());
+        metadata
+            .inner
+            .insert("certification".into(), certification.into());
+        let input = UpdateUserInput {
+            user_id: host.id.clone(),
+            metadata: Some(metadata),
+        };
+        admin_client
+            .user
+            .update(input)
+            .await
+            .expect("To update user metadata");
+        hosts.push(host);
+    }
+
+    let tomorrow = Utc::now() + Duration::days(1);
+    let bookingslots_input =
+        |attributes: Option<HashMap<String, String>>| GetServiceBookingSlotsInput {
+            duration: Some(1000 * 60 * 30),
+            interval: Some(1000 * 60 * 30),
+            service_id: service.id.clone(),
+            timezone: Some(chrono_tz::UTC),
+            end_date: format_datetime(&tomorrow),
+            start_date: format_datetime(&tomorrow),
+            host_user_ids: None,
+            rank: false,
+            best_per_day: None,
+            attributes,
+            bypass_busy_cache: false,
+        };
+    let german = vec![("language".to_string(), "de".to_string())]
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+
+    // Only the certified hosts are considered
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(None))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    let slot = &bookingslots[0].slots[0];
+    assert_eq!(slot.user_ids.len(), 2);
+    assert!(slot.user_ids.contains(&hosts[0].id));
+    assert!(slot.user_ids.contains(&hosts[1].id));
+
+    // Only the certified hosts speaking the language of the invitee are considered
+    let bookingslots = admin_client
+        .service
+        .bookingslots(bookingslots_input(Some(german.clone())))
+        .await
+        .expect("To get bookingslots")
+        .dates;
+    let slot = &bookingslots[0].slots[0];
+    assert_eq!(slot.user_ids, vec![hosts[0].id.clone()]);
+
+    let input = CreateBookingIntendInput {
+        service_id: service.id.clone(),
+        host_user_ids: None,
+        timestamp: slot.start,
+        duration: Some(1000 * 60 * 30),
+        interval: Some(1000 * 60 * 30),
+        attributes: Some(german.clone()),
+        answers: None,
+    };
+    let booking_intend = admin_client
+        .service
+        .create_booking_intend(input)
+        .await
+        .expect("To create booking intend");
+    assert_eq!(booking_intend.selected_hosts.len(), 1);
+    assert_eq!(booking_intend.selected_hosts[0].id, hosts[0].id);
+
+    // Holds are routed by the attributes of the invitee as well
+    let input = CreateBookingHoldInput {
+        service_id: service.id.clone(),
+        host_user_ids: None,
+        start_ts: slot.start,
+        duration: Some(1000 * 60 * 30),
+        interval: Some(1000 * 60 * 30),
+        ttl: None,
+        attributes: german,
+        customer_key: None,
+    };
+    let hold = admin_client
+        .booking
+        .hold(input)
+        .await
+        .expect("To hold booking slot")
+        .hold;
+    assert_eq!(hold.host_user_ids, vec![hosts[0].id.clone()]);
+}